@@ -209,6 +209,7 @@ fn testnet_genesis(
 		},
 		bridge_rialto_messages: BridgeRialtoMessagesConfig {
 			owner: Some(get_account_id_from_seed::<sr25519::Public>("RialtoMessagesOwner")),
+			opened_lanes: vec![[0, 0, 0, 0], [0, 0, 0, 1], *b"swap"],
 			..Default::default()
 		},
 	}
@@ -34,9 +34,6 @@ pub mod rialto_messages;
 
 use crate::rialto_messages::{ToRialtoMessagePayload, WithRialtoMessageBridge};
 
-use bridge_runtime_common::messages::{
-	source::estimate_message_dispatch_and_delivery_fee, MessageBridge,
-};
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
@@ -47,7 +44,7 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{Block as BlockT, IdentityLookup, NumberFor, OpaqueKeys},
-	transaction_validity::{TransactionSource, TransactionValidity},
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, FixedPointNumber, MultiSignature, MultiSigner, Perquintill,
 };
 use sp_std::prelude::*;
@@ -69,6 +66,7 @@ pub use pallet_bridge_grandpa::{
 	Call as BridgeGrandpaRialtoCall, Call as BridgeGrandpaWestendCall,
 };
 pub use pallet_bridge_messages::Call as MessagesCall;
+pub use pallet_bridge_parachains::Call as BridgeParachainsCall;
 pub use pallet_sudo::Call as SudoCall;
 pub use pallet_timestamp::Call as TimestampCall;
 
@@ -223,6 +221,7 @@ impl pallet_bridge_dispatch::Config for Runtime {
 	type Call = Call;
 	type CallFilter = frame_support::traits::Everything;
 	type EncodedCall = crate::rialto_messages::FromRialtoEncodedCall;
+	type CompatibleSpecVersions = frame_support::traits::Nothing;
 	type SourceChainAccountId = bp_rialto::AccountId;
 	type TargetChainAccountPublic = MultiSigner;
 	type TargetChainSignature = MultiSignature;
@@ -339,13 +338,19 @@ parameter_types! {
 	// Assuming the worst case of every header being finalized, we will keep headers for at least a
 	// week.
 	pub const HeadersToKeep: u32 = 7 * bp_millau::DAYS as u32;
+
+	// Forced changes still need to be approved by the pallet owner (or root) before they're
+	// accepted.
+	pub const AllowForcedChanges: bool = true;
 }
 
 pub type RialtoGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_rialto::Rialto;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
+	type AllowForcedChanges = AllowForcedChanges;
 
 	// TODO [#391]: Use weights generated for the Millau runtime instead of Rialto ones.
 	type WeightInfo = pallet_bridge_grandpa::weights::RialtoWeight<Runtime>;
@@ -353,29 +358,79 @@ impl pallet_bridge_grandpa::Config for Runtime {
 
 pub type WestendGrandpaInstance = pallet_bridge_grandpa::Instance1;
 impl pallet_bridge_grandpa::Config<WestendGrandpaInstance> for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_westend::Westend;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
+	type AllowForcedChanges = AllowForcedChanges;
 
 	// TODO [#391]: Use weights generated for the Millau runtime instead of Rialto ones.
 	type WeightInfo = pallet_bridge_grandpa::weights::RialtoWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const RialtoParasPalletName: &'static str = bp_rialto::PARAS_PALLET_NAME;
+	// Number of parachain heads to keep.
+	pub const ParaHeadsToKeep: u32 = 1024;
+}
+
+/// Instance of the parachains pallet used to track heads of Rialto parachains.
+pub type WithRialtoParachainsInstance = ();
+impl pallet_bridge_parachains::Config<WithRialtoParachainsInstance> for Runtime {
+	type BridgesGrandpaPalletInstance = RialtoGrandpaInstance;
+	type ParasPalletName = RialtoParasPalletName;
+	type TrackedParachains = frame_support::traits::Everything;
+	type HeadsToKeep = ParaHeadsToKeep;
+
+	type WeightInfo = pallet_bridge_parachains::weights::MillauWeight<Runtime>;
+}
+
 impl pallet_shift_session_manager::Config for Runtime {}
 
 parameter_types! {
 	pub const MaxMessagesToPruneAtOnce: bp_messages::MessageNonce = 8;
+	pub const MaxDispatchStatusesToKeep: bp_messages::MessageNonce = 1024;
 	pub const MaxUnrewardedRelayerEntriesAtInboundLane: bp_messages::MessageNonce =
 		bp_millau::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE;
 	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
 		bp_millau::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE;
+	// messages that are heavier than that are put into the overweight queue
+	pub MaxInlineDispatchWeight: Weight = bp_millau::max_inline_dispatch_weight();
+	pub const MaxAllowedSenders: u32 = 16;
 	// `IdentityFee` is used by Millau => we may use weight directly
 	pub const GetDeliveryConfirmationTransactionFee: Balance =
 		bp_millau::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
 	pub const RootAccountForPayments: Option<AccountId> = None;
 	pub const RialtoChainId: bp_runtime::ChainId = bp_runtime::RIALTO_CHAIN_ID;
+	pub RelayersFundAccount: AccountId =
+		pallet_bridge_messages::relayer_fund_account_id::<AccountId, bp_millau::AccountIdConverter>();
+	pub const RelayerStake: Balance = 1_000_000;
+	pub const DeliverySlotLength: BlockNumber = bp_millau::DELIVERY_SLOT_LENGTH;
+	pub const MaxRelayersPerLane: u32 = 16;
+	pub const DeliverySlotPriorityBoost: TransactionPriority = TransactionPriority::max_value() / 2;
 }
 
+impl pallet_bridge_relayers::Config for Runtime {
+	type Event = Event;
+	type Reward = Balance;
+	type PaymentProcedure = pallet_bridge_relayers::PayLaneRewardFromAccount<
+		pallet_balances::Pallet<Runtime>,
+		RelayersFundAccount,
+	>;
+	type StakeCurrency = pallet_balances::Pallet<Runtime>;
+	type RequiredStake = RelayerStake;
+	type SlotLength = DeliverySlotLength;
+	type MaxRelayersPerLane = MaxRelayersPerLane;
+	type WeightInfo = pallet_bridge_relayers::weights::MillauWeight<Runtime>;
+}
+
+/// Signed extension that prioritizes message delivery transactions of the slot relayer.
+pub type PrioritizeSlotRelayer = pallet_bridge_relayers::PrioritizeSlotRelayer<
+	Runtime,
+	rialto_messages::RialtoMessagesDeliveryCallInfo,
+	DeliverySlotPriorityBoost,
+>;
+
 /// Instance of the messages pallet used to relay messages to/from Rialto chain.
 pub type WithRialtoMessagesInstance = ();
 
@@ -385,8 +440,11 @@ impl pallet_bridge_messages::Config<WithRialtoMessagesInstance> for Runtime {
 	type WeightInfo = pallet_bridge_messages::weights::RialtoWeight<Runtime>;
 	type Parameter = rialto_messages::MillauToRialtoMessagesParameter;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxDispatchStatusesToKeep = MaxDispatchStatusesToKeep;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxInlineDispatchWeight = MaxInlineDispatchWeight;
+	type MaxAllowedSenders = MaxAllowedSenders;
 
 	type OutboundPayload = crate::rialto_messages::ToRialtoMessagePayload;
 	type OutboundMessageFee = Balance;
@@ -400,12 +458,13 @@ impl pallet_bridge_messages::Config<WithRialtoMessagesInstance> for Runtime {
 	type TargetHeaderChain = crate::rialto_messages::Rialto;
 	type LaneMessageVerifier = crate::rialto_messages::ToRialtoMessageVerifier;
 	type MessageDeliveryAndDispatchPayment =
-		pallet_bridge_messages::instant_payments::InstantCurrencyPayments<
+		pallet_bridge_relayers::MessageDeliveryAndDispatchPaymentAdapter<
 			Runtime,
-			(),
+			WithRialtoMessagesInstance,
 			pallet_balances::Pallet<Runtime>,
 			GetDeliveryConfirmationTransactionFee,
 			RootAccountForPayments,
+			crate::rialto_messages::ToRialtoUnspentDispatchFeeRefund,
 		>;
 	type OnMessageAccepted = ();
 	type OnDeliveryConfirmed =
@@ -463,13 +522,15 @@ construct_runtime!(
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage},
 
 		// Rialto bridge modules.
-		BridgeRialtoGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
-		BridgeDispatch: pallet_bridge_dispatch::{Pallet, Event<T>},
+		BridgeRialtoGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
+		BridgeDispatch: pallet_bridge_dispatch::{Pallet, Storage, Event<T>},
+		BridgeRelayers: pallet_bridge_relayers::{Pallet, Call, Storage, Event<T>},
 		BridgeRialtoMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, Config<T>},
 		BridgeRialtoTokenSwap: pallet_bridge_token_swap::{Pallet, Call, Storage, Event<T>},
+		BridgeRialtoParachains: pallet_bridge_parachains::{Pallet, Call, Storage},
 
 		// Westend bridge modules.
-		BridgeWestendGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Config<T>, Storage},
+		BridgeWestendGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Config<T>, Storage, Event<T>},
 	}
 );
 
@@ -492,6 +553,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	PrioritizeSlotRelayer,
 );
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -650,6 +712,16 @@ impl_runtime_apis! {
 		fn is_known_header(hash: bp_rialto::Hash) -> bool {
 			BridgeRialtoGrandpa::is_known_header(hash)
 		}
+
+		fn authority_set(
+			set_id: bp_header_chain::SetId,
+		) -> Option<bp_header_chain::AuthoritySet> {
+			BridgeRialtoGrandpa::authority_set(set_id)
+		}
+
+		fn header_set_id(hash: bp_rialto::Hash) -> Option<bp_header_chain::SetId> {
+			BridgeRialtoGrandpa::header_set_id(hash)
+		}
 	}
 
 	impl bp_westend::WestendFinalityApi<Block> for Runtime {
@@ -661,17 +733,28 @@ impl_runtime_apis! {
 		fn is_known_header(hash: bp_westend::Hash) -> bool {
 			BridgeWestendGrandpa::is_known_header(hash)
 		}
+
+		fn authority_set(
+			set_id: bp_header_chain::SetId,
+		) -> Option<bp_header_chain::AuthoritySet> {
+			BridgeWestendGrandpa::authority_set(set_id)
+		}
+
+		fn header_set_id(hash: bp_westend::Hash) -> Option<bp_header_chain::SetId> {
+			BridgeWestendGrandpa::header_set_id(hash)
+		}
 	}
 
 	impl bp_rialto::ToRialtoOutboundLaneApi<Block, Balance, ToRialtoMessagePayload> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
-			_lane_id: bp_messages::LaneId,
+			lane_id: bp_messages::LaneId,
 			payload: ToRialtoMessagePayload,
 		) -> Option<Balance> {
-			estimate_message_dispatch_and_delivery_fee::<WithRialtoMessageBridge>(
-				&payload,
-				WithRialtoMessageBridge::RELAYER_FEE_PERCENT,
-			).ok()
+			bridge_runtime_common::messages_api::estimate_outbound_message_fee::<
+				Runtime,
+				WithRialtoMessagesInstance,
+				WithRialtoMessageBridge,
+			>(lane_id, &payload)
 		}
 
 		fn message_details(
@@ -693,6 +776,17 @@ impl_runtime_apis! {
 		fn latest_generated_nonce(lane: bp_messages::LaneId) -> bp_messages::MessageNonce {
 			BridgeRialtoMessages::outbound_latest_generated_nonce(lane)
 		}
+
+		fn message_dispatch_status(
+			lane: bp_messages::LaneId,
+			nonce: bp_messages::MessageNonce,
+		) -> Option<bp_messages::MessageDispatchStatus> {
+			BridgeRialtoMessages::outbound_message_dispatch_status(lane, nonce)
+		}
+
+		fn lanes() -> Vec<(bp_messages::LaneId, bp_messages::LaneInfo)> {
+			BridgeRialtoMessages::lanes()
+		}
 	}
 
 	impl bp_rialto::FromRialtoInboundLaneApi<Block> for Runtime {
@@ -718,9 +812,13 @@ impl_runtime_apis! {
 			use frame_benchmarking::{list_benchmark, Benchmarking, BenchmarkList};
 			use frame_support::traits::StorageInfoTrait;
 
+			use pallet_bridge_parachains::benchmarking::Pallet as BridgeParachainsBench;
+
 			let mut list = Vec::<BenchmarkList>::new();
 
+			list_benchmark!(list, extra, pallet_bridge_parachains, BridgeParachainsBench::<Runtime, WithRialtoParachainsInstance>);
 			list_benchmark!(list, extra, pallet_bridge_token_swap, BridgeRialtoTokenSwap);
+			list_benchmark!(list, extra, pallet_bridge_relayers, BridgeRelayers);
 
 			let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -748,8 +846,27 @@ impl_runtime_apis! {
 			let mut batches = Vec::<BenchmarkBatch>::new();
 			let params = (&config, &whitelist);
 
+			use pallet_bridge_parachains::benchmarking::{
+				Pallet as BridgeParachainsBench,
+				Config as BridgeParachainsConfig,
+			};
 			use pallet_bridge_token_swap::benchmarking::Config as TokenSwapConfig;
 
+			impl BridgeParachainsConfig<WithRialtoParachainsInstance> for Runtime {
+				fn prepare_parachain_heads_proof(
+					parachains: &[bp_polkadot_core::parachains::ParaId],
+					parachain_head_size: u32,
+				) -> (
+					pallet_bridge_parachains::RelayBlockHash<Runtime, WithRialtoParachainsInstance>,
+					bp_polkadot_core::parachains::ParaHeadsProof,
+				) {
+					bridge_runtime_common::parachains_benchmarking::prepare_parachain_heads_proof::<Runtime, WithRialtoParachainsInstance>(
+						parachains,
+						parachain_head_size,
+					)
+				}
+			}
+
 			impl TokenSwapConfig<WithRialtoTokenSwapInstance> for Runtime {
 				fn initialize_environment() {
 					let relayers_fund_account = pallet_bridge_messages::relayer_fund_account_id::<
@@ -763,7 +880,18 @@ impl_runtime_apis! {
 				}
 			}
 
+			impl pallet_bridge_relayers::benchmarking::Config for Runtime {
+				fn prepare_environment(_relayer: &AccountId, reward: Balance) {
+					pallet_balances::Pallet::<Runtime>::make_free_balance_be(
+						&RelayersFundAccount::get(),
+						reward.saturating_add(ExistentialDeposit::get()),
+					);
+				}
+			}
+
+			add_benchmark!(params, batches, pallet_bridge_parachains, BridgeParachainsBench::<Runtime, WithRialtoParachainsInstance>);
 			add_benchmark!(params, batches, pallet_bridge_token_swap, BridgeRialtoTokenSwap);
+			add_benchmark!(params, batches, pallet_bridge_relayers, BridgeRelayers);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
@@ -775,11 +903,13 @@ impl_runtime_apis! {
 ///
 /// The byte vector returned by this function should be signed with a Rialto account private key.
 /// This way, the owner of `millau_account_id` on Millau proves that the Rialto account private key
-/// is also under his control.
+/// is also under his control. The `rialto_account_nonce` must match the current nonce of the
+/// Rialto account, stored by the Rialto dispatch pallet.
 pub fn millau_to_rialto_account_ownership_digest<Call, AccountId, SpecVersion>(
 	rialto_call: &Call,
 	millau_account_id: AccountId,
 	rialto_spec_version: SpecVersion,
+	rialto_account_nonce: bp_message_dispatch::TargetAccountNonce,
 ) -> sp_std::vec::Vec<u8>
 where
 	Call: codec::Encode,
@@ -792,6 +922,7 @@ where
 		rialto_spec_version,
 		bp_runtime::MILLAU_CHAIN_ID,
 		bp_runtime::RIALTO_CHAIN_ID,
+		rialto_account_nonce,
 	)
 }
 
@@ -52,8 +52,15 @@ pub type ToRialtoMessagePayload =
 	messages::source::FromThisChainMessagePayload<WithRialtoMessageBridge>;
 
 /// Message verifier for Millau -> Rialto messages.
-pub type ToRialtoMessageVerifier =
-	messages::source::FromThisChainMessageVerifier<WithRialtoMessageBridge>;
+pub type ToRialtoMessageVerifier = messages::source::FromThisChainAllowedSendersMessageVerifier<
+	WithRialtoMessageBridge,
+	crate::Runtime,
+	crate::WithRialtoMessagesInstance,
+>;
+
+/// Unspent dispatch fee refund for Millau -> Rialto messages.
+pub type ToRialtoUnspentDispatchFeeRefund =
+	messages::source::FromThisChainUnspentDispatchFeeRefund<WithRialtoMessageBridge>;
 
 /// Message payload for Rialto -> Millau messages.
 pub type FromRialtoMessagePayload =
@@ -124,6 +131,12 @@ impl messages::ThisChainWithMessages for Millau {
 		MessageNonce::MAX
 	}
 
+	fn congested_outbound_lane_threshold() -> MessageNonce {
+		// the lane is congested if there are more queued messages than the bridged chain may keep
+		// unconfirmed
+		bp_rialto::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE
+	}
+
 	fn estimate_delivery_confirmation_transaction() -> MessageTransaction<Weight> {
 		let inbound_data_size = InboundLaneData::<bp_millau::AccountId>::encoded_size_hint(
 			bp_millau::MAXIMAL_ENCODED_ACCOUNT_ID_SIZE,
@@ -267,12 +280,27 @@ impl SourceHeaderChain<bp_rialto::Balance> for Rialto {
 	) -> Result<ProvedMessages<Message<bp_rialto::Balance>>, Self::Error> {
 		messages::target::verify_messages_proof::<
 			WithRialtoMessageBridge,
-			Runtime,
-			crate::RialtoGrandpaInstance,
+			pallet_bridge_grandpa::Pallet<Runtime, crate::RialtoGrandpaInstance>,
+			bp_rialto::Header,
 		>(proof, messages_count)
 	}
 }
 
+/// Recognizes message delivery transactions from Rialto to Millau.
+#[derive(RuntimeDebug, Clone, PartialEq, Eq)]
+pub struct RialtoMessagesDeliveryCallInfo;
+
+impl pallet_bridge_relayers::DeliveryCallInfo<crate::Call> for RialtoMessagesDeliveryCallInfo {
+	fn delivery_lane(call: &crate::Call) -> Option<LaneId> {
+		match *call {
+			crate::Call::BridgeRialtoMessages(
+				pallet_bridge_messages::Call::receive_messages_proof(_, ref proof, _, _),
+			) => Some(proof.lane),
+			_ => None,
+		}
+	}
+}
+
 /// Millau -> Rialto message lane pallet parameters.
 #[derive(RuntimeDebug, Clone, Encode, Decode, PartialEq, Eq)]
 pub enum MillauToRialtoMessagesParameter {
@@ -287,6 +287,7 @@ fn testnet_genesis(
 		paras: Default::default(),
 		bridge_millau_messages: BridgeMillauMessagesConfig {
 			owner: Some(get_account_id_from_seed::<sr25519::Public>("MillauMessagesOwner")),
+			opened_lanes: vec![[0, 0, 0, 0], [0, 0, 0, 1], *b"swap"],
 			..Default::default()
 		},
 	}
@@ -41,9 +41,6 @@ pub mod rialto_poa;
 
 use crate::millau_messages::{ToMillauMessagePayload, WithMillauMessageBridge};
 
-use bridge_runtime_common::messages::{
-	source::estimate_message_dispatch_and_delivery_fee, MessageBridge,
-};
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
@@ -54,7 +51,7 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{AccountIdLookup, Block as BlockT, NumberFor, OpaqueKeys},
-	transaction_validity::{TransactionSource, TransactionValidity},
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, FixedPointNumber, MultiSignature, MultiSigner, Perquintill,
 };
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
@@ -301,6 +298,7 @@ impl pallet_bridge_dispatch::Config for Runtime {
 	type Call = Call;
 	type CallFilter = frame_support::traits::Everything;
 	type EncodedCall = crate::millau_messages::FromMillauEncodedCall;
+	type CompatibleSpecVersions = frame_support::traits::Nothing;
 	type SourceChainAccountId = bp_millau::AccountId;
 	type TargetChainAccountPublic = MultiSigner;
 	type TargetChainSignature = MultiSignature;
@@ -495,13 +493,19 @@ parameter_types! {
 	/// Assuming the worst case of every header being finalized, we will keep headers at least for a
 	/// week.
 	pub const HeadersToKeep: u32 = 7 * bp_rialto::DAYS as u32;
+
+	/// Forced changes still need to be approved by the pallet owner (or root) before they're
+	/// accepted.
+	pub const AllowForcedChanges: bool = true;
 }
 
 pub type MillauGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_millau::Millau;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
+	type AllowForcedChanges = AllowForcedChanges;
 	type WeightInfo = pallet_bridge_grandpa::weights::RialtoWeight<Runtime>;
 }
 
@@ -509,17 +513,48 @@ impl pallet_shift_session_manager::Config for Runtime {}
 
 parameter_types! {
 	pub const MaxMessagesToPruneAtOnce: bp_messages::MessageNonce = 8;
+	pub const MaxDispatchStatusesToKeep: bp_messages::MessageNonce = 1024;
 	pub const MaxUnrewardedRelayerEntriesAtInboundLane: bp_messages::MessageNonce =
 		bp_rialto::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE;
 	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
 		bp_rialto::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE;
+	// messages that are heavier than that are put into the overweight queue
+	pub MaxInlineDispatchWeight: Weight = bp_rialto::max_inline_dispatch_weight();
+	pub const MaxAllowedSenders: u32 = 16;
 	// `IdentityFee` is used by Rialto => we may use weight directly
 	pub const GetDeliveryConfirmationTransactionFee: Balance =
 		bp_rialto::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
 	pub const RootAccountForPayments: Option<AccountId> = None;
   pub const BridgedChainId: bp_runtime::ChainId = bp_runtime::MILLAU_CHAIN_ID;
+	pub RelayersFundAccount: AccountId =
+		pallet_bridge_messages::relayer_fund_account_id::<AccountId, bp_rialto::AccountIdConverter>();
+	pub const RelayerStake: Balance = 1_000_000;
+	pub const DeliverySlotLength: BlockNumber = bp_rialto::DELIVERY_SLOT_LENGTH;
+	pub const MaxRelayersPerLane: u32 = 16;
+	pub const DeliverySlotPriorityBoost: TransactionPriority = TransactionPriority::max_value() / 2;
+}
+
+impl pallet_bridge_relayers::Config for Runtime {
+	type Event = Event;
+	type Reward = Balance;
+	type PaymentProcedure = pallet_bridge_relayers::PayLaneRewardFromAccount<
+		pallet_balances::Pallet<Runtime>,
+		RelayersFundAccount,
+	>;
+	type StakeCurrency = pallet_balances::Pallet<Runtime>;
+	type RequiredStake = RelayerStake;
+	type SlotLength = DeliverySlotLength;
+	type MaxRelayersPerLane = MaxRelayersPerLane;
+	type WeightInfo = pallet_bridge_relayers::weights::MillauWeight<Runtime>;
 }
 
+/// Signed extension that prioritizes message delivery transactions of the slot relayer.
+pub type PrioritizeSlotRelayer = pallet_bridge_relayers::PrioritizeSlotRelayer<
+	Runtime,
+	millau_messages::MillauMessagesDeliveryCallInfo,
+	DeliverySlotPriorityBoost,
+>;
+
 /// Instance of the messages pallet used to relay messages to/from Millau chain.
 pub type WithMillauMessagesInstance = ();
 
@@ -528,8 +563,11 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	type WeightInfo = pallet_bridge_messages::weights::RialtoWeight<Runtime>;
 	type Parameter = millau_messages::RialtoToMillauMessagesParameter;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxDispatchStatusesToKeep = MaxDispatchStatusesToKeep;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxInlineDispatchWeight = MaxInlineDispatchWeight;
+	type MaxAllowedSenders = MaxAllowedSenders;
 
 	type OutboundPayload = crate::millau_messages::ToMillauMessagePayload;
 	type OutboundMessageFee = Balance;
@@ -543,12 +581,13 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	type TargetHeaderChain = crate::millau_messages::Millau;
 	type LaneMessageVerifier = crate::millau_messages::ToMillauMessageVerifier;
 	type MessageDeliveryAndDispatchPayment =
-		pallet_bridge_messages::instant_payments::InstantCurrencyPayments<
+		pallet_bridge_relayers::MessageDeliveryAndDispatchPaymentAdapter<
 			Runtime,
-			(),
+			WithMillauMessagesInstance,
 			pallet_balances::Pallet<Runtime>,
 			GetDeliveryConfirmationTransactionFee,
 			RootAccountForPayments,
+			crate::millau_messages::ToMillauUnspentDispatchFeeRefund,
 		>;
 	type OnMessageAccepted = ();
 	type OnDeliveryConfirmed = ();
@@ -588,8 +627,9 @@ construct_runtime!(
 		BridgeKovanCurrencyExchange: pallet_bridge_currency_exchange::<Instance2>::{Pallet, Call},
 
 		// Millau bridge modules.
-		BridgeMillauGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
-		BridgeDispatch: pallet_bridge_dispatch::{Pallet, Event<T>},
+		BridgeMillauGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
+		BridgeDispatch: pallet_bridge_dispatch::{Pallet, Storage, Event<T>},
+		BridgeRelayers: pallet_bridge_relayers::{Pallet, Call, Storage, Event<T>},
 		BridgeMillauMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, Config<T>},
 
 		// Parachain modules.
@@ -632,6 +672,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	PrioritizeSlotRelayer,
 );
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -745,6 +786,16 @@ impl_runtime_apis! {
 		fn is_known_header(hash: bp_millau::Hash) -> bool {
 			BridgeMillauGrandpa::is_known_header(hash)
 		}
+
+		fn authority_set(
+			set_id: bp_header_chain::SetId,
+		) -> Option<bp_header_chain::AuthoritySet> {
+			BridgeMillauGrandpa::authority_set(set_id)
+		}
+
+		fn header_set_id(hash: bp_millau::Hash) -> Option<bp_header_chain::SetId> {
+			BridgeMillauGrandpa::header_set_id(hash)
+		}
 	}
 
 	impl bp_currency_exchange::RialtoCurrencyExchangeApi<Block, exchange::EthereumTransactionInclusionProof> for Runtime {
@@ -974,13 +1025,14 @@ impl_runtime_apis! {
 
 	impl bp_millau::ToMillauOutboundLaneApi<Block, Balance, ToMillauMessagePayload> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
-			_lane_id: bp_messages::LaneId,
+			lane_id: bp_messages::LaneId,
 			payload: ToMillauMessagePayload,
 		) -> Option<Balance> {
-			estimate_message_dispatch_and_delivery_fee::<WithMillauMessageBridge>(
-				&payload,
-				WithMillauMessageBridge::RELAYER_FEE_PERCENT,
-			).ok()
+			bridge_runtime_common::messages_api::estimate_outbound_message_fee::<
+				Runtime,
+				WithMillauMessagesInstance,
+				WithMillauMessageBridge,
+			>(lane_id, &payload)
 		}
 
 		fn message_details(
@@ -1002,6 +1054,17 @@ impl_runtime_apis! {
 		fn latest_generated_nonce(lane: bp_messages::LaneId) -> bp_messages::MessageNonce {
 			BridgeMillauMessages::outbound_latest_generated_nonce(lane)
 		}
+
+		fn message_dispatch_status(
+			lane: bp_messages::LaneId,
+			nonce: bp_messages::MessageNonce,
+		) -> Option<bp_messages::MessageDispatchStatus> {
+			BridgeMillauMessages::outbound_message_dispatch_status(lane, nonce)
+		}
+
+		fn lanes() -> Vec<(bp_messages::LaneId, bp_messages::LaneInfo)> {
+			BridgeMillauMessages::lanes()
+		}
 	}
 
 	impl bp_millau::FromMillauInboundLaneApi<Block> for Runtime {
@@ -1040,6 +1103,7 @@ impl_runtime_apis! {
 			);
 			list_benchmark!(list, extra, pallet_bridge_messages, MessagesBench::<Runtime, WithMillauMessagesInstance>);
 			list_benchmark!(list, extra, pallet_bridge_grandpa, BridgeMillauGrandpa);
+			list_benchmark!(list, extra, pallet_bridge_relayers, BridgeRelayers);
 
 			let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -1153,6 +1217,7 @@ impl_runtime_apis! {
 						origin: dispatch_origin,
 						call: message_payload,
 						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						expiry: None,
 					};
 					(message, pallet_bridge_messages::benchmarking::MESSAGE_FEE.into())
 				}
@@ -1185,6 +1250,7 @@ impl_runtime_apis! {
 						VERSION.spec_version,
 						bp_runtime::MILLAU_CHAIN_ID,
 						bp_runtime::RIALTO_CHAIN_ID,
+						0,
 					);
 					let rialto_public = MultiSigner::Ed25519(sp_core::ed25519::Public::from_raw(rialto_raw_public));
 					let rialto_signature = MultiSignature::Ed25519(sp_core::ed25519::Signature::from_raw(
@@ -1232,6 +1298,7 @@ impl_runtime_apis! {
 								rialto_signature,
 							),
 							dispatch_fee_payment,
+							expiry: None,
 							call: call.encode(),
 						}.encode(),
 					)
@@ -1273,6 +1340,15 @@ impl_runtime_apis! {
 				}
 			}
 
+			impl pallet_bridge_relayers::benchmarking::Config for Runtime {
+				fn prepare_environment(_relayer: &AccountId, reward: Balance) {
+					pallet_balances::Pallet::<Runtime>::make_free_balance_be(
+						&RelayersFundAccount::get(),
+						reward.saturating_add(ExistentialDeposit::get()),
+					);
+				}
+			}
+
 			add_benchmark!(params, batches, pallet_bridge_eth_poa, BridgeRialtoPoa);
 			add_benchmark!(
 				params,
@@ -1287,6 +1363,7 @@ impl_runtime_apis! {
 				MessagesBench::<Runtime, WithMillauMessagesInstance>
 			);
 			add_benchmark!(params, batches, pallet_bridge_grandpa, BridgeMillauGrandpa);
+			add_benchmark!(params, batches, pallet_bridge_relayers, BridgeRelayers);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
@@ -1298,11 +1375,13 @@ impl_runtime_apis! {
 ///
 /// The byte vector returned by this function should be signed with a Millau account private key.
 /// This way, the owner of `rialto_account_id` on Rialto proves that the 'millau' account private
-/// key is also under his control.
+/// key is also under his control. The `millau_account_nonce` must match the current nonce of the
+/// Millau account, stored by the Millau dispatch pallet.
 pub fn rialto_to_millau_account_ownership_digest<Call, AccountId, SpecVersion>(
 	millau_call: &Call,
 	rialto_account_id: AccountId,
 	millau_spec_version: SpecVersion,
+	millau_account_nonce: bp_message_dispatch::TargetAccountNonce,
 ) -> sp_std::vec::Vec<u8>
 where
 	Call: codec::Encode,
@@ -1315,6 +1394,7 @@ where
 		millau_spec_version,
 		bp_runtime::RIALTO_CHAIN_ID,
 		bp_runtime::MILLAU_CHAIN_ID,
+		millau_account_nonce,
 	)
 }
 
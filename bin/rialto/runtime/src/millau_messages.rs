@@ -52,8 +52,15 @@ pub type ToMillauMessagePayload =
 	messages::source::FromThisChainMessagePayload<WithMillauMessageBridge>;
 
 /// Message verifier for Rialto -> Millau messages.
-pub type ToMillauMessageVerifier =
-	messages::source::FromThisChainMessageVerifier<WithMillauMessageBridge>;
+pub type ToMillauMessageVerifier = messages::source::FromThisChainAllowedSendersMessageVerifier<
+	WithMillauMessageBridge,
+	crate::Runtime,
+	crate::WithMillauMessagesInstance,
+>;
+
+/// Unspent dispatch fee refund for Rialto -> Millau messages.
+pub type ToMillauUnspentDispatchFeeRefund =
+	messages::source::FromThisChainUnspentDispatchFeeRefund<WithMillauMessageBridge>;
 
 /// Message payload for Millau -> Rialto messages.
 pub type FromMillauMessagePayload =
@@ -122,6 +129,12 @@ impl messages::ThisChainWithMessages for Rialto {
 		MessageNonce::MAX
 	}
 
+	fn congested_outbound_lane_threshold() -> MessageNonce {
+		// the lane is congested if there are more queued messages than the bridged chain may keep
+		// unconfirmed
+		bp_millau::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE
+	}
+
 	fn estimate_delivery_confirmation_transaction() -> MessageTransaction<Weight> {
 		let inbound_data_size = InboundLaneData::<bp_rialto::AccountId>::encoded_size_hint(
 			bp_rialto::MAXIMAL_ENCODED_ACCOUNT_ID_SIZE,
@@ -265,12 +278,27 @@ impl SourceHeaderChain<bp_millau::Balance> for Millau {
 	) -> Result<ProvedMessages<Message<bp_millau::Balance>>, Self::Error> {
 		messages::target::verify_messages_proof::<
 			WithMillauMessageBridge,
-			Runtime,
-			crate::MillauGrandpaInstance,
+			pallet_bridge_grandpa::Pallet<Runtime, crate::MillauGrandpaInstance>,
+			bp_millau::Header,
 		>(proof, messages_count)
 	}
 }
 
+/// Recognizes message delivery transactions from Millau to Rialto.
+#[derive(RuntimeDebug, Clone, PartialEq, Eq)]
+pub struct MillauMessagesDeliveryCallInfo;
+
+impl pallet_bridge_relayers::DeliveryCallInfo<crate::Call> for MillauMessagesDeliveryCallInfo {
+	fn delivery_lane(call: &crate::Call) -> Option<LaneId> {
+		match *call {
+			crate::Call::BridgeMillauMessages(
+				pallet_bridge_messages::Call::receive_messages_proof(_, ref proof, _, _),
+			) => Some(proof.lane),
+			_ => None,
+		}
+	}
+}
+
 /// Rialto -> Millau message lane pallet parameters.
 #[derive(RuntimeDebug, Clone, Encode, Decode, PartialEq, Eq)]
 pub enum RialtoToMillauMessagesParameter {
@@ -290,15 +318,20 @@ impl MessagesParameter for RialtoToMillauMessagesParameter {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::{AccountId, Call, ExistentialDeposit, Runtime, SystemCall, SystemConfig, VERSION};
+	use crate::{
+		AccountId, BridgeMillauMessages, Call, ExistentialDeposit, Origin, Runtime, SystemCall,
+		SystemConfig, VERSION,
+	};
 	use bp_message_dispatch::CallOrigin;
 	use bp_messages::{
+		source_chain::{LaneMessageVerifier, Sender},
 		target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
-		MessageKey,
+		MessageKey, OutboundLaneData,
 	};
 	use bp_runtime::{derive_account_id, messages::DispatchFeePayment, SourceAccount};
-	use bridge_runtime_common::messages::target::{
-		FromBridgedChainEncodedMessageCall, FromBridgedChainMessagePayload,
+	use bridge_runtime_common::messages::{
+		source::SENDER_IS_NOT_ALLOWED,
+		target::{FromBridgedChainEncodedMessageCall, FromBridgedChainMessagePayload},
 	};
 	use frame_support::{
 		traits::Currency,
@@ -306,6 +339,72 @@ mod tests {
 	};
 	use sp_runtime::traits::Convert;
 
+	const ALLOWED_SENDER: [u8; 32] = [1u8; 32];
+	const OTHER_SENDER: [u8; 32] = [2u8; 32];
+
+	fn verify_message_sent_by(sender: [u8; 32]) -> Result<(), &'static str> {
+		let sender: AccountId = sender.into();
+		ToMillauMessageVerifier::verify_message(
+			&Sender::Signed(sender.clone()),
+			&bp_rialto::Balance::MAX,
+			&[0, 0, 0, 0],
+			&OutboundLaneData::default(),
+			&ToMillauMessagePayload {
+				spec_version: 1,
+				weight: 0,
+				origin: CallOrigin::SourceAccount(sender),
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				call: vec![],
+				expiry: None,
+			},
+		)
+	}
+
+	fn set_lane_allowed_senders(allowed_senders: Option<Vec<[u8; 32]>>) {
+		BridgeMillauMessages::set_lane_allowed_senders(
+			Origin::root(),
+			[0, 0, 0, 0],
+			allowed_senders.map(|senders| {
+				senders.into_iter().map(|sender| Sender::Signed(sender.into())).collect()
+			}),
+		)
+		.unwrap();
+	}
+
+	#[test]
+	fn message_verifier_accepts_message_from_allowed_sender() {
+		sp_io::TestExternalities::new(Default::default()).execute_with(|| {
+			set_lane_allowed_senders(Some(vec![ALLOWED_SENDER]));
+			assert_eq!(verify_message_sent_by(ALLOWED_SENDER), Ok(()));
+		});
+	}
+
+	#[test]
+	fn message_verifier_rejects_message_from_not_allowed_sender() {
+		sp_io::TestExternalities::new(Default::default()).execute_with(|| {
+			set_lane_allowed_senders(Some(vec![ALLOWED_SENDER]));
+			assert_eq!(verify_message_sent_by(OTHER_SENDER), Err(SENDER_IS_NOT_ALLOWED));
+		});
+	}
+
+	#[test]
+	fn message_verifier_rejects_all_messages_if_allowed_senders_list_is_empty() {
+		sp_io::TestExternalities::new(Default::default()).execute_with(|| {
+			set_lane_allowed_senders(Some(vec![]));
+			assert_eq!(verify_message_sent_by(ALLOWED_SENDER), Err(SENDER_IS_NOT_ALLOWED));
+			assert_eq!(verify_message_sent_by(OTHER_SENDER), Err(SENDER_IS_NOT_ALLOWED));
+		});
+	}
+
+	#[test]
+	fn message_verifier_accepts_all_messages_if_lane_is_not_restricted() {
+		sp_io::TestExternalities::new(Default::default()).execute_with(|| {
+			set_lane_allowed_senders(None);
+			assert_eq!(verify_message_sent_by(ALLOWED_SENDER), Ok(()));
+			assert_eq!(verify_message_sent_by(OTHER_SENDER), Ok(()));
+		});
+	}
+
 	#[test]
 	fn transfer_happens_when_dispatch_fee_is_paid_at_target_chain() {
 		// this test actually belongs to the `bridge-runtime-common` crate, but there we have no
@@ -354,6 +453,7 @@ mod tests {
 							weight: dispatch_weight,
 							origin: CallOrigin::SourceRoot,
 							dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+							expiry: None,
 							call: FromBridgedChainEncodedMessageCall::new(call.encode()),
 						}),
 						fee: 1,
@@ -20,12 +20,14 @@
 //! pallet is used to dispatch incoming messages. Message identified by a tuple
 //! of to elements - message lane id and message nonce.
 
+use bp_header_chain::HeaderChain;
 use bp_message_dispatch::MessageDispatch as _;
 use bp_messages::{
-	source_chain::{LaneMessageVerifier, Sender},
+	source_chain::{LaneMessageVerifier, Sender, UnspentDispatchFeeRefund},
 	target_chain::{DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages},
 	InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce, OutboundLaneData,
 };
+use bp_polkadot_core::parachains::{ParaHash, ParaId};
 use bp_runtime::{
 	messages::{DispatchFeePayment, MessageDispatchResult},
 	ChainId, Size, StorageProofChecker,
@@ -38,7 +40,10 @@ use frame_support::{
 };
 use hash_db::Hasher;
 use sp_runtime::{
-	traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedDiv, CheckedMul, Saturating, Zero},
+	traits::{
+		AtLeast32BitUnsigned, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Header as HeaderT,
+		Saturating, UniqueSaturatedInto, Zero,
+	},
 	FixedPointNumber, FixedPointOperand, FixedU128,
 };
 use sp_std::{
@@ -93,6 +98,7 @@ pub trait ChainWithMessages {
 		+ CheckedAdd
 		+ CheckedDiv
 		+ CheckedMul
+		+ CheckedSub
 		+ PartialOrd
 		+ From<u32>
 		+ Copy;
@@ -120,6 +126,12 @@ pub trait ThisChainWithMessages: ChainWithMessages {
 	/// Any messages over this limit, will be rejected.
 	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce;
 
+	/// Number of pending (not yet delivered) messages at the outbound lane, after which the lane
+	/// is considered congested and the message fee starts growing.
+	///
+	/// Zero means that the message fee never depends on the number of pending messages.
+	fn congested_outbound_lane_threshold() -> MessageNonce;
+
 	/// Estimate size and weight of single message delivery confirmation transaction at This chain.
 	fn estimate_delivery_confirmation_transaction() -> MessageTransaction<WeightOf<Self>>;
 
@@ -255,7 +267,8 @@ pub mod source {
 	///   lane;
 	/// - check that the sender has rights to dispatch the call on target chain using provided
 	///   dispatch origin;
-	/// - check that the sender has paid enough funds for both message delivery and dispatch.
+	/// - check that the sender has paid enough funds for both message delivery and dispatch
+	///   (including the congestion surcharge of the lane).
 	#[derive(RuntimeDebug)]
 	pub struct FromThisChainMessageVerifier<B>(PhantomData<B>);
 
@@ -304,8 +317,11 @@ pub mod source {
 			pallet_bridge_dispatch::verify_message_origin(submitter, payload)
 				.map_err(|_| BAD_ORIGIN)?;
 
-			let minimal_fee_in_this_tokens =
-				estimate_message_dispatch_and_delivery_fee::<B>(payload, B::RELAYER_FEE_PERCENT)?;
+			let minimal_fee_in_this_tokens = estimate_message_dispatch_and_delivery_fee::<B>(
+				payload,
+				B::RELAYER_FEE_PERCENT,
+				pending_messages,
+			)?;
 
 			// compare with actual fee paid
 			if *delivery_and_dispatch_fee < minimal_fee_in_this_tokens {
@@ -316,6 +332,103 @@ pub mod source {
 		}
 	}
 
+	/// Message verifier that is additionally checking that the message sender is allowed to send
+	/// messages over the lane.
+	///
+	/// Allowed senders are configured per lane by the owner of the messages pallet (see
+	/// `pallet_bridge_messages::Pallet::set_lane_allowed_senders`). Lanes without configured
+	/// senders are not restricted. All other checks are the same as in the
+	/// `FromThisChainMessageVerifier`.
+	#[derive(RuntimeDebug)]
+	pub struct FromThisChainAllowedSendersMessageVerifier<B, ThisRuntime, MessagesInstance>(
+		PhantomData<(B, ThisRuntime, MessagesInstance)>,
+	);
+
+	/// Error that is returned when the message sender is not allowed to use the lane.
+	pub const SENDER_IS_NOT_ALLOWED: &str =
+		"The sender is not allowed to send messages over the lane.";
+
+	impl<B, ThisRuntime, MessagesInstance>
+		LaneMessageVerifier<
+			AccountIdOf<ThisChain<B>>,
+			FromThisChainMessagePayload<B>,
+			BalanceOf<ThisChain<B>>,
+		> for FromThisChainAllowedSendersMessageVerifier<B, ThisRuntime, MessagesInstance>
+	where
+		B: MessageBridge,
+		AccountIdOf<ThisChain<B>>: PartialEq + Clone,
+		ThisRuntime: pallet_bridge_messages::Config<MessagesInstance>
+			+ frame_system::Config<AccountId = AccountIdOf<ThisChain<B>>>,
+		MessagesInstance: 'static,
+	{
+		type Error = &'static str;
+
+		fn verify_message(
+			submitter: &Sender<AccountIdOf<ThisChain<B>>>,
+			delivery_and_dispatch_fee: &BalanceOf<ThisChain<B>>,
+			lane: &LaneId,
+			lane_outbound_data: &OutboundLaneData,
+			payload: &FromThisChainMessagePayload<B>,
+		) -> Result<(), Self::Error> {
+			// reject message if its sender is not allowed to use the lane
+			let is_sender_allowed = pallet_bridge_messages::Pallet::<
+				ThisRuntime,
+				MessagesInstance,
+			>::is_outbound_sender_allowed(lane, submitter);
+			if !is_sender_allowed {
+				return Err(SENDER_IS_NOT_ALLOWED)
+			}
+
+			FromThisChainMessageVerifier::<B>::verify_message(
+				submitter,
+				delivery_and_dispatch_fee,
+				lane,
+				lane_outbound_data,
+				payload,
+			)
+		}
+	}
+
+	/// Refund of the unspent dispatch fee of This -> Bridged chain messages.
+	///
+	/// Only messages with `DispatchFeePayment::AtSourceChain` are refunded, because otherwise
+	/// dispatch fee is paid (and refunded) at the Bridged chain. The refund is the difference
+	/// between the Bridged chain fee of declared and actually spent dispatch weight, converted
+	/// to This chain tokens. Relayer interest is never refunded.
+	#[derive(RuntimeDebug)]
+	pub struct FromThisChainUnspentDispatchFeeRefund<B>(PhantomData<B>);
+
+	impl<B: MessageBridge>
+		UnspentDispatchFeeRefund<FromThisChainMessagePayload<B>, BalanceOf<ThisChain<B>>>
+		for FromThisChainUnspentDispatchFeeRefund<B>
+	{
+		fn unspent_dispatch_fee(
+			payload: &FromThisChainMessagePayload<B>,
+			unspent_weight: Weight,
+		) -> BalanceOf<ThisChain<B>> {
+			if payload.dispatch_fee_payment != DispatchFeePayment::AtSourceChain {
+				return 0u32.into()
+			}
+
+			// dispatcher may never spend more than declared weight, so we may never refund more
+			let unspent_weight = sp_std::cmp::min(unspent_weight, payload.weight);
+			let fee_with_unspent_weight =
+				BridgedChain::<B>::transaction_payment(MessageTransaction {
+					dispatch_weight: unspent_weight.into(),
+					size: 0,
+				});
+			let fee_without_unspent_weight =
+				BridgedChain::<B>::transaction_payment(MessageTransaction {
+					dispatch_weight: 0.into(),
+					size: 0,
+				});
+			fee_with_unspent_weight
+				.checked_sub(&fee_without_unspent_weight)
+				.map(B::bridged_balance_to_this_balance)
+				.unwrap_or_else(|| 0u32.into())
+		}
+	}
+
 	/// Return maximal message size of This -> Bridged chain message.
 	pub fn maximal_message_size<B: MessageBridge>() -> u32 {
 		super::target::maximal_incoming_message_size(BridgedChain::<B>::maximal_extrinsic_size())
@@ -359,6 +472,7 @@ pub mod source {
 	pub fn estimate_message_dispatch_and_delivery_fee<B: MessageBridge>(
 		payload: &FromThisChainMessagePayload<B>,
 		relayer_fee_percent: u32,
+		pending_messages: MessageNonce,
 	) -> Result<BalanceOf<ThisChain<B>>, &'static str> {
 		// the fee (in Bridged tokens) of all transactions that are made on the Bridged chain
 		//
@@ -382,19 +496,45 @@ pub mod source {
 		let minimal_fee = B::bridged_balance_to_this_balance(delivery_transaction_fee)
 			.checked_add(&confirmation_transaction_fee);
 
-		// before returning, add extra fee that is paid to the relayer (relayer interest)
+		// add extra fee that is paid to the relayer (relayer interest) and then, before returning,
+		// add congestion surcharge of the lane
+		let congestion_fee_percent = congestion_fee_percent::<B>(pending_messages);
 		minimal_fee
-			.and_then(|fee|
-			// having message with fee that is near the `Balance::MAX_VALUE` of the chain is
-			// unlikely and should be treated as an error
-			// => let's do multiplication first
-			fee
-				.checked_mul(&relayer_fee_percent.into())
-				.and_then(|interest| interest.checked_div(&100u32.into()))
-				.and_then(|interest| fee.checked_add(&interest)))
+			.and_then(|fee| add_percent(fee, relayer_fee_percent))
+			.and_then(|fee| add_percent(fee, congestion_fee_percent))
 			.ok_or("Overflow when computing minimal required message delivery and dispatch fee")
 	}
 
+	/// Returns congestion surcharge (in percents of the message fee) of the outbound lane with
+	/// given number of pending messages.
+	///
+	/// There's no surcharge until number of pending messages reaches the
+	/// `ThisChainWithMessages::congested_outbound_lane_threshold()`. After that, every next
+	/// `threshold` pending messages are adding 100% of the base fee. Once messages are delivered
+	/// and the queue drains, the surcharge decays the same way.
+	pub fn congestion_fee_percent<B: MessageBridge>(pending_messages: MessageNonce) -> u32 {
+		let threshold = ThisChain::<B>::congested_outbound_lane_threshold();
+		if threshold == 0 || pending_messages <= threshold {
+			return 0
+		}
+
+		let surcharge = (pending_messages - threshold).saturating_mul(100) / threshold;
+		u32::try_from(surcharge).unwrap_or(u32::MAX)
+	}
+
+	/// Add given percent of the fee to the fee.
+	fn add_percent<Balance>(fee: Balance, percent: u32) -> Option<Balance>
+	where
+		Balance: CheckedAdd + CheckedDiv + CheckedMul + From<u32>,
+	{
+		// having message with fee that is near the `Balance::MAX_VALUE` of the chain is
+		// unlikely and should be treated as an error
+		// => let's do multiplication first
+		fee.checked_mul(&percent.into())
+			.and_then(|extra| extra.checked_div(&100u32.into()))
+			.and_then(|extra| fee.checked_add(&extra))
+	}
+
 	/// Verify proof of This -> Bridged chain messages delivery.
 	pub fn verify_messages_delivery_proof<B: MessageBridge, ThisRuntime, GrandpaInstance: 'static>(
 		proof: FromBridgedChainMessagesDeliveryProof<HashOf<BridgedChain<B>>>,
@@ -542,6 +682,30 @@ pub mod target {
 			message.data.payload.as_ref().map(|payload| payload.weight).unwrap_or(0)
 		}
 
+		fn is_expired(
+			message: &DispatchMessage<Self::DispatchPayload, BalanceOf<BridgedChain<B>>>,
+		) -> bool {
+			let expiry = match message.data.payload.as_ref().map(|payload| payload.expiry) {
+				Ok(Some(expiry)) => expiry,
+				_ => return false,
+			};
+
+			let block_number: bp_message_dispatch::ExpiryBlockNumber =
+				frame_system::Pallet::<ThisRuntime>::block_number().unique_saturated_into();
+			block_number > expiry
+		}
+
+		fn dispatch_fee_payment(
+			message: &DispatchMessage<Self::DispatchPayload, BalanceOf<BridgedChain<B>>>,
+		) -> DispatchFeePayment {
+			message
+				.data
+				.payload
+				.as_ref()
+				.map(|payload| payload.dispatch_fee_payment)
+				.unwrap_or(DispatchFeePayment::AtSourceChain)
+		}
+
 		fn dispatch(
 			relayer_account: &AccountIdOf<ThisChain<B>>,
 			message: DispatchMessage<Self::DispatchPayload, BalanceOf<BridgedChain<B>>>,
@@ -586,26 +750,76 @@ pub mod target {
 
 	/// Verify proof of Bridged -> This chain messages.
 	///
+	/// Headers of the Bridged chain must be tracked by some finality pallet (GRANDPA, BEEFY, ...)
+	/// instance, deployed at This chain. Messages proof is verified against finalized header,
+	/// known to this `BridgedHeaderChain`.
+	///
 	/// The `messages_count` argument verification (sane limits) is supposed to be made
 	/// outside of this function. This function only verifies that the proof declares exactly
 	/// `messages_count` messages.
-	pub fn verify_messages_proof<B: MessageBridge, ThisRuntime, GrandpaInstance: 'static>(
+	pub fn verify_messages_proof<B: MessageBridge, BridgedHeaderChain, BridgedHeader>(
 		proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
 		messages_count: u32,
 	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, &'static str>
 	where
-		ThisRuntime: pallet_bridge_grandpa::Config<GrandpaInstance>,
-		HashOf<BridgedChain<B>>: Into<
-			bp_runtime::HashOf<
-				<ThisRuntime as pallet_bridge_grandpa::Config<GrandpaInstance>>::BridgedChain,
-			>,
-		>,
+		BridgedHeaderChain: HeaderChain<BridgedHeader>,
+		BridgedHeader: HeaderT,
+		HashOf<BridgedChain<B>>: Into<BridgedHeader::Hash>,
 	{
 		verify_messages_proof_with_parser::<B, _, _>(
 			proof,
 			messages_count,
 			|bridged_header_hash, bridged_storage_proof| {
-				pallet_bridge_grandpa::Pallet::<ThisRuntime, GrandpaInstance>::parse_finalized_storage_proof(
+				let bridged_header =
+					BridgedHeaderChain::finalized_header(bridged_header_hash.into())
+						.ok_or(MessageProofError::Custom("Unknown bridged header"))?;
+				StorageProofChecker::<BridgedHeader::Hashing>::new(
+					*bridged_header.state_root(),
+					StorageProof::new(bridged_storage_proof),
+				)
+				.map(|storage| StorageProofCheckerAdapter::<_, B> {
+					storage,
+					_dummy: Default::default(),
+				})
+				.map_err(|_| MessageProofError::Custom("Storage root mismatch"))
+			},
+		)
+		.map_err(Into::into)
+	}
+
+	/// Verify proof of Bridged -> This chain messages, where Bridged chain is a parachain.
+	///
+	/// Heads of the Bridged parachain must be tracked by the bridge parachains pallet instance,
+	/// deployed at This chain. Messages proof is verified against finalized parachain head, known
+	/// to this pallet instance.
+	///
+	/// The `messages_count` argument verification (sane limits) is supposed to be made
+	/// outside of this function. This function only verifies that the proof declares exactly
+	/// `messages_count` messages.
+	pub fn verify_messages_proof_from_parachain<
+		B: MessageBridge,
+		ThisRuntime,
+		ParachainsInstance: 'static,
+		BridgedParachainHeader,
+	>(
+		bridged_parachain: ParaId,
+		proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
+		messages_count: u32,
+	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, &'static str>
+	where
+		ThisRuntime: pallet_bridge_parachains::Config<ParachainsInstance>,
+		HashOf<BridgedChain<B>>: Into<ParaHash>,
+		BridgedParachainHeader: sp_runtime::traits::Header<Hash = ParaHash>,
+	{
+		verify_messages_proof_with_parser::<B, _, _>(
+			proof,
+			messages_count,
+			|bridged_header_hash, bridged_storage_proof| {
+				pallet_bridge_parachains::Pallet::<ThisRuntime, ParachainsInstance>::parse_finalized_storage_proof::<
+					BridgedParachainHeader,
+					_,
+				>(
+					bridged_parachain,
 					bridged_header_hash.into(),
 					StorageProof::new(bridged_storage_proof),
 					|storage_adapter| storage_adapter,
@@ -892,6 +1106,20 @@ mod tests {
 					self.0.checked_mul(other.0).map(Self)
 				}
 			}
+
+			impl sp_std::ops::Sub for $name {
+				type Output = $name;
+
+				fn sub(self, other: Self) -> Self {
+					Self(self.0 - other.0)
+				}
+			}
+
+			impl CheckedSub for $name {
+				fn checked_sub(&self, other: &Self) -> Option<Self> {
+					self.0.checked_sub(other.0).map(Self)
+				}
+			}
 		};
 	}
 
@@ -920,6 +1148,10 @@ mod tests {
 			MAXIMAL_PENDING_MESSAGES_AT_TEST_LANE
 		}
 
+		fn congested_outbound_lane_threshold() -> MessageNonce {
+			CONGESTED_TEST_LANE_THRESHOLD
+		}
+
 		fn estimate_delivery_confirmation_transaction() -> MessageTransaction<WeightOf<Self>> {
 			MessageTransaction {
 				dispatch_weight: DELIVERY_CONFIRMATION_TRANSACTION_WEIGHT,
@@ -980,6 +1212,10 @@ mod tests {
 			unreachable!()
 		}
 
+		fn congested_outbound_lane_threshold() -> MessageNonce {
+			unreachable!()
+		}
+
 		fn estimate_delivery_confirmation_transaction() -> MessageTransaction<WeightOf<Self>> {
 			unreachable!()
 		}
@@ -1033,6 +1269,7 @@ mod tests {
 				weight: 100,
 				origin: bp_message_dispatch::CallOrigin::SourceRoot,
 				dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+				expiry: None,
 				call: ThisChainCall::Transfer.encode(),
 			}
 			.encode();
@@ -1050,6 +1287,7 @@ mod tests {
 				weight: 100,
 				origin: bp_message_dispatch::CallOrigin::SourceRoot,
 				dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+				expiry: None,
 				call: target::FromBridgedChainEncodedMessageCall::<ThisChainCall>::new(
 					ThisChainCall::Transfer.encode(),
 				),
@@ -1060,6 +1298,7 @@ mod tests {
 
 	const TEST_LANE_ID: &LaneId = b"test";
 	const MAXIMAL_PENDING_MESSAGES_AT_TEST_LANE: MessageNonce = 32;
+	const CONGESTED_TEST_LANE_THRESHOLD: MessageNonce = 16;
 
 	fn regular_outbound_message_payload() -> source::FromThisChainMessagePayload<OnThisChainBridge>
 	{
@@ -1068,6 +1307,7 @@ mod tests {
 			weight: 100,
 			origin: bp_message_dispatch::CallOrigin::SourceRoot,
 			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expiry: None,
 			call: vec![42],
 		}
 	}
@@ -1084,6 +1324,7 @@ mod tests {
 			source::estimate_message_dispatch_and_delivery_fee::<OnThisChainBridge>(
 				&payload,
 				OnThisChainBridge::RELAYER_FEE_PERCENT,
+				0,
 			),
 			Ok(ThisChainBalance(EXPECTED_MINIMAL_FEE)),
 		);
@@ -1095,6 +1336,7 @@ mod tests {
 			source::estimate_message_dispatch_and_delivery_fee::<OnThisChainBridge>(
 				&payload_with_pay_on_target,
 				OnThisChainBridge::RELAYER_FEE_PERCENT,
+				0,
 			)
 			.expect(
 				"estimate_message_dispatch_and_delivery_fee failed for pay-at-target-chain message",
@@ -1127,6 +1369,92 @@ mod tests {
 		.is_ok(),);
 	}
 
+	#[test]
+	fn message_fee_grows_when_lane_is_congested() {
+		let payload = regular_outbound_message_payload();
+		let estimate_fee = |pending_messages| {
+			source::estimate_message_dispatch_and_delivery_fee::<OnThisChainBridge>(
+				&payload,
+				OnThisChainBridge::RELAYER_FEE_PERCENT,
+				pending_messages,
+			)
+			.unwrap()
+		};
+
+		// no surcharge until lane is congested
+		let base_fee = estimate_fee(0);
+		assert_eq!(estimate_fee(CONGESTED_TEST_LANE_THRESHOLD), base_fee);
+
+		// surcharge grows linearly with the number of pending messages
+		assert_eq!(
+			source::congestion_fee_percent::<OnThisChainBridge>(
+				CONGESTED_TEST_LANE_THRESHOLD + CONGESTED_TEST_LANE_THRESHOLD / 2
+			),
+			50,
+		);
+		assert_eq!(
+			estimate_fee(CONGESTED_TEST_LANE_THRESHOLD + CONGESTED_TEST_LANE_THRESHOLD / 2),
+			ThisChainBalance(base_fee.0 * 3 / 2),
+		);
+		assert_eq!(
+			estimate_fee(CONGESTED_TEST_LANE_THRESHOLD * 2),
+			ThisChainBalance(base_fee.0 * 2)
+		);
+
+		// and the verifier requires the surcharge to be paid
+		let congested_lane_outbound_data = OutboundLaneData {
+			latest_generated_nonce: CONGESTED_TEST_LANE_THRESHOLD * 2,
+			..Default::default()
+		};
+		assert_eq!(
+			source::FromThisChainMessageVerifier::<OnThisChainBridge>::verify_message(
+				&Sender::Root,
+				&base_fee,
+				TEST_LANE_ID,
+				&congested_lane_outbound_data,
+				&payload,
+			),
+			Err(source::TOO_LOW_FEE)
+		);
+		assert!(source::FromThisChainMessageVerifier::<OnThisChainBridge>::verify_message(
+			&Sender::Root,
+			&ThisChainBalance(base_fee.0 * 2),
+			TEST_LANE_ID,
+			&congested_lane_outbound_data,
+			&payload,
+		)
+		.is_ok());
+	}
+
+	#[test]
+	fn unspent_dispatch_fee_refund_is_computed() {
+		type Refund = source::FromThisChainUnspentDispatchFeeRefund<OnThisChainBridge>;
+
+		// refund is computed using bridged chain weight-to-fee rate and conversion rate
+		let payload = regular_outbound_message_payload();
+		assert_eq!(
+			Refund::unspent_dispatch_fee(&payload, 10),
+			ThisChainBalance(
+				10 * BRIDGED_CHAIN_WEIGHT_TO_BALANCE_RATE as u32 *
+					BRIDGED_CHAIN_TO_THIS_CHAIN_BALANCE_RATE,
+			),
+		);
+
+		// refund never exceeds fee of declared dispatch weight
+		assert_eq!(
+			Refund::unspent_dispatch_fee(&payload, payload.weight + 1),
+			Refund::unspent_dispatch_fee(&payload, payload.weight),
+		);
+
+		// nothing is refunded if dispatch fee is paid at the target chain
+		let mut payload_with_pay_on_target = regular_outbound_message_payload();
+		payload_with_pay_on_target.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
+		assert_eq!(
+			Refund::unspent_dispatch_fee(&payload_with_pay_on_target, 10),
+			ThisChainBalance(0),
+		);
+	}
+
 	#[test]
 	fn should_disallow_root_calls_from_regular_accounts() {
 		// payload of the This -> Bridged chain message
@@ -1135,6 +1463,7 @@ mod tests {
 			weight: 100,
 			origin: bp_message_dispatch::CallOrigin::SourceRoot,
 			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expiry: None,
 			call: vec![42],
 		};
 
@@ -1177,6 +1506,7 @@ mod tests {
 			weight: 100,
 			origin: bp_message_dispatch::CallOrigin::SourceAccount(ThisChainAccountId(1)),
 			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expiry: None,
 			call: vec![42],
 		};
 
@@ -1241,6 +1571,7 @@ mod tests {
 				weight: 5,
 				origin: bp_message_dispatch::CallOrigin::SourceRoot,
 				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expiry: None,
 				call: vec![1, 2, 3, 4, 5, 6],
 			},
 		)
@@ -1255,6 +1586,7 @@ mod tests {
 				weight: BRIDGED_CHAIN_MAX_EXTRINSIC_WEIGHT + 1,
 				origin: bp_message_dispatch::CallOrigin::SourceRoot,
 				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expiry: None,
 				call: vec![1, 2, 3, 4, 5, 6],
 			},
 		)
@@ -1269,6 +1601,7 @@ mod tests {
 				weight: BRIDGED_CHAIN_MAX_EXTRINSIC_WEIGHT,
 				origin: bp_message_dispatch::CallOrigin::SourceRoot,
 				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expiry: None,
 				call: vec![0; source::maximal_message_size::<OnThisChainBridge>() as usize + 1],
 			},
 		)
@@ -1284,6 +1617,7 @@ mod tests {
 					weight: BRIDGED_CHAIN_MAX_EXTRINSIC_WEIGHT,
 					origin: bp_message_dispatch::CallOrigin::SourceRoot,
 					dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+					expiry: None,
 					call: vec![0; source::maximal_message_size::<OnThisChainBridge>() as _],
 				},
 			),
@@ -16,7 +16,10 @@
 
 //! Helpers for implementing various message-related runtime API mthods.
 
-use crate::messages::{source::FromThisChainMessagePayload, MessageBridge};
+use crate::messages::{
+	source::{estimate_message_dispatch_and_delivery_fee, FromThisChainMessagePayload},
+	BalanceOf, MessageBridge, ThisChain,
+};
 
 use bp_messages::{LaneId, MessageDetails, MessageNonce};
 use codec::Decode;
@@ -49,3 +52,28 @@ where
 		})
 		.collect()
 }
+
+/// Implementation of the `To*OutboundLaneApi::estimate_message_delivery_and_dispatch_fee`.
+///
+/// The estimated fee includes congestion surcharge of the outbound lane.
+pub fn estimate_outbound_message_fee<Runtime, MessagesPalletInstance, BridgeConfig>(
+	lane: LaneId,
+	payload: &FromThisChainMessagePayload<BridgeConfig>,
+) -> Option<BalanceOf<ThisChain<BridgeConfig>>>
+where
+	Runtime: pallet_bridge_messages::Config<MessagesPalletInstance>,
+	MessagesPalletInstance: 'static,
+	BridgeConfig: MessageBridge,
+{
+	let latest_generated_nonce =
+		pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::outbound_latest_generated_nonce(lane);
+	let latest_received_nonce =
+		pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::outbound_latest_received_nonce(lane);
+	let pending_messages = latest_generated_nonce.saturating_sub(latest_received_nonce);
+	estimate_message_dispatch_and_delivery_fee::<BridgeConfig>(
+		payload,
+		BridgeConfig::RELAYER_FEE_PERCENT,
+		pending_messages,
+	)
+	.ok()
+}
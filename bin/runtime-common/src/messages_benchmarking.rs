@@ -24,6 +24,7 @@ use crate::messages::{
 	AccountIdOf, BalanceOf, BridgedChain, HashOf, MessageBridge, ThisChain,
 };
 
+use bp_message_dispatch::TargetAccountNonce;
 use bp_messages::{LaneId, MessageData, MessageKey, MessagePayload};
 use bp_runtime::ChainId;
 use codec::Encode;
@@ -47,6 +48,7 @@ pub fn ed25519_sign(
 	target_spec_version: u32,
 	source_chain_id: ChainId,
 	target_chain_id: ChainId,
+	target_account_nonce: TargetAccountNonce,
 ) -> ([u8; 32], [u8; 64]) {
 	// key from the repo example (https://docs.rs/ed25519-dalek/1.0.1/ed25519_dalek/struct.SecretKey.html)
 	let target_secret = SecretKey::from_bytes(&[
@@ -68,6 +70,7 @@ pub fn ed25519_sign(
 		target_spec_version,
 		source_chain_id,
 		target_chain_id,
+		target_account_nonce,
 	);
 	let target_origin_signature = target_pair
 		.try_sign(&signature_message)
@@ -0,0 +1,82 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Everything required to run benchmarks of parachains finality module.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use bp_polkadot_core::parachains::{ParaHead, ParaHeadsProof, ParaId};
+use codec::Encode;
+use frame_support::traits::Get;
+use pallet_bridge_grandpa::BridgedHeader;
+use pallet_bridge_parachains::{RelayBlockHash, RelayBlockHasher};
+use sp_runtime::traits::Header;
+use sp_std::prelude::*;
+use sp_trie::{record_all_keys, trie_types::TrieDBMut, Layout, MemoryDB, Recorder, TrieMut};
+
+/// Prepare proof of parachain heads for the `submit_parachain_heads` call.
+///
+/// All parachain heads are of the same `parachain_head_size` size. The relay chain header,
+/// which storage root matches the proof, is inserted into the bridge GRANDPA pallet.
+pub fn prepare_parachain_heads_proof<R, PI>(
+	parachains: &[ParaId],
+	parachain_head_size: u32,
+) -> (RelayBlockHash<R, PI>, ParaHeadsProof)
+where
+	R: pallet_bridge_parachains::Config<PI>,
+	PI: 'static,
+{
+	let parachain_head = ParaHead(vec![0u8; parachain_head_size as usize]);
+
+	// insert parachain heads to the trie
+	let mut state_root = Default::default();
+	let mut mdb = MemoryDB::default();
+	{
+		let mut trie = TrieDBMut::<RelayBlockHasher<R, PI>>::new(&mut mdb, &mut state_root);
+
+		for parachain in parachains {
+			let storage_key = bp_parachains::parachain_head_storage_key_at_source(
+				R::ParasPalletName::get(),
+				*parachain,
+			);
+			trie.insert(&storage_key.0, &parachain_head.encode())
+				.map_err(|_| "TrieMut::insert has failed")
+				.expect("TrieMut::insert should not fail in benchmarks");
+		}
+	}
+
+	// generate heads storage proof
+	let mut proof_recorder = Recorder::<RelayBlockHash<R, PI>>::new();
+	record_all_keys::<Layout<RelayBlockHasher<R, PI>>, _>(&mdb, &state_root, &mut proof_recorder)
+		.map_err(|_| "record_all_keys has failed")
+		.expect("record_all_keys should not fail in benchmarks");
+	let proof = proof_recorder.drain().into_iter().map(|n| n.data.to_vec()).collect();
+
+	// prepare relay chain header and insert it into the GRANDPA pallet
+	let relay_header = BridgedHeader::<R, R::BridgesGrandpaPalletInstance>::new(
+		Default::default(),
+		Default::default(),
+		state_root,
+		Default::default(),
+		Default::default(),
+	);
+	let relay_block_hash = relay_header.hash();
+	pallet_bridge_grandpa::initialize_for_benchmarks::<R, R::BridgesGrandpaPalletInstance>(
+		relay_header,
+	);
+
+	(relay_block_hash, proof)
+}
@@ -0,0 +1,114 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks for the BEEFY Pallet.
+//!
+//! The main dispatchable for the BEEFY pallet is `submit_commitment`. Our main goal is to
+//! determine the cost of this call in the worst case scenario. The cost depends on:
+//!
+//! 1. The number of commitment signatures. Every signature is recovered and the Merkle proof of the
+//!    signer address is verified.
+//! 2. The number of MMR proof items. Every item is hashed when the proof is verified.
+//!
+//! The Merkle proof of the signer address can't be longer than the depth of the validators
+//! Merkle tree, so it is covered by the number of signatures.
+
+use crate::{
+	test_utils::{
+		perfect_mmr_root_and_proof, sign_commitment, validator_secrets, validator_set_info,
+	},
+	*,
+};
+
+use frame_benchmarking::{benchmarks_instance_pallet, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Header as HeaderT, One, Zero};
+use sp_std::{boxed::Box, vec::Vec};
+
+// The maximum number of commitment signatures. In practice this scales with the number of
+// validators.
+const MAX_SIGNATURES: u32 = 1024;
+
+// The maximum number of MMR proof items. In practice this scales with the logarithm of the
+// number of bridged chain blocks.
+const MAX_MMR_PROOF_ITEMS: u32 = 24;
+
+/// Prepare arguments of the `submit_commitment` call.
+fn prepare_benchmark_data<T: Config<I>, I: 'static>(
+	signatures: u32,
+	mmr_proof_items: u32,
+) -> (
+	BridgedSignedCommitment<T, I>,
+	Vec<Vec<BeefyHash>>,
+	BridgedMmrLeaf<T, I>,
+	MmrLeafProof,
+	BridgedHeader<T, I>,
+) {
+	// the pallet only requires supermajority of signatures, so the validator set is selected
+	// so that `signatures` signatures are exactly the threshold
+	let validators_count = signatures + (signatures - 1) / 2;
+	let validators = validator_secrets(0, validators_count);
+	let next_validator_set = validator_set_info(1, 1);
+
+	initialize_bridge::<T, I>(InitializationData {
+		header: Box::new(bp_test_utils::test_header(Zero::zero())),
+		validator_set: validator_set_info(0, validators_count),
+		next_validator_set: next_validator_set.clone(),
+		first_mmr_leaf_block: One::one(),
+		is_halted: false,
+	});
+
+	// the MMR with `2^mmr_proof_items` leaves has a single peak, so the proof of its latest
+	// leaf has exactly `mmr_proof_items` items
+	let block_number: BridgedBlockNumber<T, I> = (1u32 << mmr_proof_items).into();
+	let header: BridgedHeader<T, I> = bp_test_utils::test_header(block_number - One::one());
+	let mmr_leaf = MmrLeaf {
+		version: MmrLeafVersion::new(SUPPORTED_MMR_LEAF_MAJOR_VERSION, 0),
+		parent_number_and_hash: (*header.number(), header.hash()),
+		beefy_next_authority_set: next_validator_set,
+		parachain_heads: Default::default(),
+	};
+	let (mmr_root, mmr_proof) = perfect_mmr_root_and_proof(&mmr_leaf, mmr_proof_items);
+	let commitment = Commitment { payload: mmr_root, block_number, validator_set_id: 0 };
+	let (signatures, validator_proofs) =
+		sign_commitment(&commitment, &validators, signatures as usize);
+
+	(SignedCommitment { commitment, signatures }, validator_proofs, mmr_leaf, mmr_proof, header)
+}
+
+benchmarks_instance_pallet! {
+	// This is the "gold standard" benchmark for this extrinsic, and it's what should be used to
+	// annotate the weight in the pallet.
+	submit_commitment {
+		let s in 1..MAX_SIGNATURES;
+		let m in 1..MAX_MMR_PROOF_ITEMS;
+		let caller: T::AccountId = whitelisted_caller();
+		let (signed_commitment, validator_proofs, mmr_leaf, mmr_proof, header) =
+			prepare_benchmark_data::<T, I>(s, m);
+		let expected_hash = header.hash();
+	}: submit_commitment(
+		RawOrigin::Signed(caller),
+		signed_commitment,
+		validator_proofs,
+		mmr_leaf,
+		mmr_proof,
+		Box::new(header)
+	)
+	verify {
+		assert_eq!(<BestFinalized<T, I>>::get(), expected_hash);
+		assert!(<ImportedHeaders<T, I>>::contains_key(expected_hash));
+	}
+}
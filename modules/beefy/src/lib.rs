@@ -0,0 +1,1098 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! BEEFY bridge pallet.
+//!
+//! This pallet is an on-chain BEEFY light client for Substrate based chains. It is an
+//! alternative to the GRANDPA bridge pallet: instead of verifying (potentially huge) GRANDPA
+//! justifications, it verifies BEEFY signed commitments. Every commitment is signed by the
+//! supermajority of BEEFY validators and commits to the MMR root of the bridged chain.
+//!
+//! The pallet only stores the Merkle root of the current and next validator sets Ethereum
+//! addresses. So every signature of the commitment is accompanied by the Merkle proof of the
+//! signer address. The header is imported if its hash is found in the latest MMR leaf, that is
+//! proved to be a part of the MMR with the signed root. The same leaf contains details of the
+//! next validator set, which is used to verify commitments once the current set is changed.
+//!
+//! Imported headers may be used by other pallets through the `bp_header_chain::HeaderChain`
+//! trait, exactly as headers imported by the GRANDPA pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// Runtime-generated enums
+#![allow(clippy::large_enum_variant)]
+
+use crate::weights::WeightInfo;
+
+use bp_header_chain::HeaderChain;
+use bp_runtime::{BlockNumberOf, Chain, HashOf, HeaderOf};
+use codec::{Decode, Encode};
+use frame_support::{ensure, fail, RuntimeDebug};
+use frame_system::{ensure_signed, RawOrigin};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{
+	traits::{BadOrigin, CheckedSub, Header as HeaderT, One},
+	SaturatedConversion,
+};
+use sp_std::{boxed::Box, convert::TryInto, vec::Vec};
+
+pub mod merkle;
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(any(feature = "runtime-benchmarks", test))]
+pub mod test_utils;
+
+pub use pallet::*;
+
+/// Block number of the bridged chain.
+pub type BridgedBlockNumber<T, I> = BlockNumberOf<<T as Config<I>>::BridgedChain>;
+/// Block hash of the bridged chain.
+pub type BridgedBlockHash<T, I> = HashOf<<T as Config<I>>::BridgedChain>;
+/// Header of the bridged chain.
+pub type BridgedHeader<T, I> = HeaderOf<<T as Config<I>>::BridgedChain>;
+/// BEEFY signed commitment of the bridged chain.
+pub type BridgedSignedCommitment<T, I> = SignedCommitment<BridgedBlockNumber<T, I>>;
+/// BEEFY MMR leaf of the bridged chain.
+pub type BridgedMmrLeaf<T, I> = MmrLeaf<BridgedBlockNumber<T, I>, BridgedBlockHash<T, I>>;
+
+/// BEEFY validator Ethereum address (last 20 bytes of the Keccak-256 hash of the public key).
+pub type BeefyValidatorAddress = sp_core::H160;
+/// BEEFY validator signature.
+pub type BeefyValidatorSignature = sp_core::ecdsa::Signature;
+/// Monotonic identifier of BEEFY validator set.
+pub type ValidatorSetId = u64;
+/// Hash that is used by BEEFY MMR and validator set Merkle tree (Keccak-256).
+pub type BeefyHash = sp_core::H256;
+
+/// BEEFY commitment: the bridged chain MMR root at given block.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone)]
+pub struct Commitment<BlockNumber> {
+	/// MMR root of the bridged chain at `block_number`.
+	pub payload: BeefyHash,
+	/// Number of the block the commitment is created for.
+	pub block_number: BlockNumber,
+	/// Id of the validator set that is signing the commitment.
+	pub validator_set_id: ValidatorSetId,
+}
+
+/// BEEFY commitment, signed by the validator set.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone)]
+pub struct SignedCommitment<BlockNumber> {
+	/// The commitment.
+	pub commitment: Commitment<BlockNumber>,
+	/// Signatures of the Keccak-256 hash of encoded commitment.
+	///
+	/// There's a signature for every validator of the set, in the same order. The signature is
+	/// `None` if the validator has not signed the commitment.
+	pub signatures: Vec<Option<BeefyValidatorSignature>>,
+}
+
+/// Details of BEEFY validator set, that are required to verify commitments signed by this set.
+#[derive(Default, Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ValidatorSetInfo {
+	/// Id of the validator set.
+	pub id: ValidatorSetId,
+	/// Number of validators in the set.
+	pub len: u32,
+	/// Root of the Merkle tree, built from validators Ethereum addresses.
+	pub root: BeefyHash,
+}
+
+/// Major version of the MMR leaf format, supported by the pallet.
+pub const SUPPORTED_MMR_LEAF_MAJOR_VERSION: u8 = 0;
+
+/// Version of the MMR leaf format.
+///
+/// The major version is stored in 3 most significant bits and the minor version is stored in
+/// the remaining 5 bits. Minor version changes are only appending new fields to the leaf.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone, Copy)]
+pub struct MmrLeafVersion(u8);
+
+impl MmrLeafVersion {
+	/// Create new version from major and minor components.
+	///
+	/// The major component must fit into 3 bits and the minor component must fit into 5 bits.
+	pub fn new(major: u8, minor: u8) -> Self {
+		Self((major << 5) | (minor & 0b11111))
+	}
+
+	/// Split version into major and minor components.
+	pub fn split(&self) -> (u8, u8) {
+		(self.0 >> 5, self.0 & 0b11111)
+	}
+}
+
+/// Leaf of the bridged chain MMR.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone)]
+pub struct MmrLeaf<BlockNumber, Hash> {
+	/// Version of the leaf format.
+	pub version: MmrLeafVersion,
+	/// Number and hash of the parent of the block that has appended this leaf.
+	pub parent_number_and_hash: (BlockNumber, Hash),
+	/// Details of the next BEEFY validator set.
+	pub beefy_next_authority_set: ValidatorSetInfo,
+	/// Root of the Merkle tree of parachain heads, included in the block.
+	pub parachain_heads: BeefyHash,
+}
+
+/// Proof of the MMR leaf inclusion.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone)]
+pub struct MmrLeafProof {
+	/// Index of the proved leaf.
+	pub leaf_index: u64,
+	/// Number of leaves in the MMR.
+	pub leaf_count: u64,
+	/// Proof items (peaks and siblings).
+	pub items: Vec<BeefyHash>,
+}
+
+/// Data required for initializing the BEEFY bridge pallet.
+#[derive(Default, Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct InitializationData<H: HeaderT> {
+	/// The header from which we should start syncing.
+	pub header: Box<H>,
+	/// The current BEEFY validator set.
+	pub validator_set: ValidatorSetInfo,
+	/// The next BEEFY validator set.
+	pub next_validator_set: ValidatorSetInfo,
+	/// Number of the block that has appended the first leaf to the bridged chain MMR.
+	pub first_mmr_leaf_block: H::Number,
+	/// Should the pallet block transaction immediately after initialization.
+	pub is_halted: bool,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The chain we are bridging to here.
+		type BridgedChain: Chain;
+
+		/// The upper bound on the number of requests allowed by the pallet.
+		///
+		/// A request refers to an action which writes a header to storage.
+		///
+		/// Once this bound is reached the pallet will not allow any dispatchables to be called
+		/// until the request count has decreased.
+		#[pallet::constant]
+		type MaxRequests: Get<u32>;
+
+		/// Maximal number of finalized headers to keep in the storage.
+		///
+		/// The setting is there to prevent growing the on-chain state indefinitely. Note
+		/// the setting does not relate to block numbers - we will simply keep as much items
+		/// in the storage, so it doesn't guarantee any fixed timeframe for finality headers.
+		#[pallet::constant]
+		type HeadersToKeep: Get<u32>;
+
+		/// Weights gathered through benchmarking.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(_n: T::BlockNumber) -> frame_support::weights::Weight {
+			<RequestCount<T, I>>::mutate(|count| *count = count.saturating_sub(1));
+
+			(0_u64)
+				.saturating_add(T::DbWeight::get().reads(1))
+				.saturating_add(T::DbWeight::get().writes(1))
+		}
+
+		fn integrity_test() {
+			assert!(T::HeadersToKeep::get() > 0, "HeadersToKeep must be larger than zero");
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Import finalized header, using BEEFY signed commitment.
+		///
+		/// The commitment must be signed by the supermajority of the current or next validator
+		/// set. Every signature is accompanied by the Merkle proof of the signer address in
+		/// `validator_proofs`, in the same order. The `mmr_leaf` must be the leaf, appended by
+		/// the commitment block, so it is the latest leaf of the MMR with the signed root. Its
+		/// inclusion is proved by the `mmr_proof`. The leaf must reference the `header`, which
+		/// is the parent of the commitment block.
+		#[pallet::weight(T::WeightInfo::submit_commitment(
+			validator_proofs.len().try_into().unwrap_or(u32::MAX),
+			mmr_proof.items.len().try_into().unwrap_or(u32::MAX),
+		))]
+		pub fn submit_commitment(
+			origin: OriginFor<T>,
+			signed_commitment: BridgedSignedCommitment<T, I>,
+			validator_proofs: Vec<Vec<BeefyHash>>,
+			mmr_leaf: BridgedMmrLeaf<T, I>,
+			mmr_proof: MmrLeafProof,
+			header: Box<BridgedHeader<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+
+			ensure!(Self::request_count() < T::MaxRequests::get(), <Error<T, I>>::TooManyRequests);
+
+			let (hash, number) = (header.hash(), *header.number());
+			log::trace!(
+				target: "runtime::bridge-beefy",
+				"Going to try and finalize header {:?} using commitment {:?}",
+				header,
+				signed_commitment.commitment,
+			);
+
+			let best_finalized = match <ImportedHeaders<T, I>>::get(<BestFinalized<T, I>>::get()) {
+				Some(best_finalized) => best_finalized,
+				None => {
+					log::error!(
+						target: "runtime::bridge-beefy",
+						"Cannot finalize header {:?} because pallet is not yet initialized",
+						header,
+					);
+					fail!(<Error<T, I>>::NotInitialized);
+				},
+			};
+			ensure!(*best_finalized.number() < number, <Error<T, I>>::OldHeader);
+
+			let current_validator_set = <CurrentValidatorSet<T, I>>::get();
+			let next_validator_set = <NextValidatorSet<T, I>>::get();
+			let commitment_set_id = signed_commitment.commitment.validator_set_id;
+			let validator_set = if commitment_set_id == current_validator_set.id {
+				&current_validator_set
+			} else if commitment_set_id == next_validator_set.id {
+				&next_validator_set
+			} else {
+				fail!(<Error<T, I>>::InvalidValidatorSetId);
+			};
+			verify_signed_commitment::<T, I>(&signed_commitment, validator_set, &validator_proofs)?;
+
+			// the latest MMR leaf contains the latest known next validator set, so we only accept
+			// proofs of the latest leaf. Every block appends a single leaf to the MMR, so the
+			// number of leaves at the commitment block is known
+			let commitment_block_number = signed_commitment.commitment.block_number;
+			let expected_leaf_count = commitment_block_number
+				.checked_sub(&<FirstMmrLeafBlock<T, I>>::get())
+				.map(|leaf_index| leaf_index.saturated_into::<u64>().saturating_add(1));
+			ensure!(
+				expected_leaf_count == Some(mmr_proof.leaf_count) &&
+					mmr_proof.leaf_index.checked_add(1) == Some(mmr_proof.leaf_count),
+				<Error<T, I>>::InvalidMmrProof,
+			);
+			ensure!(
+				mmr_leaf.version.split().0 == SUPPORTED_MMR_LEAF_MAJOR_VERSION,
+				<Error<T, I>>::UnsupportedMmrLeafVersion,
+			);
+			ensure!(
+				merkle::verify_mmr_leaf_proof(
+					signed_commitment.commitment.payload,
+					&mmr_leaf,
+					&mmr_proof,
+				),
+				<Error<T, I>>::InvalidMmrProof,
+			);
+			ensure!(
+				number + One::one() == commitment_block_number &&
+					mmr_leaf.parent_number_and_hash == (number, hash),
+				<Error<T, I>>::InvalidHeader
+			);
+
+			// header that changes the next validator set is mandatory, because the pallet can't
+			// verify commitments of the next set without it. So relayer is not paying fee for
+			// mandatory headers import transactions
+			let is_mandatory_header = mmr_leaf.beefy_next_authority_set != next_validator_set;
+			if commitment_set_id == next_validator_set.id {
+				<CurrentValidatorSet<T, I>>::put(&next_validator_set);
+			}
+			<NextValidatorSet<T, I>>::put(&mmr_leaf.beefy_next_authority_set);
+			<RequestCount<T, I>>::mutate(|count| *count += 1);
+			insert_header::<T, I>(*header, hash);
+			log::info!(
+				target: "runtime::bridge-beefy",
+				"Succesfully imported finalized header with hash {:?}, signed by validator set {}!",
+				hash,
+				commitment_set_id,
+			);
+
+			let pays_fee = if is_mandatory_header { Pays::No } else { Pays::Yes };
+
+			Ok(pays_fee.into())
+		}
+
+		/// Bootstrap the bridge pallet with an initial header and validator sets from which to
+		/// sync.
+		///
+		/// This function is only allowed to be called from a trusted origin and writes to storage
+		/// with practically no checks in terms of the validity of the data. It is important that
+		/// you ensure that valid data is being passed in.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 7), DispatchClass::Operational))]
+		pub fn initialize(
+			origin: OriginFor<T>,
+			init_data: super::InitializationData<BridgedHeader<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+
+			let init_allowed = !<BestFinalized<T, I>>::exists();
+			ensure!(init_allowed, <Error<T, I>>::AlreadyInitialized);
+			initialize_bridge::<T, I>(init_data.clone());
+
+			log::info!(
+				target: "runtime::bridge-beefy",
+				"Pallet has been initialized with the following parameters: {:?}",
+				init_data
+			);
+
+			Ok(().into())
+		}
+
+		/// Change `PalletOwner`.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_owner(
+			origin: OriginFor<T>,
+			new_owner: Option<T::AccountId>,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			match new_owner {
+				Some(new_owner) => {
+					PalletOwner::<T, I>::put(&new_owner);
+					log::info!(target: "runtime::bridge-beefy", "Setting pallet Owner to: {:?}", new_owner);
+				},
+				None => {
+					PalletOwner::<T, I>::kill();
+					log::info!(target: "runtime::bridge-beefy", "Removed Owner of pallet.");
+				},
+			}
+
+			Ok(().into())
+		}
+
+		/// Halt or resume all pallet operations.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_operational(
+			origin: OriginFor<T>,
+			operational: bool,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			<IsHalted<T, I>>::put(!operational);
+
+			if operational {
+				log::info!(target: "runtime::bridge-beefy", "Resuming pallet operations.");
+			} else {
+				log::warn!(target: "runtime::bridge-beefy", "Stopping pallet operations.");
+			}
+
+			Ok(().into())
+		}
+	}
+
+	/// The current number of requests which have written to storage.
+	///
+	/// If the `RequestCount` hits `MaxRequests`, no more calls will be allowed to the pallet until
+	/// the request capacity is increased.
+	///
+	/// The `RequestCount` is decreased by one at the beginning of every block. This is to ensure
+	/// that the pallet can always make progress.
+	#[pallet::storage]
+	#[pallet::getter(fn request_count)]
+	pub(super) type RequestCount<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Hash of the best finalized header.
+	#[pallet::storage]
+	pub(super) type BestFinalized<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BridgedBlockHash<T, I>, ValueQuery>;
+
+	/// A ring buffer of imported hashes. Ordered by the insertion time.
+	#[pallet::storage]
+	pub(super) type ImportedHashes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, u32, BridgedBlockHash<T, I>>;
+
+	/// Current ring buffer position.
+	#[pallet::storage]
+	pub(super) type ImportedHashesPointer<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, u32, ValueQuery>;
+
+	/// Headers which have been imported into the pallet.
+	#[pallet::storage]
+	pub(super) type ImportedHeaders<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, BridgedBlockHash<T, I>, BridgedHeader<T, I>>;
+
+	/// The current BEEFY validator set.
+	#[pallet::storage]
+	pub(super) type CurrentValidatorSet<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, ValidatorSetInfo, ValueQuery>;
+
+	/// The next BEEFY validator set, as it is known from the latest imported MMR leaf.
+	#[pallet::storage]
+	pub(super) type NextValidatorSet<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, ValidatorSetInfo, ValueQuery>;
+
+	/// Number of the block that has appended the first leaf to the bridged chain MMR.
+	#[pallet::storage]
+	pub(super) type FirstMmrLeafBlock<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BridgedBlockNumber<T, I>, ValueQuery>;
+
+	/// Optional pallet owner.
+	///
+	/// Pallet owner has a right to halt all pallet operations and then resume it. If it is
+	/// `None`, then there are no direct ways to halt/resume pallet operations, but other
+	/// runtime methods may still be used to do that (i.e. democracy::referendum to update halt
+	/// flag directly or call the `halt_operations`).
+	#[pallet::storage]
+	pub(super) type PalletOwner<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, T::AccountId, OptionQuery>;
+
+	/// If true, all pallet transactions are failed immediately.
+	#[pallet::storage]
+	pub(super) type IsHalted<T: Config<I>, I: 'static = ()> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+		/// Optional module owner account.
+		pub owner: Option<T::AccountId>,
+		/// Optional module initialization data.
+		pub init_data: Option<super::InitializationData<BridgedHeader<T, I>>>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
+		fn default() -> Self {
+			Self { owner: None, init_data: None }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
+		fn build(&self) {
+			if let Some(ref owner) = self.owner {
+				<PalletOwner<T, I>>::put(owner);
+			}
+
+			if let Some(init_data) = self.init_data.clone() {
+				initialize_bridge::<T, I>(init_data);
+			} else {
+				// Since the bridge hasn't been initialized we shouldn't allow anyone to perform
+				// transactions.
+				<IsHalted<T, I>>::put(true);
+			}
+		}
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// The pallet has not been initialized yet.
+		NotInitialized,
+		/// The pallet has already been initialized.
+		AlreadyInitialized,
+		/// There are too many requests for the current window to handle.
+		TooManyRequests,
+		/// The header being imported is older than the best finalized header known to the pallet.
+		OldHeader,
+		/// All pallet operations are halted.
+		Halted,
+		/// The commitment is signed by unknown validator set.
+		InvalidValidatorSetId,
+		/// The number of commitment signatures doesn't match the validator set length.
+		InvalidSignaturesCount,
+		/// The commitment is not signed by the supermajority of validators.
+		NotEnoughSignatures,
+		/// The commitment signature is invalid.
+		InvalidSignature,
+		/// The Merkle proof of the signer address is invalid.
+		InvalidValidatorProof,
+		/// The MMR leaf proof is invalid.
+		InvalidMmrProof,
+		/// The MMR leaf has unsupported format version.
+		UnsupportedMmrLeafVersion,
+		/// The MMR leaf or the commitment doesn't reference the header being imported.
+		InvalidHeader,
+	}
+
+	/// Verify that the commitment is signed by the supermajority of the given validator set.
+	pub(crate) fn verify_signed_commitment<T: Config<I>, I: 'static>(
+		signed_commitment: &BridgedSignedCommitment<T, I>,
+		validator_set: &ValidatorSetInfo,
+		validator_proofs: &[Vec<BeefyHash>],
+	) -> Result<(), Error<T, I>> {
+		let validators_count = validator_set.len as usize;
+		ensure!(
+			signed_commitment.signatures.len() == validators_count,
+			<Error<T, I>>::InvalidSignaturesCount,
+		);
+
+		let signatures_count = signed_commitment
+			.signatures
+			.iter()
+			.filter(|signature| signature.is_some())
+			.count();
+		ensure!(
+			signatures_count >= required_signatures(validators_count),
+			<Error<T, I>>::NotEnoughSignatures,
+		);
+		ensure!(validator_proofs.len() == signatures_count, <Error<T, I>>::InvalidValidatorProof);
+		// the proof of valid leaf has at most one item at every level of the tree, so we may
+		// reject proofs with extra items before doing any expensive work
+		let max_validator_proof_len = merkle::max_proof_len(validators_count);
+		ensure!(
+			validator_proofs.iter().all(|proof| proof.len() <= max_validator_proof_len),
+			<Error<T, I>>::InvalidValidatorProof,
+		);
+
+		let commitment_hash = sp_io::hashing::keccak_256(&signed_commitment.commitment.encode());
+		let signatures =
+			signed_commitment
+				.signatures
+				.iter()
+				.enumerate()
+				.filter_map(|(index, signature)| {
+					signature.as_ref().map(|signature| (index, signature))
+				});
+		for ((index, signature), validator_proof) in signatures.zip(validator_proofs) {
+			let validator = sp_io::crypto::secp256k1_ecdsa_recover(&signature.0, &commitment_hash)
+				.map_err(|_| <Error<T, I>>::InvalidSignature)?;
+			ensure!(
+				merkle::verify_merkle_proof(
+					validator_set.root,
+					validator_address(&validator).as_bytes(),
+					index,
+					validators_count,
+					validator_proof,
+				),
+				<Error<T, I>>::InvalidValidatorProof,
+			);
+		}
+
+		Ok(())
+	}
+
+	/// Returns Ethereum address of the validator with given (uncompressed) public key.
+	pub fn validator_address(public: &[u8; 64]) -> BeefyValidatorAddress {
+		BeefyValidatorAddress::from_slice(&sp_io::hashing::keccak_256(public)[12..])
+	}
+
+	/// Returns number of signatures that is required to consider commitment valid.
+	pub(crate) fn required_signatures(validators_count: usize) -> usize {
+		let faulty_validators = validators_count.saturating_sub(1) / 3;
+		sp_std::cmp::max(1, validators_count - faulty_validators)
+	}
+
+	/// Import a previously verified header to the storage.
+	///
+	/// Note this function solely takes care of updating the storage and pruning old entries,
+	/// but does not verify the validity of such import.
+	pub(crate) fn insert_header<T: Config<I>, I: 'static>(
+		header: BridgedHeader<T, I>,
+		hash: BridgedBlockHash<T, I>,
+	) {
+		let index = <ImportedHashesPointer<T, I>>::get();
+		let pruning = <ImportedHashes<T, I>>::try_get(index);
+		<BestFinalized<T, I>>::put(hash);
+		<ImportedHeaders<T, I>>::insert(hash, header);
+		<ImportedHashes<T, I>>::insert(index, hash);
+
+		// Update ring buffer pointer and remove old header.
+		<ImportedHashesPointer<T, I>>::put((index + 1) % T::HeadersToKeep::get());
+		if let Ok(hash) = pruning {
+			log::debug!(target: "runtime::bridge-beefy", "Pruning old header: {:?}.", hash);
+			<ImportedHeaders<T, I>>::remove(hash);
+		}
+	}
+
+	/// Since this writes to storage with no real checks this should only be used in functions that
+	/// were called by a trusted origin.
+	pub(crate) fn initialize_bridge<T: Config<I>, I: 'static>(
+		init_params: super::InitializationData<BridgedHeader<T, I>>,
+	) {
+		let super::InitializationData {
+			header,
+			validator_set,
+			next_validator_set,
+			first_mmr_leaf_block,
+			is_halted,
+		} = init_params;
+
+		let initial_hash = header.hash();
+		<ImportedHashesPointer<T, I>>::put(0);
+		insert_header::<T, I>(*header, initial_hash);
+
+		<CurrentValidatorSet<T, I>>::put(validator_set);
+		<NextValidatorSet<T, I>>::put(next_validator_set);
+		<FirstMmrLeafBlock<T, I>>::put(first_mmr_leaf_block);
+
+		<IsHalted<T, I>>::put(is_halted);
+	}
+
+	/// Ensure that the origin is either root, or `PalletOwner`.
+	fn ensure_owner_or_root<T: Config<I>, I: 'static>(origin: T::Origin) -> Result<(), BadOrigin> {
+		match origin.into() {
+			Ok(RawOrigin::Root) => Ok(()),
+			Ok(RawOrigin::Signed(ref signer))
+				if Some(signer) == <PalletOwner<T, I>>::get().as_ref() =>
+				Ok(()),
+			_ => Err(BadOrigin),
+		}
+	}
+
+	/// Ensure that the pallet is in operational mode (not halted).
+	fn ensure_operational<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
+		if <IsHalted<T, I>>::get() {
+			Err(<Error<T, I>>::Halted)
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Get the best finalized header the pallet knows of.
+	///
+	/// Returns a dummy header if there is no best header. This can only happen
+	/// if the pallet has not been initialized yet.
+	pub fn best_finalized() -> BridgedHeader<T, I> {
+		let hash = <BestFinalized<T, I>>::get();
+		<ImportedHeaders<T, I>>::get(hash).unwrap_or_else(|| {
+			<BridgedHeader<T, I>>::new(
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			)
+		})
+	}
+
+	/// Get finalized header with given hash, if it is known to the bridge pallet.
+	pub fn finalized_header(hash: BridgedBlockHash<T, I>) -> Option<BridgedHeader<T, I>> {
+		<ImportedHeaders<T, I>>::get(hash)
+	}
+
+	/// Get the current BEEFY validator set.
+	pub fn current_validator_set() -> ValidatorSetInfo {
+		<CurrentValidatorSet<T, I>>::get()
+	}
+
+	/// Get the next BEEFY validator set.
+	pub fn next_validator_set() -> ValidatorSetInfo {
+		<NextValidatorSet<T, I>>::get()
+	}
+}
+
+impl<T: Config<I>, I: 'static> HeaderChain<BridgedHeader<T, I>> for Pallet<T, I> {
+	fn best_finalized() -> BridgedHeader<T, I> {
+		Pallet::<T, I>::best_finalized()
+	}
+
+	fn finalized_header(hash: BridgedBlockHash<T, I>) -> Option<BridgedHeader<T, I>> {
+		Pallet::<T, I>::finalized_header(hash)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		commitment_for_header, commitment_for_header_at_block, init_data, run_test, test_header,
+		validator_set_info, HeadersToKeep, Origin, TestCommitment, TestHeader, TestRuntime,
+		VALIDATORS_COUNT,
+	};
+	use frame_support::{assert_noop, assert_ok, dispatch::DispatchResultWithPostInfo};
+	use sp_runtime::DispatchError;
+
+	/// Number of signatures that is enough to import header.
+	const ENOUGH_SIGNERS: usize = VALIDATORS_COUNT as usize - 1;
+
+	fn initialize_substrate_bridge() {
+		assert_ok!(Pallet::<TestRuntime>::initialize(Origin::root(), init_data(test_header(0))));
+	}
+
+	fn submit_commitment(
+		header: TestHeader,
+		commitment: TestCommitment,
+	) -> DispatchResultWithPostInfo {
+		Pallet::<TestRuntime>::submit_commitment(
+			Origin::signed(1),
+			commitment.signed_commitment,
+			commitment.validator_proofs,
+			commitment.mmr_leaf,
+			commitment.mmr_proof,
+			Box::new(header),
+		)
+	}
+
+	fn submit_commitment_for_header(header: TestHeader) -> DispatchResultWithPostInfo {
+		let commitment = commitment_for_header(&header, 0, 1, ENOUGH_SIGNERS);
+		submit_commitment(header, commitment)
+	}
+
+	fn next_block() {
+		use frame_support::traits::OnInitialize;
+
+		let current_number = frame_system::Pallet::<TestRuntime>::block_number();
+		frame_system::Pallet::<TestRuntime>::set_block_number(current_number + 1);
+		let _ = Pallet::<TestRuntime>::on_initialize(current_number);
+	}
+
+	#[test]
+	fn init_root_or_owner_origin_can_initialize_pallet() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::initialize(Origin::signed(1), init_data(test_header(0))),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::root(), Some(1)));
+			assert_ok!(Pallet::<TestRuntime>::initialize(
+				Origin::signed(1),
+				init_data(test_header(0))
+			));
+
+			assert_eq!(Pallet::<TestRuntime>::best_finalized(), test_header(0));
+			assert_eq!(Pallet::<TestRuntime>::current_validator_set(), validator_set_info(0));
+			assert_eq!(Pallet::<TestRuntime>::next_validator_set(), validator_set_info(1));
+		})
+	}
+
+	#[test]
+	fn init_can_only_initialize_pallet_once() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_noop!(
+				Pallet::<TestRuntime>::initialize(Origin::root(), init_data(test_header(0))),
+				<Error<TestRuntime>>::AlreadyInitialized,
+			);
+		})
+	}
+
+	#[test]
+	fn pallet_rejects_commitments_if_not_initialized_yet() {
+		run_test(|| {
+			assert_noop!(
+				submit_commitment_for_header(test_header(1)),
+				<Error<TestRuntime>>::NotInitialized,
+			);
+		})
+	}
+
+	#[test]
+	fn pallet_rejects_commitments_if_halted() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), false));
+			assert_noop!(
+				submit_commitment_for_header(test_header(1)),
+				<Error<TestRuntime>>::Halted,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), true));
+			assert_ok!(submit_commitment_for_header(test_header(1)));
+		})
+	}
+
+	#[test]
+	fn succesfully_imports_header_with_valid_commitment() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let result = submit_commitment_for_header(header.clone());
+			assert_ok!(result);
+			assert_eq!(result.unwrap().pays_fee, frame_support::weights::Pays::Yes);
+
+			assert_eq!(Pallet::<TestRuntime>::best_finalized(), header);
+			assert_eq!(
+				<Pallet<TestRuntime> as HeaderChain<TestHeader>>::finalized_header(header.hash()),
+				Some(header),
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_old_header() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_commitment_for_header(test_header(2)));
+			assert_noop!(
+				submit_commitment_for_header(test_header(1)),
+				<Error<TestRuntime>>::OldHeader,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_commitment_of_unknown_validator_set() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let commitment = commitment_for_header(&header, 2, 3, ENOUGH_SIGNERS);
+			assert_noop!(
+				submit_commitment(header, commitment),
+				<Error<TestRuntime>>::InvalidValidatorSetId,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_commitment_without_supermajority_of_signatures() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let commitment = commitment_for_header(&header, 0, 1, ENOUGH_SIGNERS - 1);
+			assert_noop!(
+				submit_commitment(header, commitment),
+				<Error<TestRuntime>>::NotEnoughSignatures,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_commitment_with_invalid_signatures_count() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let mut commitment = commitment_for_header(&header, 0, 1, ENOUGH_SIGNERS);
+			commitment.signed_commitment.signatures.push(None);
+			assert_noop!(
+				submit_commitment(header, commitment),
+				<Error<TestRuntime>>::InvalidSignaturesCount,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_commitment_with_signatures_of_other_commitment() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let mut commitment = commitment_for_header(&header, 0, 1, ENOUGH_SIGNERS);
+			commitment.signed_commitment.commitment.block_number += 1;
+			assert_noop!(
+				submit_commitment(header, commitment),
+				<Error<TestRuntime>>::InvalidValidatorProof,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_commitment_with_invalid_validator_proof() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let mut commitment = commitment_for_header(&header, 0, 1, ENOUGH_SIGNERS);
+			commitment.validator_proofs.swap(0, 1);
+			assert_noop!(
+				submit_commitment(header.clone(), commitment),
+				<Error<TestRuntime>>::InvalidValidatorProof,
+			);
+
+			let mut commitment = commitment_for_header(&header, 0, 1, ENOUGH_SIGNERS);
+			commitment.validator_proofs.pop();
+			assert_noop!(
+				submit_commitment(header.clone(), commitment),
+				<Error<TestRuntime>>::InvalidValidatorProof,
+			);
+
+			let mut commitment = commitment_for_header(&header, 0, 1, ENOUGH_SIGNERS);
+			commitment.validator_proofs[0].push(Default::default());
+			assert_noop!(
+				submit_commitment(header, commitment),
+				<Error<TestRuntime>>::InvalidValidatorProof,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_commitment_with_invalid_mmr_proof() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let mut commitment = commitment_for_header(&header, 0, 1, ENOUGH_SIGNERS);
+			commitment.mmr_leaf.parachain_heads = BeefyHash::repeat_byte(42);
+			assert_noop!(
+				submit_commitment(header.clone(), commitment),
+				<Error<TestRuntime>>::InvalidMmrProof,
+			);
+
+			let mut commitment = commitment_for_header(&header, 0, 1, ENOUGH_SIGNERS);
+			commitment.mmr_proof.leaf_index = 0;
+			assert_noop!(
+				submit_commitment(header, commitment),
+				<Error<TestRuntime>>::InvalidMmrProof,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_mmr_proof_with_leaf_count_that_does_not_match_commitment_block() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let mut commitment = commitment_for_header(&header, 0, 1, ENOUGH_SIGNERS);
+			commitment.mmr_proof.leaf_index += 1;
+			commitment.mmr_proof.leaf_count += 1;
+			assert_noop!(
+				submit_commitment(header, commitment),
+				<Error<TestRuntime>>::InvalidMmrProof,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_mmr_leaf_of_unsupported_version() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let mut commitment = commitment_for_header(&header, 0, 1, ENOUGH_SIGNERS);
+			commitment.mmr_leaf.version =
+				MmrLeafVersion::new(SUPPORTED_MMR_LEAF_MAJOR_VERSION + 1, 0);
+			assert_noop!(
+				submit_commitment(header, commitment),
+				<Error<TestRuntime>>::UnsupportedMmrLeafVersion,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_header_that_is_not_referenced_by_mmr_leaf() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let commitment = commitment_for_header(&test_header(1), 0, 1, ENOUGH_SIGNERS);
+			assert_noop!(
+				submit_commitment(test_header(2), commitment),
+				<Error<TestRuntime>>::InvalidHeader,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_header_that_is_not_parent_of_commitment_block() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let commitment = commitment_for_header_at_block(&header, 3, 0, 1, ENOUGH_SIGNERS);
+			assert_noop!(
+				submit_commitment(header, commitment),
+				<Error<TestRuntime>>::InvalidHeader,
+			);
+		})
+	}
+
+	#[test]
+	fn enacts_next_validator_set() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// header 1 announces set#2 as the next set => it is mandatory
+			let header = test_header(1);
+			let commitment = commitment_for_header(&header, 0, 2, ENOUGH_SIGNERS);
+			let result = submit_commitment(header, commitment);
+			assert_ok!(result);
+			assert_eq!(result.unwrap().pays_fee, frame_support::weights::Pays::No);
+			assert_eq!(Pallet::<TestRuntime>::current_validator_set(), validator_set_info(0));
+			assert_eq!(Pallet::<TestRuntime>::next_validator_set(), validator_set_info(2));
+
+			// header 2 is finalized by set#2
+			let header = test_header(2);
+			let commitment = commitment_for_header(&header, 2, 3, ENOUGH_SIGNERS);
+			assert_ok!(submit_commitment(header, commitment));
+			assert_eq!(Pallet::<TestRuntime>::current_validator_set(), validator_set_info(2));
+			assert_eq!(Pallet::<TestRuntime>::next_validator_set(), validator_set_info(3));
+
+			// commitments of the set#0 are not accepted anymore
+			next_block();
+			let header = test_header(3);
+			let commitment = commitment_for_header(&header, 0, 3, ENOUGH_SIGNERS);
+			assert_noop!(
+				submit_commitment(header, commitment),
+				<Error<TestRuntime>>::InvalidValidatorSetId,
+			);
+		})
+	}
+
+	#[test]
+	fn rate_limiter_disallows_imports_once_limit_is_hit_in_single_block() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_ok!(submit_commitment_for_header(test_header(1)));
+			assert_ok!(submit_commitment_for_header(test_header(2)));
+			assert_noop!(
+				submit_commitment_for_header(test_header(3)),
+				<Error<TestRuntime>>::TooManyRequests,
+			);
+
+			next_block();
+			assert_ok!(submit_commitment_for_header(test_header(3)));
+		})
+	}
+
+	#[test]
+	fn old_headers_are_pruned() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			for number in 1..=5 {
+				next_block();
+				assert_ok!(submit_commitment_for_header(test_header(number)));
+			}
+
+			assert_eq!(Pallet::<TestRuntime>::finalized_header(test_header(0).hash()), None);
+			assert!(Pallet::<TestRuntime>::finalized_header(test_header(1).hash()).is_some());
+		})
+	}
+
+	#[test]
+	#[should_panic(expected = "HeadersToKeep must be larger than zero")]
+	fn integrity_test_fails_if_headers_to_keep_is_zero() {
+		run_test(|| {
+			HeadersToKeep::set(&0);
+			<Pallet<TestRuntime> as frame_support::traits::Hooks<u64>>::integrity_test();
+		})
+	}
+
+	#[test]
+	fn required_signatures_is_supermajority() {
+		assert_eq!(required_signatures(0), 1);
+		assert_eq!(required_signatures(1), 1);
+		assert_eq!(required_signatures(3), 3);
+		assert_eq!(required_signatures(4), 3);
+		assert_eq!(required_signatures(5), 4);
+		assert_eq!(required_signatures(100), 67);
+	}
+}
@@ -0,0 +1,215 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Merkle trees that are used by BEEFY light client.
+//!
+//! BEEFY validators are committing to the MMR root of the bridged chain. Every MMR leaf contains
+//! the root of the binary Merkle tree, built from the public keys of the next BEEFY validator
+//! set. Both trees are using Keccak-256 hashing.
+
+use crate::{BeefyHash, MmrLeafProof};
+
+use codec::Encode;
+use sp_std::{vec, vec::Vec};
+
+/// Hash the leaf of the validator set Merkle tree.
+pub fn hash_leaf(leaf: &[u8]) -> BeefyHash {
+	sp_io::hashing::keccak_256(leaf).into()
+}
+
+/// Hash two children nodes of both Merkle trees.
+pub fn hash_node(left: &BeefyHash, right: &BeefyHash) -> BeefyHash {
+	let mut concat = [0u8; 64];
+	concat[..32].copy_from_slice(left.as_bytes());
+	concat[32..].copy_from_slice(right.as_bytes());
+	sp_io::hashing::keccak_256(&concat).into()
+}
+
+/// Compute root of the binary Merkle tree, built from given leaves.
+///
+/// If the number of nodes at some level is odd, the last node is promoted to the upper level
+/// without hashing. Root of the empty tree is the zero hash.
+pub fn merkle_root<L: AsRef<[u8]>>(leaves: impl IntoIterator<Item = L>) -> BeefyHash {
+	let mut layer = hash_leaves(leaves);
+	while layer.len() > 1 {
+		layer = next_layer(&layer);
+	}
+	layer.pop().unwrap_or_default()
+}
+
+/// Compute Merkle proof of the leaf with given index.
+///
+/// The proof contains sibling nodes of all levels (bottom-up), where the node has a sibling.
+/// Returns `None` if the index is out of bounds.
+pub fn merkle_proof<L: AsRef<[u8]>>(
+	leaves: impl IntoIterator<Item = L>,
+	mut index: usize,
+) -> Option<Vec<BeefyHash>> {
+	let mut layer = hash_leaves(leaves);
+	if index >= layer.len() {
+		return None
+	}
+
+	let mut proof = Vec::new();
+	while layer.len() > 1 {
+		if let Some(sibling) = layer.get(index ^ 1) {
+			proof.push(*sibling);
+		}
+		layer = next_layer(&layer);
+		index /= 2;
+	}
+
+	Some(proof)
+}
+
+/// Returns maximal length of the proof of the leaf of Merkle tree, built from `leaves_count`
+/// leaves.
+pub fn max_proof_len(leaves_count: usize) -> usize {
+	let mut width = leaves_count;
+	let mut proof_len = 0;
+	while width > 1 {
+		proof_len += 1;
+		width = (width + 1) / 2;
+	}
+	proof_len
+}
+
+/// Verify that the `leaf` is the leaf with given `index` of the Merkle tree with given `root`,
+/// built from `leaves_count` leaves.
+pub fn verify_merkle_proof(
+	root: BeefyHash,
+	leaf: &[u8],
+	mut index: usize,
+	leaves_count: usize,
+	proof: &[BeefyHash],
+) -> bool {
+	if index >= leaves_count {
+		return false
+	}
+
+	let mut proof = proof.iter();
+	let mut hash = hash_leaf(leaf);
+	let mut width = leaves_count;
+	while width > 1 {
+		if index % 2 == 1 {
+			hash = match proof.next() {
+				Some(sibling) => hash_node(sibling, &hash),
+				None => return false,
+			};
+		} else if index + 1 < width {
+			hash = match proof.next() {
+				Some(sibling) => hash_node(&hash, sibling),
+				None => return false,
+			};
+		}
+
+		index /= 2;
+		width = (width + 1) / 2;
+	}
+
+	proof.next().is_none() && hash == root
+}
+
+/// Verify that the `leaf` is a part of the MMR with given `root`.
+pub fn verify_mmr_leaf_proof(root: BeefyHash, leaf: &impl Encode, proof: &MmrLeafProof) -> bool {
+	if proof.leaf_index >= proof.leaf_count {
+		return false
+	}
+
+	let leaf_hash = leaf.using_encoded(hash_leaf);
+	let mmr_size = mmr_lib::leaf_index_to_mmr_size(proof.leaf_count - 1);
+	let leaf_position = mmr_lib::leaf_index_to_pos(proof.leaf_index);
+	mmr_lib::MerkleProof::<BeefyHash, MmrMerge>::new(mmr_size, proof.items.clone())
+		.verify(root, vec![(leaf_position, leaf_hash)])
+		.unwrap_or(false)
+}
+
+/// Merge operation of the BEEFY MMR nodes.
+pub(crate) struct MmrMerge;
+
+impl mmr_lib::Merge for MmrMerge {
+	type Item = BeefyHash;
+
+	fn merge(left: &Self::Item, right: &Self::Item) -> Self::Item {
+		hash_node(left, right)
+	}
+}
+
+fn hash_leaves<L: AsRef<[u8]>>(leaves: impl IntoIterator<Item = L>) -> Vec<BeefyHash> {
+	leaves.into_iter().map(|leaf| hash_leaf(leaf.as_ref())).collect()
+}
+
+fn next_layer(layer: &[BeefyHash]) -> Vec<BeefyHash> {
+	layer
+		.chunks(2)
+		.map(|pair| match pair {
+			[left, right] => hash_node(left, right),
+			[single] => *single,
+			_ => unreachable!("chunks(2) yields one or two items; qed"),
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn leaves(count: u8) -> Vec<Vec<u8>> {
+		(0..count).map(|i| vec![i; 33]).collect()
+	}
+
+	#[test]
+	fn merkle_proofs_are_verified_for_all_leaves() {
+		for count in 1..10 {
+			let leaves = leaves(count);
+			let root = merkle_root(&leaves);
+			for (index, leaf) in leaves.iter().enumerate() {
+				let proof = merkle_proof(&leaves, index).unwrap();
+				assert!(proof.len() <= max_proof_len(leaves.len()));
+				assert!(verify_merkle_proof(root, leaf, index, leaves.len(), &proof));
+			}
+		}
+	}
+
+	#[test]
+	fn merkle_proof_is_rejected_for_wrong_leaf_or_index() {
+		let leaves = leaves(5);
+		let root = merkle_root(&leaves);
+		let proof = merkle_proof(&leaves, 2).unwrap();
+
+		assert!(!verify_merkle_proof(root, &leaves[3], 2, leaves.len(), &proof));
+		assert!(!verify_merkle_proof(root, &leaves[2], 3, leaves.len(), &proof));
+		assert!(!verify_merkle_proof(root, &leaves[2], 2, leaves.len() + 1, &proof));
+		assert!(!verify_merkle_proof(root, &leaves[2], 5, leaves.len(), &proof));
+		assert_eq!(merkle_proof(&leaves, 5), None);
+	}
+
+	#[test]
+	fn mmr_leaf_proof_is_verified() {
+		let first_leaf_hash = 0u32.using_encoded(hash_leaf);
+		let second_leaf_hash = 1u32.using_encoded(hash_leaf);
+		let root = hash_node(&first_leaf_hash, &second_leaf_hash);
+		let proof = MmrLeafProof { leaf_index: 1, leaf_count: 2, items: vec![first_leaf_hash] };
+
+		assert!(verify_mmr_leaf_proof(root, &1u32, &proof));
+		assert!(!verify_mmr_leaf_proof(root, &2u32, &proof));
+		assert!(!verify_mmr_leaf_proof(
+			root,
+			&1u32,
+			&MmrLeafProof { leaf_index: 2, ..proof.clone() }
+		));
+	}
+}
@@ -0,0 +1,200 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use crate::{
+	test_utils, BeefyHash, Commitment, MmrLeaf, MmrLeafProof, MmrLeafVersion, SignedCommitment,
+	ValidatorSetId, ValidatorSetInfo, SUPPORTED_MMR_LEAF_MAJOR_VERSION,
+};
+
+use bp_runtime::Chain;
+use frame_support::{construct_runtime, parameter_types, weights::Weight};
+use secp256k1::SecretKey;
+use sp_runtime::{
+	testing::{Header, H256},
+	traits::{BlakeTwo256, Header as HeaderT, IdentityLookup},
+	AnySignature, Perbill,
+};
+
+pub type AccountId = u64;
+pub type TestHeader = crate::BridgedHeader<TestRuntime, ()>;
+pub type TestNumber = crate::BridgedBlockNumber<TestRuntime, ()>;
+pub type TestSignedCommitment = crate::BridgedSignedCommitment<TestRuntime, ()>;
+pub type TestMmrLeaf = crate::BridgedMmrLeaf<TestRuntime, ()>;
+
+type Block = frame_system::mocking::MockBlock<TestRuntime>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+use crate as beefy;
+
+construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Beefy: beefy::{Pallet},
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type SystemWeightInfo = ();
+	type DbWeight = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const MaxRequests: u32 = 2;
+	pub storage HeadersToKeep: u32 = 5;
+}
+
+impl beefy::Config for TestRuntime {
+	type BridgedChain = TestBridgedChain;
+	type MaxRequests = MaxRequests;
+	type HeadersToKeep = HeadersToKeep;
+	type WeightInfo = ();
+}
+
+#[derive(Debug)]
+pub struct TestBridgedChain;
+
+impl Chain for TestBridgedChain {
+	type BlockNumber = <TestRuntime as frame_system::Config>::BlockNumber;
+	type Hash = <TestRuntime as frame_system::Config>::Hash;
+	type Hasher = <TestRuntime as frame_system::Config>::Hashing;
+	type Header = <TestRuntime as frame_system::Config>::Header;
+
+	type AccountId = AccountId;
+	type Balance = u64;
+	type Index = u64;
+	type Signature = AnySignature;
+}
+
+/// Number of validators in every test validator set.
+pub const VALIDATORS_COUNT: u32 = 5;
+
+/// Commitment, signed by test validators, along with the rest of `submit_commitment` arguments.
+pub struct TestCommitment {
+	pub signed_commitment: TestSignedCommitment,
+	pub validator_proofs: Vec<Vec<BeefyHash>>,
+	pub mmr_leaf: TestMmrLeaf,
+	pub mmr_proof: MmrLeafProof,
+}
+
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	sp_io::TestExternalities::new(Default::default()).execute_with(test)
+}
+
+pub fn test_header(num: TestNumber) -> TestHeader {
+	// We wrap the call to avoid explicit type annotations in our tests
+	bp_test_utils::test_header(num)
+}
+
+/// Secret keys of validators from the set with given id.
+pub fn validator_secrets(set_id: ValidatorSetId) -> Vec<SecretKey> {
+	test_utils::validator_secrets(set_id, VALIDATORS_COUNT)
+}
+
+/// Details of validator set with given id.
+pub fn validator_set_info(set_id: ValidatorSetId) -> ValidatorSetInfo {
+	test_utils::validator_set_info(set_id, VALIDATORS_COUNT)
+}
+
+/// Initialization data with validator sets `0` and `1`.
+///
+/// The first MMR leaf is appended by the block#1, so the block#N is appending the leaf#N-1.
+pub fn init_data(header: TestHeader) -> crate::InitializationData<TestHeader> {
+	crate::InitializationData {
+		header: Box::new(header),
+		validator_set: validator_set_info(0),
+		next_validator_set: validator_set_info(1),
+		first_mmr_leaf_block: 1,
+		is_halted: false,
+	}
+}
+
+/// Create commitment that finalizes given header.
+///
+/// The commitment is created for the child of the header, so it is valid.
+pub fn commitment_for_header(
+	header: &TestHeader,
+	set_id: ValidatorSetId,
+	next_set_id: ValidatorSetId,
+	signers: usize,
+) -> TestCommitment {
+	commitment_for_header_at_block(header, *header.number() + 1, set_id, next_set_id, signers)
+}
+
+/// Create commitment for given block, that references given header.
+///
+/// The commitment is signed by first `signers` validators of the set `set_id`. The latest leaf
+/// of the committed MMR is referencing the header and is announcing `next_set_id` as the next
+/// validator set.
+pub fn commitment_for_header_at_block(
+	header: &TestHeader,
+	block_number: TestNumber,
+	set_id: ValidatorSetId,
+	next_set_id: ValidatorSetId,
+	signers: usize,
+) -> TestCommitment {
+	let mmr_leaf = MmrLeaf {
+		version: MmrLeafVersion::new(SUPPORTED_MMR_LEAF_MAJOR_VERSION, 0),
+		parent_number_and_hash: (*header.number(), header.hash()),
+		beefy_next_authority_set: validator_set_info(next_set_id),
+		parachain_heads: Default::default(),
+	};
+	let (mmr_root, mmr_proof) = test_utils::mmr_root_and_proof(&mmr_leaf, block_number);
+	let commitment = Commitment { payload: mmr_root, block_number, validator_set_id: set_id };
+	let (signatures, validator_proofs) =
+		test_utils::sign_commitment(&commitment, &validator_secrets(set_id), signers);
+
+	TestCommitment {
+		signed_commitment: SignedCommitment { commitment, signatures },
+		validator_proofs,
+		mmr_leaf,
+		mmr_proof,
+	}
+}
@@ -0,0 +1,154 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Utilities for testing and benchmarking the BEEFY Bridge Pallet.
+//!
+//! Although the name implies that it is used by tests, it shouldn't be be used _directly_ by tests.
+//! Instead these utilities should be used by the Mock runtime, which in turn is used by tests.
+//!
+//! On the other hand, they may be used directly by the benchmark module.
+
+// Since this is test code it's fine that not everything is used
+#![allow(dead_code)]
+
+use crate::{
+	merkle, BeefyHash, BeefyValidatorAddress, BeefyValidatorSignature, Commitment, MmrLeafProof,
+	ValidatorSetId, ValidatorSetInfo,
+};
+
+use codec::Encode;
+use secp256k1::{Message, PublicKey, SecretKey};
+use sp_std::{vec, vec::Vec};
+
+/// Secret keys of validators from the set with given id.
+pub fn validator_secrets(set_id: ValidatorSetId, count: u32) -> Vec<SecretKey> {
+	(0..count)
+		.map(|index| {
+			let mut seed = [0u8; 32];
+			seed[16..24].copy_from_slice(&set_id.to_be_bytes());
+			seed[24..28].copy_from_slice(&(index + 1).to_be_bytes());
+			SecretKey::parse(&seed).expect("seed is non-zero and is less than curve order; qed")
+		})
+		.collect()
+}
+
+/// Ethereum address of the validator with given secret key.
+pub fn validator_address(secret: &SecretKey) -> BeefyValidatorAddress {
+	let public = PublicKey::from_secret_key(secret).serialize();
+	let mut raw_public = [0u8; 64];
+	raw_public.copy_from_slice(&public[1..]);
+	crate::validator_address(&raw_public)
+}
+
+/// Details of validator set with given id.
+pub fn validator_set_info(set_id: ValidatorSetId, count: u32) -> ValidatorSetInfo {
+	let validators = validator_secrets(set_id, count)
+		.iter()
+		.map(validator_address)
+		.collect::<Vec<_>>();
+	ValidatorSetInfo { id: set_id, len: count, root: merkle::merkle_root(&validators) }
+}
+
+/// Sign commitment by first `signers` validators of the set.
+///
+/// Returns commitment signatures, along with Merkle proofs of signers addresses.
+pub fn sign_commitment<BlockNumber: Encode>(
+	commitment: &Commitment<BlockNumber>,
+	validators: &[SecretKey],
+	signers: usize,
+) -> (Vec<Option<BeefyValidatorSignature>>, Vec<Vec<BeefyHash>>) {
+	let message = Message::parse(&sp_io::hashing::keccak_256(&commitment.encode()));
+	let addresses = validators.iter().map(validator_address).collect::<Vec<_>>();
+	let signatures = validators
+		.iter()
+		.enumerate()
+		.map(|(index, secret)| if index < signers { Some(sign(&message, secret)) } else { None })
+		.collect();
+	let validator_proofs = (0..signers)
+		.map(|index| {
+			merkle::merkle_proof(&addresses, index)
+				.expect("index is less than validators count; qed")
+		})
+		.collect();
+
+	(signatures, validator_proofs)
+}
+
+/// Returns root of the MMR with `leaf_count` leaves, where `leaf` is the latest leaf, along with
+/// the proof of this leaf.
+pub fn mmr_root_and_proof(leaf: &impl Encode, leaf_count: u64) -> (BeefyHash, MmrLeafProof) {
+	let mut mmr = mmr_lib::MMR::<BeefyHash, merkle::MmrMerge, _>::new(0, UncommittedMmrStore);
+	for index in 0..leaf_count - 1 {
+		mmr.push(index.using_encoded(merkle::hash_leaf))
+			.expect("MMR store never fails; qed");
+	}
+	let leaf_position = mmr
+		.push(leaf.using_encoded(merkle::hash_leaf))
+		.expect("MMR store never fails; qed");
+	let root = mmr.get_root().expect("MMR is not empty; qed");
+	let proof = mmr.gen_proof(vec![leaf_position]).expect("leaf is in the MMR; qed");
+
+	(
+		root,
+		MmrLeafProof {
+			leaf_index: leaf_count - 1,
+			leaf_count,
+			items: proof.proof_items().to_vec(),
+		},
+	)
+}
+
+/// Returns root of the MMR with `2^proof_len` leaves, where `leaf` is the latest leaf, along with
+/// the proof of this leaf.
+///
+/// Such MMR has a single peak, so the proof has exactly `proof_len` items. Unlike
+/// `mmr_root_and_proof`, the whole MMR isn't built here, so it is cheap to generate large proofs.
+pub fn perfect_mmr_root_and_proof(leaf: &impl Encode, proof_len: u32) -> (BeefyHash, MmrLeafProof) {
+	let items = (0..proof_len)
+		.map(|index| index.using_encoded(merkle::hash_leaf))
+		.collect::<Vec<_>>();
+	// the latest leaf is always the right child, so all its siblings are on the left
+	let root = items.iter().fold(leaf.using_encoded(merkle::hash_leaf), |hash, sibling| {
+		merkle::hash_node(sibling, &hash)
+	});
+	let leaf_count = 1u64 << proof_len;
+
+	(root, MmrLeafProof { leaf_index: leaf_count - 1, leaf_count, items })
+}
+
+/// Sign message with given secret key.
+fn sign(message: &Message, secret: &SecretKey) -> BeefyValidatorSignature {
+	let (signature, recovery_id) = secp256k1::sign(message, secret);
+	let mut raw_signature = [0u8; 65];
+	raw_signature[..64].copy_from_slice(&signature.serialize());
+	raw_signature[64] = recovery_id.serialize();
+	BeefyValidatorSignature::from_raw(raw_signature)
+}
+
+/// MMR store that is never storing anything.
+///
+/// All MMR nodes are kept in the MMR batch, which we never commit.
+struct UncommittedMmrStore;
+
+impl mmr_lib::MMRStore<BeefyHash> for UncommittedMmrStore {
+	fn get_elem(&self, _pos: u64) -> mmr_lib::Result<Option<BeefyHash>> {
+		Ok(None)
+	}
+
+	fn append(&mut self, _pos: u64, _elems: Vec<BeefyHash>) -> mmr_lib::Result<()> {
+		Ok(())
+	}
+}
@@ -0,0 +1,59 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_bridge_beefy`.
+//!
+//! The benchmarks live in the `benchmarking` module of this pallet. The pallet isn't a part of
+//! any runtime of this repository yet, so the weights below are placeholders. They must be
+//! regenerated with the `benchmark` command of the node, which runtime includes the pallet.
+
+#![allow(clippy::all)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for `pallet_bridge_beefy`.
+pub trait WeightInfo {
+	fn submit_commitment(s: u32, m: u32) -> Weight;
+}
+
+/// Weights for `pallet_bridge_beefy`, that are using runtime database weights.
+pub struct MillauWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for MillauWeight<T> {
+	fn submit_commitment(s: u32, m: u32) -> Weight {
+		(100_000_000 as Weight)
+			.saturating_add((60_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((2_000_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn submit_commitment(s: u32, m: u32) -> Weight {
+		(100_000_000 as Weight)
+			.saturating_add((60_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((2_000_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+}
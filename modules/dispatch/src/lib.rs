@@ -18,15 +18,20 @@
 //!
 //! The messages are interpreted directly as runtime `Call`. We attempt to decode
 //! them and then dispatch as usual. To prevent compatibility issues, the Calls have
-//! to include a `spec_version`. This will be checked before dispatch. In the case of
-//! a successful dispatch an event is emitted.
+//! to include a `spec_version`. This will be checked before dispatch. The runtime may
+//! declare a set of previous spec versions, whose `Call` encoding is compatible with the
+//! current runtime. Messages, prepared for these versions, are dispatched as usual, so
+//! runtime upgrades do not necessarily break messages that are already in flight. In the
+//! case of a successful dispatch an event is emitted.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 // Generated by `decl_event!`
 #![allow(clippy::unused_unit)]
 
-use bp_message_dispatch::{CallOrigin, MessageDispatch, MessagePayload, SpecVersion};
+use bp_message_dispatch::{
+	CallOrigin, MessageDispatch, MessagePayload, SpecVersion, TargetAccountNonce,
+};
 use bp_runtime::{
 	derive_account_id,
 	messages::{DispatchFeePayment, MessageDispatchResult},
@@ -37,7 +42,7 @@ use frame_support::{
 	dispatch::Dispatchable,
 	ensure,
 	traits::{Contains, Get},
-	weights::{extract_actual_weight, GetDispatchInfo},
+	weights::{extract_actual_weight, GetDispatchInfo, RuntimeDbWeight},
 };
 use frame_system::RawOrigin;
 use sp_runtime::traits::{BadOrigin, Convert, IdentifyAccount, MaybeDisplay, Verify};
@@ -91,6 +96,14 @@ pub mod pallet {
 		/// `Call` which has been encoded using previous `spec_version`, then we might end
 		/// up with decoding error, instead of `MessageVersionSpecMismatch`.
 		type EncodedCall: Decode + Encode + Into<Result<<Self as Config<I>>::Call, ()>>;
+		/// Spec versions of previous runtimes, whose `Call` encoding is compatible with the
+		/// current runtime.
+		///
+		/// Messages with the current spec version are always accepted. Messages with spec
+		/// version from this set are accepted too. All other messages are rejected with
+		/// `MessageVersionSpecMismatch` event. Use `frame_support::traits::Nothing` if only
+		/// messages with the current spec version are accepted.
+		type CompatibleSpecVersions: Contains<SpecVersion>;
 		/// A type which can be turned into an AccountId from a 256-bit hash.
 		///
 		/// Used when deriving target chain AccountIds from source chain AccountIds.
@@ -139,6 +152,16 @@ pub mod pallet {
 		/// Phantom member, never used. Needed to handle multiple pallet instances.
 		_Dummy(PhantomData<I>),
 	}
+
+	/// Nonces of target accounts, used to dispatch messages with `CallOrigin::TargetAccount`
+	/// origin.
+	///
+	/// The nonce is a part of the digest, signed by the target account. It is incremented
+	/// whenever the signature is verified, so the same signed message can't be dispatched twice.
+	#[pallet::storage]
+	#[pallet::getter(fn target_account_nonce)]
+	pub type TargetAccountNonces<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, TargetAccountNonce, ValueQuery>;
 }
 
 impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId> for Pallet<T, I> {
@@ -180,14 +203,17 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId>
 		};
 
 		// verify spec version
-		// (we want it to be the same, because otherwise we may decode Call improperly)
+		// (we want it to be the same or compatible, because otherwise we may decode Call
+		// improperly)
 		let mut dispatch_result = MessageDispatchResult {
 			dispatch_result: false,
 			unspent_weight: message.weight,
 			dispatch_fee_paid_during_dispatch: false,
 		};
 		let expected_version = <T as frame_system::Config>::Version::get().spec_version;
-		if message.spec_version != expected_version {
+		if message.spec_version != expected_version &&
+			!T::CompatibleSpecVersions::contains(&message.spec_version)
+		{
 			log::trace!(
 				"Message {:?}/{:?}: spec_version mismatch. Expected {:?}, got {:?}",
 				source_chain,
@@ -220,6 +246,7 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId>
 		};
 
 		// prepare dispatch origin
+		let mut origin_overhead_weight: bp_message_dispatch::Weight = 0;
 		let origin_account = match message.origin {
 			CallOrigin::SourceRoot => {
 				let hex_id =
@@ -229,15 +256,17 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId>
 				target_id
 			},
 			CallOrigin::TargetAccount(source_account_id, target_public, target_signature) => {
+				let target_account = target_public.into_account();
+				let target_account_nonce = TargetAccountNonces::<T, I>::get(&target_account);
 				let digest = account_ownership_digest(
 					&call,
 					source_account_id,
 					message.spec_version,
 					source_chain,
 					target_chain,
+					target_account_nonce,
 				);
 
-				let target_account = target_public.into_account();
 				if !target_signature.verify(&digest[..], &target_account) {
 					log::trace!(
 						target: "runtime::bridge-dispatch",
@@ -251,6 +280,13 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId>
 					return dispatch_result
 				}
 
+				// the signature has been verified => it can't be used again
+				TargetAccountNonces::<T, I>::insert(
+					&target_account,
+					target_account_nonce.wrapping_add(1),
+				);
+				origin_overhead_weight = target_account_origin_overhead(T::DbWeight::get());
+
 				log::trace!(target: "runtime::bridge-dispatch", "Target Account: {:?}", &target_account);
 				target_account
 			},
@@ -280,7 +316,7 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId>
 		// (we want passed weight to be at least equal to pre-dispatch weight of the call
 		// because otherwise Calls may be dispatched at lower price)
 		let dispatch_info = call.get_dispatch_info();
-		let expected_weight = dispatch_info.weight;
+		let expected_weight = dispatch_info.weight.saturating_add(origin_overhead_weight);
 		if message.weight < expected_weight {
 			log::trace!(
 				target: "runtime::bridge-dispatch",
@@ -329,7 +365,10 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId>
 		let result = call.dispatch(origin);
 		let actual_call_weight = extract_actual_weight(&result, &dispatch_info);
 		dispatch_result.dispatch_result = result.is_ok();
-		dispatch_result.unspent_weight = message.weight.saturating_sub(actual_call_weight);
+		dispatch_result.unspent_weight = message
+			.weight
+			.saturating_sub(actual_call_weight)
+			.saturating_sub(origin_overhead_weight);
 
 		log::trace!(
 			target: "runtime::bridge-dispatch",
@@ -395,17 +434,29 @@ where
 	}
 }
 
+/// Weight of the `CallOrigin::TargetAccount` origin verification.
+///
+/// The nonce of the target account is read and updated when the message is dispatched with this
+/// origin, so the message weight must cover both the call weight and this overhead.
+pub fn target_account_origin_overhead(db_weight: RuntimeDbWeight) -> bp_message_dispatch::Weight {
+	db_weight.reads_writes(1, 1)
+}
+
 /// Target account ownership digest from the source chain.
 ///
 /// The byte vector returned by this function will be signed with a target chain account
 /// private key. This way, the owner of `source_account_id` on the source chain proves that
 /// the target chain account private key is also under his control.
+///
+/// The `target_account_nonce` must be equal to the current nonce of the target account at the
+/// target chain (see `Pallet::target_account_nonce`).
 pub fn account_ownership_digest<Call, AccountId, SpecVersion>(
 	call: &Call,
 	source_account_id: AccountId,
 	target_spec_version: SpecVersion,
 	source_chain_id: ChainId,
 	target_chain_id: ChainId,
+	target_account_nonce: TargetAccountNonce,
 ) -> Vec<u8>
 where
 	Call: Encode,
@@ -418,10 +469,29 @@ where
 	target_spec_version.encode_to(&mut proof);
 	source_chain_id.encode_to(&mut proof);
 	target_chain_id.encode_to(&mut proof);
+	target_account_nonce.encode_to(&mut proof);
 
 	proof
 }
 
+/// Getting storage keys for the dispatch pallet state.
+pub mod storage_keys {
+	use super::*;
+	use sp_core::storage::StorageKey;
+
+	/// Storage key of the target account nonce in the runtime storage.
+	pub fn target_account_nonce_key<AccountId: Encode>(
+		pallet_prefix: &str,
+		target_account: &AccountId,
+	) -> StorageKey {
+		bp_runtime::storage_map_final_key_blake2_128concat(
+			pallet_prefix,
+			"TargetAccountNonces",
+			&target_account.encode(),
+		)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	// From construct_runtime macro
@@ -456,13 +526,17 @@ mod tests {
 	}
 
 	#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
-	pub struct TestSignature(AccountId);
+	pub struct TestSignature(AccountId, TargetAccountNonce);
 
 	impl Verify for TestSignature {
 		type Signer = TestAccountPublic;
 
-		fn verify<L: sp_runtime::traits::Lazy<[u8]>>(&self, _msg: L, signer: &AccountId) -> bool {
-			self.0 == *signer
+		fn verify<L: sp_runtime::traits::Lazy<[u8]>>(
+			&self,
+			mut msg: L,
+			signer: &AccountId,
+		) -> bool {
+			self.0 == *signer && msg.get().ends_with(&self.1.encode())
 		}
 	}
 
@@ -486,7 +560,7 @@ mod tests {
 			UncheckedExtrinsic = UncheckedExtrinsic,
 		{
 			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-			Dispatch: call_dispatch::{Pallet, Call, Event<T>},
+			Dispatch: call_dispatch::{Pallet, Call, Storage, Event<T>},
 		}
 	}
 
@@ -532,6 +606,7 @@ mod tests {
 		type Call = Call;
 		type CallFilter = TestCallFilter;
 		type EncodedCall = EncodedCall;
+		type CompatibleSpecVersions = TestCompatibleSpecVersions;
 		type AccountIdConverter = AccountIdConverter;
 	}
 
@@ -552,7 +627,16 @@ mod tests {
 		}
 	}
 
+	pub struct TestCompatibleSpecVersions;
+
+	impl Contains<SpecVersion> for TestCompatibleSpecVersions {
+		fn contains(spec_version: &SpecVersion) -> bool {
+			*spec_version == COMPATIBLE_SPEC_VERSION
+		}
+	}
+
 	const TEST_SPEC_VERSION: SpecVersion = 0;
+	const COMPATIBLE_SPEC_VERSION: SpecVersion = 1;
 	const TEST_WEIGHT: Weight = 1_000_000_000;
 
 	fn new_test_ext() -> sp_io::TestExternalities {
@@ -572,6 +656,7 @@ mod tests {
 			weight: TEST_WEIGHT,
 			origin,
 			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expiry: None,
 			call: EncodedCall(call.encode()),
 		}
 	}
@@ -591,7 +676,7 @@ mod tests {
 		AccountId,
 		<TestRuntime as Config>::BridgeMessageId,
 	>>::Message {
-		let origin = CallOrigin::TargetAccount(1, TestAccountPublic(1), TestSignature(1));
+		let origin = CallOrigin::TargetAccount(1, TestAccountPublic(1), TestSignature(1, 0));
 		prepare_message(origin, call)
 	}
 
@@ -646,6 +731,41 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn should_dispatch_message_with_compatible_spec_version() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let mut message = prepare_root_message(Call::System(
+				<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3]),
+			));
+			message.spec_version = COMPATIBLE_SPEC_VERSION;
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message),
+				|_, _| unreachable!(),
+			);
+			assert!(result.dispatch_result);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageDispatched(
+						SOURCE_CHAIN_ID,
+						id,
+						Ok(())
+					)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
 	#[test]
 	fn should_fail_on_weight_mismatch() {
 		new_test_ext().execute_with(|| {
@@ -690,7 +810,8 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			let id = [0; 4];
 
-			let call_origin = CallOrigin::TargetAccount(1, TestAccountPublic(1), TestSignature(99));
+			let call_origin =
+				CallOrigin::TargetAccount(1, TestAccountPublic(1), TestSignature(99, 0));
 			let message = prepare_message(
 				call_origin,
 				Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3])),
@@ -1007,6 +1128,48 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn should_reject_replayed_message_from_target_origin() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![]));
+			let message = prepare_target_message(call);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.clone()),
+				|_, _| unreachable!(),
+			);
+			assert!(result.dispatch_result);
+			assert_eq!(Dispatch::target_account_nonce(1), 1);
+
+			System::set_block_number(2);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message),
+				|_, _| unreachable!(),
+			);
+			assert!(!result.dispatch_result);
+			assert_eq!(Dispatch::target_account_nonce(1), 1);
+
+			assert_eq!(
+				System::events().last().map(|record| record.event.clone()),
+				Some(Event::Dispatch(
+					call_dispatch::Event::<TestRuntime>::MessageSignatureMismatch(
+						SOURCE_CHAIN_ID,
+						id
+					)
+				)),
+			);
+		})
+	}
+
 	#[test]
 	fn should_dispatch_bridge_message_from_source_origin() {
 		new_test_ext().execute_with(|| {
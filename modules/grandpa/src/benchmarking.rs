@@ -41,16 +41,18 @@
 
 use crate::*;
 
+use codec::Encode;
+
 use bp_test_utils::{
-	accounts, make_justification_for_header, JustificationGeneratorParams, TEST_GRANDPA_ROUND,
-	TEST_GRANDPA_SET_ID,
+	accounts, make_equivocation_proof, make_justification_for_header, JustificationGeneratorParams,
+	TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID,
 };
 use frame_benchmarking::{benchmarks_instance_pallet, whitelisted_caller};
 use frame_support::traits::Get;
 use frame_system::RawOrigin;
-use sp_finality_grandpa::AuthorityId;
-use sp_runtime::traits::Zero;
-use sp_std::vec::Vec;
+use sp_finality_grandpa::{AuthorityId, ConsensusLog, ScheduledChange, GRANDPA_ENGINE_ID};
+use sp_runtime::{traits::Zero, DigestItem};
+use sp_std::{vec, vec::Vec};
 
 // The maximum number of vote ancestries to include in a justification.
 //
@@ -75,7 +77,10 @@ fn prepare_benchmark_data<T: Config<I>, I: 'static>(
 	precommits: u32,
 	ancestors: u32,
 ) -> (BridgedHeader<T, I>, GrandpaJustification<BridgedHeader<T, I>>) {
-	let authority_list = accounts(precommits as u16)
+	// the pallet rejects justifications with redundant precommits, so the authorities set is
+	// selected so that `precommits` signatures are exactly the threshold
+	let authorities_count = precommits + (precommits - 1) / 2;
+	let authority_list = accounts(authorities_count as u16)
 		.iter()
 		.map(|id| (AuthorityId::from(*id), 1))
 		.collect::<Vec<_>>();
@@ -102,6 +107,38 @@ fn prepare_benchmark_data<T: Config<I>, I: 'static>(
 	(header, justification)
 }
 
+/// Prepare equivocation proof to submit using `report_equivocation`.
+fn prepare_equivocation_proof<T: Config<I>, I: 'static>(
+) -> GrandpaEquivocationProof<BridgedHeader<T, I>> {
+	let authority = accounts(1)[0];
+	initialize_bridge::<T, I>(InitializationData {
+		header: Box::new(bp_test_utils::test_header(Zero::zero())),
+		authority_list: vec![(AuthorityId::from(authority), 1)],
+		set_id: TEST_GRANDPA_SET_ID,
+		is_halted: false,
+	});
+
+	make_equivocation_proof(&authority, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID)
+}
+
+/// Prepare header with forced change to submit using `approve_forced_change`.
+fn prepare_forced_change_header<T: Config<I>, I: 'static>() -> BridgedHeader<T, I> {
+	let next_authorities = accounts(MAX_VALIDATOR_SET_SIZE as u16)
+		.iter()
+		.map(|id| (AuthorityId::from(*id), 1))
+		.collect::<Vec<_>>();
+	let consensus_log = ConsensusLog::<BridgedBlockNumber<T, I>>::ForcedChange(
+		Zero::zero(),
+		ScheduledChange { next_authorities, delay: Zero::zero() },
+	);
+
+	let mut header: BridgedHeader<T, I> = bp_test_utils::test_header(header_number::<T, I, _>());
+	header
+		.digest_mut()
+		.push(DigestItem::Consensus(GRANDPA_ENGINE_ID, consensus_log.encode()));
+	header
+}
+
 benchmarks_instance_pallet! {
 	// This is the "gold standard" benchmark for this extrinsic, and it's what should be used to
 	// annotate the weight in the pallet.
@@ -118,4 +155,20 @@ benchmarks_instance_pallet! {
 		assert_eq!(<BestFinalized<T, I>>::get(), expected_hash);
 		assert!(<ImportedHeaders<T, I>>::contains_key(expected_hash));
 	}
+
+	report_equivocation {
+		let caller: T::AccountId = whitelisted_caller();
+		let equivocation_proof = prepare_equivocation_proof::<T, I>();
+	}: report_equivocation(RawOrigin::Signed(caller), equivocation_proof)
+	verify {
+		assert!(<IsHalted<T, I>>::get());
+	}
+
+	// The worst case is the change to the set of `MAX_VALIDATOR_SET_SIZE` authorities.
+	approve_forced_change {
+		let header = prepare_forced_change_header::<T, I>();
+	}: approve_forced_change(RawOrigin::Root, Box::new(header))
+	verify {
+		assert!(<PendingForcedChange<T, I>>::exists());
+	}
 }
@@ -24,13 +24,31 @@
 //!
 //! The pallet is responsible for tracking GRANDPA validator set hand-offs. We only import headers
 //! with justifications signed by the current validator set we know of. The header is inspected for
-//! a `ScheduledChanges` digest item, which is then used to update to next validator set.
+//! a `ScheduledChanges` digest item, which is then used to update to next validator set. If the
+//! change has non-zero delay, it is remembered and enacted once the header at which it takes effect
+//! is imported. The bridged chain persists justification of this header and it is signed by the
+//! current validator set. No other headers are accepted until the enactment header is imported.
+//!
+//! Relayers that are far behind the bridged chain may use `submit_finality_proofs` call to import
+//! a chain of headers (possibly spanning multiple validator set changes) in a single transaction.
+//! Every header of the chain is counted as a separate request by the rate limiter.
+//!
+//! Forced changes (`ForcedChange` digest item) are normally issued when GRANDPA is stalled, so
+//! they can't be verified using the current validator set. They are only accepted if the
+//! `AllowForcedChanges` is set to `true` and the header containing the change has been approved
+//! by the pallet owner or root (using `approve_forced_change` call). Justifications of headers
+//! after the median last finalized header of the change are verified using the next validator set.
+//!
+//! The pallet keeps the history of GRANDPA authority sets, that have finalized imported headers.
+//! The authority set is pruned once all headers that it has finalized are pruned.
 //!
 //! Since this pallet only tracks finalized headers it does not deal with forks. Forks can only
 //! occur if the GRANDPA validator set on the bridged chain is either colluding or there is a severe
-//! bug causing resulting in an equivocation. Such events are outside the scope of this pallet.
-//! Shall the fork occur on the bridged chain governance intervention will be required to
-//! re-initialize the bridge and track the right fork.
+//! bug causing resulting in an equivocation. Anyone may report such equivocation (two conflicting
+//! precommits, signed by the same authority of the current set in the same round) using the
+//! `report_equivocation` call. Once valid report is received, the pallet is halted. Shall the fork
+//! occur on the bridged chain governance intervention will be required to re-initialize the bridge
+//! and track the right fork.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 // Runtime-generated enums
@@ -38,14 +56,17 @@
 
 use crate::weights::WeightInfo;
 
-use bp_header_chain::{justification::GrandpaJustification, InitializationData};
+use bp_header_chain::{
+	justification::{GrandpaEquivocationProof, GrandpaJustification},
+	HeaderChain, InitializationData,
+};
 use bp_runtime::{BlockNumberOf, Chain, HashOf, HasherOf, HeaderOf};
 use finality_grandpa::voter_set::VoterSet;
 use frame_support::{ensure, fail};
 use frame_system::{ensure_signed, RawOrigin};
 use sp_finality_grandpa::{ConsensusLog, GRANDPA_ENGINE_ID};
 use sp_runtime::traits::{BadOrigin, Header as HeaderT, Zero};
-use sp_std::{boxed::Box, convert::TryInto};
+use sp_std::{boxed::Box, convert::TryInto, vec::Vec};
 
 #[cfg(test)]
 mod mock;
@@ -76,6 +97,9 @@ pub mod pallet {
 
 	#[pallet::config]
 	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+
 		/// The chain we are bridging to here.
 		type BridgedChain: Chain;
 
@@ -96,6 +120,13 @@ pub mod pallet {
 		#[pallet::constant]
 		type HeadersToKeep: Get<u32>;
 
+		/// Whether the pallet accepts forced GRANDPA authority set changes.
+		///
+		/// Even if it is `true`, every forced change must be approved by the pallet owner or root
+		/// before the header containing it is imported.
+		#[pallet::constant]
+		type AllowForcedChanges: Get<bool>;
+
 		/// Weights gathered through benchmarking.
 		type WeightInfo: WeightInfo;
 	}
@@ -137,42 +168,16 @@ pub mod pallet {
 
 			ensure!(Self::request_count() < T::MaxRequests::get(), <Error<T, I>>::TooManyRequests);
 
-			let (hash, number) = (finality_target.hash(), finality_target.number());
-			log::trace!(target: "runtime::bridge-grandpa", "Going to try and finalize header {:?}", finality_target);
-
-			let best_finalized = match <ImportedHeaders<T, I>>::get(<BestFinalized<T, I>>::get()) {
-				Some(best_finalized) => best_finalized,
-				None => {
-					log::error!(
-						target: "runtime::bridge-grandpa",
-						"Cannot finalize header {:?} because pallet is not yet initialized",
-						finality_target,
-					);
-					fail!(<Error<T, I>>::NotInitialized);
-				},
-			};
-
-			// We do a quick check here to ensure that our header chain is making progress and isn't
-			// "travelling back in time" (which could be indicative of something bad, e.g a
-			// hard-fork).
-			ensure!(best_finalized.number() < number, <Error<T, I>>::OldHeader);
-
-			let authority_set = <CurrentAuthoritySet<T, I>>::get();
-			let set_id = authority_set.set_id;
-			verify_justification::<T, I>(&justification, hash, *number, authority_set)?;
-
-			let is_authorities_change_enacted =
-				try_enact_authority_change::<T, I>(&finality_target, set_id)?;
+			let is_mandatory_header =
+				import_finality_proof::<T, I>(*finality_target, justification)?;
 			<RequestCount<T, I>>::mutate(|count| *count += 1);
-			insert_header::<T, I>(*finality_target, hash);
-			log::info!(target: "runtime::bridge-grandpa", "Succesfully imported finalized header with hash {:?}!", hash);
 
-			// mandatory header is a header that changes authorities set. The pallet can't go
-			// further without importing this header. So every bridge MUST import mandatory headers.
+			// mandatory header is a header that signals or enacts authorities set change. The
+			// pallet can't go further without importing this header. So every bridge MUST import
+			// mandatory headers.
 			//
 			// We don't want to charge extra costs for mandatory operations. So relayer is not
 			// paying fee for mandatory headers import transactions.
-			let is_mandatory_header = is_authorities_change_enacted;
 			let pays_fee = if is_mandatory_header { Pays::No } else { Pays::Yes };
 
 			Ok(pays_fee.into())
@@ -239,7 +244,7 @@ pub mod pallet {
 			operational: bool,
 		) -> DispatchResultWithPostInfo {
 			ensure_owner_or_root::<T, I>(origin)?;
-			<IsHalted<T, I>>::put(operational);
+			<IsHalted<T, I>>::put(!operational);
 
 			if operational {
 				log::info!(target: "runtime::bridge-grandpa", "Resuming pallet operations.");
@@ -249,6 +254,117 @@ pub mod pallet {
 
 			Ok(().into())
 		}
+
+		/// Approve forced authority set change, signalled by the given header.
+		///
+		/// Forced changes are normally issued by the bridged chain governance when GRANDPA is
+		/// stalled, so the pallet can't verify them using current authorities. Once the change
+		/// is approved, justifications of all headers after the median last finalized header of
+		/// the change must be signed by the new authorities set. The change is enacted when the
+		/// first such header is imported.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::WeightInfo::approve_forced_change(), DispatchClass::Operational))]
+		pub fn approve_forced_change(
+			origin: OriginFor<T>,
+			header: Box<BridgedHeader<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			ensure!(T::AllowForcedChanges::get(), <Error<T, I>>::ForcedChangesNotAllowed);
+
+			let (median_last_finalized, change) =
+				super::find_forced_change(&*header).ok_or(<Error<T, I>>::NoForcedChange)?;
+			log::info!(
+				target: "runtime::bridge-grandpa",
+				"Approved forced authority set change, signalled by header {:?}. It is enacted at \
+				block {:?} and headers after {:?} are signed by new authorities: {:?}",
+				header.hash(),
+				*header.number() + change.delay,
+				median_last_finalized,
+				change.next_authorities,
+			);
+
+			<PendingForcedChange<T, I>>::put(bp_header_chain::ForcedAuthoritySetChange {
+				authorities: change.next_authorities,
+				median_last_finalized,
+			});
+
+			Ok(().into())
+		}
+
+		/// Report that the authority from the current GRANDPA authorities set has signed two
+		/// conflicting precommits in the same round.
+		///
+		/// Justifications of the misbehaving authorities set can't be trusted anymore, so if the
+		/// equivocation proof is valid, the pallet is halted. It may only be resumed by the
+		/// `PalletOwner` or root (most likely after re-initialization).
+		///
+		/// Valid reports are free.
+		#[pallet::weight(T::WeightInfo::report_equivocation())]
+		pub fn report_equivocation(
+			origin: OriginFor<T>,
+			equivocation_proof: GrandpaEquivocationProof<BridgedHeader<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+
+			let authority_set = <CurrentAuthoritySet<T, I>>::get();
+			let set_id = authority_set.set_id;
+			verify_equivocation_proof::<T, I>(&equivocation_proof, authority_set)?;
+
+			<IsHalted<T, I>>::put(true);
+			log::warn!(
+				target: "runtime::bridge-grandpa",
+				"Authority {:?} from the set {} has equivocated in round {}. Stopping pallet operations.",
+				equivocation_proof.first.id,
+				set_id,
+				equivocation_proof.round,
+			);
+
+			Self::deposit_event(Event::EquivocationReported(equivocation_proof.first.id, set_id));
+
+			Ok(Pays::No.into())
+		}
+
+		/// Verify and import a chain of finalized headers in a single transaction.
+		///
+		/// Headers must be ordered by their numbers. Every justification is verified using the
+		/// authority set that is known to the pallet after importing all previous headers, so the
+		/// chain may span multiple authority set changes. This is used by relayers to catch up
+		/// with the bridged chain. Every header is counted as a separate request, so the batch
+		/// can't have more than `MaxRequests` headers.
+		///
+		/// If any of headers is rejected, the whole batch is reverted. The relayer is not paying
+		/// fee for the transaction if all headers are mandatory.
+		#[pallet::weight(submit_finality_proofs_weight::<T, I>(finality_proofs))]
+		#[frame_support::transactional]
+		pub fn submit_finality_proofs(
+			origin: OriginFor<T>,
+			finality_proofs: Vec<(BridgedHeader<T, I>, GrandpaJustification<BridgedHeader<T, I>>)>,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+
+			ensure!(!finality_proofs.is_empty(), <Error<T, I>>::NoFinalityProofs);
+			let headers_count: u32 = finality_proofs.len().try_into().unwrap_or(u32::MAX);
+			ensure!(
+				Self::request_count().saturating_add(headers_count) <= T::MaxRequests::get(),
+				<Error<T, I>>::TooManyRequests
+			);
+
+			let mut are_all_headers_mandatory = true;
+			for (finality_target, justification) in finality_proofs {
+				let is_mandatory_header =
+					import_finality_proof::<T, I>(finality_target, justification)?;
+				are_all_headers_mandatory = are_all_headers_mandatory && is_mandatory_header;
+			}
+			<RequestCount<T, I>>::mutate(|count| *count += headers_count);
+
+			// see `submit_finality_proof` for details
+			let pays_fee = if are_all_headers_mandatory { Pays::No } else { Pays::Yes };
+
+			Ok(pays_fee.into())
+		}
 	}
 
 	/// The current number of requests which have written to storage.
@@ -292,6 +408,36 @@ pub mod pallet {
 	pub(super) type CurrentAuthoritySet<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, bp_header_chain::AuthoritySet, ValueQuery>;
 
+	/// GRANDPA authority sets, known to the pallet, by their ids.
+	///
+	/// The set is pruned when all imported headers, that it has finalized, are pruned.
+	#[pallet::storage]
+	pub(super) type AuthoritySets<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, sp_finality_grandpa::SetId, bp_header_chain::AuthoritySet>;
+
+	/// Ids of authority sets that have finalized imported headers.
+	#[pallet::storage]
+	pub(super) type ImportedHeaderSetIds<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, BridgedBlockHash<T, I>, sp_finality_grandpa::SetId>;
+
+	/// Authority set change that has been signalled by one of imported headers, but is not yet
+	/// enacted.
+	#[pallet::storage]
+	pub(super) type PendingScheduledChange<T: Config<I>, I: 'static = ()> = StorageValue<
+		_,
+		bp_header_chain::ScheduledAuthoritySetChange<BridgedBlockNumber<T, I>>,
+		OptionQuery,
+	>;
+
+	/// Forced authority set change, that has been approved by the pallet owner or root, but is not
+	/// yet enacted.
+	#[pallet::storage]
+	pub(super) type PendingForcedChange<T: Config<I>, I: 'static = ()> = StorageValue<
+		_,
+		bp_header_chain::ForcedAuthoritySetChange<BridgedBlockNumber<T, I>>,
+		OptionQuery,
+	>;
+
 	/// Optional pallet owner.
 	///
 	/// Pallet owner has a right to halt all pallet operations and then resume it. If it is
@@ -338,6 +484,13 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Authority of the given set has equivocated. All pallet operations are halted.
+		EquivocationReported(sp_finality_grandpa::AuthorityId, sp_finality_grandpa::SetId),
+	}
+
 	#[pallet::error]
 	pub enum Error<T, I = ()> {
 		/// The given justification is invalid for the given header.
@@ -352,8 +505,16 @@ pub mod pallet {
 		UnknownHeader,
 		/// The scheduled authority set change found in the header is unsupported by the pallet.
 		///
-		/// This is the case for non-standard (e.g forced) authority set changes.
+		/// This is the case for scheduled changes that are signalled while other change is still
+		/// pending.
 		UnsupportedScheduledChange,
+		/// The header doesn't enact pending scheduled authority set change. No other headers may
+		/// be imported until the enactment header is imported.
+		ExpectedEnactmentHeader,
+		/// Forced authority set changes are not allowed by the pallet configuration.
+		ForcedChangesNotAllowed,
+		/// The header doesn't signal forced authority set change.
+		NoForcedChange,
 		/// The pallet is not yet initialized.
 		NotInitialized,
 		/// The pallet has already been initialized.
@@ -362,52 +523,215 @@ pub mod pallet {
 		Halted,
 		/// The storage proof doesn't contains storage root. So it is invalid for given header.
 		StorageRootMismatch,
+		/// The given equivocation proof is invalid.
+		InvalidEquivocationProof,
+		/// No finality proofs have been provided.
+		NoFinalityProofs,
+	}
+
+	/// Verify finality proof of the given header and import the header.
+	///
+	/// Returns true if the header is mandatory, i.e. it has signalled or enacted authority set
+	/// change.
+	pub(crate) fn import_finality_proof<T: Config<I>, I: 'static>(
+		finality_target: BridgedHeader<T, I>,
+		justification: GrandpaJustification<BridgedHeader<T, I>>,
+	) -> Result<bool, sp_runtime::DispatchError> {
+		let (hash, number) = (finality_target.hash(), *finality_target.number());
+		log::trace!(target: "runtime::bridge-grandpa", "Going to try and finalize header {:?}", finality_target);
+
+		let best_finalized = match <ImportedHeaders<T, I>>::get(<BestFinalized<T, I>>::get()) {
+			Some(best_finalized) => best_finalized,
+			None => {
+				log::error!(
+					target: "runtime::bridge-grandpa",
+					"Cannot finalize header {:?} because pallet is not yet initialized",
+					finality_target,
+				);
+				fail!(<Error<T, I>>::NotInitialized);
+			},
+		};
+
+		// We do a quick check here to ensure that our header chain is making progress and isn't
+		// "travelling back in time" (which could be indicative of something bad, e.g a
+		// hard-fork).
+		ensure!(*best_finalized.number() < number, <Error<T, I>>::OldHeader);
+
+		let authority_set = justification_authority_set::<T, I>(&finality_target)?;
+		let set_id = authority_set.set_id;
+		verify_justification::<T, I>(&justification, hash, number, authority_set)?;
+
+		let is_mandatory_header = try_enact_authority_change::<T, I>(&finality_target, hash)?;
+		insert_header::<T, I>(finality_target, hash, set_id);
+		log::info!(
+			target: "runtime::bridge-grandpa",
+			"Succesfully imported finalized header with hash {:?}, signed by authority set {}!",
+			hash,
+			set_id,
+		);
+
+		Ok(is_mandatory_header)
+	}
+
+	/// Returns weight of the `submit_finality_proofs` call.
+	///
+	/// Batches with more than `MaxRequests` headers are always rejected by the call, so we don't
+	/// iterate over them and return the maximal weight, which makes the transaction invalid.
+	pub(crate) fn submit_finality_proofs_weight<T: Config<I>, I: 'static>(
+		finality_proofs: &[(BridgedHeader<T, I>, GrandpaJustification<BridgedHeader<T, I>>)],
+	) -> frame_support::weights::Weight {
+		if finality_proofs.len() > T::MaxRequests::get() as usize {
+			return frame_support::weights::Weight::MAX
+		}
+
+		finality_proofs.iter().fold(0, |weight, (_, justification)| {
+			weight.saturating_add(T::WeightInfo::submit_finality_proof(
+				justification.commit.precommits.len().try_into().unwrap_or(u32::MAX),
+				justification.votes_ancestries.len().try_into().unwrap_or(u32::MAX),
+			))
+		})
+	}
+
+	/// Returns authority set that must have signed justification of the given header.
+	///
+	/// Normally it is the current authority set. If there's pending scheduled change, the header
+	/// must be the enactment header of this change, which is also signed by the current set.
+	/// If there's approved forced change and the header is after its median last finalized
+	/// header, then it is the next authority set.
+	///
+	/// The only exception is the scheduled change that is enacted after the median last finalized
+	/// header of the forced change. GRANDPA drops such changes when the forced change is
+	/// enacted, so we don't require its enactment header.
+	pub(crate) fn justification_authority_set<T: Config<I>, I: 'static>(
+		header: &BridgedHeader<T, I>,
+	) -> Result<bp_header_chain::AuthoritySet, sp_runtime::DispatchError> {
+		let current_set = <CurrentAuthoritySet<T, I>>::get();
+		let number = *header.number();
+		let forced_change = <PendingForcedChange<T, I>>::get();
+		let pending_change = <PendingScheduledChange<T, I>>::get().filter(|pending_change| {
+			forced_change
+				.as_ref()
+				.map(|forced_change| pending_change.enact_at <= forced_change.median_last_finalized)
+				.unwrap_or(true)
+		});
+
+		if let Some(pending_change) = pending_change {
+			ensure!(pending_change.enact_at == number, <Error<T, I>>::ExpectedEnactmentHeader);
+			return Ok(current_set)
+		}
+
+		match forced_change {
+			Some(forced_change) if forced_change.median_last_finalized < number =>
+				Ok(bp_header_chain::AuthoritySet::new(
+					forced_change.authorities,
+					current_set.set_id + 1,
+				)),
+			_ => Ok(current_set),
+		}
 	}
 
-	/// Check the given header for a GRANDPA scheduled authority set change. If a change
-	/// is found it will be enacted immediately.
+	/// Check the given header for a GRANDPA authority set changes and enact them.
+	///
+	/// Changes are enacted in the following order:
+	///
+	/// 1) approved forced change is enacted if the header is after its median last finalized
+	///    header, dropping any pending scheduled change;
 	///
-	/// This function does not support forced changes, or scheduled changes with delays
-	/// since these types of changes are indicative of abnormal behavior from GRANDPA.
+	/// 2) pending scheduled change is enacted if the header is the enactment header;
 	///
-	/// Returned value will indicate if a change was enacted or not.
+	/// 3) scheduled change with zero delay, signalled by the header is enacted immediately. Change
+	///    with non-zero delay is stored and enacted later.
+	///
+	/// The header must have been already verified using `justification_authority_set`.
+	///
+	/// Returned value will indicate if the header has signalled or enacted a change.
 	pub(crate) fn try_enact_authority_change<T: Config<I>, I: 'static>(
 		header: &BridgedHeader<T, I>,
-		current_set_id: sp_finality_grandpa::SetId,
+		hash: BridgedBlockHash<T, I>,
 	) -> Result<bool, sp_runtime::DispatchError> {
+		let mut current_set_id = <CurrentAuthoritySet<T, I>>::get().set_id;
+		let number = *header.number();
+
 		let mut change_enacted = false;
+		let forced_change = <PendingForcedChange<T, I>>::get()
+			.filter(|change| change.median_last_finalized < number);
+		if let Some(forced_change) = forced_change {
+			<PendingForcedChange<T, I>>::kill();
+			<PendingScheduledChange<T, I>>::kill();
+			enact_authority_set::<T, I>(forced_change.authorities, current_set_id, hash);
+			current_set_id += 1;
+			change_enacted = true;
+		}
+
+		let pending_change =
+			<PendingScheduledChange<T, I>>::get().filter(|change| change.enact_at == number);
+		let signalled_change = super::find_scheduled_change(header);
 
-		// We don't support forced changes - at that point governance intervention is required.
+		// GRANDPA allows only one pending standard change at a time, so let's do all checks
+		// before changing anything in the storage
 		ensure!(
-			super::find_forced_change(header).is_none(),
+			signalled_change.is_none() ||
+				pending_change.is_some() ||
+				!<PendingScheduledChange<T, I>>::exists(),
 			<Error<T, I>>::UnsupportedScheduledChange
 		);
 
-		if let Some(change) = super::find_scheduled_change(header) {
-			// GRANDPA only includes a `delay` for forced changes, so this isn't valid.
-			ensure!(change.delay == Zero::zero(), <Error<T, I>>::UnsupportedScheduledChange);
-
-			// TODO [#788]: Stop manually increasing the `set_id` here.
-			let next_authorities = bp_header_chain::AuthoritySet {
-				authorities: change.next_authorities,
-				set_id: current_set_id + 1,
-			};
-
-			// Since our header schedules a change and we know the delay is 0, it must also enact
-			// the change.
-			<CurrentAuthoritySet<T, I>>::put(&next_authorities);
+		if let Some(pending_change) = pending_change {
+			<PendingScheduledChange<T, I>>::kill();
+			enact_authority_set::<T, I>(pending_change.authorities, current_set_id, hash);
+			current_set_id += 1;
 			change_enacted = true;
+		}
+
+		let change = match signalled_change {
+			Some(change) => change,
+			None => return Ok(change_enacted),
+		};
 
+		if change.delay == Zero::zero() {
+			enact_authority_set::<T, I>(change.next_authorities, current_set_id, hash);
+		} else {
+			let enact_at = number + change.delay;
 			log::info!(
 				target: "runtime::bridge-grandpa",
-				"Transitioned from authority set {} to {}! New authorities are: {:?}",
-				current_set_id,
-				current_set_id + 1,
-				next_authorities,
+				"Header {:?} has scheduled authority set change at block {:?}. New authorities \
+				are: {:?}",
+				hash,
+				enact_at,
+				change.next_authorities,
 			);
-		};
 
-		Ok(change_enacted)
+			<PendingScheduledChange<T, I>>::put(bp_header_chain::ScheduledAuthoritySetChange {
+				authorities: change.next_authorities,
+				enact_at,
+			});
+		}
+
+		Ok(true)
+	}
+
+	/// Replace current authority set with the next one.
+	fn enact_authority_set<T: Config<I>, I: 'static>(
+		next_authorities: sp_finality_grandpa::AuthorityList,
+		current_set_id: sp_finality_grandpa::SetId,
+		hash: BridgedBlockHash<T, I>,
+	) {
+		// TODO [#788]: Stop manually increasing the `set_id` here.
+		let next_authorities = bp_header_chain::AuthoritySet {
+			authorities: next_authorities,
+			set_id: current_set_id + 1,
+		};
+		<CurrentAuthoritySet<T, I>>::put(&next_authorities);
+		<AuthoritySets<T, I>>::insert(next_authorities.set_id, &next_authorities);
+
+		log::info!(
+			target: "runtime::bridge-grandpa",
+			"Transitioned from authority set {} to {} at header {:?}! New authorities are: {:?}",
+			current_set_id,
+			current_set_id + 1,
+			hash,
+			next_authorities,
+		);
 	}
 
 	/// Verify a GRANDPA justification (finality proof) for a given header.
@@ -445,6 +769,28 @@ pub mod pallet {
 		})?)
 	}
 
+	/// Verify a GRANDPA equivocation proof, using GRANDPA current authorities known to the pallet.
+	pub(crate) fn verify_equivocation_proof<T: Config<I>, I: 'static>(
+		equivocation_proof: &GrandpaEquivocationProof<BridgedHeader<T, I>>,
+		authority_set: bp_header_chain::AuthoritySet,
+	) -> Result<(), sp_runtime::DispatchError> {
+		use bp_header_chain::justification::verify_equivocation_proof;
+
+		let voter_set =
+			VoterSet::new(authority_set.authorities).ok_or(<Error<T, I>>::InvalidAuthoritySet)?;
+		let set_id = authority_set.set_id;
+
+		Ok(verify_equivocation_proof::<BridgedHeader<T, I>>(set_id, &voter_set, equivocation_proof)
+			.map_err(|e| {
+				log::error!(
+					target: "runtime::bridge-grandpa",
+					"Received invalid equivocation proof: {:?}",
+					e,
+				);
+				<Error<T, I>>::InvalidEquivocationProof
+			})?)
+	}
+
 	/// Import a previously verified header to the storage.
 	///
 	/// Note this function solely takes care of updating the storage and pruning old entries,
@@ -452,11 +798,13 @@ pub mod pallet {
 	pub(crate) fn insert_header<T: Config<I>, I: 'static>(
 		header: BridgedHeader<T, I>,
 		hash: BridgedBlockHash<T, I>,
+		set_id: sp_finality_grandpa::SetId,
 	) {
 		let index = <ImportedHashesPointer<T, I>>::get();
 		let pruning = <ImportedHashes<T, I>>::try_get(index);
 		<BestFinalized<T, I>>::put(hash);
 		<ImportedHeaders<T, I>>::insert(hash, header);
+		<ImportedHeaderSetIds<T, I>>::insert(hash, set_id);
 		<ImportedHashes<T, I>>::insert(index, hash);
 
 		// Update ring buffer pointer and remove old header.
@@ -464,6 +812,27 @@ pub mod pallet {
 		if let Ok(hash) = pruning {
 			log::debug!(target: "runtime::bridge-grandpa", "Pruning old header: {:?}.", hash);
 			<ImportedHeaders<T, I>>::remove(hash);
+			if let Some(pruned_set_id) = <ImportedHeaderSetIds<T, I>>::take(hash) {
+				prune_authority_sets::<T, I>(pruned_set_id);
+			}
+		}
+	}
+
+	/// Prune all authority sets that are older than the given set.
+	///
+	/// Headers are pruned in the order of their import, so once header finalized by the given set
+	/// is pruned, there are no more headers finalized by any of previous sets.
+	fn prune_authority_sets<T: Config<I>, I: 'static>(
+		oldest_used_set_id: sp_finality_grandpa::SetId,
+	) {
+		let mut set_id = oldest_used_set_id;
+		while set_id > 0 {
+			set_id -= 1;
+			if <AuthoritySets<T, I>>::take(set_id).is_none() {
+				break
+			}
+
+			log::debug!(target: "runtime::bridge-grandpa", "Pruning old authority set: {}.", set_id);
 		}
 	}
 
@@ -477,10 +846,13 @@ pub mod pallet {
 		let initial_hash = header.hash();
 		<InitialHash<T, I>>::put(initial_hash);
 		<ImportedHashesPointer<T, I>>::put(0);
-		insert_header::<T, I>(*header, initial_hash);
+		insert_header::<T, I>(*header, initial_hash, set_id);
 
 		let authority_set = bp_header_chain::AuthoritySet::new(authority_list, set_id);
+		<AuthoritySets<T, I>>::insert(set_id, &authority_set);
 		<CurrentAuthoritySet<T, I>>::put(authority_set);
+		<PendingScheduledChange<T, I>>::kill();
+		<PendingForcedChange<T, I>>::kill();
 
 		<IsHalted<T, I>>::put(is_halted);
 	}
@@ -491,6 +863,7 @@ pub mod pallet {
 	) {
 		let start_number = *init_params.header.number();
 		let end_number = start_number + T::HeadersToKeep::get().into();
+		let set_id = init_params.set_id;
 		initialize_bridge::<T, I>(init_params);
 
 		let mut number = start_number;
@@ -504,7 +877,7 @@ pub mod pallet {
 				Default::default(),
 			);
 			let hash = header.hash();
-			insert_header::<T, I>(header, hash);
+			insert_header::<T, I>(header, hash, set_id);
 		}
 	}
 
@@ -552,6 +925,23 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		<ImportedHeaders<T, I>>::contains_key(hash)
 	}
 
+	/// Get finalized header with given hash, if it is known to the bridge pallet.
+	pub fn finalized_header(hash: BridgedBlockHash<T, I>) -> Option<BridgedHeader<T, I>> {
+		<ImportedHeaders<T, I>>::get(hash)
+	}
+
+	/// Get authority set with given id, if it is known to the bridge pallet.
+	pub fn authority_set(
+		set_id: sp_finality_grandpa::SetId,
+	) -> Option<bp_header_chain::AuthoritySet> {
+		<AuthoritySets<T, I>>::get(set_id)
+	}
+
+	/// Get id of the authority set that has finalized imported header with given hash.
+	pub fn header_set_id(hash: BridgedBlockHash<T, I>) -> Option<sp_finality_grandpa::SetId> {
+		<ImportedHeaderSetIds<T, I>>::get(hash)
+	}
+
 	/// Verify that the passed storage proof is valid, given it is crafted using
 	/// known finalized header. If the proof is valid, then the `parse` callback
 	/// is called and the function returns its result.
@@ -569,6 +959,16 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 	}
 }
 
+impl<T: Config<I>, I: 'static> HeaderChain<BridgedHeader<T, I>> for Pallet<T, I> {
+	fn best_finalized() -> BridgedHeader<T, I> {
+		Pallet::<T, I>::best_finalized()
+	}
+
+	fn finalized_header(hash: BridgedBlockHash<T, I>) -> Option<BridgedHeader<T, I>> {
+		Pallet::<T, I>::finalized_header(hash)
+	}
+}
+
 pub(crate) fn find_scheduled_change<H: HeaderT>(
 	header: &H,
 ) -> Option<sp_finality_grandpa::ScheduledChange<H::Number>> {
@@ -621,14 +1021,17 @@ pub fn initialize_for_benchmarks<T: Config<I>, I: 'static>(header: BridgedHeader
 mod tests {
 	use super::*;
 	use crate::mock::{
-		run_test, test_header, Origin, TestHash, TestHeader, TestNumber, TestRuntime,
+		run_test, test_header, AllowForcedChanges, Event as TestEvent, Origin, TestHash,
+		TestHeader, TestNumber, TestRuntime,
 	};
 	use bp_test_utils::{
-		authority_list, make_default_justification, make_justification_for_header,
-		JustificationGeneratorParams, ALICE, BOB,
+		authority_list, make_default_justification, make_equivocation_proof,
+		make_justification_for_header, JustificationGeneratorParams, ALICE, BOB, FERDIE,
+		TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID,
 	};
 	use codec::Encode;
 	use frame_support::{assert_err, assert_noop, assert_ok, weights::PostDispatchInfo};
+	use frame_system::{EventRecord, Pallet as System, Phase};
 	use sp_runtime::{Digest, DigestItem, DispatchError};
 
 	fn initialize_substrate_bridge() {
@@ -684,9 +1087,9 @@ mod tests {
 		}
 	}
 
-	fn forced_change_log(delay: u64) -> Digest<TestHash> {
+	fn forced_change_log(median_last_finalized: u64, delay: u64) -> Digest<TestHash> {
 		let consensus_log = ConsensusLog::<TestNumber>::ForcedChange(
-			delay,
+			median_last_finalized,
 			sp_finality_grandpa::ScheduledChange {
 				next_authorities: vec![(ALICE.into(), 1), (BOB.into(), 1)],
 				delay,
@@ -772,7 +1175,10 @@ mod tests {
 	fn pallet_may_be_halted_by_root() {
 		run_test(|| {
 			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), false));
+			assert!(IsHalted::<TestRuntime>::get());
+
 			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), true));
+			assert!(!IsHalted::<TestRuntime>::get());
 		});
 	}
 
@@ -810,6 +1216,81 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn pallet_accepts_valid_equivocation_proof_and_halts() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			System::<TestRuntime>::set_block_number(1);
+			System::<TestRuntime>::reset_events();
+
+			let result = Pallet::<TestRuntime>::report_equivocation(
+				Origin::signed(1),
+				make_equivocation_proof(&ALICE, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID),
+			);
+			assert_eq!(result.unwrap().pays_fee, frame_support::weights::Pays::No);
+			assert!(IsHalted::<TestRuntime>::get());
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Grandpa(Event::EquivocationReported(
+						ALICE.into(),
+						TEST_GRANDPA_SET_ID
+					)),
+					topics: vec![],
+				}],
+			);
+
+			assert_noop!(submit_finality_proof(1), Error::<TestRuntime>::Halted);
+		})
+	}
+
+	#[test]
+	fn pallet_rejects_equivocation_proof_of_unknown_authority() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::report_equivocation(
+					Origin::signed(1),
+					make_equivocation_proof(&FERDIE, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID),
+				),
+				Error::<TestRuntime>::InvalidEquivocationProof,
+			);
+		})
+	}
+
+	#[test]
+	fn pallet_rejects_equivocation_proof_signed_by_other_authority_set() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::report_equivocation(
+					Origin::signed(1),
+					make_equivocation_proof(&ALICE, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID + 1),
+				),
+				Error::<TestRuntime>::InvalidEquivocationProof,
+			);
+		})
+	}
+
+	#[test]
+	fn pallet_rejects_equivocation_proof_if_halted() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			<IsHalted<TestRuntime>>::put(true);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::report_equivocation(
+					Origin::signed(1),
+					make_equivocation_proof(&ALICE, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID),
+				),
+				Error::<TestRuntime>::Halted,
+			);
+		})
+	}
+
 	#[test]
 	fn pallet_rejects_header_if_not_initialized_yet() {
 		run_test(|| {
@@ -877,6 +1358,26 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn does_not_import_header_with_redundant_votes_in_finality_proof() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+			let mut justification = make_default_justification(&header);
+			justification.commit.precommits.push(justification.commit.precommits[0].clone());
+
+			assert_err!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header),
+					justification,
+				),
+				<Error<TestRuntime>>::InvalidJustification
+			);
+		})
+	}
+
 	#[test]
 	fn disallows_invalid_authority_set() {
 		run_test(|| {
@@ -958,53 +1459,421 @@ mod tests {
 		})
 	}
 
+	fn next_authorities_justification(header: &TestHeader) -> GrandpaJustification<TestHeader> {
+		make_justification_for_header(JustificationGeneratorParams::<TestHeader> {
+			header: header.clone(),
+			set_id: 2,
+			authorities: vec![(ALICE, 1), (BOB, 1)],
+			..Default::default()
+		})
+	}
+
+	fn schedule_delayed_authority_set_change() {
+		let mut header = test_header(2);
+		header.digest = change_log(2);
+		let justification = make_default_justification(&header);
+		assert_ok!(
+			Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				Box::new(header),
+				justification
+			),
+			PostDispatchInfo { actual_weight: None, pays_fee: frame_support::weights::Pays::No },
+		);
+	}
+
 	#[test]
-	fn importing_header_rejects_header_with_scheduled_change_delay() {
+	fn importing_header_schedules_delayed_authority_set_change() {
 		run_test(|| {
 			initialize_substrate_bridge();
+			schedule_delayed_authority_set_change();
 
-			// Need to update the header digest to indicate that our header signals an authority set
-			// change. However, the change doesn't happen until the next block.
-			let mut header = test_header(2);
-			header.digest = change_log(1);
-
-			// Create a valid justification for the header
-			let justification = make_default_justification(&header);
-
-			// Should not be allowed to import this header
-			assert_err!(
-				Pallet::<TestRuntime>::submit_finality_proof(
-					Origin::signed(1),
-					Box::new(header),
-					justification
-				),
-				<Error<TestRuntime>>::UnsupportedScheduledChange
+			// the change is not yet enacted
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 1);
+			assert_eq!(
+				<PendingScheduledChange<TestRuntime>>::get(),
+				Some(bp_header_chain::ScheduledAuthoritySetChange {
+					authorities: vec![(ALICE.into(), 1), (BOB.into(), 1)],
+					enact_at: 4,
+				}),
 			);
 		})
 	}
 
 	#[test]
-	fn importing_header_rejects_header_with_forced_changes() {
+	fn importing_header_rejects_headers_before_enactment_header() {
 		run_test(|| {
 			initialize_substrate_bridge();
+			schedule_delayed_authority_set_change();
 
-			// Need to update the header digest to indicate that it signals a forced authority set
-			// change.
-			let mut header = test_header(2);
-			header.digest = forced_change_log(0);
-
-			// Create a valid justification for the header
-			let justification = make_default_justification(&header);
+			// header#3 is signed by the current set, but pending change must be enacted first
+			assert_noop!(submit_finality_proof(3), <Error<TestRuntime>>::ExpectedEnactmentHeader);
+		})
+	}
 
-			// Should not be allowed to import this header
+	#[test]
+	fn importing_enactment_header_enacts_delayed_authority_set_change() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			schedule_delayed_authority_set_change();
+
+			// enactment header is signed by the current set
+			assert_ok!(
+				submit_finality_proof(4),
+				PostDispatchInfo {
+					actual_weight: None,
+					pays_fee: frame_support::weights::Pays::No,
+				},
+			);
+			assert_eq!(
+				<CurrentAuthoritySet<TestRuntime>>::get(),
+				bp_header_chain::AuthoritySet::new(vec![(ALICE.into(), 1), (BOB.into(), 1)], 2),
+			);
+			assert_eq!(<PendingScheduledChange<TestRuntime>>::get(), None);
+		})
+	}
+
+	#[test]
+	fn importing_header_rejects_header_after_enactment_header() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			schedule_delayed_authority_set_change();
+
+			// honest voters of the current set never vote past the enactment header and the next
+			// set doesn't exist until the enactment header is finalized
+			let header = test_header(5);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header.clone()),
+					make_default_justification(&header),
+				),
+				<Error<TestRuntime>>::ExpectedEnactmentHeader
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header.clone()),
+					next_authorities_justification(&header),
+				),
+				<Error<TestRuntime>>::ExpectedEnactmentHeader
+			);
+		})
+	}
+
+	#[test]
+	fn imports_delayed_change_using_persisted_justification_of_enactment_header() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// header#2 signals the change with delay 3. The bridged chain only persists the
+			// justification of the enactment header#5, which is signed by the current set.
+			// Relayer submits both headers in the same transaction
+			let mut header2 = test_header(2);
+			header2.digest = change_log(3);
+			let header5 = test_header(5);
+			assert_ok!(
+				Pallet::<TestRuntime>::submit_finality_proofs(
+					Origin::signed(1),
+					vec![
+						(header2.clone(), justification_of_set(&header2, 1)),
+						(header5.clone(), justification_of_set(&header5, 1)),
+					],
+				),
+				PostDispatchInfo {
+					actual_weight: None,
+					pays_fee: frame_support::weights::Pays::No,
+				},
+			);
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), header5.hash());
+			assert_eq!(<ImportedHeaderSetIds<TestRuntime>>::get(header5.hash()), Some(1));
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 2);
+			assert_eq!(<PendingScheduledChange<TestRuntime>>::get(), None);
+
+			// descendants of the enactment header are signed by the next set
+			next_block();
+			let header6 = test_header(6);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header6.clone()),
+					justification_of_set(&header6, 1),
+				),
+				<Error<TestRuntime>>::InvalidJustification
+			);
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				Box::new(header6.clone()),
+				justification_of_set(&header6, 2),
+			));
+			assert_eq!(<ImportedHeaderSetIds<TestRuntime>>::get(header6.hash()), Some(2));
+		})
+	}
+
+	#[test]
+	fn enactment_header_may_signal_next_change() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			schedule_delayed_authority_set_change();
+
+			// header#4 is signed by set#1, enacts set#2 and signals set#3
+			let mut header = test_header(4);
+			header.digest = change_log(0);
+			let justification = make_default_justification(&header);
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				Box::new(header.clone()),
+				justification
+			));
+
+			assert_eq!(<ImportedHeaderSetIds<TestRuntime>>::get(header.hash()), Some(1));
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 3);
+			assert_eq!(<PendingScheduledChange<TestRuntime>>::get(), None);
+		})
+	}
+
+	#[test]
+	fn importing_header_rejects_header_with_unapproved_forced_change() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// Need to update the header digest to indicate that it signals a forced authority set
+			// change.
+			let mut header = test_header(2);
+			header.digest = forced_change_log(1, 0);
+
+			// Create a valid justification for the header
+			let justification = next_authorities_justification(&header);
+
+			// Should not be allowed to import this header
 			assert_err!(
 				Pallet::<TestRuntime>::submit_finality_proof(
 					Origin::signed(1),
 					Box::new(header),
 					justification
 				),
-				<Error<TestRuntime>>::UnsupportedScheduledChange
+				<Error<TestRuntime>>::InvalidJustification
+			);
+		})
+	}
+
+	#[test]
+	fn importing_header_enacts_approved_forced_change() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			schedule_delayed_authority_set_change();
+
+			// the forced change is signalled by header#3 with delay 2. New authorities are voting
+			// from header#1, so pending change that is enacted at header#4 is dropped
+			let mut header3 = test_header(3);
+			header3.digest = forced_change_log(1, 2);
+			assert_ok!(Pallet::<TestRuntime>::approve_forced_change(
+				Origin::root(),
+				Box::new(header3)
+			));
+			assert_eq!(
+				<PendingForcedChange<TestRuntime>>::get(),
+				Some(bp_header_chain::ForcedAuthoritySetChange {
+					authorities: vec![(ALICE.into(), 1), (BOB.into(), 1)],
+					median_last_finalized: 1,
+				}),
+			);
+
+			// header#6 is signed by the new set, even though the signal header isn't imported
+			let header6 = test_header(6);
+			assert_ok!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header6.clone()),
+					next_authorities_justification(&header6),
+				),
+				PostDispatchInfo {
+					actual_weight: None,
+					pays_fee: frame_support::weights::Pays::No,
+				},
+			);
+
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), header6.hash());
+			assert_eq!(
+				<CurrentAuthoritySet<TestRuntime>>::get(),
+				bp_header_chain::AuthoritySet::new(vec![(ALICE.into(), 1), (BOB.into(), 1)], 2),
+			);
+			assert_eq!(<PendingScheduledChange<TestRuntime>>::get(), None);
+			assert_eq!(<PendingForcedChange<TestRuntime>>::get(), None);
+		})
+	}
+
+	#[test]
+	fn forced_change_waits_for_pending_change_enacted_before_median_last_finalized_header() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			schedule_delayed_authority_set_change();
+
+			// the pending change is enacted at header#4 and new authorities are voting from #5
+			let mut header = test_header(6);
+			header.digest = forced_change_log(5, 0);
+			assert_ok!(Pallet::<TestRuntime>::approve_forced_change(
+				Origin::root(),
+				Box::new(header)
+			));
+
+			// header#6 is rejected until pending change is enacted
+			let header6 = test_header(6);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header6.clone()),
+					justification_of_set(&header6, 3),
+				),
+				<Error<TestRuntime>>::ExpectedEnactmentHeader
+			);
+
+			// header#4 enacts set#2 and header#5 is still signed by set#2
+			next_block();
+			let header4 = test_header(4);
+			let header5 = test_header(5);
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proofs(
+				Origin::signed(1),
+				vec![
+					(header4.clone(), justification_of_set(&header4, 1)),
+					(header5.clone(), justification_of_set(&header5, 2)),
+				],
+			));
+
+			// header#6 is signed by set#3
+			next_block();
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				Box::new(header6.clone()),
+				justification_of_set(&header6, 3),
+			));
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), header6.hash());
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 3);
+			assert_eq!(<PendingForcedChange<TestRuntime>>::get(), None);
+		})
+	}
+
+	#[test]
+	fn only_root_or_owner_may_approve_forced_change() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			PalletOwner::<TestRuntime>::put(2);
+
+			let mut header = test_header(2);
+			header.digest = forced_change_log(1, 0);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::approve_forced_change(
+					Origin::signed(1),
+					Box::new(header.clone())
+				),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::approve_forced_change(
+				Origin::signed(2),
+				Box::new(header.clone())
+			));
+			assert_ok!(Pallet::<TestRuntime>::approve_forced_change(
+				Origin::root(),
+				Box::new(header)
+			));
+		})
+	}
+
+	#[test]
+	fn approve_forced_change_rejects_header_without_forced_change() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let mut header = test_header(2);
+			header.digest = change_log(0);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::approve_forced_change(Origin::root(), Box::new(header)),
+				<Error<TestRuntime>>::NoForcedChange,
+			);
+		})
+	}
+
+	#[test]
+	fn approve_forced_change_fails_if_forced_changes_are_not_allowed() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			AllowForcedChanges::set(&false);
+
+			let mut header = test_header(2);
+			header.digest = forced_change_log(1, 0);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::approve_forced_change(Origin::root(), Box::new(header)),
+				<Error<TestRuntime>>::ForcedChangesNotAllowed,
+			);
+		})
+	}
+
+	#[test]
+	fn imported_headers_are_stored_with_authority_set_id() {
+		run_test(|| {
+			let init_data = init_with_origin(Origin::root()).unwrap();
+			assert_eq!(
+				Pallet::<TestRuntime>::authority_set(1),
+				Some(bp_header_chain::AuthoritySet::new(init_data.authority_list, 1)),
 			);
+			assert_eq!(Pallet::<TestRuntime>::header_set_id(init_data.header.hash()), Some(1));
+
+			assert_ok!(submit_finality_proof(1));
+			assert_eq!(Pallet::<TestRuntime>::header_set_id(test_header(1).hash()), Some(1));
+			assert_eq!(Pallet::<TestRuntime>::header_set_id(test_header(2).hash()), None);
+		})
+	}
+
+	#[test]
+	fn authority_sets_history_is_pruned_with_headers() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// header#1 is finalized by the set#1 and enacts the set#2
+			let mut header = test_header(1);
+			header.digest = change_log(0);
+			let justification = make_default_justification(&header);
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				Box::new(header.clone()),
+				justification,
+			));
+			assert_eq!(Pallet::<TestRuntime>::header_set_id(header.hash()), Some(1));
+			assert_eq!(
+				Pallet::<TestRuntime>::authority_set(2),
+				Some(bp_header_chain::AuthoritySet::new(
+					vec![(ALICE.into(), 1), (BOB.into(), 1)],
+					2
+				)),
+			);
+
+			// all next headers are finalized by the set#2
+			let import_header = |number| {
+				next_block();
+				let header = test_header(number);
+				assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header.clone()),
+					next_authorities_justification(&header),
+				));
+				assert_eq!(Pallet::<TestRuntime>::header_set_id(header.hash()), Some(2));
+			};
+			for number in 2..=6 {
+				import_header(number);
+			}
+
+			// headers#0 and #1 are pruned, but the set#1 is still there, because header#2 (that is
+			// finalized by the set#2) is not yet pruned
+			assert_eq!(Pallet::<TestRuntime>::header_set_id(test_header(1).hash()), None);
+			assert!(Pallet::<TestRuntime>::authority_set(1).is_some());
+
+			// once header#2 is pruned, the set#1 is pruned too
+			import_header(7);
+			assert_eq!(Pallet::<TestRuntime>::authority_set(1), None);
+			assert!(Pallet::<TestRuntime>::authority_set(2).is_some());
 		})
 	}
 
@@ -1041,6 +1910,211 @@ mod tests {
 		});
 	}
 
+	fn header_with_change_log(number: TestNumber) -> TestHeader {
+		let mut header = test_header(number);
+		header.digest = change_log(0);
+		header
+	}
+
+	fn justification_of_set(header: &TestHeader, set_id: u64) -> GrandpaJustification<TestHeader> {
+		if set_id == TEST_GRANDPA_SET_ID {
+			return make_default_justification(header)
+		}
+
+		make_justification_for_header(JustificationGeneratorParams::<TestHeader> {
+			header: header.clone(),
+			set_id,
+			authorities: vec![(ALICE, 1), (BOB, 1)],
+			..Default::default()
+		})
+	}
+
+	#[test]
+	fn submit_finality_proofs_imports_headers_spanning_authority_set_changes() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// header#2 is signed by the initial set and enacts set#2, header#3 is signed by set#2
+			let header2 = header_with_change_log(2);
+			let header3 = test_header(3);
+			assert_ok!(
+				Pallet::<TestRuntime>::submit_finality_proofs(
+					Origin::signed(1),
+					vec![
+						(header2.clone(), justification_of_set(&header2, 1)),
+						(header3.clone(), justification_of_set(&header3, 2)),
+					],
+				),
+				PostDispatchInfo {
+					actual_weight: None,
+					pays_fee: frame_support::weights::Pays::Yes,
+				},
+			);
+
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), header3.hash());
+			assert!(<ImportedHeaders<TestRuntime>>::contains_key(header2.hash()));
+			assert_eq!(<ImportedHeaderSetIds<TestRuntime>>::get(header3.hash()), Some(2));
+			assert_eq!(<CurrentAuthoritySet<TestRuntime>>::get().set_id, 2);
+		})
+	}
+
+	#[test]
+	fn submit_finality_proofs_is_free_if_all_headers_are_mandatory() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header2 = header_with_change_log(2);
+			let header3 = header_with_change_log(3);
+			assert_ok!(
+				Pallet::<TestRuntime>::submit_finality_proofs(
+					Origin::signed(1),
+					vec![
+						(header2.clone(), justification_of_set(&header2, 1)),
+						(header3.clone(), justification_of_set(&header3, 2)),
+					],
+				),
+				PostDispatchInfo {
+					actual_weight: None,
+					pays_fee: frame_support::weights::Pays::No,
+				},
+			);
+		})
+	}
+
+	#[test]
+	fn submit_finality_proofs_rejects_empty_batch() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proofs(Origin::signed(1), vec![]),
+				<Error<TestRuntime>>::NoFinalityProofs,
+			);
+		})
+	}
+
+	#[test]
+	fn submit_finality_proofs_reverts_all_headers_if_any_is_rejected() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// header#3 is signed by the initial set, but set#2 is enacted by header#2
+			let header2 = header_with_change_log(2);
+			let header3 = test_header(3);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proofs(
+					Origin::signed(1),
+					vec![
+						(header2.clone(), justification_of_set(&header2, 1)),
+						(header3.clone(), justification_of_set(&header3, 1)),
+					],
+				),
+				<Error<TestRuntime>>::InvalidJustification,
+			);
+		})
+	}
+
+	#[test]
+	fn submit_finality_proofs_rejects_unordered_headers() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header2 = test_header(2);
+			let header3 = test_header(3);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proofs(
+					Origin::signed(1),
+					vec![
+						(header3.clone(), justification_of_set(&header3, 1)),
+						(header2.clone(), justification_of_set(&header2, 1)),
+					],
+				),
+				<Error<TestRuntime>>::OldHeader,
+			);
+		})
+	}
+
+	#[test]
+	fn submit_finality_proofs_counts_every_header_as_request() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let headers = |numbers: sp_std::ops::RangeInclusive<u32>| {
+				numbers
+					.map(|number| {
+						let header = test_header(number.into());
+						let justification = justification_of_set(&header, 1);
+						(header, justification)
+					})
+					.collect::<Vec<_>>()
+			};
+			let max_requests = <TestRuntime as Config>::MaxRequests::get();
+
+			// batch that is larger than `MaxRequests` is rejected
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proofs(
+					Origin::signed(1),
+					headers(1..=max_requests + 1),
+				),
+				<Error<TestRuntime>>::TooManyRequests,
+			);
+
+			// batch is only accepted if it fits the remaining capacity
+			assert_ok!(submit_finality_proof(1));
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proofs(
+					Origin::signed(1),
+					headers(2..=max_requests + 1),
+				),
+				<Error<TestRuntime>>::TooManyRequests,
+			);
+			next_block();
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proofs(
+				Origin::signed(1),
+				headers(2..=max_requests + 1),
+			));
+			assert_eq!(Pallet::<TestRuntime>::request_count(), max_requests);
+		})
+	}
+
+	#[test]
+	fn submit_finality_proofs_weight_is_sum_of_single_proofs_weights() {
+		let header1 = test_header(1);
+		let header2 = test_header(2);
+		let justification1 = justification_of_set(&header1, 1);
+		let justification2 = justification_of_set(&header2, 1);
+		let single_proof_weight = |justification: &GrandpaJustification<TestHeader>| {
+			<TestRuntime as Config>::WeightInfo::submit_finality_proof(
+				justification.commit.precommits.len() as u32,
+				justification.votes_ancestries.len() as u32,
+			)
+		};
+
+		assert_eq!(
+			submit_finality_proofs_weight::<TestRuntime, ()>(&[
+				(header1, justification1.clone()),
+				(header2, justification2.clone()),
+			]),
+			single_proof_weight(&justification1) + single_proof_weight(&justification2),
+		);
+	}
+
+	#[test]
+	fn submit_finality_proofs_weight_is_maximal_if_batch_is_too_large() {
+		let finality_proofs = (1..=<TestRuntime as Config>::MaxRequests::get() + 1)
+			.map(|number| {
+				let header = test_header(number.into());
+				let justification = justification_of_set(&header, 1);
+				(header, justification)
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			submit_finality_proofs_weight::<TestRuntime, ()>(&finality_proofs),
+			frame_support::weights::Weight::MAX,
+		);
+	}
+
 	#[test]
 	fn rate_limiter_disallows_imports_once_limit_is_hit_in_single_block() {
 		run_test(|| {
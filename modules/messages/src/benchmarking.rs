@@ -17,22 +17,27 @@
 //! Messages pallet benchmarking.
 
 use crate::{
-	inbound_lane::InboundLaneStorage, inbound_lane_storage, outbound_lane,
-	outbound_lane::ReceivalConfirmationResult, weights_ext::EXPECTED_DEFAULT_MESSAGE_LENGTH, Call,
+	inbound_lane::{InboundLaneStorage, ReceivalResult},
+	inbound_lane_storage, outbound_lane,
+	outbound_lane::ReceivalConfirmationResult,
+	weights_ext::EXPECTED_DEFAULT_MESSAGE_LENGTH,
+	Call,
 };
 
 use bp_messages::{
-	source_chain::TargetHeaderChain, target_chain::SourceHeaderChain, DeliveredMessages,
-	InboundLaneData, LaneId, MessageData, MessageNonce, OutboundLaneData, UnrewardedRelayer,
-	UnrewardedRelayersState,
+	source_chain::{Sender, TargetHeaderChain},
+	target_chain::SourceHeaderChain,
+	DeliveredMessages, InboundLaneData, LaneId, LaneInfo, MessageData, MessageKey, MessageNonce,
+	OutboundLaneData, UnrewardedRelayer, UnrewardedRelayersState,
 };
 use bp_runtime::messages::DispatchFeePayment;
+use codec::Encode;
 use frame_benchmarking::{account, benchmarks_instance_pallet};
 use frame_support::{traits::Get, weights::Weight};
 use frame_system::RawOrigin;
 use sp_std::{
 	collections::{btree_map::BTreeMap, vec_deque::VecDeque},
-	convert::TryInto,
+	convert::{TryFrom, TryInto},
 	ops::RangeInclusive,
 	prelude::*,
 };
@@ -138,6 +143,7 @@ benchmarks_instance_pallet! {
 	// (estimated using `send_half_maximal_message_worst_case` and `send_maximal_message_worst_case`) is
 	// added.
 	send_minimal_message_worst_case {
+		open_bench_lane::<T, I>();
 		let lane_id = T::bench_lane_id();
 		let sender = account("sender", 0, SEED);
 		T::endow_account(&sender);
@@ -169,6 +175,7 @@ benchmarks_instance_pallet! {
 	// With single KB of message size, the weight of the call is increased (roughly) by
 	// `(send_16_kb_message_worst_case - send_1_kb_message_worst_case) / 15`.
 	send_1_kb_message_worst_case {
+		open_bench_lane::<T, I>();
 		let lane_id = T::bench_lane_id();
 		let sender = account("sender", 0, SEED);
 		T::endow_account(&sender);
@@ -206,6 +213,7 @@ benchmarks_instance_pallet! {
 	// With single KB of message size, the weight of the call is increased (roughly) by
 	// `(send_16_kb_message_worst_case - send_1_kb_message_worst_case) / 15`.
 	send_16_kb_message_worst_case {
+		open_bench_lane::<T, I>();
 		let lane_id = T::bench_lane_id();
 		let sender = account("sender", 0, SEED);
 		T::endow_account(&sender);
@@ -234,12 +242,55 @@ benchmarks_instance_pallet! {
 		);
 	}
 
+	// Benchmark `send_message` extrinsic with the same conditions as the
+	// `send_minimal_message_worst_case`, but the lane is restricted to the given number of allowed
+	// senders and the message sender is the last sender in the list.
+	//
+	// The cost of allowed senders check is computed as
+	// `send_minimal_message_with_allowed_senders(i) - send_minimal_message_worst_case`.
+	send_minimal_message_with_allowed_senders {
+		let i in 1..T::MaxAllowedSenders::get();
+
+		open_bench_lane::<T, I>();
+		let lane_id = T::bench_lane_id();
+		let sender: T::AccountId = account("sender", 0, SEED);
+		T::endow_account(&sender);
+
+		let mut allowed_senders: Vec<Sender<T::AccountId>> = (1..i)
+			.map(|index| Sender::Signed(account("allowed_sender", index, SEED)))
+			.collect();
+		allowed_senders.push(Sender::Signed(sender.clone()));
+		crate::OutboundLaneAllowedSenders::<T, I>::insert(
+			lane_id,
+			frame_support::BoundedVec::<_, T::MaxAllowedSenders>::try_from(allowed_senders)
+				.expect("i is limited by T::MaxAllowedSenders; qed"),
+		);
+
+		// 'send' messages that are to be pruned when our message is sent
+		for _nonce in 1..=T::MaxMessagesToPruneAtOnce::get() {
+			send_regular_message::<T, I>();
+		}
+		confirm_message_delivery::<T, I>(T::MaxMessagesToPruneAtOnce::get());
+
+		let (payload, fee) = T::prepare_outbound_message(MessageParams {
+			size: 0,
+			sender_account: sender.clone(),
+		});
+	}: send_message(RawOrigin::Signed(sender), lane_id, payload, fee)
+	verify {
+		assert_eq!(
+			crate::Pallet::<T, I>::outbound_latest_generated_nonce(T::bench_lane_id()),
+			T::MaxMessagesToPruneAtOnce::get() + 1,
+		);
+	}
+
 	// Benchmark `increase_message_fee` with following conditions:
 	// * message has maximal message;
 	// * submitter account is killed because its balance is less than ED after payment.
 	//
 	// Result of this benchmark is directly used by weight formula of the call.
 	maximal_increase_message_fee {
+		open_bench_lane::<T, I>();
 		let sender = account("sender", 42, SEED);
 		T::endow_account(&sender);
 
@@ -259,6 +310,7 @@ benchmarks_instance_pallet! {
 	increase_message_fee {
 		let i in 0..T::maximal_message_size().try_into().unwrap_or_default();
 
+		open_bench_lane::<T, I>();
 		let sender = account("sender", 42, SEED);
 		T::endow_account(&sender);
 
@@ -281,6 +333,7 @@ benchmarks_instance_pallet! {
 	//
 	// This is base benchmark for all other message delivery benchmarks.
 	receive_single_message_proof {
+		open_bench_lane::<T, I>();
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 
@@ -315,6 +368,7 @@ benchmarks_instance_pallet! {
 	// This won't be super-accurate if message has non-zero dispatch weight, but estimation should
 	// be close enough to real weight.
 	receive_two_messages_proof {
+		open_bench_lane::<T, I>();
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 
@@ -349,6 +403,7 @@ benchmarks_instance_pallet! {
 	// This won't be super-accurate if message has non-zero dispatch weight, but estimation should
 	// be close enough to real weight.
 	receive_single_message_proof_with_outbound_lane_state {
+		open_bench_lane::<T, I>();
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 
@@ -389,6 +444,7 @@ benchmarks_instance_pallet! {
 	// With single KB of messages proof, the weight of the call is increased (roughly) by
 	// `(receive_single_message_proof_16KB - receive_single_message_proof_1_kb) / 15`.
 	receive_single_message_proof_1_kb {
+		open_bench_lane::<T, I>();
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 
@@ -423,6 +479,7 @@ benchmarks_instance_pallet! {
 	// With single KB of messages proof, the weight of the call is increased (roughly) by
 	// `(receive_single_message_proof_16KB - receive_single_message_proof) / 15`.
 	receive_single_message_proof_16_kb {
+		open_bench_lane::<T, I>();
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 
@@ -456,6 +513,7 @@ benchmarks_instance_pallet! {
 	// this information in two places: (1) to reduce weight of delivery tx if sender pays fee at the source chain
 	// and (2) to refund relayer with this weight if fee has been paid at the source chain.
 	receive_single_prepaid_message_proof {
+		open_bench_lane::<T, I>();
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 
@@ -484,6 +542,7 @@ benchmarks_instance_pallet! {
 	//
 	// This is base benchmark for all other confirmations delivery benchmarks.
 	receive_delivery_proof_for_single_message {
+		open_bench_lane::<T, I>();
 		let relayers_fund_id = crate::relayer_fund_account_id::<T::AccountId, T::AccountIdConverter>();
 		let relayer_id: T::AccountId = account("relayer", 0, SEED);
 		let relayer_balance = T::account_balance(&relayer_id);
@@ -502,7 +561,7 @@ benchmarks_instance_pallet! {
 			inbound_lane_data: InboundLaneData {
 				relayers: vec![UnrewardedRelayer {
 					relayer: relayer_id.clone(),
-					messages: DeliveredMessages::new(1, true),
+					messages: DeliveredMessages::new(1, true, 0),
 				}].into_iter().collect(),
 				last_confirmed_nonce: 0,
 			},
@@ -524,6 +583,7 @@ benchmarks_instance_pallet! {
 	// as `weight(receive_delivery_proof_for_two_messages_by_single_relayer)
 	//   - weight(receive_delivery_proof_for_single_message)`.
 	receive_delivery_proof_for_two_messages_by_single_relayer {
+		open_bench_lane::<T, I>();
 		let relayers_fund_id = crate::relayer_fund_account_id::<T::AccountId, T::AccountIdConverter>();
 		let relayer_id: T::AccountId = account("relayer", 0, SEED);
 		let relayer_balance = T::account_balance(&relayer_id);
@@ -538,8 +598,8 @@ benchmarks_instance_pallet! {
 			messages_in_oldest_entry: 2,
 			total_messages: 2,
 		};
-		let mut delivered_messages = DeliveredMessages::new(1, true);
-		delivered_messages.note_dispatched_message(true);
+		let mut delivered_messages = DeliveredMessages::new(1, true, 0);
+		delivered_messages.note_dispatched_message(true, 0);
 		let proof = T::prepare_message_delivery_proof(MessageDeliveryProofParams {
 			lane: T::bench_lane_id(),
 			inbound_lane_data: InboundLaneData {
@@ -564,6 +624,7 @@ benchmarks_instance_pallet! {
 	// as `weight(receive_delivery_proof_for_two_messages_by_two_relayers)
 	//   - weight(receive_delivery_proof_for_two_messages_by_single_relayer)`.
 	receive_delivery_proof_for_two_messages_by_two_relayers {
+		open_bench_lane::<T, I>();
 		let relayers_fund_id = crate::relayer_fund_account_id::<T::AccountId, T::AccountIdConverter>();
 		let relayer1_id: T::AccountId = account("relayer1", 1, SEED);
 		let relayer1_balance = T::account_balance(&relayer1_id);
@@ -586,11 +647,11 @@ benchmarks_instance_pallet! {
 				relayers: vec![
 					UnrewardedRelayer {
 						relayer: relayer1_id.clone(),
-						messages: DeliveredMessages::new(1, true),
+						messages: DeliveredMessages::new(1, true, 0),
 					},
 					UnrewardedRelayer {
 						relayer: relayer2_id.clone(),
-						messages: DeliveredMessages::new(2, true),
+						messages: DeliveredMessages::new(2, true, 0),
 					},
 				].into_iter().collect(),
 				last_confirmed_nonce: 0,
@@ -603,6 +664,137 @@ benchmarks_instance_pallet! {
 		ensure_relayer_rewarded::<T, I>(&relayer2_id, &relayer2_balance);
 	}
 
+	// Benchmark `receive_messages_delivery_proof` extrinsic with following conditions:
+	// * single relayer is rewarded for relaying single message;
+	// * the whole dispatch weight of the message is unspent, so the unspent dispatch fee is
+	//   refunded to the message sender.
+	//
+	// Additional weight for refunding unspent dispatch fee of single message could be computed
+	// as `weight(receive_delivery_proof_for_single_message_with_refund)
+	//   - weight(receive_delivery_proof_for_single_message)`.
+	receive_delivery_proof_for_single_message_with_refund {
+		open_bench_lane::<T, I>();
+		let relayers_fund_id = crate::relayer_fund_account_id::<T::AccountId, T::AccountIdConverter>();
+		let relayer_id: T::AccountId = account("relayer", 0, SEED);
+		let sender: T::AccountId = account("sender", 0, SEED);
+		T::endow_account(&relayers_fund_id);
+		T::endow_account(&sender);
+
+		// send message that we're going to confirm
+		let (payload, _) = T::prepare_outbound_message(MessageParams {
+			size: 0,
+			sender_account: sender.clone(),
+		});
+		send_regular_message_with_payload::<T, I>(payload.encode());
+		crate::OutboundMessageSenders::<T, I>::insert(
+			MessageKey { lane_id: T::bench_lane_id(), nonce: 1 },
+			Sender::Signed(sender),
+		);
+
+		let relayers_state = UnrewardedRelayersState {
+			unrewarded_relayer_entries: 1,
+			messages_in_oldest_entry: 1,
+			total_messages: 1,
+		};
+		let proof = T::prepare_message_delivery_proof(MessageDeliveryProofParams {
+			lane: T::bench_lane_id(),
+			inbound_lane_data: InboundLaneData {
+				relayers: vec![UnrewardedRelayer {
+					relayer: relayer_id.clone(),
+					messages: DeliveredMessages::new(1, true, Weight::MAX),
+				}].into_iter().collect(),
+				last_confirmed_nonce: 0,
+			},
+			size: ProofSize::Minimal(0),
+		});
+	}: receive_messages_delivery_proof(RawOrigin::Signed(relayer_id), proof, relayers_state)
+	verify {
+		assert_eq!(
+			crate::OutboundLanes::<T, I>::get(T::bench_lane_id()).latest_received_nonce,
+			1,
+		);
+	}
+
+	// Benchmark `refund_expired_message` extrinsic with following conditions:
+	// * the delivery of expired message has been confirmed;
+	// * the message fee is refunded from the relayers fund account to the message sender.
+	refund_expired_message {
+		open_bench_lane::<T, I>();
+		let relayers_fund_id = crate::relayer_fund_account_id::<T::AccountId, T::AccountIdConverter>();
+		let submitter: T::AccountId = account("submitter", 0, SEED);
+		let sender: T::AccountId = account("sender", 0, SEED);
+		T::endow_account(&relayers_fund_id);
+		T::endow_account(&sender);
+
+		let message_key = MessageKey { lane_id: T::bench_lane_id(), nonce: 1 };
+		crate::ExpiredMessagesRefunds::<T, I>::insert(
+			&message_key,
+			(Sender::Signed(sender.clone()), T::OutboundMessageFee::from(MESSAGE_FEE)),
+		);
+		let sender_balance = T::account_balance(&sender);
+	}: refund_expired_message(RawOrigin::Signed(submitter), T::bench_lane_id(), 1)
+	verify {
+		assert!(!crate::ExpiredMessagesRefunds::<T, I>::contains_key(&message_key));
+		assert!(T::account_balance(&sender) > sender_balance);
+	}
+
+	// Benchmark `service_overweight` extrinsic with following conditions:
+	// * inbound lane already has state, so it needs to be read and decoded;
+	// * message is in the overweight queue;
+	// * message is successfully dispatched;
+	// * message requires all heavy checks done by dispatcher;
+	// * message dispatch fee is paid at target (this) chain.
+	//
+	// The message has minimal dispatch weight, so results of this benchmark are (roughly) the
+	// overhead of servicing overweight message, excluding its dispatch weight.
+	service_overweight {
+		open_bench_lane::<T, I>();
+		let caller = account("caller", 0, SEED);
+
+		// mark messages 1..=20 as delivered and put message 21 into the overweight queue
+		receive_messages::<T, I>(20);
+		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
+			lane: T::bench_lane_id(),
+			message_nonces: 21..=21,
+			outbound_lane_data: None,
+			size: ProofSize::Minimal(EXPECTED_DEFAULT_MESSAGE_LENGTH),
+			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+		});
+		let message = T::SourceHeaderChain::verify_messages_proof(proof, 1)
+			.ok()
+			.and_then(|mut messages| messages.remove(&T::bench_lane_id()))
+			.and_then(|lane_messages| lane_messages.messages.into_iter().next())
+			.expect("benchmark messages proof is valid");
+		assert_eq!(
+			crate::inbound_lane::<T, I>(T::bench_lane_id())
+				.receive_overweight_message(&T::bridged_relayer_id(), 21),
+			ReceivalResult::Overweight,
+		);
+		crate::OverweightMessages::<T, I>::insert(&message.key, message.data);
+	}: service_overweight(RawOrigin::Signed(caller), T::bench_lane_id(), 21, dispatch_weight)
+	verify {
+		assert!(!crate::OverweightMessages::<T, I>::contains_key(MessageKey {
+			lane_id: T::bench_lane_id(),
+			nonce: 21,
+		}));
+		assert!(T::is_message_dispatched(21));
+	}
+
+	// Benchmark `set_lane_allowed_senders` extrinsic with given number of allowed senders.
+	set_lane_allowed_senders {
+		let i in 1..T::MaxAllowedSenders::get();
+
+		let allowed_senders: Vec<Sender<T::AccountId>> = (0..i)
+			.map(|index| Sender::Signed(account("allowed_sender", index, SEED)))
+			.collect();
+	}: set_lane_allowed_senders(RawOrigin::Root, T::bench_lane_id(), Some(allowed_senders))
+	verify {
+		assert_eq!(
+			crate::OutboundLaneAllowedSenders::<T, I>::decode_len(T::bench_lane_id()),
+			Some(i as usize),
+		);
+	}
+
 	//
 	// Benchmarks for manual checks.
 	//
@@ -617,6 +809,7 @@ benchmarks_instance_pallet! {
 	send_messages_of_various_lengths {
 		let i in 0..T::maximal_message_size().try_into().unwrap_or_default();
 
+		open_bench_lane::<T, I>();
 		let lane_id = T::bench_lane_id();
 		let sender = account("sender", 0, SEED);
 		T::endow_account(&sender);
@@ -639,6 +832,41 @@ benchmarks_instance_pallet! {
 		);
 	}
 
+	// Benchmark `send_messages` extrinsic with following conditions:
+	// * outbound lane already has state, so it needs to be read and decoded;
+	// * relayers fund account does not exists (in practice it needs to exist in production environment);
+	// * maximal number of messages is being pruned during the call;
+	// * every message has minimal size.
+	//
+	// Results of this benchmark are used to compute weight of the `send_messages` call.
+	send_multiple_messages {
+		let i in 1..16;
+
+		open_bench_lane::<T, I>();
+		let lane_id = T::bench_lane_id();
+		let sender = account("sender", 0, SEED);
+		T::endow_account(&sender);
+
+		// 'send' messages that are to be pruned when our messages are sent
+		for _nonce in 1..=T::MaxMessagesToPruneAtOnce::get() {
+			send_regular_message::<T, I>();
+		}
+		confirm_message_delivery::<T, I>(T::MaxMessagesToPruneAtOnce::get());
+
+		let messages = (0..i)
+			.map(|_| T::prepare_outbound_message(MessageParams {
+				size: 0,
+				sender_account: sender.clone(),
+			}))
+			.collect::<Vec<_>>();
+	}: send_messages(RawOrigin::Signed(sender), lane_id, messages)
+	verify {
+		assert_eq!(
+			crate::Pallet::<T, I>::outbound_latest_generated_nonce(T::bench_lane_id()),
+			T::MaxMessagesToPruneAtOnce::get() + i as MessageNonce,
+		);
+	}
+
 	// Benchmark `receive_messages_proof` extrinsic with multiple minimal-weight messages and following conditions:
 	// * proof does not include outbound lane state proof;
 	// * inbound lane already has state, so it needs to be read and decoded;
@@ -651,6 +879,7 @@ benchmarks_instance_pallet! {
 	receive_multiple_messages_proof {
 		let i in 1..64;
 
+		open_bench_lane::<T, I>();
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 		let messages_count = i as _;
@@ -689,6 +918,7 @@ benchmarks_instance_pallet! {
 	receive_message_proofs_with_extra_nodes {
 		let i in 0..T::maximal_message_size();
 
+		open_bench_lane::<T, I>();
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 		let messages_count = 1u32;
@@ -727,6 +957,7 @@ benchmarks_instance_pallet! {
 	receive_message_proofs_with_large_leaf {
 		let i in 0..T::maximal_message_size();
 
+		open_bench_lane::<T, I>();
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 		let messages_count = 1u32;
@@ -767,6 +998,7 @@ benchmarks_instance_pallet! {
 	receive_multiple_messages_proof_with_outbound_lane_state {
 		let i in 1..128;
 
+		open_bench_lane::<T, I>();
 		let relayer_id_on_source = T::bridged_relayer_id();
 		let relayer_id_on_target = account("relayer", 0, SEED);
 		let messages_count = i as _;
@@ -805,6 +1037,7 @@ benchmarks_instance_pallet! {
 
 	// Benchmark `receive_messages_delivery_proof` extrinsic where single relayer delivers multiple messages.
 	receive_delivery_proof_for_multiple_messages_by_single_relayer {
+		open_bench_lane::<T, I>();
 		// there actually should be used value of `MaxUnrewardedRelayerEntriesAtInboundLane` from the bridged
 		// chain, but we're more interested in additional weight/message than in max weight
 		let i in 1..T::MaxUnrewardedRelayerEntriesAtInboundLane::get()
@@ -826,9 +1059,9 @@ benchmarks_instance_pallet! {
 			messages_in_oldest_entry: 1,
 			total_messages: i as MessageNonce,
 		};
-		let mut delivered_messages = DeliveredMessages::new(1, true);
+		let mut delivered_messages = DeliveredMessages::new(1, true, 0);
 		for nonce in 2..=i {
-			delivered_messages.note_dispatched_message(true);
+			delivered_messages.note_dispatched_message(true, 0);
 		}
 		let proof = T::prepare_message_delivery_proof(MessageDeliveryProofParams {
 			lane: T::bench_lane_id(),
@@ -848,6 +1081,7 @@ benchmarks_instance_pallet! {
 
 	// Benchmark `receive_messages_delivery_proof` extrinsic where every relayer delivers single messages.
 	receive_delivery_proof_for_multiple_messages_by_multiple_relayers {
+		open_bench_lane::<T, I>();
 		// there actually should be used value of `MaxUnconfirmedMessagesAtInboundLane` from the bridged
 		// chain, but we're more interested in additional weight/message than in max weight
 		let i in 1..T::MaxUnconfirmedMessagesAtInboundLane::get()
@@ -883,7 +1117,7 @@ benchmarks_instance_pallet! {
 					.enumerate()
 					.map(|(j, relayer)| UnrewardedRelayer {
 						relayer: relayer.clone(),
-						messages: DeliveredMessages::new(j as MessageNonce + 1, true),
+						messages: DeliveredMessages::new(j as MessageNonce + 1, true, 0),
 					})
 					.collect(),
 				last_confirmed_nonce: 0,
@@ -898,6 +1132,10 @@ benchmarks_instance_pallet! {
 	}
 }
 
+fn open_bench_lane<T: Config<I>, I: 'static>() {
+	crate::Lanes::<T, I>::insert(T::bench_lane_id(), LaneInfo::opened());
+}
+
 fn send_regular_message<T: Config<I>, I: 'static>() {
 	let mut outbound_lane = outbound_lane::<T, I>(T::bench_lane_id());
 	outbound_lane.send_message(MessageData { payload: vec![], fee: MESSAGE_FEE.into() });
@@ -915,7 +1153,7 @@ fn confirm_message_delivery<T: Config<I>, I: 'static>(nonce: MessageNonce) {
 	for nonce in latest_received_nonce + 1..=nonce {
 		relayers.push_back(UnrewardedRelayer {
 			relayer: (),
-			messages: DeliveredMessages::new(nonce, true),
+			messages: DeliveredMessages::new(nonce, true, 0),
 		});
 	}
 	assert!(matches!(
@@ -929,7 +1167,7 @@ fn receive_messages<T: Config<I>, I: 'static>(nonce: MessageNonce) {
 	inbound_lane_storage.set_data(InboundLaneData {
 		relayers: vec![UnrewardedRelayer {
 			relayer: T::bridged_relayer_id(),
-			messages: DeliveredMessages::new(nonce, true),
+			messages: DeliveredMessages::new(nonce, true, 0),
 		}]
 		.into_iter()
 		.collect(),
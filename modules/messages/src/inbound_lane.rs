@@ -19,7 +19,7 @@
 use bp_messages::{
 	target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
 	DeliveredMessages, InboundLaneData, LaneId, MessageKey, MessageNonce, OutboundLaneData,
-	UnrewardedRelayer,
+	UnrewardedRelayer, Weight,
 };
 use bp_runtime::messages::MessageDispatchResult;
 use frame_support::RuntimeDebug;
@@ -52,6 +52,12 @@ pub enum ReceivalResult {
 	///
 	/// The message dispatch result is also returned.
 	Dispatched(MessageDispatchResult),
+	/// Message has expired before it has been delivered. It is accepted by the lane, but not
+	/// dispatched.
+	Expired,
+	/// Message is too heavy to be dispatched right now. It is accepted by the lane, but its
+	/// dispatch is deferred.
+	Overweight,
 	/// Message has invalid nonce and lane has rejected to accept this message.
 	InvalidNonce,
 	/// There are too many unrewarded relayer entries at the lane.
@@ -107,6 +113,14 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 					.messages
 					.dispatch_results
 					.split_off((new_confirmed_nonce + 1 - entry.messages.begin) as _);
+				entry.messages.unspent_weights = entry
+					.messages
+					.unspent_weights
+					.split_off((new_confirmed_nonce + 1 - entry.messages.begin) as _);
+				entry.messages.expired_messages = entry
+					.messages
+					.expired_messages
+					.split_off((new_confirmed_nonce + 1 - entry.messages.begin) as _);
 				entry.messages.begin = new_confirmed_nonce + 1;
 			},
 			_ => {},
@@ -125,48 +139,139 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 		message_data: DispatchMessageData<P::DispatchPayload, S::MessageFee>,
 	) -> ReceivalResult {
 		let mut data = self.storage.data();
+		if let Err(rejection) = self.ensure_message_is_acceptable(&data, nonce) {
+			return rejection
+		}
+
+		let message = DispatchMessage {
+			key: MessageKey { lane_id: self.storage.id(), nonce },
+			data: message_data,
+		};
+
+		// expired messages are recorded as not dispatched
+		if P::is_expired(&message) {
+			match data.relayers.back_mut() {
+				Some(entry) if entry.relayer == *relayer_at_bridged_chain => {
+					entry.messages.note_expired_message();
+				},
+				_ => {
+					data.relayers.push_back(UnrewardedRelayer {
+						relayer: (*relayer_at_bridged_chain).clone(),
+						messages: DeliveredMessages::new_expired(nonce),
+					});
+				},
+			}
+			self.storage.set_data(data);
+
+			return ReceivalResult::Expired
+		}
+
+		// then, dispatch message
+		let dispatch_result = P::dispatch(relayer_at_this_chain, message);
+
+		// now let's update inbound lane storage
+		note_delivered_message(
+			&mut data,
+			relayer_at_bridged_chain,
+			nonce,
+			dispatch_result.dispatch_result,
+			dispatch_result.unspent_weight,
+		);
+		self.storage.set_data(data);
+
+		ReceivalResult::Dispatched(dispatch_result)
+	}
+
+	/// Receive new message that is too heavy to be dispatched right now.
+	///
+	/// The message is accepted by the lane (so that the lane isn't blocked), but it is not
+	/// dispatched. Until the message is dispatched from the overweight queue, it is recorded as
+	/// failed, with zero unspent weight.
+	pub fn receive_overweight_message(
+		&mut self,
+		relayer_at_bridged_chain: &S::Relayer,
+		nonce: MessageNonce,
+	) -> ReceivalResult {
+		let mut data = self.storage.data();
+		if let Err(rejection) = self.ensure_message_is_acceptable(&data, nonce) {
+			return rejection
+		}
+
+		note_delivered_message(&mut data, relayer_at_bridged_chain, nonce, false, 0);
+		self.storage.set_data(data);
+
+		ReceivalResult::Overweight
+	}
+
+	/// Update dispatch result of the overweight message that has been dispatched after delivery.
+	///
+	/// Returns false if the delivery of the message has already been confirmed to the bridged
+	/// chain. Then the dispatch result can't be reported back to the bridged chain.
+	pub fn note_overweight_message_dispatched(
+		&mut self,
+		nonce: MessageNonce,
+		dispatch_result: &MessageDispatchResult,
+	) -> bool {
+		let mut data = self.storage.data();
+		let entry = data.relayers.iter_mut().find(|entry| entry.messages.contains_message(nonce));
+		match entry {
+			Some(entry) => {
+				entry.messages.set_message_dispatch_result(
+					nonce,
+					dispatch_result.dispatch_result,
+					dispatch_result.unspent_weight,
+				);
+				self.storage.set_data(data);
+				true
+			},
+			None => false,
+		}
+	}
+
+	/// Ensure that the message with given nonce may be accepted by the lane.
+	fn ensure_message_is_acceptable(
+		&self,
+		data: &InboundLaneData<S::Relayer>,
+		nonce: MessageNonce,
+	) -> Result<(), ReceivalResult> {
 		let is_correct_message = nonce == data.last_delivered_nonce() + 1;
 		if !is_correct_message {
-			return ReceivalResult::InvalidNonce
+			return Err(ReceivalResult::InvalidNonce)
 		}
 
 		// if there are more unrewarded relayer entries than we may accept, reject this message
 		if data.relayers.len() as MessageNonce >= self.storage.max_unrewarded_relayer_entries() {
-			return ReceivalResult::TooManyUnrewardedRelayers
+			return Err(ReceivalResult::TooManyUnrewardedRelayers)
 		}
 
 		// if there are more unconfirmed messages than we may accept, reject this message
 		let unconfirmed_messages_count = nonce.saturating_sub(data.last_confirmed_nonce);
 		if unconfirmed_messages_count > self.storage.max_unconfirmed_messages() {
-			return ReceivalResult::TooManyUnconfirmedMessages
+			return Err(ReceivalResult::TooManyUnconfirmedMessages)
 		}
 
-		// then, dispatch message
-		let dispatch_result = P::dispatch(
-			relayer_at_this_chain,
-			DispatchMessage {
-				key: MessageKey { lane_id: self.storage.id(), nonce },
-				data: message_data,
-			},
-		);
+		Ok(())
+	}
+}
 
-		// now let's update inbound lane storage
-		let push_new = match data.relayers.back_mut() {
-			Some(entry) if entry.relayer == *relayer_at_bridged_chain => {
-				entry.messages.note_dispatched_message(dispatch_result.dispatch_result);
-				false
-			},
-			_ => true,
-		};
-		if push_new {
+/// Note delivered (and maybe dispatched) message in the inbound lane data.
+fn note_delivered_message<Relayer: Clone + PartialEq>(
+	data: &mut InboundLaneData<Relayer>,
+	relayer_at_bridged_chain: &Relayer,
+	nonce: MessageNonce,
+	dispatch_result: bool,
+	unspent_weight: Weight,
+) {
+	match data.relayers.back_mut() {
+		Some(entry) if entry.relayer == *relayer_at_bridged_chain => {
+			entry.messages.note_dispatched_message(dispatch_result, unspent_weight);
+		},
+		_ => {
 			data.relayers.push_back(UnrewardedRelayer {
 				relayer: (*relayer_at_bridged_chain).clone(),
-				messages: DeliveredMessages::new(nonce, dispatch_result.dispatch_result),
+				messages: DeliveredMessages::new(nonce, dispatch_result, unspent_weight),
 			});
-		}
-		self.storage.set_data(data);
-
-		ReceivalResult::Dispatched(dispatch_result)
+		},
 	}
 }
 
@@ -501,4 +606,76 @@ mod tests {
 			);
 		});
 	}
+
+	#[test]
+	fn expired_message_is_not_dispatched() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			receive_regular_message(&mut lane, 1);
+
+			frame_system::Pallet::<TestRuntime>::set_block_number(10);
+			let mut payload = REGULAR_PAYLOAD;
+			payload.expiry = Some(9);
+			assert_eq!(
+				lane.receive_message::<TestMessageDispatch, _>(
+					&TEST_RELAYER_A,
+					&TEST_RELAYER_A,
+					2,
+					message_data(payload).into()
+				),
+				ReceivalResult::Expired
+			);
+
+			let data = lane.storage.data();
+			assert_eq!(data.last_delivered_nonce(), 2);
+			assert!(!data.relayers[0].messages.message_expired(1));
+			assert!(data.relayers[0].messages.message_expired(2));
+			assert!(!data.relayers[0].messages.message_dispatch_result(2));
+		});
+	}
+
+	#[test]
+	fn overweight_message_is_not_dispatched_until_serviced() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			receive_regular_message(&mut lane, 1);
+			assert_eq!(
+				lane.receive_overweight_message(&TEST_RELAYER_A, 3),
+				ReceivalResult::InvalidNonce
+			);
+			assert_eq!(
+				lane.receive_overweight_message(&TEST_RELAYER_A, 2),
+				ReceivalResult::Overweight
+			);
+
+			let data = lane.storage.data();
+			assert_eq!(data.last_delivered_nonce(), 2);
+			assert!(!data.relayers[0].messages.message_dispatch_result(2));
+			assert_eq!(data.relayers[0].messages.message_unspent_weight(2), 0);
+
+			assert!(lane.note_overweight_message_dispatched(2, &dispatch_result(10)));
+
+			let data = lane.storage.data();
+			assert!(data.relayers[0].messages.message_dispatch_result(2));
+			assert_eq!(data.relayers[0].messages.message_unspent_weight(2), 10);
+		});
+	}
+
+	#[test]
+	fn overweight_message_dispatch_result_is_not_noted_if_delivery_is_confirmed() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			assert_eq!(
+				lane.receive_overweight_message(&TEST_RELAYER_A, 1),
+				ReceivalResult::Overweight
+			);
+			receive_regular_message(&mut lane, 2);
+			lane.receive_state_update(OutboundLaneData {
+				latest_received_nonce: 1,
+				..Default::default()
+			});
+
+			assert!(!lane.note_overweight_message_dispatched(1, &dispatch_result(0)));
+		});
+	}
 }
@@ -17,19 +17,26 @@
 //! Implementation of `MessageDeliveryAndDispatchPayment` trait on top of `Currency` trait.
 //!
 //! The payment is first transferred to a special `relayers-fund` account and only transferred
-//! to the actual relayer in case confirmation is received.
+//! to the actual relayer in case confirmation is received. If the message dispatch has spent
+//! less weight than declared, the corresponding part of the fee is refunded to the sender. The
+//! whole fee of expired messages may be refunded to the sender as well.
 
-use crate::OutboundMessages;
+use crate::{OutboundMessageSenders, OutboundMessages};
 
 use bp_messages::{
-	source_chain::{MessageDeliveryAndDispatchPayment, RelayersRewards, Sender},
+	source_chain::{
+		MessageDeliveryAndDispatchPayment, RelayersRewards, Sender, UnspentDispatchFeeRefund,
+	},
 	LaneId, MessageKey, MessageNonce, UnrewardedRelayer,
 };
-use codec::Encode;
-use frame_support::traits::{Currency as CurrencyT, ExistenceRequirement, Get};
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Currency as CurrencyT, ExistenceRequirement, Get},
+	weights::Weight,
+};
 use num_traits::{SaturatingAdd, Zero};
 use sp_runtime::traits::Saturating;
-use sp_std::{collections::vec_deque::VecDeque, fmt::Debug, ops::RangeInclusive};
+use sp_std::{collections::vec_deque::VecDeque, fmt::Debug, ops::RangeInclusive, prelude::*};
 
 /// Instant message payments made in given currency.
 ///
@@ -39,18 +46,35 @@ use sp_std::{collections::vec_deque::VecDeque, fmt::Debug, ops::RangeInclusive};
 /// Additionally, confirmation transaction submitter (`confirmation_relayer`) is reimbursed
 /// with the confirmation rewards (part of message fee, reserved to pay for delivery confirmation).
 ///
+/// Message submitters are refunded with the part of message fee that corresponds to the unspent
+/// dispatch weight (see `UnspentFeeRefund`). The refund is deducted from the relayer reward.
+///
 /// NOTE The `relayers-fund` account must always exist i.e. be over Existential Deposit (ED; the
 /// pallet enforces that) to make sure that even if the message cost is below ED it is still paid
 /// to the relayer account.
 /// NOTE It's within relayer's interest to keep their balance above ED as well, to make sure they
 /// can receive the payment.
-pub struct InstantCurrencyPayments<T, I, Currency, GetConfirmationFee, RootAccount> {
-	_phantom: sp_std::marker::PhantomData<(T, I, Currency, GetConfirmationFee, RootAccount)>,
+pub struct InstantCurrencyPayments<
+	T,
+	I,
+	Currency,
+	GetConfirmationFee,
+	RootAccount,
+	UnspentFeeRefund = (),
+> {
+	_phantom: sp_std::marker::PhantomData<(
+		T,
+		I,
+		Currency,
+		GetConfirmationFee,
+		RootAccount,
+		UnspentFeeRefund,
+	)>,
 }
 
-impl<T, I, Currency, GetConfirmationFee, RootAccount>
+impl<T, I, Currency, GetConfirmationFee, RootAccount, UnspentFeeRefund>
 	MessageDeliveryAndDispatchPayment<T::AccountId, Currency::Balance>
-	for InstantCurrencyPayments<T, I, Currency, GetConfirmationFee, RootAccount>
+	for InstantCurrencyPayments<T, I, Currency, GetConfirmationFee, RootAccount, UnspentFeeRefund>
 where
 	T: frame_system::Config + crate::Config<I>,
 	I: 'static,
@@ -58,6 +82,8 @@ where
 	Currency::Balance: From<MessageNonce>,
 	GetConfirmationFee: Get<Currency::Balance>,
 	RootAccount: Get<Option<T::AccountId>>,
+	UnspentFeeRefund: UnspentDispatchFeeRefund<T::OutboundPayload, Currency::Balance>,
+	T::OutboundMessageFee: Saturating,
 {
 	type Error = &'static str;
 
@@ -88,6 +114,29 @@ where
 		.map_err(Into::into)
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &Sender<T::AccountId>,
+		fee: &Currency::Balance,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		let root_account = RootAccount::get();
+		let account = match submitter {
+			Sender::Signed(submitter) => submitter,
+			Sender::Root | Sender::None => root_account
+				.as_ref()
+				.ok_or("Refunding messages, sent using Root or None origin, is disallowed.")?,
+		};
+
+		Currency::transfer(
+			relayer_fund_account,
+			account,
+			*fee,
+			// the relayer fund account must stay above ED (needs to be pre-funded)
+			ExistenceRequirement::KeepAlive,
+		)
+		.map_err(Into::into)
+	}
+
 	fn pay_relayers_rewards(
 		lane_id: LaneId,
 		messages_relayers: VecDeque<UnrewardedRelayer<T::AccountId>>,
@@ -95,8 +144,19 @@ where
 		received_range: &RangeInclusive<MessageNonce>,
 		relayer_fund_account: &T::AccountId,
 	) {
-		let relayers_rewards =
-			cal_relayers_rewards::<T, I>(lane_id, messages_relayers, received_range);
+		let (relayers_rewards, refunds) = cal_relayers_rewards_and_refunds::<T, I, UnspentFeeRefund>(
+			lane_id,
+			messages_relayers,
+			received_range,
+		);
+		if !refunds.is_empty() {
+			let root_account = RootAccount::get();
+			pay_unspent_dispatch_fee_refunds::<Currency, _>(
+				refunds,
+				root_account.as_ref(),
+				relayer_fund_account,
+			);
+		}
 		if !relayers_rewards.is_empty() {
 			pay_relayers_rewards::<Currency, _>(
 				confirmation_relayer,
@@ -108,6 +168,9 @@ where
 	}
 }
 
+/// Unspent dispatch fee refunds, paid to message senders.
+pub type UnspentDispatchFeeRefunds<AccountId, Balance> = Vec<(Sender<AccountId>, Balance)>;
+
 /// Calculate the relayers rewards
 pub(crate) fn cal_relayers_rewards<T, I>(
 	lane_id: LaneId,
@@ -117,10 +180,34 @@ pub(crate) fn cal_relayers_rewards<T, I>(
 where
 	T: frame_system::Config + crate::Config<I>,
 	I: 'static,
+	T::OutboundMessageFee: Saturating,
+{
+	cal_relayers_rewards_and_refunds::<T, I, ()>(lane_id, messages_relayers, received_range).0
+}
+
+/// Calculate the relayers rewards and unspent dispatch fee refunds.
+///
+/// The refund of every message is deducted from the reward of the relayer that has delivered
+/// this message. Relayers are not rewarded for delivering expired messages, because the fee of
+/// such messages is refunded to their senders.
+pub fn cal_relayers_rewards_and_refunds<T, I, UnspentFeeRefund>(
+	lane_id: LaneId,
+	messages_relayers: VecDeque<UnrewardedRelayer<T::AccountId>>,
+	received_range: &RangeInclusive<MessageNonce>,
+) -> (
+	RelayersRewards<T::AccountId, T::OutboundMessageFee>,
+	UnspentDispatchFeeRefunds<T::AccountId, T::OutboundMessageFee>,
+)
+where
+	T: frame_system::Config + crate::Config<I>,
+	I: 'static,
+	UnspentFeeRefund: UnspentDispatchFeeRefund<T::OutboundPayload, T::OutboundMessageFee>,
+	T::OutboundMessageFee: Saturating,
 {
 	// remember to reward relayers that have delivered messages
 	// this loop is bounded by `T::MaxUnrewardedRelayerEntriesAtInboundLane` on the bridged chain
 	let mut relayers_rewards: RelayersRewards<_, T::OutboundMessageFee> = RelayersRewards::new();
+	let mut refunds = UnspentDispatchFeeRefunds::new();
 	for entry in messages_relayers {
 		let nonce_begin = sp_std::cmp::max(entry.messages.begin, *received_range.start());
 		let nonce_end = sp_std::cmp::min(entry.messages.end, *received_range.end());
@@ -129,13 +216,111 @@ where
 		// this loop is bound by `T::MaxUnconfirmedMessagesAtInboundLane` on the bridged chain
 		let mut relayer_reward = relayers_rewards.entry(entry.relayer).or_default();
 		for nonce in nonce_begin..nonce_end + 1 {
-			let message_data = OutboundMessages::<T, I>::get(MessageKey { lane_id, nonce })
+			if entry.messages.message_expired(nonce) {
+				continue
+			}
+
+			let message_key = MessageKey { lane_id, nonce };
+			let message_data = OutboundMessages::<T, I>::get(&message_key)
 				.expect("message was just confirmed; we never prune unconfirmed messages; qed");
-			relayer_reward.reward = relayer_reward.reward.saturating_add(&message_data.fee);
+			let refund = cal_unspent_dispatch_fee_refund::<T, I, UnspentFeeRefund>(
+				&message_key,
+				&message_data.payload,
+				&message_data.fee,
+				entry.messages.message_unspent_weight(nonce),
+			);
+			let relayer_fee = match refund {
+				Some((sender, refund)) => {
+					let relayer_fee = Saturating::saturating_sub(message_data.fee, refund);
+					refunds.push((sender, refund));
+					relayer_fee
+				},
+				None => message_data.fee,
+			};
+			relayer_reward.reward =
+				SaturatingAdd::saturating_add(&relayer_reward.reward, &relayer_fee);
 			relayer_reward.messages += 1;
 		}
 	}
-	relayers_rewards
+	(relayers_rewards, refunds)
+}
+
+/// Calculate refund of the unspent dispatch fee of single message.
+///
+/// Returns `None` if nothing needs to be refunded. The refund never exceeds the message fee.
+fn cal_unspent_dispatch_fee_refund<T, I, UnspentFeeRefund>(
+	message_key: &MessageKey,
+	encoded_payload: &[u8],
+	fee: &T::OutboundMessageFee,
+	unspent_weight: Weight,
+) -> Option<(Sender<T::AccountId>, T::OutboundMessageFee)>
+where
+	T: frame_system::Config + crate::Config<I>,
+	I: 'static,
+	UnspentFeeRefund: UnspentDispatchFeeRefund<T::OutboundPayload, T::OutboundMessageFee>,
+{
+	if unspent_weight == 0 {
+		return None
+	}
+
+	let payload = T::OutboundPayload::decode(&mut &encoded_payload[..]).ok()?;
+	let refund = UnspentFeeRefund::unspent_dispatch_fee(&payload, unspent_weight);
+	let refund = if refund > *fee { *fee } else { refund };
+	if refund.is_zero() {
+		return None
+	}
+
+	let sender = OutboundMessageSenders::<T, I>::get(message_key)?;
+	Some((sender, refund))
+}
+
+/// Refund unspent dispatch fee to message senders.
+///
+/// Refunds of messages that have been sent by `Root` or `None` origin are paid to the
+/// `root_account` (if it is set), because this account has paid the message fee.
+pub fn pay_unspent_dispatch_fee_refunds<Currency, AccountId>(
+	refunds: UnspentDispatchFeeRefunds<AccountId, Currency::Balance>,
+	root_account: Option<&AccountId>,
+	relayer_fund_account: &AccountId,
+) where
+	AccountId: Debug,
+	Currency: CurrencyT<AccountId>,
+{
+	for (sender, refund) in refunds {
+		let sender_account = match sender {
+			Sender::Signed(ref sender_account) => Some(sender_account),
+			Sender::Root | Sender::None => root_account,
+		};
+
+		let sender_account = match sender_account {
+			Some(sender_account) => sender_account,
+			None => continue,
+		};
+
+		let refund_result = Currency::transfer(
+			relayer_fund_account,
+			sender_account,
+			refund,
+			// the relayer fund account must stay above ED (needs to be pre-funded)
+			ExistenceRequirement::KeepAlive,
+		);
+
+		match refund_result {
+			Ok(_) => log::trace!(
+				target: "runtime::bridge-messages",
+				"Refunded message sender {:?} with {:?}",
+				sender_account,
+				refund,
+			),
+			Err(error) => log::trace!(
+				target: "runtime::bridge-messages",
+				"Failed to refund message sender {:?} with {:?}: {:?}",
+				sender_account,
+				refund,
+				error,
+			),
+		}
+	}
 }
 
 /// Pay rewards to given relayers, optionally rewarding confirmation relayer.
@@ -148,9 +333,29 @@ fn pay_relayers_rewards<Currency, AccountId>(
 	AccountId: Debug + Default + Encode + PartialEq,
 	Currency: CurrencyT<AccountId>,
 	Currency::Balance: From<u64>,
+{
+	distribute_relayers_rewards(
+		confirmation_relayer,
+		relayers_rewards,
+		confirmation_fee,
+		|relayer, reward| pay_relayer_reward::<Currency, _>(relayer_fund_account, relayer, reward),
+	);
+}
+
+/// Distribute rewards between given relayers, optionally rewarding confirmation relayer.
+///
+/// The `pay_relayer_reward` is called once for every relayer, including the confirmation relayer.
+pub fn distribute_relayers_rewards<AccountId, Balance>(
+	confirmation_relayer: &AccountId,
+	relayers_rewards: RelayersRewards<AccountId, Balance>,
+	confirmation_fee: Balance,
+	mut pay_relayer_reward: impl FnMut(&AccountId, Balance),
+) where
+	AccountId: PartialEq,
+	Balance: Copy + From<u64> + Saturating + PartialOrd + Zero,
 {
 	// reward every relayer except `confirmation_relayer`
-	let mut confirmation_relayer_reward = Currency::Balance::zero();
+	let mut confirmation_relayer_reward = Balance::zero();
 	for (relayer, reward) in relayers_rewards {
 		let mut relayer_reward = reward.reward;
 
@@ -174,15 +379,11 @@ fn pay_relayers_rewards<Currency, AccountId>(
 			continue
 		}
 
-		pay_relayer_reward::<Currency, _>(relayer_fund_account, &relayer, relayer_reward);
+		pay_relayer_reward(&relayer, relayer_reward);
 	}
 
 	// finally - pay reward to confirmation relayer
-	pay_relayer_reward::<Currency, _>(
-		relayer_fund_account,
-		confirmation_relayer,
-		confirmation_relayer_reward,
-	);
+	pay_relayer_reward(confirmation_relayer, confirmation_relayer_reward);
 }
 
 /// Transfer funds from relayers fund account to given relayer.
@@ -226,8 +427,12 @@ fn pay_relayer_reward<Currency, AccountId>(
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::mock::{run_test, AccountId as TestAccountId, Balance as TestBalance, TestRuntime};
-	use bp_messages::source_chain::RelayerRewards;
+	use crate::mock::{
+		message_data, run_test, unrewarded_relayer, AccountId as TestAccountId,
+		Balance as TestBalance, TestPayload, TestRuntime, REGULAR_PAYLOAD, TEST_LANE_ID,
+	};
+	use bp_messages::{source_chain::RelayerRewards, MessageData};
+	use codec::Compact;
 
 	type Balances = pallet_balances::Pallet<TestRuntime>;
 
@@ -235,6 +440,17 @@ mod tests {
 	const RELAYER_2: TestAccountId = 2;
 	const RELAYER_3: TestAccountId = 3;
 	const RELAYERS_FUND_ACCOUNT: TestAccountId = crate::mock::ENDOWED_ACCOUNT;
+	const SENDER: TestAccountId = 4;
+	const ROOT_ACCOUNT: TestAccountId = 5;
+
+	/// Refunds single balance unit for every unit of unspent weight.
+	struct TestUnspentFeeRefund;
+
+	impl UnspentDispatchFeeRefund<TestPayload, TestBalance> for TestUnspentFeeRefund {
+		fn unspent_dispatch_fee(_payload: &TestPayload, unspent_weight: Weight) -> TestBalance {
+			unspent_weight
+		}
+	}
 
 	fn relayers_rewards() -> RelayersRewards<TestAccountId, TestBalance> {
 		vec![
@@ -291,4 +507,91 @@ mod tests {
 			assert_eq!(Balances::free_balance(&RELAYER_3), 200);
 		});
 	}
+
+	#[test]
+	fn unspent_dispatch_fee_refund_is_deducted_from_relayer_reward() {
+		run_test(|| {
+			// message 1 has unspent weight and known sender => it is refunded
+			// message 2 has unspent weight that is larger than fee => refund is limited by fee
+			// message 3 has no unspent weight => it is not refunded
+			// message 4 has unspent weight, but unknown sender => it is not refunded
+			for nonce in 1..=4 {
+				let message_key = MessageKey { lane_id: TEST_LANE_ID, nonce };
+				OutboundMessages::<TestRuntime, ()>::insert(
+					&message_key,
+					MessageData { fee: 100, ..message_data(REGULAR_PAYLOAD) },
+				);
+				if nonce != 4 {
+					OutboundMessageSenders::<TestRuntime, ()>::insert(
+						&message_key,
+						Sender::Signed(SENDER),
+					);
+				}
+			}
+			let mut relayer_entry = unrewarded_relayer(1, 4, RELAYER_1);
+			relayer_entry.messages.unspent_weights =
+				vec![Compact(30), Compact(200), Compact(0), Compact(40)];
+
+			let (relayers_rewards, refunds) =
+				cal_relayers_rewards_and_refunds::<TestRuntime, (), TestUnspentFeeRefund>(
+					TEST_LANE_ID,
+					vec![relayer_entry].into_iter().collect(),
+					&(1..=4),
+				);
+
+			assert_eq!(relayers_rewards.get(&RELAYER_1).map(|r| r.reward), Some(270));
+			assert_eq!(relayers_rewards.get(&RELAYER_1).map(|r| r.messages), Some(4));
+			assert_eq!(refunds, vec![(Sender::Signed(SENDER), 30), (Sender::Signed(SENDER), 100)]);
+		});
+	}
+
+	#[test]
+	fn relayers_are_not_rewarded_for_expired_messages() {
+		run_test(|| {
+			for nonce in 1..=2 {
+				OutboundMessages::<TestRuntime, ()>::insert(
+					MessageKey { lane_id: TEST_LANE_ID, nonce },
+					MessageData { fee: 100, ..message_data(REGULAR_PAYLOAD) },
+				);
+			}
+			let mut relayer_entry = unrewarded_relayer(1, 2, RELAYER_1);
+			relayer_entry.messages.expired_messages.set(1, true);
+
+			let relayers_rewards = cal_relayers_rewards::<TestRuntime, ()>(
+				TEST_LANE_ID,
+				vec![relayer_entry].into_iter().collect(),
+				&(1..=2),
+			);
+
+			assert_eq!(relayers_rewards.get(&RELAYER_1).map(|r| r.reward), Some(100));
+			assert_eq!(relayers_rewards.get(&RELAYER_1).map(|r| r.messages), Some(1));
+		});
+	}
+
+	#[test]
+	fn unspent_dispatch_fee_is_refunded_to_message_senders() {
+		run_test(|| {
+			pay_unspent_dispatch_fee_refunds::<Balances, _>(
+				vec![(Sender::Signed(SENDER), 10), (Sender::Root, 20), (Sender::None, 30)],
+				Some(&ROOT_ACCOUNT),
+				&RELAYERS_FUND_ACCOUNT,
+			);
+
+			assert_eq!(Balances::free_balance(&SENDER), 10);
+			assert_eq!(Balances::free_balance(&ROOT_ACCOUNT), 50);
+		});
+	}
+
+	#[test]
+	fn unspent_dispatch_fee_is_not_refunded_if_root_account_is_not_set() {
+		run_test(|| {
+			pay_unspent_dispatch_fee_refunds::<Balances, _>(
+				vec![(Sender::Root, 20)],
+				None,
+				&RELAYERS_FUND_ACCOUNT,
+			);
+
+			assert_eq!(Balances::free_balance(&RELAYERS_FUND_ACCOUNT), 1_000_000);
+		});
+	}
 }
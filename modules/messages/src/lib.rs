@@ -26,6 +26,30 @@
 //! Once message is sent, its progress can be tracked by looking at module events.
 //! The assigned nonce is reported using `MessageAccepted` event. When message is
 //! delivered to the the bridged chain, it is reported using `MessagesDelivered` event.
+//! If message dispatch at the bridged chain has failed, the `MessageDispatchFailed` event
+//! is emitted. Dispatch statuses of last confirmed messages are also kept in the runtime
+//! storage and may be queried using `Pallet::outbound_message_dispatch_status`.
+//!
+//! The message may have expired before it has been delivered to the bridged chain. Such messages
+//! are not dispatched and the `MessageExpired` event is emitted when the delivery is confirmed.
+//! Relayers are not rewarded for delivering expired messages - instead, the message fee may be
+//! refunded to the sender using `refund_expired_message()` call.
+//!
+//! Inbound messages with dispatch weight larger than `MaxInlineDispatchWeight`, larger than the
+//! dispatch weight that is left from the weight declared by relayer or larger than the weight left
+//! in the block, are not dispatched during delivery. Instead, they're put into the overweight queue
+//! (the `MessageOverweight` event is emitted) and may later be dispatched by anyone, using
+//! `service_overweight()` call. If this happens before the delivery is confirmed, the dispatch
+//! result is reported back to the source chain.
+//!
+//! Lanes are managed by the pallet owner (or root). Only lanes that are explicitly opened (either
+//! at genesis, or using `set_lane_state()` call) may be used to send messages. Closing lane no
+//! longer accepts new messages, but already queued messages are still delivered and confirmed.
+//! Closed lanes reject everything. Every lane also has its own operating mode, that is checked in
+//! addition to the pallet operating mode. Registered lanes may be listed using `Pallet::lanes`.
+//! The owner may also restrict the set of senders that are allowed to send messages over the lane
+//! (see `set_lane_allowed_senders()` call). The restriction itself is enforced by the
+//! `LaneMessageVerifier` implementation.
 //!
 //! **IMPORTANT NOTE**: after generating weights (custom `WeighInfo` implementation) for
 //! your runtime (where this module is plugged to), please add test for these weights.
@@ -51,33 +75,38 @@ use crate::{
 use bp_messages::{
 	source_chain::{
 		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, OnDeliveryConfirmed,
-		OnMessageAccepted, SendMessageArtifacts, TargetHeaderChain,
+		OnMessageAccepted, SendMessageArtifacts, Sender, TargetHeaderChain,
 	},
 	target_chain::{
-		DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages, SourceHeaderChain,
+		DispatchMessage, DispatchMessageData, MessageDispatch, ProvedMessages, SourceHeaderChain,
 	},
-	total_unrewarded_messages, DeliveredMessages, InboundLaneData, LaneId, MessageData, MessageKey,
-	MessageNonce, OperatingMode, OutboundLaneData, Parameter as MessagesParameter,
-	UnrewardedRelayersState,
+	total_unrewarded_messages, DeliveredMessages, InboundLaneData, LaneId, LaneInfo, LaneState,
+	Message, MessageData, MessageDispatchStatus, MessageKey, MessageNonce, OperatingMode,
+	OutboundLaneData, Parameter as MessagesParameter, UnrewardedRelayersState,
 };
-use bp_runtime::{ChainId, Size};
+use bp_runtime::{messages::DispatchFeePayment, ChainId, Size};
 use codec::{Decode, Encode};
 use frame_support::{
-	fail,
+	ensure, fail,
+	storage::with_transaction,
 	traits::Get,
-	weights::{Pays, PostDispatchInfo},
+	weights::{Pays, PostDispatchInfo, Weight},
 };
 use frame_system::RawOrigin;
 use num_traits::{SaturatingAdd, Zero};
 use sp_core::H256;
-use sp_runtime::traits::{BadOrigin, Convert};
-use sp_std::{cell::RefCell, cmp::PartialOrd, marker::PhantomData, prelude::*};
+use sp_runtime::{
+	traits::{BadOrigin, Convert},
+	TransactionOutcome,
+};
+use sp_std::{cell::RefCell, cmp::PartialOrd, convert::TryFrom, marker::PhantomData, prelude::*};
 
 mod inbound_lane;
 mod outbound_lane;
 mod weights_ext;
 
 pub mod instant_payments;
+pub mod migrations;
 pub mod weights;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -116,6 +145,10 @@ pub mod pallet {
 		/// whenever new message is sent. The reason is that if you want to use lane, you should
 		/// be ready to pay for its maintenance.
 		type MaxMessagesToPruneAtOnce: Get<MessageNonce>;
+		/// Maximal number of dispatch statuses of confirmed outbound messages that are kept in the
+		/// storage of every lane. When delivery of message with nonce `N` is confirmed, status of
+		/// the message with nonce `N - MaxDispatchStatusesToKeep` is removed from the storage.
+		type MaxDispatchStatusesToKeep: Get<MessageNonce>;
 		/// Maximal number of unrewarded relayer entries at inbound lane. Unrewarded means that the
 		/// relayer has delivered messages, but either confirmations haven't been delivered back to
 		/// the source chain, or we haven't received reward confirmations yet.
@@ -139,6 +172,14 @@ pub mod pallet {
 		/// Transaction that is declaring more messages than this value, will be rejected. Even if
 		/// these messages are from different lanes.
 		type MaxUnconfirmedMessagesAtInboundLane: Get<MessageNonce>;
+		/// Maximal dispatch weight of the inbound message that is dispatched during delivery.
+		///
+		/// Messages with larger dispatch weight are put into the overweight queue. They may be
+		/// dispatched later, using the `service_overweight` call.
+		type MaxInlineDispatchWeight: Get<Weight>;
+		/// Maximal number of senders that may be allowed to send messages over single outbound
+		/// lane (see `set_lane_allowed_senders` call).
+		type MaxAllowedSenders: Get<u32>;
 
 		/// Payload type of outbound messages. This payload is dispatched on the bridged chain.
 		type OutboundPayload: Parameter + Size;
@@ -154,7 +195,7 @@ pub mod pallet {
 		/// Payload type of inbound messages. This payload is dispatched on this chain.
 		type InboundPayload: Decode;
 		/// Message fee type of inbound messages. This fee is paid on the bridged chain.
-		type InboundMessageFee: Decode;
+		type InboundMessageFee: Decode + Encode;
 		/// Identifier of relayer that deliver messages to this chain. Relayer reward is paid on the
 		/// bridged chain.
 		type InboundRelayer: Parameter;
@@ -209,8 +250,16 @@ pub mod pallet {
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(crate::migrations::STORAGE_VERSION)]
 	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::migrate::<T, I>()
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		/// Change `PalletOwner`.
@@ -250,6 +299,92 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Open, start closing or close the lane.
+		///
+		/// The lane that is not yet registered may only be opened. Just opened lane is operating
+		/// in `Normal` mode.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Operational))]
+		pub fn set_lane_state(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			state: LaneState,
+		) -> DispatchResult {
+			ensure_owner_or_root::<T, I>(origin)?;
+			Lanes::<T, I>::try_mutate(&lane_id, |lane_info| -> DispatchResult {
+				match lane_info {
+					Some(ref mut lane_info) => lane_info.state = state,
+					None if state == LaneState::Opened => *lane_info = Some(LaneInfo::opened()),
+					None => fail!(Error::<T, I>::UnknownLane),
+				}
+				Ok(())
+			})?;
+
+			log::info!(
+				target: "runtime::bridge-messages",
+				"Setting state of lane {:?} to {:?}.",
+				lane_id,
+				state,
+			);
+			Self::deposit_event(Event::LaneStateChanged(lane_id, state));
+			Ok(())
+		}
+
+		/// Halt or resume all/some operations at given lane.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Operational))]
+		pub fn set_lane_operating_mode(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			operating_mode: OperatingMode,
+		) -> DispatchResult {
+			ensure_owner_or_root::<T, I>(origin)?;
+			Lanes::<T, I>::try_mutate(&lane_id, |lane_info| -> DispatchResult {
+				let lane_info = lane_info.as_mut().ok_or(Error::<T, I>::UnknownLane)?;
+				lane_info.operating_mode = operating_mode;
+				Ok(())
+			})?;
+
+			log::info!(
+				target: "runtime::bridge-messages",
+				"Setting operating mode of lane {:?} to {:?}.",
+				lane_id,
+				operating_mode,
+			);
+			Self::deposit_event(Event::LaneOperatingModeChanged(lane_id, operating_mode));
+			Ok(())
+		}
+
+		/// Set or remove the list of senders that are allowed to send messages over given lane.
+		///
+		/// If the list is `None`, the lane is not restricted. Please keep in mind that the list
+		/// is only enforced if the runtime is using lane verifier that respects it.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((
+			T::WeightInfo::set_lane_allowed_senders(
+				allowed_senders.as_ref().map(|senders| senders.len() as u32).unwrap_or(0),
+			),
+			DispatchClass::Operational,
+		))]
+		pub fn set_lane_allowed_senders(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			allowed_senders: Option<Vec<Sender<T::AccountId>>>,
+		) -> DispatchResult {
+			ensure_owner_or_root::<T, I>(origin)?;
+			let allowed_senders = allowed_senders
+				.map(|senders| {
+					BoundedVec::try_from(senders).map_err(|_| Error::<T, I>::TooManyAllowedSenders)
+				})
+				.transpose()?;
+			OutboundLaneAllowedSenders::<T, I>::set(&lane_id, allowed_senders);
+			Self::deposit_event(Event::LaneAllowedSendersUpdated(lane_id));
+			Ok(())
+		}
+
 		/// Update pallet parameter.
 		///
 		/// May only be called either by root, or by `PalletOwner`.
@@ -267,8 +402,33 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Send multiple messages over the same lane.
+		///
+		/// Either all messages are accepted, or the whole batch is rejected.
+		#[pallet::weight(send_messages_declared_weight::<T, I>(messages))]
+		pub fn send_messages(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			messages: Vec<(T::OutboundPayload, T::OutboundMessageFee)>,
+		) -> DispatchResultWithPostInfo {
+			let declared_weight = send_messages_declared_weight::<T, I>(&messages);
+			let declared_messages_weights = messages
+				.iter()
+				.map(|(payload, _)| send_message_declared_weight::<T, I>(payload))
+				.collect::<Vec<_>>();
+			crate::send_messages::<T, I>(origin.into().map_err(|_| BadOrigin)?, lane_id, messages)
+				.map(|sent_messages| PostDispatchInfo {
+					actual_weight: Some(send_messages_actual_weight(
+						declared_weight,
+						&declared_messages_weights,
+						&sent_messages,
+					)),
+					pays_fee: Pays::Yes,
+				})
+		}
+
 		/// Send message over lane.
-		#[pallet::weight(T::WeightInfo::send_message_weight(payload, T::DbWeight::get()))]
+		#[pallet::weight(send_message_declared_weight::<T, I>(payload))]
 		pub fn send_message(
 			origin: OriginFor<T>,
 			lane_id: LaneId,
@@ -355,6 +515,47 @@ pub mod pallet {
 			Ok(PostDispatchInfo { actual_weight: Some(actual_weight), pays_fee: Pays::Yes })
 		}
 
+		/// Refund fee of the message that has expired before it has been delivered to the
+		/// bridged chain.
+		///
+		/// The fee is refunded to the message sender, so anyone may submit this transaction.
+		/// It is only possible to refund the message after its delivery has been confirmed.
+		#[pallet::weight(T::WeightInfo::refund_expired_message())]
+		pub fn refund_expired_message(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			nonce: MessageNonce,
+		) -> DispatchResult {
+			ensure_not_halted::<T, I>()?;
+			ensure_signed(origin)?;
+
+			let message_key = MessageKey { lane_id, nonce };
+			let (sender, fee) = ExpiredMessagesRefunds::<T, I>::get(&message_key)
+				.ok_or(Error::<T, I>::MessageIsNotRefundable)?;
+			T::MessageDeliveryAndDispatchPayment::refund_delivery_and_dispatch_fee(
+				&sender,
+				&fee,
+				&relayer_fund_account_id::<T::AccountId, T::AccountIdConverter>(),
+			)
+			.map_err(|err| {
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Failed to refund fee {:?} of the expired message {:?}/{:?} to {:?}: {:?}",
+					fee,
+					lane_id,
+					nonce,
+					sender,
+					err,
+				);
+
+				Error::<T, I>::FailedToRefundMessageFee
+			})?;
+			ExpiredMessagesRefunds::<T, I>::remove(&message_key);
+
+			Self::deposit_event(Event::ExpiredMessageRefunded(lane_id, nonce));
+			Ok(())
+		}
+
 		/// Receive messages proof from bridged chain.
 		///
 		/// The weight of the call assumes that the transaction always brings outbound lane
@@ -394,12 +595,11 @@ pub mod pallet {
 			);
 			let mut actual_weight = declared_weight;
 
-			// verify messages proof && convert proof into messages
-			let messages = verify_and_decode_messages_proof::<
-				T::SourceHeaderChain,
-				T::InboundMessageFee,
-				T::InboundPayload,
-			>(proof, messages_count)
+			// verify messages proof
+			let messages = verify_messages_proof::<T::SourceHeaderChain, T::InboundMessageFee>(
+				proof,
+				messages_count,
+			)
 			.map_err(|err| {
 				log::trace!(
 					target: "runtime::bridge-messages",
@@ -410,10 +610,20 @@ pub mod pallet {
 				Error::<T, I>::InvalidMessagesProof
 			})?;
 
+			// reject messages to closed or halted lanes
+			for lane_id in messages.keys() {
+				ensure_lane_accepts_inbound_messages::<T, I>(lane_id)?;
+			}
+
 			// dispatch messages and (optionally) update lane(s) state(s)
 			let mut total_messages = 0;
 			let mut valid_messages = 0;
 			let mut dispatch_weight_left = dispatch_weight;
+			let mut refunded_dispatch_weight: Weight = 0;
+			let mut overweight_dispatch_weight: Weight = 0;
+			let block_weight_left = T::BlockWeights::get()
+				.max_block
+				.saturating_sub(frame_system::Pallet::<T>::block_weight().total());
 			for (lane_id, lane_data) in messages {
 				let mut lane = inbound_lane::<T, I>(lane_id);
 
@@ -432,10 +642,66 @@ pub mod pallet {
 				for message in lane_data.messages {
 					debug_assert_eq!(message.key.lane_id, lane_id);
 
+					let Message { key: message_key, data: MessageData { payload, fee } } = message;
+					let message = DispatchMessage {
+						key: message_key,
+						data: DispatchMessageData {
+							payload: T::InboundPayload::decode(&mut &payload[..]),
+							fee,
+						},
+					};
+
+					// messages that can't be dispatched during delivery are put into the overweight
+					// queue. It happens when the message is too heavy, when relayer hasn't declared
+					// enough dispatch weight or when the block has no room for the dispatch.
+					// They're not blocking the lane and relayer doesn't need to declare their
+					// dispatch weight
+					let dispatch_weight = T::MessageDispatch::dispatch_weight(&message);
+					let is_overweight = dispatch_weight > T::MaxInlineDispatchWeight::get() ||
+						dispatch_weight > dispatch_weight_left ||
+						dispatch_weight > block_weight_left;
+					if is_overweight && !T::MessageDispatch::is_expired(&message) {
+						total_messages += 1;
+
+						// if relayer has declared dispatch weight of this message, it is refunded
+						// below - when we know how much of declared weight is left unused
+						overweight_dispatch_weight =
+							overweight_dispatch_weight.saturating_add(dispatch_weight);
+
+						// the message isn't dispatched during delivery, so if its dispatch fee is
+						// paid at this chain, nobody pays it here. Instead, the relayer pays for
+						// storing the message in the overweight queue
+						if T::MessageDispatch::dispatch_fee_payment(&message) ==
+							DispatchFeePayment::AtTargetChain
+						{
+							actual_weight = actual_weight
+								.saturating_sub(T::WeightInfo::pay_inbound_dispatch_fee_overhead());
+						}
+
+						let nonce = message.key.nonce;
+						let receival_result =
+							lane.receive_overweight_message(&relayer_id_at_bridged_chain, nonce);
+						if receival_result == ReceivalResult::Overweight {
+							valid_messages += 1;
+							OverweightMessages::<T, I>::insert(
+								&message.key,
+								MessageData { payload, fee: message.data.fee },
+							);
+							Self::deposit_event(Event::MessageOverweight(lane_id, nonce));
+
+							actual_weight = actual_weight.saturating_add(
+								T::WeightInfo::overweight_message_storage_overhead(
+									T::DbWeight::get(),
+								),
+							);
+						}
+
+						continue
+					}
+
 					// ensure that relayer has declared enough weight for dispatching next message
 					// on this lane. We can't dispatch lane messages out-of-order, so if declared
 					// weight is not enough, let's move to next lane
-					let dispatch_weight = T::MessageDispatch::dispatch_weight(&message);
 					if dispatch_weight > dispatch_weight_left {
 						log::trace!(
 							target: "runtime::bridge-messages",
@@ -469,6 +735,11 @@ pub mod pallet {
 								!dispatch_result.dispatch_fee_paid_during_dispatch,
 							)
 						},
+						ReceivalResult::Expired => {
+							valid_messages += 1;
+							(dispatch_weight, true)
+						},
+						ReceivalResult::Overweight |
 						ReceivalResult::InvalidNonce |
 						ReceivalResult::TooManyUnrewardedRelayers |
 						ReceivalResult::TooManyUnconfirmedMessages => (dispatch_weight, true),
@@ -476,6 +747,8 @@ pub mod pallet {
 
 					let unspent_weight = sp_std::cmp::min(unspent_weight, dispatch_weight);
 					dispatch_weight_left -= dispatch_weight - unspent_weight;
+					refunded_dispatch_weight =
+						refunded_dispatch_weight.saturating_add(unspent_weight);
 					actual_weight = actual_weight.saturating_sub(unspent_weight).saturating_sub(
 						// delivery call weight formula assumes that the fee is paid at
 						// this (target) chain. If the message is prepaid at the source
@@ -489,6 +762,16 @@ pub mod pallet {
 				}
 			}
 
+			// refund dispatch weight of overweight messages, but only if relayer has declared it
+			// (i.e. if it hasn't been spent on dispatching other messages and hasn't been refunded
+			// yet)
+			let unused_dispatch_weight =
+				dispatch_weight_left.saturating_sub(refunded_dispatch_weight);
+			actual_weight = actual_weight.saturating_sub(sp_std::cmp::min(
+				overweight_dispatch_weight,
+				unused_dispatch_weight,
+			));
+
 			log::trace!(
 				target: "runtime::bridge-messages",
 				"Received messages: total={}, valid={}. Weight used: {}/{}",
@@ -501,6 +784,57 @@ pub mod pallet {
 			Ok(PostDispatchInfo { actual_weight: Some(actual_weight), pays_fee: Pays::Yes })
 		}
 
+		/// Dispatch message from the overweight queue.
+		///
+		/// Anyone may call this method. The caller pays for the message dispatch and if dispatch
+		/// fee of the message is paid at this chain, the caller receives it.
+		#[pallet::weight(
+			T::WeightInfo::service_overweight_overhead().saturating_add(*weight_limit)
+		)]
+		pub fn service_overweight(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			nonce: MessageNonce,
+			weight_limit: Weight,
+		) -> DispatchResultWithPostInfo {
+			ensure_not_halted::<T, I>()?;
+			let caller = ensure_signed(origin)?;
+			ensure_lane_accepts_inbound_messages::<T, I>(&lane_id)?;
+
+			let message_key = MessageKey { lane_id, nonce };
+			let message_data = OverweightMessages::<T, I>::get(&message_key)
+				.ok_or(Error::<T, I>::UnknownOverweightMessage)?;
+			let message: DispatchMessage<T::InboundPayload, T::InboundMessageFee> =
+				Message { key: message_key.clone(), data: message_data }.into();
+			let dispatch_weight = T::MessageDispatch::dispatch_weight(&message);
+			ensure!(dispatch_weight <= weight_limit, Error::<T, I>::WeightLimitIsTooLow);
+
+			OverweightMessages::<T, I>::remove(&message_key);
+			let dispatch_result = T::MessageDispatch::dispatch(&caller, message);
+			let is_result_reported = inbound_lane::<T, I>(lane_id)
+				.note_overweight_message_dispatched(nonce, &dispatch_result);
+
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Dispatched overweight message {:?}/{}: {:?}. Result is reported to the bridged chain: {}",
+				lane_id,
+				nonce,
+				dispatch_result,
+				is_result_reported,
+			);
+
+			Self::deposit_event(Event::OverweightMessageDispatched(
+				lane_id,
+				nonce,
+				dispatch_result.dispatch_result,
+			));
+
+			let unspent_weight = sp_std::cmp::min(dispatch_result.unspent_weight, dispatch_weight);
+			let actual_weight = T::WeightInfo::service_overweight_overhead()
+				.saturating_add(dispatch_weight - unspent_weight);
+			Ok(PostDispatchInfo { actual_weight: Some(actual_weight), pays_fee: Pays::Yes })
+		}
+
 		/// Receive messages delivery proof from bridged chain.
 		#[pallet::weight(T::WeightInfo::receive_messages_delivery_proof_weight(
 			proof,
@@ -543,6 +877,7 @@ pub mod pallet {
 
 					Error::<T, I>::InvalidMessagesDeliveryProof
 				})?;
+			ensure_lane_accepts_inbound_messages::<T, I>(&lane_id)?;
 
 			// verify that the relayer has declared correct `lane_data::relayers` state
 			// (we only care about total number of entries and messages, because this affects call
@@ -623,6 +958,9 @@ pub mod pallet {
 					},
 				}
 
+				// remember dispatch statuses of confirmed messages
+				save_dispatch_statuses::<T, I>(lane_id, &confirmed_messages);
+
 				// emit 'delivered' event
 				let received_range = confirmed_messages.begin..=confirmed_messages.end;
 				Self::deposit_event(Event::MessagesDelivered(lane_id, confirmed_messages));
@@ -637,6 +975,11 @@ pub mod pallet {
 					&received_range,
 					&relayer_fund_account,
 				);
+
+				// senders of confirmed messages are no longer required
+				for nonce in received_range {
+					OutboundMessageSenders::<T, I>::remove(MessageKey { lane_id, nonce });
+				}
 			}
 
 			log::trace!(
@@ -660,6 +1003,25 @@ pub mod pallet {
 		MessageAccepted(LaneId, MessageNonce),
 		/// Messages in the inclusive range have been delivered to the bridged chain.
 		MessagesDelivered(LaneId, DeliveredMessages),
+		/// Message has been delivered to the bridged chain, but its dispatch has failed.
+		MessageDispatchFailed(LaneId, MessageNonce),
+		/// Message has expired before it has been delivered to the bridged chain. Its fee may be
+		/// refunded to the sender using `refund_expired_message` call.
+		MessageExpired(LaneId, MessageNonce),
+		/// Fee of the expired message has been refunded to the message sender.
+		ExpiredMessageRefunded(LaneId, MessageNonce),
+		/// Lane state has been changed.
+		LaneStateChanged(LaneId, LaneState),
+		/// Lane operating mode has been changed.
+		LaneOperatingModeChanged(LaneId, OperatingMode),
+		/// The list of senders that are allowed to send messages over the lane has been updated.
+		LaneAllowedSendersUpdated(LaneId),
+		/// Inbound message has been delivered, but it is too heavy to be dispatched during
+		/// delivery. It has been put into the overweight queue.
+		MessageOverweight(LaneId, MessageNonce),
+		/// Message from the overweight queue has been dispatched. The dispatch result is
+		/// included.
+		OverweightMessageDispatched(LaneId, MessageNonce, bool),
 	}
 
 	#[pallet::error]
@@ -690,6 +1052,25 @@ pub mod pallet {
 		/// The number of actually confirmed messages is going to be larger than the number of
 		/// messages in the proof. This may mean that this or bridged chain storage is corrupted.
 		TryingToConfirmMoreMessagesThanExpected,
+		/// The message is either not expired, or its delivery is not yet confirmed, or its fee
+		/// has been already refunded.
+		MessageIsNotRefundable,
+		/// Failed to refund fee of the expired message.
+		FailedToRefundMessageFee,
+		/// The lane is not registered.
+		UnknownLane,
+		/// The lane is not accepting new outbound messages, because it is closing or closed.
+		LaneIsNotOpened,
+		/// The lane is closed.
+		LaneIsClosed,
+		/// The message is not in the overweight queue.
+		UnknownOverweightMessage,
+		/// The weight limit is lower than the dispatch weight of the message.
+		WeightLimitIsTooLow,
+		/// Too many senders are allowed to send messages over the lane.
+		TooManyAllowedSenders,
+		/// The batch of messages to send is empty.
+		EmptyMessagesBatch,
 	}
 
 	/// Optional pallet owner.
@@ -725,12 +1106,60 @@ pub mod pallet {
 	pub type OutboundMessages<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Blake2_128Concat, MessageKey, MessageData<T::OutboundMessageFee>>;
 
+	/// Senders of not yet confirmed outbound messages.
+	///
+	/// The sender is kept until message delivery is confirmed, so that the message payment
+	/// mechanism is able to refund unspent part of the message fee.
+	#[pallet::storage]
+	pub type OutboundMessageSenders<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, Sender<T::AccountId>>;
+
+	/// Dispatch statuses of confirmed outbound messages.
+	///
+	/// Only last `MaxDispatchStatusesToKeep` statuses are kept for every lane.
+	#[pallet::storage]
+	pub type OutboundMessageDispatchStatuses<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, MessageDispatchStatus>;
+
+	/// Senders and fees of outbound messages that have expired before they have been delivered.
+	///
+	/// The entry is removed when the fee is refunded to the sender.
+	#[pallet::storage]
+	pub type ExpiredMessagesRefunds<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, (Sender<T::AccountId>, T::OutboundMessageFee)>;
+
+	/// Inbound messages that are waiting to be dispatched from the overweight queue.
+	#[pallet::storage]
+	pub type OverweightMessages<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, MessageData<T::InboundMessageFee>>;
+
+	/// Registered lanes.
+	///
+	/// Lanes that are missing from this map are treated as closed.
+	#[pallet::storage]
+	#[pallet::getter(fn lane_info)]
+	pub type Lanes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, LaneInfo>;
+
+	/// Senders that are allowed to send messages over the outbound lane.
+	///
+	/// Lanes that are missing from this map are not restricted.
+	#[pallet::storage]
+	pub type OutboundLaneAllowedSenders<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Blake2_128Concat,
+		LaneId,
+		BoundedVec<Sender<T::AccountId>, T::MaxAllowedSenders>,
+	>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
 		/// Initial pallet operating mode.
 		pub operating_mode: OperatingMode,
 		/// Initial pallet owner.
 		pub owner: Option<T::AccountId>,
+		/// Lanes that are opened at genesis.
+		pub opened_lanes: Vec<LaneId>,
 		/// Dummy marker.
 		pub phantom: sp_std::marker::PhantomData<I>,
 	}
@@ -741,6 +1170,7 @@ pub mod pallet {
 			Self {
 				operating_mode: Default::default(),
 				owner: Default::default(),
+				opened_lanes: Default::default(),
 				phantom: Default::default(),
 			}
 		}
@@ -753,10 +1183,25 @@ pub mod pallet {
 			if let Some(ref owner) = self.owner {
 				PalletOwner::<T, I>::put(owner);
 			}
+			for lane_id in &self.opened_lanes {
+				Lanes::<T, I>::insert(lane_id, LaneInfo::opened());
+			}
 		}
 	}
 
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Get all registered lanes.
+		pub fn lanes() -> Vec<(LaneId, LaneInfo)> {
+			Lanes::<T, I>::iter().collect()
+		}
+
+		/// Returns true if the sender is allowed to send messages over given outbound lane.
+		pub fn is_outbound_sender_allowed(lane: &LaneId, sender: &Sender<T::AccountId>) -> bool {
+			OutboundLaneAllowedSenders::<T, I>::get(lane)
+				.map(|allowed_senders| allowed_senders.contains(sender))
+				.unwrap_or(true)
+		}
+
 		/// Get stored data of the outbound message with given nonce.
 		pub fn outbound_message_data(
 			lane: LaneId,
@@ -765,6 +1210,17 @@ pub mod pallet {
 			OutboundMessages::<T, I>::get(MessageKey { lane_id: lane, nonce })
 		}
 
+		/// Get dispatch status of the confirmed outbound message with given nonce.
+		///
+		/// Returns `None` if the message delivery is not yet confirmed, or if its status has
+		/// already been pruned.
+		pub fn outbound_message_dispatch_status(
+			lane: LaneId,
+			nonce: MessageNonce,
+		) -> Option<MessageDispatchStatus> {
+			OutboundMessageDispatchStatuses::<T, I>::get(MessageKey { lane_id: lane, nonce })
+		}
+
 		/// Get nonce of the latest generated message at given outbound lane.
 		pub fn outbound_latest_generated_nonce(lane: LaneId) -> MessageNonce {
 			OutboundLanes::<T, I>::get(&lane).latest_generated_nonce
@@ -859,6 +1315,14 @@ where
 	) -> Result<SendMessageArtifacts, Self::Error> {
 		crate::send_message::<T, I>(sender, lane, message, delivery_and_dispatch_fee)
 	}
+
+	fn send_messages(
+		sender: bp_messages::source_chain::Sender<T::AccountId>,
+		lane: LaneId,
+		messages: Vec<(T::OutboundPayload, T::OutboundMessageFee)>,
+	) -> Result<Vec<SendMessageArtifacts>, Self::Error> {
+		crate::send_messages::<T, I>(sender, lane, messages)
+	}
 }
 
 /// Function that actually sends message.
@@ -872,9 +1336,10 @@ fn send_message<T: Config<I>, I: 'static>(
 	sp_runtime::DispatchErrorWithPostInfo<PostDispatchInfo>,
 > {
 	ensure_normal_operating_mode::<T, I>()?;
+	ensure_lane_accepts_outbound_messages::<T, I>(&lane_id)?;
 
 	// initially, actual (post-dispatch) weight is equal to pre-dispatch weight
-	let mut actual_weight = T::WeightInfo::send_message_weight(&payload, T::DbWeight::get());
+	let mut actual_weight = send_message_declared_weight::<T, I>(&payload);
 
 	// let's first check if message can be delivered to target chain
 	T::TargetHeaderChain::verify_message(&payload).map_err(|err| {
@@ -908,6 +1373,13 @@ fn send_message<T: Config<I>, I: 'static>(
 		Error::<T, I>::MessageRejectedByLaneVerifier
 	})?;
 
+	// sender pays for checking the maximal allowed senders list => refund if the list is shorter
+	let allowed_senders =
+		OutboundLaneAllowedSenders::<T, I>::decode_len(&lane_id).unwrap_or(0) as u32;
+	actual_weight = actual_weight
+		.saturating_sub(max_allowed_senders_check_overhead::<T, I>())
+		.saturating_add(T::WeightInfo::allowed_senders_check_overhead(allowed_senders));
+
 	// let's withdraw delivery and dispatch fee from submitter
 	T::MessageDeliveryAndDispatchPayment::pay_delivery_and_dispatch_fee(
 		&submitter,
@@ -932,6 +1404,7 @@ fn send_message<T: Config<I>, I: 'static>(
 	let encoded_payload_len = encoded_payload.len();
 	let nonce =
 		lane.send_message(MessageData { payload: encoded_payload, fee: delivery_and_dispatch_fee });
+	OutboundMessageSenders::<T, I>::insert(MessageKey { lane_id, nonce }, submitter);
 	// Guaranteed to be called outside only when the message is accepted.
 	// We assume that the maximum weight call back used is `single_message_callback_overhead`, so do
 	// not perform complex db operation in callback. If you want to, put these magic logic in
@@ -986,6 +1459,126 @@ fn send_message<T: Config<I>, I: 'static>(
 	Ok(SendMessageArtifacts { nonce, weight: actual_weight })
 }
 
+/// Function that actually sends batch of messages.
+///
+/// Either all messages are accepted, or none of them.
+fn send_messages<T: Config<I>, I: 'static>(
+	submitter: bp_messages::source_chain::Sender<T::AccountId>,
+	lane_id: LaneId,
+	messages: Vec<(T::OutboundPayload, T::OutboundMessageFee)>,
+) -> sp_std::result::Result<
+	Vec<SendMessageArtifacts>,
+	sp_runtime::DispatchErrorWithPostInfo<PostDispatchInfo>,
+> {
+	ensure!(!messages.is_empty(), Error::<T, I>::EmptyMessagesBatch);
+
+	with_transaction(|| {
+		let mut sent_messages = Vec::with_capacity(messages.len());
+		for (payload, delivery_and_dispatch_fee) in messages {
+			match send_message::<T, I>(
+				submitter.clone(),
+				lane_id,
+				payload,
+				delivery_and_dispatch_fee,
+			) {
+				Ok(sent_message) => sent_messages.push(sent_message),
+				Err(err) => return TransactionOutcome::Rollback(Err(err)),
+			}
+		}
+
+		TransactionOutcome::Commit(Ok(sent_messages))
+	})
+}
+
+/// Returns weight of checking the sender against the longest possible list of allowed senders.
+fn max_allowed_senders_check_overhead<T: Config<I>, I: 'static>() -> Weight {
+	T::WeightInfo::allowed_senders_check_overhead(T::MaxAllowedSenders::get())
+}
+
+/// Returns pre-dispatch weight of the message send extrinsic.
+fn send_message_declared_weight<T: Config<I>, I: 'static>(payload: &T::OutboundPayload) -> Weight {
+	T::WeightInfo::send_message_weight(payload, T::DbWeight::get())
+		.saturating_add(max_allowed_senders_check_overhead::<T, I>())
+}
+
+/// Returns pre-dispatch weight of the batch message send extrinsic.
+fn send_messages_declared_weight<T: Config<I>, I: 'static>(
+	messages: &[(T::OutboundPayload, T::OutboundMessageFee)],
+) -> Weight {
+	T::WeightInfo::send_messages_weight(
+		messages.iter().map(|(payload, _)| payload),
+		T::DbWeight::get(),
+	)
+	.saturating_add(
+		max_allowed_senders_check_overhead::<T, I>().saturating_mul(messages.len() as Weight),
+	)
+}
+
+/// Returns actual weight of the batch message send extrinsic.
+///
+/// Every sent message reports its actual weight, computed against the pre-dispatch weight of the
+/// single message send extrinsic. Whatever has been refunded there is refunded from the
+/// pre-dispatch weight of the batch.
+fn send_messages_actual_weight(
+	declared_weight: Weight,
+	declared_messages_weights: &[Weight],
+	sent_messages: &[SendMessageArtifacts],
+) -> Weight {
+	let refund = declared_messages_weights.iter().zip(sent_messages).fold(
+		0,
+		|total: Weight, (declared_message_weight, sent_message)| {
+			total.saturating_add(declared_message_weight.saturating_sub(sent_message.weight))
+		},
+	);
+	declared_weight.saturating_sub(refund)
+}
+
+/// Save dispatch statuses of confirmed messages, pruning statuses that are no longer kept, and
+/// emit event for every message that has failed to dispatch.
+///
+/// Fees of expired messages are remembered, so that they may be refunded to message senders.
+fn save_dispatch_statuses<T: Config<I>, I: 'static>(
+	lane_id: LaneId,
+	confirmed_messages: &DeliveredMessages,
+) {
+	let max_statuses_to_keep = T::MaxDispatchStatusesToKeep::get();
+	for nonce in confirmed_messages.begin..=confirmed_messages.end {
+		let status = confirmed_messages.message_dispatch_status(nonce);
+		OutboundMessageDispatchStatuses::<T, I>::insert(MessageKey { lane_id, nonce }, status);
+		if let Some(nonce_to_prune) = nonce.checked_sub(max_statuses_to_keep) {
+			OutboundMessageDispatchStatuses::<T, I>::remove(MessageKey {
+				lane_id,
+				nonce: nonce_to_prune,
+			});
+		}
+
+		if status.expired {
+			save_expired_message_refund::<T, I>(MessageKey { lane_id, nonce });
+			Pallet::<T, I>::deposit_event(Event::MessageExpired(lane_id, nonce));
+		} else if !status.dispatch_result {
+			Pallet::<T, I>::deposit_event(Event::MessageDispatchFailed(lane_id, nonce));
+		}
+	}
+}
+
+/// Remember sender and fee of the expired message.
+fn save_expired_message_refund<T: Config<I>, I: 'static>(message_key: MessageKey) {
+	let sender = OutboundMessageSenders::<T, I>::get(&message_key);
+	let message_data = OutboundMessages::<T, I>::get(&message_key);
+	match (sender, message_data) {
+		(Some(sender), Some(message_data)) => {
+			ExpiredMessagesRefunds::<T, I>::insert(&message_key, (sender, message_data.fee));
+		},
+		_ => {
+			log::error!(
+				target: "runtime::bridge-messages",
+				"Missing sender or data of the confirmed expired message {:?}",
+				message_key,
+			);
+		},
+	}
+}
+
 /// Ensure that the origin is either root, or `PalletOwner`.
 fn ensure_owner_or_root<T: Config<I>, I: 'static>(origin: T::Origin) -> Result<(), BadOrigin> {
 	match origin.into() {
@@ -1015,6 +1608,34 @@ fn ensure_not_halted<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
 	}
 }
 
+/// Ensure that the lane is opened and it is operating in normal mode.
+fn ensure_lane_accepts_outbound_messages<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+) -> Result<(), Error<T, I>> {
+	let lane_info = Lanes::<T, I>::get(lane_id).ok_or(Error::<T, I>::UnknownLane)?;
+	if lane_info.state != LaneState::Opened {
+		Err(Error::<T, I>::LaneIsNotOpened)
+	} else if lane_info.operating_mode != OperatingMode::Normal {
+		Err(Error::<T, I>::Halted)
+	} else {
+		Ok(())
+	}
+}
+
+/// Ensure that the lane is not closed and it is not halted.
+fn ensure_lane_accepts_inbound_messages<T: Config<I>, I: 'static>(
+	lane_id: &LaneId,
+) -> Result<(), Error<T, I>> {
+	let lane_info = Lanes::<T, I>::get(lane_id).ok_or(Error::<T, I>::UnknownLane)?;
+	if lane_info.state == LaneState::Closed {
+		Err(Error::<T, I>::LaneIsClosed)
+	} else if lane_info.operating_mode == OperatingMode::Halted {
+		Err(Error::<T, I>::Halted)
+	} else {
+		Ok(())
+	}
+}
+
 /// Creates new inbound lane object, backed by runtime storage.
 fn inbound_lane<T: Config<I>, I: 'static>(
 	lane_id: LaneId,
@@ -1125,28 +1746,15 @@ impl<T: Config<I>, I: 'static> OutboundLaneStorage for RuntimeOutboundLaneStorag
 	}
 }
 
-/// Verify messages proof and return proved messages with decoded payload.
-fn verify_and_decode_messages_proof<Chain: SourceHeaderChain<Fee>, Fee, DispatchPayload: Decode>(
+/// Verify messages proof and return proved messages.
+fn verify_messages_proof<Chain: SourceHeaderChain<Fee>, Fee>(
 	proof: Chain::MessagesProof,
 	messages_count: u32,
-) -> Result<ProvedMessages<DispatchMessage<DispatchPayload, Fee>>, Chain::Error> {
+) -> Result<ProvedMessages<Message<Fee>>, Chain::Error> {
 	// `receive_messages_proof` weight formula and `MaxUnconfirmedMessagesAtInboundLane` check
 	// guarantees that the `message_count` is sane and Vec<Message> may be allocated.
 	// (tx with too many messages will either be rejected from the pool, or will fail earlier)
-	Chain::verify_messages_proof(proof, messages_count).map(|messages_by_lane| {
-		messages_by_lane
-			.into_iter()
-			.map(|(lane, lane_data)| {
-				(
-					lane,
-					ProvedLaneMessages {
-						lane_state: lane_data.lane_state,
-						messages: lane_data.messages.into_iter().map(Into::into).collect(),
-					},
-				)
-			})
-			.collect()
-	})
+	Chain::verify_messages_proof(proof, messages_count)
 }
 
 #[cfg(test)]
@@ -1216,7 +1824,7 @@ mod tests {
 					last_confirmed_nonce: 1,
 					relayers: vec![UnrewardedRelayer {
 						relayer: 0,
-						messages: DeliveredMessages::new(1, true),
+						messages: DeliveredMessages::new(1, true, 0),
 					}]
 					.into_iter()
 					.collect(),
@@ -1235,7 +1843,7 @@ mod tests {
 				phase: Phase::Initialization,
 				event: TestEvent::Messages(Event::MessagesDelivered(
 					TEST_LANE_ID,
-					DeliveredMessages::new(1, true),
+					DeliveredMessages::new(1, true, 0),
 				)),
 				topics: vec![],
 			}],
@@ -1528,48 +2136,404 @@ mod tests {
 	}
 
 	#[test]
-	fn send_message_works() {
+	fn lane_state_may_be_changed_by_owner() {
 		run_test(|| {
-			send_regular_message();
-		});
-	}
+			const NEW_LANE_ID: LaneId = [0, 0, 0, 2];
+
+			PalletOwner::<TestRuntime>::put(2);
 
-	#[test]
-	fn chain_verifier_rejects_invalid_message_in_send_message() {
-		run_test(|| {
-			// messages with this payload are rejected by target chain verifier
 			assert_noop!(
-				Pallet::<TestRuntime>::send_message(
+				Pallet::<TestRuntime>::set_lane_state(
 					Origin::signed(1),
-					TEST_LANE_ID,
-					PAYLOAD_REJECTED_BY_TARGET_CHAIN,
-					PAYLOAD_REJECTED_BY_TARGET_CHAIN.declared_weight
+					NEW_LANE_ID,
+					LaneState::Opened
 				),
-				Error::<TestRuntime, ()>::MessageRejectedByChainVerifier,
+				DispatchError::BadOrigin,
 			);
-		});
-	}
-
-	#[test]
-	fn lane_verifier_rejects_invalid_message_in_send_message() {
-		run_test(|| {
-			// messages with zero fee are rejected by lane verifier
 			assert_noop!(
-				Pallet::<TestRuntime>::send_message(
+				Pallet::<TestRuntime>::set_lane_operating_mode(
 					Origin::signed(1),
 					TEST_LANE_ID,
-					REGULAR_PAYLOAD,
-					0
+					OperatingMode::Halted,
 				),
-				Error::<TestRuntime, ()>::MessageRejectedByLaneVerifier,
+				DispatchError::BadOrigin,
 			);
-		});
-	}
 
-	#[test]
-	fn message_send_fails_if_submitter_cant_pay_message_fee() {
-		run_test(|| {
-			TestMessageDeliveryAndDispatchPayment::reject_payments();
+			assert_noop!(
+				Pallet::<TestRuntime>::set_lane_state(
+					Origin::root(),
+					NEW_LANE_ID,
+					LaneState::Closing
+				),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_lane_operating_mode(
+					Origin::root(),
+					NEW_LANE_ID,
+					OperatingMode::Halted,
+				),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_lane_state(
+				Origin::signed(2),
+				NEW_LANE_ID,
+				LaneState::Opened,
+			));
+			assert_eq!(Pallet::<TestRuntime>::lane_info(NEW_LANE_ID), Some(LaneInfo::opened()));
+
+			assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+				Origin::root(),
+				NEW_LANE_ID,
+				OperatingMode::Halted,
+			));
+			assert_ok!(Pallet::<TestRuntime>::set_lane_state(
+				Origin::root(),
+				NEW_LANE_ID,
+				LaneState::Closed,
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::lane_info(NEW_LANE_ID),
+				Some(LaneInfo { state: LaneState::Closed, operating_mode: OperatingMode::Halted }),
+			);
+
+			let mut lanes = Pallet::<TestRuntime>::lanes();
+			lanes.sort_by_key(|(lane_id, _)| *lane_id);
+			assert_eq!(
+				lanes,
+				vec![
+					(TEST_LANE_ID, LaneInfo::opened()),
+					(
+						NEW_LANE_ID,
+						LaneInfo {
+							state: LaneState::Closed,
+							operating_mode: OperatingMode::Halted
+						}
+					),
+				],
+			);
+		});
+	}
+
+	#[test]
+	fn send_message_rejects_messages_to_unknown_lane() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					[0, 0, 0, 2],
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+		});
+	}
+
+	#[test]
+	fn closing_lane_rejects_new_messages_and_accepts_proofs() {
+		run_test(|| {
+			// send message first to be able to check that delivery_proof is accepted later
+			send_regular_message();
+
+			assert_ok!(Pallet::<TestRuntime>::set_lane_state(
+				Origin::root(),
+				TEST_LANE_ID,
+				LaneState::Closing,
+			));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::LaneIsNotOpened,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			receive_messages_delivery_proof();
+		});
+	}
+
+	#[test]
+	fn closed_lane_rejects_proofs() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::set_lane_state(
+				Origin::root(),
+				TEST_LANE_ID,
+				LaneState::Closed,
+			));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::LaneIsClosed,
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((
+						TEST_LANE_ID,
+						InboundLaneData {
+							last_confirmed_nonce: 1,
+							relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)]
+								.into_iter()
+								.collect(),
+						},
+					))),
+					UnrewardedRelayersState {
+						unrewarded_relayer_entries: 1,
+						messages_in_oldest_entry: 1,
+						total_messages: 1,
+					},
+				),
+				Error::<TestRuntime, ()>::LaneIsClosed,
+			);
+		});
+	}
+
+	#[test]
+	fn halted_lane_rejects_transactions() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::set_lane_operating_mode(
+				Origin::root(),
+				TEST_LANE_ID,
+				OperatingMode::Halted,
+			));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::Halted,
+			);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, ()>::Halted,
+			);
+		});
+	}
+
+	#[test]
+	fn lane_allowed_senders_may_be_updated_by_owner() {
+		run_test(|| {
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::set_lane_allowed_senders(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					Some(vec![RawOrigin::Signed(1)]),
+				),
+				DispatchError::BadOrigin,
+			);
+
+			assert!(Pallet::<TestRuntime>::is_outbound_sender_allowed(
+				&TEST_LANE_ID,
+				&RawOrigin::Signed(1)
+			));
+			assert!(Pallet::<TestRuntime>::is_outbound_sender_allowed(
+				&TEST_LANE_ID,
+				&RawOrigin::Root
+			));
+
+			assert_ok!(Pallet::<TestRuntime>::set_lane_allowed_senders(
+				Origin::signed(2),
+				TEST_LANE_ID,
+				Some(vec![RawOrigin::Signed(1)]),
+			));
+			assert!(Pallet::<TestRuntime>::is_outbound_sender_allowed(
+				&TEST_LANE_ID,
+				&RawOrigin::Signed(1)
+			));
+			assert!(!Pallet::<TestRuntime>::is_outbound_sender_allowed(
+				&TEST_LANE_ID,
+				&RawOrigin::Signed(2)
+			));
+			assert!(!Pallet::<TestRuntime>::is_outbound_sender_allowed(
+				&TEST_LANE_ID,
+				&RawOrigin::Root
+			));
+
+			assert_ok!(Pallet::<TestRuntime>::set_lane_allowed_senders(
+				Origin::root(),
+				TEST_LANE_ID,
+				None,
+			));
+			assert!(Pallet::<TestRuntime>::is_outbound_sender_allowed(
+				&TEST_LANE_ID,
+				&RawOrigin::Signed(2)
+			));
+		});
+	}
+
+	#[test]
+	fn lane_allowed_senders_are_bounded() {
+		run_test(|| {
+			let max_allowed_senders = crate::mock::MaxAllowedSenders::get() as u64;
+			assert_noop!(
+				Pallet::<TestRuntime>::set_lane_allowed_senders(
+					Origin::root(),
+					TEST_LANE_ID,
+					Some((0..=max_allowed_senders).map(RawOrigin::Signed).collect()),
+				),
+				Error::<TestRuntime, ()>::TooManyAllowedSenders,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_lane_allowed_senders(
+				Origin::root(),
+				TEST_LANE_ID,
+				Some((1..=max_allowed_senders).map(RawOrigin::Signed).collect()),
+			));
+			assert!(Pallet::<TestRuntime>::is_outbound_sender_allowed(
+				&TEST_LANE_ID,
+				&RawOrigin::Signed(max_allowed_senders)
+			));
+		});
+	}
+
+	#[test]
+	fn send_message_works() {
+		run_test(|| {
+			send_regular_message();
+		});
+	}
+
+	#[test]
+	fn send_messages_works() {
+		run_test(|| {
+			get_ready_for_events();
+
+			let actual_weight = Pallet::<TestRuntime>::send_messages(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				vec![
+					(REGULAR_PAYLOAD, REGULAR_PAYLOAD.declared_weight),
+					(REGULAR_PAYLOAD, REGULAR_PAYLOAD.declared_weight),
+				],
+			)
+			.expect("send_messages has failed")
+			.actual_weight
+			.expect("send_messages always returns Some");
+
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::MessageAccepted(TEST_LANE_ID, 1)),
+						topics: vec![],
+					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::MessageAccepted(TEST_LANE_ID, 2)),
+						topics: vec![],
+					},
+				],
+			);
+			assert_eq!(Pallet::<TestRuntime>::outbound_latest_generated_nonce(TEST_LANE_ID), 2);
+
+			// actual weight never exceeds the pre-dispatch weight of the batch
+			let declared_weight = send_messages_declared_weight::<TestRuntime, ()>(&[
+				(REGULAR_PAYLOAD, REGULAR_PAYLOAD.declared_weight),
+				(REGULAR_PAYLOAD, REGULAR_PAYLOAD.declared_weight),
+			]);
+			assert!(actual_weight <= declared_weight);
+		});
+	}
+
+	#[test]
+	fn send_messages_rejects_empty_batch() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::send_messages(Origin::signed(1), TEST_LANE_ID, vec![]),
+				Error::<TestRuntime, ()>::EmptyMessagesBatch,
+			);
+		});
+	}
+
+	#[test]
+	fn send_messages_rejects_whole_batch_if_any_message_is_rejected() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::send_messages(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					vec![
+						(REGULAR_PAYLOAD, REGULAR_PAYLOAD.declared_weight),
+						(
+							PAYLOAD_REJECTED_BY_TARGET_CHAIN,
+							PAYLOAD_REJECTED_BY_TARGET_CHAIN.declared_weight
+						),
+					],
+				),
+				Error::<TestRuntime, ()>::MessageRejectedByChainVerifier,
+			);
+		});
+	}
+
+	#[test]
+	fn chain_verifier_rejects_invalid_message_in_send_message() {
+		run_test(|| {
+			// messages with this payload are rejected by target chain verifier
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					PAYLOAD_REJECTED_BY_TARGET_CHAIN,
+					PAYLOAD_REJECTED_BY_TARGET_CHAIN.declared_weight
+				),
+				Error::<TestRuntime, ()>::MessageRejectedByChainVerifier,
+			);
+		});
+	}
+
+	#[test]
+	fn lane_verifier_rejects_invalid_message_in_send_message() {
+		run_test(|| {
+			// messages with zero fee are rejected by lane verifier
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					0
+				),
+				Error::<TestRuntime, ()>::MessageRejectedByLaneVerifier,
+			);
+		});
+	}
+
+	#[test]
+	fn message_send_fails_if_submitter_cant_pay_message_fee() {
+		run_test(|| {
+			TestMessageDeliveryAndDispatchPayment::reject_payments();
 			assert_noop!(
 				Pallet::<TestRuntime>::send_message(
 					Origin::signed(1),
@@ -1660,7 +2624,7 @@ mod tests {
 	}
 
 	#[test]
-	fn receive_messages_proof_does_not_accept_message_if_dispatch_weight_is_not_enough() {
+	fn receive_messages_proof_queues_message_if_declared_dispatch_weight_is_not_enough() {
 		run_test(|| {
 			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
 				Origin::signed(1),
@@ -1669,7 +2633,34 @@ mod tests {
 				1,
 				REGULAR_PAYLOAD.declared_weight - 1,
 			));
-			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 0);
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 1);
+			assert!(OverweightMessages::<TestRuntime>::contains_key(MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce: 1,
+			}));
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_queues_message_if_block_weight_is_not_enough() {
+		run_test(|| {
+			frame_system::Pallet::<TestRuntime>::register_extra_weight_unchecked(
+				<TestRuntime as frame_system::Config>::BlockWeights::get().max_block,
+				frame_support::weights::DispatchClass::Normal,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 1);
+			assert!(OverweightMessages::<TestRuntime>::contains_key(MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce: 1,
+			}));
 		});
 	}
 
@@ -1944,7 +2935,143 @@ mod tests {
 				3,
 				Weight::MAX,
 			));
-			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 2);
+			// messages don't fit into the block => they're all queued
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 3);
+		});
+	}
+
+	const OVERWEIGHT_MESSAGE_WEIGHT: Weight = Weight::MAX / 2 + 1;
+
+	fn receive_overweight_message() {
+		assert_ok!(Pallet::<TestRuntime, ()>::receive_messages_proof(
+			Origin::signed(1),
+			TEST_RELAYER_A,
+			Ok(vec![message(1, message_payload(0, OVERWEIGHT_MESSAGE_WEIGHT))]).into(),
+			1,
+			0,
+		));
+	}
+
+	#[test]
+	fn overweight_message_is_put_into_overweight_queue() {
+		run_test(|| {
+			get_ready_for_events();
+			receive_overweight_message();
+
+			let inbound_lane_data = InboundLanes::<TestRuntime>::get(TEST_LANE_ID);
+			assert_eq!(inbound_lane_data.last_delivered_nonce(), 1);
+			assert!(!inbound_lane_data.relayers[0].messages.message_dispatch_result(1));
+			assert!(OverweightMessages::<TestRuntime>::contains_key(MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce: 1,
+			}));
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::MessageOverweight(TEST_LANE_ID, 1)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_refunds_declared_dispatch_weight_of_overweight_message() {
+		run_test(|| {
+			let proof = Ok(vec![message(1, message_payload(0, OVERWEIGHT_MESSAGE_WEIGHT))]).into();
+			let pre_dispatch_weight =
+				<TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(
+					&proof,
+					1,
+					OVERWEIGHT_MESSAGE_WEIGHT,
+				);
+			let post_dispatch_weight = Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				proof,
+				1,
+				OVERWEIGHT_MESSAGE_WEIGHT,
+			)
+			.expect("delivery has failed")
+			.actual_weight
+			.expect("receive_messages_proof always returns Some");
+
+			assert_eq!(
+				post_dispatch_weight,
+				pre_dispatch_weight -
+					OVERWEIGHT_MESSAGE_WEIGHT -
+					<TestRuntime as Config>::WeightInfo::pay_inbound_dispatch_fee_overhead() +
+					<TestRuntime as Config>::WeightInfo::overweight_message_storage_overhead(
+						crate::mock::DbWeight::get(),
+					),
+			);
+		});
+	}
+
+	#[test]
+	fn service_overweight_rejects_unknown_message() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::service_overweight(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					1,
+					Weight::MAX,
+				),
+				Error::<TestRuntime, ()>::UnknownOverweightMessage,
+			);
+		});
+	}
+
+	#[test]
+	fn service_overweight_rejects_too_low_weight_limit() {
+		run_test(|| {
+			receive_overweight_message();
+
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::service_overweight(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					1,
+					OVERWEIGHT_MESSAGE_WEIGHT - 1,
+				),
+				Error::<TestRuntime, ()>::WeightLimitIsTooLow,
+			);
+		});
+	}
+
+	#[test]
+	fn service_overweight_dispatches_message() {
+		run_test(|| {
+			receive_overweight_message();
+			get_ready_for_events();
+
+			assert_ok!(Pallet::<TestRuntime, ()>::service_overweight(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				1,
+				OVERWEIGHT_MESSAGE_WEIGHT,
+			));
+
+			let inbound_lane_data = InboundLanes::<TestRuntime>::get(TEST_LANE_ID);
+			assert!(inbound_lane_data.relayers[0].messages.message_dispatch_result(1));
+			assert!(!OverweightMessages::<TestRuntime>::contains_key(MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce: 1,
+			}));
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::OverweightMessageDispatched(
+						TEST_LANE_ID,
+						1,
+						true,
+					)),
+					topics: vec![],
+				}],
+			);
 		});
 	}
 
@@ -2083,8 +3210,8 @@ mod tests {
 
 			// messages 1+2 are confirmed in 1 tx, message 3 in a separate tx
 			// dispatch of message 2 has failed
-			let mut delivered_messages_1_and_2 = DeliveredMessages::new(1, true);
-			delivered_messages_1_and_2.note_dispatched_message(false);
+			let mut delivered_messages_1_and_2 = DeliveredMessages::new(1, true, 0);
+			delivered_messages_1_and_2.note_dispatched_message(false, 0);
 			let messages_1_and_2_proof = Ok((
 				TEST_LANE_ID,
 				InboundLaneData {
@@ -2097,7 +3224,7 @@ mod tests {
 					.collect(),
 				},
 			));
-			let delivered_message_3 = DeliveredMessages::new(3, true);
+			let delivered_message_3 = DeliveredMessages::new(3, true, 0);
 			let messages_3_proof = Ok((
 				TEST_LANE_ID,
 				InboundLaneData {
@@ -2140,6 +3267,171 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn message_sender_is_kept_until_delivery_is_confirmed() {
+		run_test(|| {
+			let message_key = MessageKey { lane_id: TEST_LANE_ID, nonce: 1 };
+
+			send_regular_message();
+			assert_eq!(
+				OutboundMessageSenders::<TestRuntime>::get(&message_key),
+				Some(Sender::Signed(1)),
+			);
+
+			receive_messages_delivery_proof();
+			assert_eq!(OutboundMessageSenders::<TestRuntime>::get(&message_key), None);
+		});
+	}
+
+	#[test]
+	fn dispatch_statuses_are_saved_and_pruned() {
+		run_test(|| {
+			send_regular_message();
+			send_regular_message();
+			send_regular_message();
+
+			// messages 1+2+3 are confirmed in 1 tx, dispatch of message 2 has failed
+			let mut delivered_messages = DeliveredMessages::new(1, true, 10);
+			delivered_messages.note_dispatched_message(false, 20);
+			delivered_messages.note_dispatched_message(true, 30);
+
+			System::<TestRuntime>::reset_events();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						last_confirmed_nonce: 0,
+						relayers: vec![UnrewardedRelayer {
+							relayer: 0,
+							messages: delivered_messages.clone(),
+						}]
+						.into_iter()
+						.collect(),
+					},
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 3,
+					..Default::default()
+				},
+			));
+
+			// failed dispatch is reported using event
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::MessageDispatchFailed(TEST_LANE_ID, 2)),
+						topics: vec![],
+					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(Event::MessagesDelivered(
+							TEST_LANE_ID,
+							delivered_messages,
+						)),
+						topics: vec![],
+					},
+				],
+			);
+
+			// only last `MaxDispatchStatusesToKeep` statuses are kept
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_message_dispatch_status(TEST_LANE_ID, 1),
+				None
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_message_dispatch_status(TEST_LANE_ID, 2),
+				Some(MessageDispatchStatus {
+					dispatch_result: false,
+					unspent_weight: 20,
+					expired: false,
+				}),
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_message_dispatch_status(TEST_LANE_ID, 3),
+				Some(MessageDispatchStatus {
+					dispatch_result: true,
+					unspent_weight: 30,
+					expired: false,
+				}),
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_message_dispatch_status(TEST_LANE_ID, 4),
+				None
+			);
+		});
+	}
+
+	#[test]
+	fn expired_message_fee_is_refunded_to_sender() {
+		run_test(|| {
+			send_regular_message();
+			send_regular_message();
+
+			// message 1 has been dispatched and message 2 has expired
+			let mut delivered_messages = DeliveredMessages::new(1, true, 0);
+			delivered_messages.note_expired_message();
+
+			// the message can't be refunded before its delivery is confirmed
+			assert_noop!(
+				Pallet::<TestRuntime>::refund_expired_message(Origin::signed(2), TEST_LANE_ID, 2),
+				Error::<TestRuntime, ()>::MessageIsNotRefundable,
+			);
+
+			System::<TestRuntime>::reset_events();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						last_confirmed_nonce: 0,
+						relayers: vec![UnrewardedRelayer {
+							relayer: 0,
+							messages: delivered_messages.clone(),
+						}]
+						.into_iter()
+						.collect(),
+					},
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 2,
+					..Default::default()
+				},
+			));
+			assert_eq!(
+				System::<TestRuntime>::events().first().map(|record| record.event.clone()),
+				Some(TestEvent::Messages(Event::MessageExpired(TEST_LANE_ID, 2))),
+			);
+
+			// dispatched message can't be refunded
+			assert_noop!(
+				Pallet::<TestRuntime>::refund_expired_message(Origin::signed(2), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::MessageIsNotRefundable,
+			);
+
+			// anyone may refund the expired message, but the fee is refunded to the sender
+			assert_ok!(Pallet::<TestRuntime>::refund_expired_message(
+				Origin::signed(2),
+				TEST_LANE_ID,
+				2,
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+
+			// the message can't be refunded twice
+			assert_noop!(
+				Pallet::<TestRuntime>::refund_expired_message(Origin::signed(2), TEST_LANE_ID, 2),
+				Error::<TestRuntime, ()>::MessageIsNotRefundable,
+			);
+		});
+	}
+
 	fn confirm_3_messages_delivery() -> (Weight, Weight) {
 		send_regular_message();
 		send_regular_message();
@@ -2283,10 +3575,10 @@ mod tests {
 			// send first MAX messages - no messages are pruned
 			let max_messages_to_prune = crate::mock::MaxMessagesToPruneAtOnce::get();
 			let when_zero_messages_are_pruned = send_regular_message();
-			let mut delivered_messages = DeliveredMessages::new(1, true);
+			let mut delivered_messages = DeliveredMessages::new(1, true, 0);
 			for _ in 1..max_messages_to_prune {
 				assert_eq!(send_regular_message(), when_zero_messages_are_pruned);
-				delivered_messages.note_dispatched_message(true);
+				delivered_messages.note_dispatched_message(true, 0);
 			}
 
 			// confirm delivery of all sent messages
@@ -2350,7 +3642,8 @@ mod tests {
 			let pre_dispatch_weight = <TestRuntime as Config>::WeightInfo::send_message_weight(
 				&REGULAR_PAYLOAD,
 				crate::mock::DbWeight::get(),
-			);
+			)
+			.saturating_add(<TestRuntime as Config>::WeightInfo::allowed_senders_check_overhead(0));
 			let prune_weight = crate::mock::DbWeight::get()
 				.writes(<TestRuntime as Config>::MaxMessagesToPruneAtOnce::get());
 
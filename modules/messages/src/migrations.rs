@@ -0,0 +1,418 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations of the messages pallet.
+
+use crate::{Config, InboundLanes, Lanes, OutboundLanes, OutboundMessages, Pallet};
+
+use bp_messages::{
+	DeliveredMessages, DispatchResultsBitVec, InboundLaneData, LaneInfo, MessageNonce,
+	UnrewardedRelayer,
+};
+use codec::{Compact, Decode, DecodeAll, Encode};
+use frame_support::{
+	traits::{Get, GetStorageVersion, PalletInfoAccess, StorageVersion},
+	weights::Weight,
+};
+use sp_std::{collections::vec_deque::VecDeque, vec, vec::Vec};
+
+/// Current storage version of the pallet.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+/// Migrate pallet storage to the current version.
+///
+/// Every migration is applied once, so it is safe to call this function on every runtime
+/// upgrade.
+pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+	let on_chain_version = Pallet::<T, I>::on_chain_storage_version();
+	let mut weight = T::DbWeight::get().reads(1);
+
+	if on_chain_version < 1 {
+		weight = weight.saturating_add(v1::migrate::<T, I>());
+	}
+	if on_chain_version < 2 {
+		weight = weight.saturating_add(v2::migrate::<T, I>());
+	}
+	if on_chain_version < 3 {
+		weight = weight.saturating_add(v3::migrate::<T, I>());
+	}
+
+	if on_chain_version < STORAGE_VERSION {
+		STORAGE_VERSION.put::<Pallet<T, I>>();
+		weight = weight.saturating_add(T::DbWeight::get().writes(1));
+		log::info!(
+			target: "runtime::bridge-messages",
+			"Migrated storage of {} from {:?} to {:?}",
+			<Pallet<T, I> as PalletInfoAccess>::name(),
+			on_chain_version,
+			STORAGE_VERSION,
+		);
+	}
+
+	weight
+}
+
+/// Migration to the version 1 of the pallet storage.
+///
+/// Dispatch results of delivered messages are now stored along with unspent dispatch weight and
+/// expiration flag of every message. So all `InboundLaneData` entries are re-encoded. Unspent
+/// weight of messages that have been delivered before the upgrade is unknown, so it is set to
+/// zero. None of those messages has expired.
+pub mod v1 {
+	use super::*;
+
+	/// Delivered messages, as they have been stored by the version 0 of the pallet.
+	#[derive(Encode, Decode)]
+	pub struct DeliveredMessagesV0 {
+		/// Nonce of the first message that has been delivered (inclusive).
+		pub begin: MessageNonce,
+		/// Nonce of the last message that has been delivered (inclusive).
+		pub end: MessageNonce,
+		/// Dispatch result of every message in the `[begin; end]` range.
+		pub dispatch_results: DispatchResultsBitVec,
+	}
+
+	/// Unrewarded relayer entry, as it has been stored by the version 0 of the pallet.
+	#[derive(Encode, Decode)]
+	pub struct UnrewardedRelayerV0<RelayerId> {
+		/// Identifier of the relayer.
+		pub relayer: RelayerId,
+		/// Messages range, delivered by this relayer.
+		pub messages: DeliveredMessagesV0,
+	}
+
+	/// Inbound lane data, as it has been stored by the version 0 of the pallet.
+	#[derive(Encode, Decode)]
+	pub struct InboundLaneDataV0<RelayerId> {
+		/// Identifiers of relayers and messages that they have delivered to this lane.
+		pub relayers: VecDeque<UnrewardedRelayerV0<RelayerId>>,
+		/// Nonce of the last message that has been confirmed at the source chain.
+		pub last_confirmed_nonce: MessageNonce,
+	}
+
+	impl From<DeliveredMessagesV0> for DeliveredMessages {
+		fn from(old: DeliveredMessagesV0) -> Self {
+			let messages_count = old.dispatch_results.len();
+			let mut expired_messages = DispatchResultsBitVec::with_capacity(messages_count);
+			expired_messages.resize(messages_count, false);
+			DeliveredMessages {
+				begin: old.begin,
+				end: old.end,
+				dispatch_results: old.dispatch_results,
+				unspent_weights: vec![Compact(0); messages_count],
+				expired_messages,
+			}
+		}
+	}
+
+	impl<RelayerId> From<InboundLaneDataV0<RelayerId>> for InboundLaneData<RelayerId> {
+		fn from(old: InboundLaneDataV0<RelayerId>) -> Self {
+			InboundLaneData {
+				relayers: old
+					.relayers
+					.into_iter()
+					.map(|entry| UnrewardedRelayer {
+						relayer: entry.relayer,
+						messages: entry.messages.into(),
+					})
+					.collect(),
+				last_confirmed_nonce: old.last_confirmed_nonce,
+			}
+		}
+	}
+
+	/// Re-encode all `InboundLanes` entries.
+	pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+		let mut translated: Weight = 0;
+		InboundLanes::<T, I>::translate::<InboundLaneDataV0<T::InboundRelayer>, _>(|_, old| {
+			translated += 1;
+			Some(old.into())
+		});
+
+		T::DbWeight::get().reads_writes(translated, translated)
+	}
+}
+
+/// Migration to the version 2 of the pallet storage.
+///
+/// Message payload (`bp_message_dispatch::MessagePayload`) may now contain the number of the
+/// bridged chain block, after which the message expires. The optional `expiry` is the last field
+/// of the payload, so payloads of queued outbound messages, that have been encoded before, are
+/// upgraded by appending encoded `None` to them. Payloads that can't be decoded even after that
+/// are left untouched.
+///
+/// The migration is executed within single block, so it reads at most
+/// `max_block_weight / (read + write weight)` outbound messages. There are never that many
+/// queued messages on our testnets, so this limit is only a safeguard - if it is hit, remaining
+/// messages are not upgraded and the error is logged.
+pub mod v2 {
+	use super::*;
+
+	/// Returns payload in the current format, if given payload has been encoded before the
+	/// `expiry` field has been added. Otherwise returns `None`.
+	pub fn upgrade_payload<P: Decode>(encoded_payload: &[u8]) -> Option<Vec<u8>> {
+		if P::decode_all(encoded_payload).is_ok() {
+			return None
+		}
+
+		let mut upgraded_payload = encoded_payload.to_vec();
+		Option::<bp_message_dispatch::ExpiryBlockNumber>::None.encode_to(&mut upgraded_payload);
+		P::decode_all(&upgraded_payload).ok().map(|_| upgraded_payload)
+	}
+
+	/// Maximal number of outbound messages that the migration may touch.
+	pub fn max_migrated_messages<T: Config<I>, I: 'static>() -> usize {
+		let max_block_weight = T::BlockWeights::get().max_block;
+		let single_message_weight = T::DbWeight::get().reads_writes(1, 1).max(1);
+		(max_block_weight / single_message_weight) as usize
+	}
+
+	/// Upgrade payloads of `OutboundMessages` entries.
+	pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+		let max_messages = max_migrated_messages::<T, I>();
+		let messages = OutboundMessages::<T, I>::iter().take(max_messages + 1).collect::<Vec<_>>();
+		if messages.len() > max_messages {
+			log::error!(
+				target: "runtime::bridge-messages",
+				"There are more than {} outbound messages. Only first {} are upgraded",
+				max_messages,
+				max_messages,
+			);
+		}
+
+		let reads = messages.len().min(max_messages) as Weight;
+		let mut writes: Weight = 0;
+		for (message_key, mut message_data) in messages.into_iter().take(max_messages) {
+			if let Some(payload) = upgrade_payload::<T::OutboundPayload>(&message_data.payload) {
+				message_data.payload = payload;
+				OutboundMessages::<T, I>::insert(&message_key, message_data);
+				writes += 1;
+			}
+		}
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+/// Migration to the version 3 of the pallet storage.
+///
+/// Only lanes that are registered in the `Lanes` map may now be used. Before the upgrade, lanes
+/// have been registered at genesis only, so every lane that already has inbound or outbound state
+/// is registered as opened. Otherwise the bridge would stop working right after the upgrade.
+pub mod v3 {
+	use super::*;
+
+	/// Open all lanes that have inbound or outbound state.
+	pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+		let mut reads: Weight = 0;
+		let mut writes: Weight = 0;
+		let lanes_in_use =
+			InboundLanes::<T, I>::iter_keys().chain(OutboundLanes::<T, I>::iter_keys());
+		for lane_id in lanes_in_use {
+			reads += 2;
+			if !Lanes::<T, I>::contains_key(&lane_id) {
+				Lanes::<T, I>::insert(&lane_id, LaneInfo::opened());
+				writes += 1;
+				log::info!(
+					target: "runtime::bridge-messages",
+					"Opened lane {:?} that has been used before the upgrade",
+					lane_id,
+				);
+			}
+		}
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{run_test, TestRelayer, TestRuntime, REGULAR_PAYLOAD};
+	use bitvec::prelude::*;
+	use bp_message_dispatch::{CallOrigin, MessagePayload};
+	use bp_messages::{LaneId, LaneState, MessageData, MessageKey, OutboundLaneData};
+	use bp_runtime::messages::DispatchFeePayment;
+	use frame_support::{storage::unhashed, traits::OnRuntimeUpgrade};
+
+	const TEST_LANE_ID: LaneId = [0, 0, 0, 1];
+
+	fn inbound_lane_data_v0() -> v1::InboundLaneDataV0<TestRelayer> {
+		v1::InboundLaneDataV0 {
+			relayers: vec![
+				v1::UnrewardedRelayerV0 {
+					relayer: 100,
+					messages: v1::DeliveredMessagesV0 {
+						begin: 1,
+						end: 2,
+						dispatch_results: bitvec![Msb0, u8; 1, 0],
+					},
+				},
+				v1::UnrewardedRelayerV0 {
+					relayer: 200,
+					messages: v1::DeliveredMessagesV0 {
+						begin: 3,
+						end: 3,
+						dispatch_results: bitvec![Msb0, u8; 1],
+					},
+				},
+			]
+			.into_iter()
+			.collect(),
+			last_confirmed_nonce: 0,
+		}
+	}
+
+	#[test]
+	fn inbound_lane_data_v0_is_migrated() {
+		run_test(|| {
+			StorageVersion::new(0).put::<Pallet<TestRuntime, ()>>();
+			unhashed::put_raw(
+				&InboundLanes::<TestRuntime, ()>::hashed_key_for(TEST_LANE_ID),
+				&inbound_lane_data_v0().encode(),
+			);
+
+			Pallet::<TestRuntime, ()>::on_runtime_upgrade();
+
+			let migrated = InboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID);
+			assert_eq!(migrated.last_confirmed_nonce, 0);
+			assert_eq!(migrated.relayers.len(), 2);
+			assert_eq!(migrated.relayers[0].relayer, 100);
+			assert_eq!(
+				migrated.relayers[0].messages.message_dispatch_status(1),
+				bp_messages::MessageDispatchStatus {
+					dispatch_result: true,
+					unspent_weight: 0,
+					expired: false,
+				},
+			);
+			assert!(!migrated.relayers[0].messages.message_dispatch_result(2));
+			assert_eq!(migrated.relayers[1].relayer, 200);
+			assert_eq!(migrated.relayers[1].messages.end, 3);
+			assert!(migrated.relayers[1].messages.message_dispatch_result(3));
+			assert_eq!(Pallet::<TestRuntime, ()>::on_chain_storage_version(), STORAGE_VERSION);
+		});
+	}
+
+	#[test]
+	fn migration_is_not_applied_twice() {
+		run_test(|| {
+			STORAGE_VERSION.put::<Pallet<TestRuntime, ()>>();
+			let data = InboundLaneData::<TestRelayer> {
+				relayers: vec![UnrewardedRelayer {
+					relayer: 100,
+					messages: DeliveredMessages::new(1, true, 42),
+				}]
+				.into_iter()
+				.collect(),
+				last_confirmed_nonce: 0,
+			};
+			InboundLanes::<TestRuntime, ()>::insert(TEST_LANE_ID, data.clone());
+
+			Pallet::<TestRuntime, ()>::on_runtime_upgrade();
+
+			assert_eq!(InboundLanes::<TestRuntime, ()>::get(TEST_LANE_ID), data);
+		});
+	}
+
+	#[test]
+	fn outbound_messages_are_migrated() {
+		run_test(|| {
+			StorageVersion::new(1).put::<Pallet<TestRuntime, ()>>();
+			let message_key = |nonce| MessageKey { lane_id: TEST_LANE_ID, nonce };
+			let message = MessageData { payload: REGULAR_PAYLOAD.encode(), fee: 100 };
+			let broken_message = MessageData { payload: vec![42], fee: 100 };
+			OutboundMessages::<TestRuntime, ()>::insert(message_key(1), message.clone());
+			OutboundMessages::<TestRuntime, ()>::insert(message_key(2), broken_message.clone());
+
+			Pallet::<TestRuntime, ()>::on_runtime_upgrade();
+
+			assert_eq!(OutboundMessages::<TestRuntime, ()>::get(message_key(1)), Some(message));
+			assert_eq!(
+				OutboundMessages::<TestRuntime, ()>::get(message_key(2)),
+				Some(broken_message),
+			);
+			assert_eq!(Pallet::<TestRuntime, ()>::on_chain_storage_version(), STORAGE_VERSION);
+		});
+	}
+
+	#[test]
+	fn payload_without_expiry_is_upgraded() {
+		type Payload = MessagePayload<u64, u64, u64, Vec<u8>>;
+
+		// this is how payloads have been encoded before `expiry` has been added
+		let old_payload = (
+			1u32,
+			100u64,
+			CallOrigin::<u64, u64, u64>::SourceAccount(42),
+			DispatchFeePayment::AtSourceChain,
+			vec![1u8, 2, 3],
+		)
+			.encode();
+		assert!(Payload::decode(&mut &old_payload[..]).is_err());
+
+		let upgraded_payload = v2::upgrade_payload::<Payload>(&old_payload).unwrap();
+		assert_eq!(
+			Payload::decode(&mut &upgraded_payload[..]),
+			Ok(MessagePayload {
+				spec_version: 1,
+				weight: 100,
+				origin: CallOrigin::SourceAccount(42),
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				call: vec![1, 2, 3],
+				expiry: None,
+			}),
+		);
+
+		// payloads in the current format are not touched
+		assert_eq!(v2::upgrade_payload::<Payload>(&upgraded_payload), None);
+	}
+
+	#[test]
+	fn lanes_in_use_are_opened() {
+		run_test(|| {
+			const OUTBOUND_LANE_ID: LaneId = [0, 0, 0, 0];
+			const CLOSED_LANE_ID: LaneId = [0, 0, 0, 2];
+			const UNUSED_LANE_ID: LaneId = [0, 0, 0, 3];
+
+			StorageVersion::new(2).put::<Pallet<TestRuntime, ()>>();
+			// genesis has registered the lane, but lanes of already running chains are not
+			// registered
+			Lanes::<TestRuntime, ()>::remove(TEST_LANE_ID);
+			OutboundLanes::<TestRuntime, ()>::insert(
+				OUTBOUND_LANE_ID,
+				OutboundLaneData { latest_generated_nonce: 1, ..Default::default() },
+			);
+			InboundLanes::<TestRuntime, ()>::insert(TEST_LANE_ID, InboundLaneData::default());
+			InboundLanes::<TestRuntime, ()>::insert(CLOSED_LANE_ID, InboundLaneData::default());
+			Lanes::<TestRuntime, ()>::insert(
+				CLOSED_LANE_ID,
+				LaneInfo { state: LaneState::Closed, ..LaneInfo::opened() },
+			);
+
+			Pallet::<TestRuntime, ()>::on_runtime_upgrade();
+
+			assert_eq!(Lanes::<TestRuntime, ()>::get(OUTBOUND_LANE_ID), Some(LaneInfo::opened()));
+			assert_eq!(Lanes::<TestRuntime, ()>::get(TEST_LANE_ID), Some(LaneInfo::opened()));
+			assert_eq!(
+				Lanes::<TestRuntime, ()>::get(CLOSED_LANE_ID).map(|lane_info| lane_info.state),
+				Some(LaneState::Closed),
+			);
+			assert_eq!(Lanes::<TestRuntime, ()>::get(UNUSED_LANE_ID), None);
+			assert_eq!(Pallet::<TestRuntime, ()>::on_chain_storage_version(), STORAGE_VERSION);
+		});
+	}
+}
@@ -31,10 +31,14 @@ use bp_messages::{
 	DeliveredMessages, InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce,
 	OutboundLaneData, Parameter as MessagesParameter, UnrewardedRelayer,
 };
-use bp_runtime::{messages::MessageDispatchResult, Size};
-use codec::{Decode, Encode};
+use bp_runtime::{
+	messages::{DispatchFeePayment, MessageDispatchResult},
+	Size,
+};
+use codec::{Compact, Decode, Encode};
 use frame_support::{
 	parameter_types,
+	traits::GenesisBuild,
 	weights::{RuntimeDbWeight, Weight},
 };
 use sp_core::H256;
@@ -61,6 +65,8 @@ pub struct TestPayload {
 	/// Note: in correct code `dispatch_result.unspent_weight` will always be <= `declared_weight`,
 	/// but for test purposes we'll be making it larger than `declared_weight` sometimes.
 	pub dispatch_result: MessageDispatchResult,
+	/// Number of the block after which the message is considered expired.
+	pub expiry: Option<u64>,
 	/// Extra bytes that affect payload size.
 	pub extra: Vec<u8>,
 }
@@ -88,7 +94,7 @@ frame_support::construct_runtime! {
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Event<T>},
-		Messages: pallet_bridge_messages::{Pallet, Call, Event<T>},
+		Messages: pallet_bridge_messages::{Pallet, Call, Config<T>, Event<T>},
 	}
 }
 
@@ -144,8 +150,11 @@ impl pallet_balances::Config for TestRuntime {
 
 parameter_types! {
 	pub const MaxMessagesToPruneAtOnce: u64 = 10;
+	pub const MaxDispatchStatusesToKeep: u64 = 2;
 	pub const MaxUnrewardedRelayerEntriesAtInboundLane: u64 = 16;
 	pub const MaxUnconfirmedMessagesAtInboundLane: u64 = 32;
+	pub const MaxInlineDispatchWeight: Weight = Weight::MAX / 2;
+	pub const MaxAllowedSenders: u32 = 4;
 	pub storage TokenConversionRate: FixedU128 = 1.into();
   pub const TestBridgedChainId: bp_runtime::ChainId = *b"test";
 }
@@ -169,8 +178,11 @@ impl Config for TestRuntime {
 	type WeightInfo = ();
 	type Parameter = TestMessagesParameter;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxDispatchStatusesToKeep = MaxDispatchStatusesToKeep;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxInlineDispatchWeight = MaxInlineDispatchWeight;
+	type MaxAllowedSenders = MaxAllowedSenders;
 
 	type OutboundPayload = TestPayload;
 	type OutboundMessageFee = TestMessageFee;
@@ -326,6 +338,12 @@ impl TestMessageDeliveryAndDispatchPayment {
 			Some((Sender::Signed(submitter), fee))
 	}
 
+	/// Returns true if given fee has been refunded to given submitter.
+	pub fn is_fee_refunded(submitter: AccountId, fee: TestMessageFee) -> bool {
+		frame_support::storage::unhashed::get(b":message-fee-refund:") ==
+			Some((Sender::Signed(submitter), fee))
+	}
+
 	/// Returns true if given relayer has been rewarded with given balance. The reward-paid flag is
 	/// cleared after the call.
 	pub fn is_reward_paid(relayer: AccountId, fee: TestMessageFee) -> bool {
@@ -352,6 +370,15 @@ impl MessageDeliveryAndDispatchPayment<AccountId, TestMessageFee>
 		Ok(())
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &Sender<AccountId>,
+		fee: &TestMessageFee,
+		_relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error> {
+		frame_support::storage::unhashed::put(b":message-fee-refund:", &(submitter, fee));
+		Ok(())
+	}
+
 	fn pay_relayers_rewards(
 		lane_id: LaneId,
 		message_relayers: VecDeque<UnrewardedRelayer<AccountId>>,
@@ -481,6 +508,24 @@ impl MessageDispatch<AccountId, TestMessageFee> for TestMessageDispatch {
 		}
 	}
 
+	fn is_expired(message: &DispatchMessage<TestPayload, TestMessageFee>) -> bool {
+		match message.data.payload.as_ref() {
+			Ok(TestPayload { expiry: Some(expiry), .. }) =>
+				frame_system::Pallet::<TestRuntime>::block_number() > *expiry,
+			_ => false,
+		}
+	}
+
+	fn dispatch_fee_payment(
+		message: &DispatchMessage<TestPayload, TestMessageFee>,
+	) -> DispatchFeePayment {
+		match message.data.payload.as_ref() {
+			Ok(payload) if payload.dispatch_result.dispatch_fee_paid_during_dispatch =>
+				DispatchFeePayment::AtTargetChain,
+			_ => DispatchFeePayment::AtSourceChain,
+		}
+	}
+
 	fn dispatch(
 		_relayer_account: &AccountId,
 		message: DispatchMessage<TestPayload, TestMessageFee>,
@@ -499,7 +544,13 @@ pub fn message(nonce: MessageNonce, payload: TestPayload) -> Message<TestMessage
 
 /// Constructs message payload using given arguments and zero unspent weight.
 pub const fn message_payload(id: u64, declared_weight: Weight) -> TestPayload {
-	TestPayload { id, declared_weight, dispatch_result: dispatch_result(0), extra: Vec::new() }
+	TestPayload {
+		id,
+		declared_weight,
+		dispatch_result: dispatch_result(0),
+		expiry: None,
+		extra: Vec::new(),
+	}
 }
 
 /// Return message data with valid fee for given payload.
@@ -532,6 +583,16 @@ pub fn unrewarded_relayer(
 			} else {
 				Default::default()
 			},
+			unspent_weights: if end >= begin {
+				vec![Compact(0); (end - begin + 1) as _]
+			} else {
+				Default::default()
+			},
+			expired_messages: if end >= begin {
+				bitvec![Msb0, u8; 0; (end - begin + 1) as _]
+			} else {
+				Default::default()
+			},
 		},
 	}
 }
@@ -542,6 +603,12 @@ pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
 	pallet_balances::GenesisConfig::<TestRuntime> { balances: vec![(ENDOWED_ACCOUNT, 1_000_000)] }
 		.assimilate_storage(&mut t)
 		.unwrap();
+	pallet_bridge_messages::GenesisConfig::<TestRuntime> {
+		opened_lanes: vec![TEST_LANE_ID],
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
 	let mut ext = sp_io::TestExternalities::new(t);
 	ext.execute_with(test)
 }
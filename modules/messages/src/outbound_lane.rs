@@ -18,11 +18,10 @@
 
 use bitvec::prelude::*;
 use bp_messages::{
-	DeliveredMessages, DispatchResultsBitVec, LaneId, MessageData, MessageNonce, OutboundLaneData,
-	UnrewardedRelayer,
+	DeliveredMessages, LaneId, MessageData, MessageNonce, OutboundLaneData, UnrewardedRelayer,
 };
 use frame_support::RuntimeDebug;
-use sp_std::collections::vec_deque::VecDeque;
+use sp_std::{collections::vec_deque::VecDeque, prelude::*};
 
 /// Outbound lane storage.
 pub trait OutboundLaneStorage {
@@ -61,8 +60,8 @@ pub enum ReceivalConfirmationResult {
 	/// The unrewarded relayers vec contains non-consecutive entries. May be a result of invalid
 	/// bridged chain storage.
 	NonConsecutiveUnrewardedRelayerEntries,
-	/// The unrewarded relayers vec contains entry with mismatched number of dispatch results. May
-	/// be a result of invalid bridged chain storage.
+	/// The unrewarded relayers vec contains entry with mismatched number of dispatch results,
+	/// unspent weights or expiration flags. May be a result of invalid bridged chain storage.
 	InvalidNumberOfDispatchResults,
 	/// The chain has more messages that need to be confirmed than there is in the proof.
 	TryingToConfirmMoreMessagesThanExpected(MessageNonce),
@@ -123,24 +122,19 @@ impl<S: OutboundLaneStorage> OutboundLane<S> {
 			)
 		}
 
-		let dispatch_results = match extract_dispatch_results(
+		let confirmed_messages = match extract_dispatch_results(
 			data.latest_received_nonce,
 			latest_delivered_nonce,
 			relayers,
 		) {
-			Ok(dispatch_results) => dispatch_results,
+			Ok(confirmed_messages) => confirmed_messages,
 			Err(extract_error) => return extract_error,
 		};
 
-		let prev_latest_received_nonce = data.latest_received_nonce;
 		data.latest_received_nonce = latest_delivered_nonce;
 		self.storage.set_data(data);
 
-		ReceivalConfirmationResult::ConfirmedMessages(DeliveredMessages {
-			begin: prev_latest_received_nonce + 1,
-			end: latest_delivered_nonce,
-			dispatch_results,
-		})
+		ReceivalConfirmationResult::ConfirmedMessages(confirmed_messages)
 	}
 
 	/// Prune at most `max_messages_to_prune` already received messages.
@@ -168,7 +162,8 @@ impl<S: OutboundLaneStorage> OutboundLane<S> {
 	}
 }
 
-/// Extract new dispatch results from the unrewarded relayers vec.
+/// Extract new dispatch results, unspent dispatch weights and expiration flags from the
+/// unrewarded relayers vec.
 ///
 /// Returns `Err(_)` if unrewarded relayers vec contains invalid data, meaning that the bridged
 /// chain has invalid runtime storage.
@@ -176,13 +171,17 @@ fn extract_dispatch_results<RelayerId>(
 	prev_latest_received_nonce: MessageNonce,
 	latest_received_nonce: MessageNonce,
 	relayers: &VecDeque<UnrewardedRelayer<RelayerId>>,
-) -> Result<DispatchResultsBitVec, ReceivalConfirmationResult> {
+) -> Result<DeliveredMessages, ReceivalConfirmationResult> {
 	// the only caller of this functions checks that the
 	// prev_latest_received_nonce..=latest_received_nonce is valid, so we're ready to accept
 	// messages in this range => with_capacity call must succeed here or we'll be unable to receive
 	// confirmations at all
 	let mut received_dispatch_result =
 		BitVec::with_capacity((latest_received_nonce - prev_latest_received_nonce + 1) as _);
+	let mut received_unspent_weights =
+		Vec::with_capacity((latest_received_nonce - prev_latest_received_nonce + 1) as _);
+	let mut received_expired_messages =
+		BitVec::with_capacity((latest_received_nonce - prev_latest_received_nonce + 1) as _);
 	let mut last_entry_end: Option<MessageNonce> = None;
 	for entry in relayers {
 		// unrewarded relayer entry must have at least 1 unconfirmed message
@@ -207,10 +206,12 @@ fn extract_dispatch_results<RelayerId>(
 			// this is detected now
 			return Err(ReceivalConfirmationResult::FailedToConfirmFutureMessages)
 		}
-		// entry must have single dispatch result for every message
-		// (guaranteed by the `InboundLane::receive_message()`)
-		if entry.messages.dispatch_results.len() as MessageNonce !=
-			entry.messages.end - entry.messages.begin + 1
+		// entry must have single dispatch result, single unspent weight and single expiration
+		// flag for every message (guaranteed by the `InboundLane::receive_message()`)
+		let entry_messages_count = entry.messages.end - entry.messages.begin + 1;
+		if entry.messages.dispatch_results.len() as MessageNonce != entry_messages_count ||
+			entry.messages.unspent_weights.len() as MessageNonce != entry_messages_count ||
+			entry.messages.expired_messages.len() as MessageNonce != entry_messages_count
 		{
 			return Err(ReceivalConfirmationResult::InvalidNumberOfDispatchResults)
 		}
@@ -226,14 +227,23 @@ fn extract_dispatch_results<RelayerId>(
 		}
 
 		// now we know that entry brings new confirmations
-		// => let's extract dispatch results
-		received_dispatch_result.extend_from_bitslice(
-			&entry.messages.dispatch_results
-				[(new_messages_begin - entry.messages.begin) as usize..],
-		);
+		// => let's extract dispatch results, unspent weights and expiration flags
+		let new_messages_offset = (new_messages_begin - entry.messages.begin) as usize;
+		received_dispatch_result
+			.extend_from_bitslice(&entry.messages.dispatch_results[new_messages_offset..]);
+		received_unspent_weights
+			.extend_from_slice(&entry.messages.unspent_weights[new_messages_offset..]);
+		received_expired_messages
+			.extend_from_bitslice(&entry.messages.expired_messages[new_messages_offset..]);
 	}
 
-	Ok(received_dispatch_result)
+	Ok(DeliveredMessages {
+		begin: prev_latest_received_nonce + 1,
+		end: latest_received_nonce,
+		dispatch_results: received_dispatch_result,
+		unspent_weights: received_unspent_weights,
+		expired_messages: received_expired_messages,
+	})
 }
 
 #[cfg(test)]
@@ -246,6 +256,7 @@ mod tests {
 		},
 		outbound_lane,
 	};
+	use codec::Compact;
 	use sp_std::ops::RangeInclusive;
 
 	fn unrewarded_relayers(
@@ -261,6 +272,8 @@ mod tests {
 			begin: *nonces.start(),
 			end: *nonces.end(),
 			dispatch_results: bitvec![Msb0, u8; 1; (nonces.end() - nonces.start() + 1) as _],
+			unspent_weights: vec![Compact(0); (nonces.end() - nonces.start() + 1) as _],
+			expired_messages: bitvec![Msb0, u8; 0; (nonces.end() - nonces.start() + 1) as _],
 		}
 	}
 
@@ -408,6 +421,34 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn confirm_delivery_fails_if_number_of_unspent_weights_in_entry_is_invalid() {
+		let mut relayers: VecDeque<_> = unrewarded_relayers(1..=1)
+			.into_iter()
+			.chain(unrewarded_relayers(2..=2).into_iter())
+			.chain(unrewarded_relayers(3..=3).into_iter())
+			.collect();
+		relayers[1].messages.unspent_weights.clear();
+		assert_eq!(
+			assert_3_messages_confirmation_fails(3, &relayers),
+			ReceivalConfirmationResult::InvalidNumberOfDispatchResults,
+		);
+	}
+
+	#[test]
+	fn confirm_delivery_fails_if_number_of_expiration_flags_in_entry_is_invalid() {
+		let mut relayers: VecDeque<_> = unrewarded_relayers(1..=1)
+			.into_iter()
+			.chain(unrewarded_relayers(2..=2).into_iter())
+			.chain(unrewarded_relayers(3..=3).into_iter())
+			.collect();
+		relayers[1].messages.expired_messages.clear();
+		assert_eq!(
+			assert_3_messages_confirmation_fails(3, &relayers),
+			ReceivalConfirmationResult::InvalidNumberOfDispatchResults,
+		);
+	}
+
 	#[test]
 	fn prune_messages_works() {
 		run_test(|| {
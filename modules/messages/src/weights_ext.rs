@@ -45,12 +45,14 @@ pub fn ensure_weights_are_correct<W: WeightInfoExt>(
 	// verify `send_message` weight components
 	assert_ne!(W::send_message_overhead(), 0);
 	assert_ne!(W::send_message_size_overhead(0), 0);
+	assert_ne!(W::send_messages_overhead(1), 0);
 
 	// verify `receive_messages_proof` weight components
 	assert_ne!(W::receive_messages_proof_overhead(), 0);
 	assert_ne!(W::receive_messages_proof_messages_overhead(1), 0);
 	assert_ne!(W::receive_messages_proof_outbound_lane_state_overhead(), 0);
 	assert_ne!(W::storage_proof_size_overhead(1), 0);
+	assert_ne!(W::service_overweight_overhead(), 0);
 
 	// verify that the hardcoded value covers `receive_messages_proof` weight
 	let actual_single_regular_message_delivery_tx_weight = W::receive_messages_proof_weight(
@@ -81,6 +83,8 @@ pub fn ensure_weights_are_correct<W: WeightInfoExt>(
 	assert_ne!(W::receive_messages_delivery_proof_overhead(), 0);
 	assert_ne!(W::receive_messages_delivery_proof_messages_overhead(1), 0);
 	assert_ne!(W::receive_messages_delivery_proof_relayers_overhead(1), 0);
+	assert_ne!(W::single_message_refund_overhead(), 0);
+	assert_ne!(W::allowed_senders_check_overhead(1), 0);
 	assert_ne!(W::storage_proof_size_overhead(1), 0);
 
 	// verify that the hardcoded value covers `receive_messages_delivery_proof` weight
@@ -207,6 +211,30 @@ pub trait WeightInfoExt: WeightInfo {
 			.saturating_add(call_back_overhead)
 	}
 
+	/// Weight of batch message send extrinsic.
+	fn send_messages_weight<'a, M: Size + 'a>(
+		messages: impl IntoIterator<Item = &'a M>,
+		db_weight: RuntimeDbWeight,
+	) -> Weight {
+		let (messages_count, messages_size_overhead) = messages.into_iter().fold(
+			(0u32, 0),
+			|(count, size_overhead): (u32, Weight), message| {
+				(
+					count.saturating_add(1),
+					size_overhead
+						.saturating_add(Self::send_message_size_overhead(message.size_hint())),
+				)
+			},
+		);
+		let transaction_overhead = Self::send_messages_overhead(messages_count);
+		let call_back_overhead = Self::single_message_callback_overhead(db_weight)
+			.saturating_mul(messages_count as Weight);
+
+		transaction_overhead
+			.saturating_add(messages_size_overhead)
+			.saturating_add(call_back_overhead)
+	}
+
 	/// Weight of message delivery extrinsic.
 	fn receive_messages_proof_weight(
 		proof: &impl Size,
@@ -251,7 +279,9 @@ pub trait WeightInfoExt: WeightInfo {
 			relayers_state.unrewarded_relayer_entries,
 		);
 
-		// proof size overhead weight
+		// proof size overhead weight. Unspent weights of confirmed messages (up to
+		// `Compact<Weight>` per message) are not included into expected proof size, so they're
+		// always paid as proof size overhead
 		let expected_proof_size = Self::expected_extra_storage_proof_size();
 		let actual_proof_size = proof.size_hint();
 		let proof_size_overhead = Self::storage_proof_size_overhead(
@@ -264,20 +294,45 @@ pub trait WeightInfoExt: WeightInfo {
 			.total_messages
 			.saturating_mul(Self::single_message_callback_overhead(db_weight));
 
+		// and cost of refunding unspent dispatch fee to the submitter of every confirmed message
+		// (or remembering the fee refund of every expired message)
+		let refunds_overhead = relayers_state
+			.total_messages
+			.saturating_mul(Self::single_message_refund_overhead());
+
 		transaction_overhead
 			.saturating_add(messages_overhead)
 			.saturating_add(relayers_overhead)
 			.saturating_add(proof_size_overhead)
 			.saturating_add(callback_overhead)
+			.saturating_add(refunds_overhead)
 	}
 
 	// Functions that are used by extrinsics weights formulas.
 
+	/// Weight of the overweight message dispatch, excluding dispatch weight of the message
+	/// (`service_overweight`).
+	fn service_overweight_overhead() -> Weight {
+		Self::service_overweight()
+	}
+
+	/// Returns weight of storing the message in the overweight queue during delivery
+	/// (`receive_messages_proof`).
+	fn overweight_message_storage_overhead(db_weight: RuntimeDbWeight) -> Weight {
+		db_weight.writes(1)
+	}
+
 	/// Returns weight of message send transaction (`send_message`).
 	fn send_message_overhead() -> Weight {
 		Self::send_minimal_message_worst_case()
 	}
 
+	/// Returns weight of batch message send transaction (`send_messages`), given number of
+	/// messages in the batch.
+	fn send_messages_overhead(messages_count: u32) -> Weight {
+		Self::send_multiple_messages(messages_count)
+	}
+
 	/// Returns weight that needs to be accounted when message of given size is sent
 	/// (`send_message`).
 	fn send_message_size_overhead(message_size: u32) -> Weight {
@@ -287,6 +342,13 @@ pub trait WeightInfoExt: WeightInfo {
 		message_size_in_kb * single_kb_weight
 	}
 
+	/// Returns weight of checking whether the message sender is in the list of senders that are
+	/// allowed to use the lane (`send_message`), given the list length.
+	fn allowed_senders_check_overhead(allowed_senders: u32) -> Weight {
+		Self::send_minimal_message_with_allowed_senders(allowed_senders)
+			.saturating_sub(Self::send_minimal_message_worst_case())
+	}
+
 	/// Returns weight overhead of message delivery transaction (`receive_messages_proof`).
 	fn receive_messages_proof_overhead() -> Weight {
 		let weight_of_two_messages_and_two_tx_overheads =
@@ -382,6 +444,15 @@ pub trait WeightInfoExt: WeightInfo {
 	fn single_message_callback_overhead(db_weight: RuntimeDbWeight) -> Weight {
 		db_weight.reads_writes(1, 1)
 	}
+
+	/// Returns weight of refunding unspent dispatch fee to the submitter of single message.
+	///
+	/// It includes reading message sender from the storage and transferring the refund from the
+	/// relayers fund account to the sender account.
+	fn single_message_refund_overhead() -> Weight {
+		Self::receive_delivery_proof_for_single_message_with_refund()
+			.saturating_sub(Self::receive_delivery_proof_for_single_message())
+	}
 }
 
 impl WeightInfoExt for () {
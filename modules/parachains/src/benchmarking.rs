@@ -0,0 +1,65 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parachains finality pallet benchmarking.
+
+use crate::{Call, RelayBlockHash};
+
+use bp_polkadot_core::parachains::{ParaHeadsProof, ParaId};
+use frame_benchmarking::{account, benchmarks_instance_pallet};
+use frame_system::RawOrigin;
+use sp_std::prelude::*;
+
+/// Pallet we're benchmarking here.
+pub struct Pallet<T: Config<I>, I: 'static>(crate::Pallet<T, I>);
+
+/// Maximal number of parachains, which heads are submitted in a single call.
+const MAX_PARACHAINS: u32 = 1024;
+
+/// Size of the parachain head that is used in benchmarks.
+pub const DEFAULT_PARACHAIN_HEAD_SIZE: u32 = 384;
+
+/// Trait that must be implemented by runtime.
+pub trait Config<I: 'static>: crate::Config<I> {
+	/// Generate parachain heads proof and prepare environment for verifying this proof.
+	///
+	/// The relay chain header that is referenced by the returned hash must be imported
+	/// by the bridge GRANDPA pallet and its state root must match the returned proof.
+	fn prepare_parachain_heads_proof(
+		parachains: &[ParaId],
+		parachain_head_size: u32,
+	) -> (RelayBlockHash<Self, I>, ParaHeadsProof);
+}
+
+benchmarks_instance_pallet! {
+	// Benchmark `submit_parachain_heads` extrinsic with different number of parachains.
+	//
+	// All parachains are tracked by the pallet and all heads are updated, so this is the
+	// worst case for given number of parachains.
+	submit_parachain_heads {
+		let p in 1..MAX_PARACHAINS;
+
+		let sender = account("sender", 0, 0);
+		let parachains = (1..=p).map(ParaId).collect::<Vec<_>>();
+		let (relay_block_hash, parachain_heads_proof) =
+			T::prepare_parachain_heads_proof(&parachains, DEFAULT_PARACHAIN_HEAD_SIZE);
+	}: submit_parachain_heads(RawOrigin::Signed(sender), relay_block_hash, parachains.clone(), parachain_heads_proof)
+	verify {
+		for parachain in parachains {
+			assert!(crate::Pallet::<T, I>::best_parachain_head(parachain).is_some());
+		}
+	}
+}
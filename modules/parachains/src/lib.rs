@@ -0,0 +1,701 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parachains finality module.
+//!
+//! This module needs to be deployed together with the GRANDPA module, which is syncing relay
+//! chain blocks. The main entry point of this module is `submit_parachain_heads`, which
+//! accepts storage proof of some parachain `Heads` entries from bridged relay chain.
+//! It requires corresponding relay headers to be already synced.
+//!
+//! Finalized parachain heads, stored by the module, may then be used to verify storage proofs
+//! of the parachain (e.g. proofs of messages, sent by the parachain).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use crate::weights::WeightInfo;
+
+use bp_parachains::{parachain_head_storage_key_at_source, BestParaHead};
+use bp_polkadot_core::parachains::{ParaHash, ParaHasher, ParaHead, ParaHeadsProof, ParaId};
+use codec::Decode;
+use frame_support::traits::Contains;
+use sp_runtime::traits::Header as HeaderT;
+use sp_std::{convert::TryInto, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+/// Pallet containing weights for this pallet.
+pub mod weights;
+
+// Re-export in crate namespace for `construct_runtime!`
+pub use pallet::*;
+
+/// Block number of the bridged relay chain.
+pub type RelayBlockNumber<T, I> =
+	pallet_bridge_grandpa::BridgedBlockNumber<T, <T as Config<I>>::BridgesGrandpaPalletInstance>;
+/// Block hash of the bridged relay chain.
+pub type RelayBlockHash<T, I> =
+	pallet_bridge_grandpa::BridgedBlockHash<T, <T as Config<I>>::BridgesGrandpaPalletInstance>;
+/// Hasher of the bridged relay chain.
+pub type RelayBlockHasher<T, I> =
+	pallet_bridge_grandpa::BridgedBlockHasher<T, <T as Config<I>>::BridgesGrandpaPalletInstance>;
+/// GRANDPA pallet that is used to verify parachain heads proofs.
+type GrandpaPalletOf<T, I> =
+	pallet_bridge_grandpa::Pallet<T, <T as Config<I>>::BridgesGrandpaPalletInstance>;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	#[pallet::disable_frame_system_supertrait_check]
+	pub trait Config<I: 'static = ()>:
+		pallet_bridge_grandpa::Config<Self::BridgesGrandpaPalletInstance>
+	{
+		/// Instance of bridges GRANDPA pallet (within this runtime) that this pallet is linked to.
+		///
+		/// The GRANDPA pallet instance must be configured to import headers of relay chain that
+		/// we're interested in.
+		type BridgesGrandpaPalletInstance: 'static;
+
+		/// Name of the `paras` pallet in the `construct_runtime!()` call at the bridged relay
+		/// chain.
+		type ParasPalletName: Get<&'static str>;
+
+		/// Set of parachains, which heads are tracked by the pallet.
+		///
+		/// Heads of other parachains are ignored, even if they're in the submitted proof.
+		type TrackedParachains: Contains<ParaId>;
+
+		/// Maximal number of single parachain heads to keep in the storage.
+		///
+		/// The setting is there to prevent growing the on-chain state indefinitely. Note
+		/// the setting does not relate to parachain block numbers - we will simply keep as much
+		/// items in the storage, so it doesn't guarantee any fixed timeframe for heads.
+		#[pallet::constant]
+		type HeadsToKeep: Get<u32>;
+
+		/// Weights gathered through benchmarking.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn integrity_test() {
+			assert!(
+				T::HeadsToKeep::get() > 0,
+				"HeadsToKeep must be greater than zero, because it is used as a ring buffer size",
+			);
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Submit proof of one or several parachain heads.
+		///
+		/// The proof is supposed to be proof of some `Heads` entries from the
+		/// `polkadot-runtime-parachains::paras` pallet instance, deployed at the bridged chain.
+		/// The proof is supposed to be crafted at the `relay_block_hash` that must already be
+		/// imported by the corresponding GRANDPA pallet at this chain.
+		///
+		/// Parachains that are missing from the proof, are not tracked by the pallet, or have
+		/// already been updated at the better relay chain block, are ignored.
+		#[pallet::weight(T::WeightInfo::submit_parachain_heads(
+			parachains.len().try_into().unwrap_or(u32::MAX),
+		))]
+		pub fn submit_parachain_heads(
+			origin: OriginFor<T>,
+			relay_block_hash: RelayBlockHash<T, I>,
+			parachains: Vec<ParaId>,
+			parachain_heads_proof: ParaHeadsProof,
+		) -> DispatchResultWithPostInfo {
+			let _ = ensure_signed(origin)?;
+
+			// we'll need relay chain header to verify that parachains heads are always increasing
+			let relay_block = GrandpaPalletOf::<T, I>::finalized_header(relay_block_hash)
+				.ok_or(Error::<T, I>::UnknownRelayChainBlock)?;
+			let relay_block_number = *relay_block.number();
+
+			// now parse storage proof and read parachain heads
+			GrandpaPalletOf::<T, I>::parse_finalized_storage_proof(
+				relay_block_hash,
+				sp_trie::StorageProof::new(parachain_heads_proof),
+				move |storage| {
+					for parachain in parachains {
+						if !T::TrackedParachains::contains(&parachain) {
+							log::trace!(
+								target: "runtime::bridge-parachains",
+								"The head of untracked parachain {:?} has been provided. Ignoring",
+								parachain,
+							);
+							continue
+						}
+
+						let parachain_head =
+							match Pallet::<T, I>::read_parachain_head(&storage, parachain) {
+								Some(parachain_head) => parachain_head,
+								None => {
+									log::trace!(
+										target: "runtime::bridge-parachains",
+										"The head of parachain {:?} is missing from the proof. Ignoring",
+										parachain,
+									);
+									continue
+								},
+							};
+
+						let _ = Pallet::<T, I>::update_parachain_head(
+							parachain,
+							relay_block_number,
+							parachain_head,
+						);
+					}
+				},
+			)
+			.map_err(|_| Error::<T, I>::InvalidStorageProof)?;
+
+			Ok(().into())
+		}
+	}
+
+	/// Best parachain heads.
+	#[pallet::storage]
+	pub(super) type BestParaHeads<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, ParaId, BestParaHead<RelayBlockNumber<T, I>>>;
+
+	/// Parachain heads which have been imported into the pallet.
+	#[pallet::storage]
+	pub(super) type ImportedParaHeads<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Blake2_128Concat, ParaId, Blake2_128Concat, ParaHash, ParaHead>;
+
+	/// A ring buffer of imported parachain head hashes. Ordered by the insertion time.
+	#[pallet::storage]
+	pub(super) type ImportedParaHashes<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Blake2_128Concat, ParaId, Twox64Concat, u32, ParaHash>;
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// Relay chain block is unknown to us.
+		UnknownRelayChainBlock,
+		/// Invalid storage proof has been passed.
+		InvalidStorageProof,
+		/// Parachain head is unknown to us.
+		UnknownParaHead,
+		/// Failed to decode parachain header from the parachain head.
+		FailedToDecodeParaHead,
+		/// The storage proof doesn't contains storage root. So it is invalid for given head.
+		StorageRootMismatch,
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Get best finalized head of the given parachain.
+	pub fn best_parachain_head(parachain: ParaId) -> Option<ParaHead> {
+		let best_para_head_hash = BestParaHeads::<T, I>::get(parachain)?.head_hash;
+		ImportedParaHeads::<T, I>::get(parachain, best_para_head_hash)
+	}
+
+	/// Get parachain head with given hash.
+	pub fn parachain_head(parachain: ParaId, hash: ParaHash) -> Option<ParaHead> {
+		ImportedParaHeads::<T, I>::get(parachain, hash)
+	}
+
+	/// Verify that the passed storage proof is valid, given it is crafted using
+	/// known finalized head of the given parachain. If the proof is valid, then the `parse`
+	/// callback is called and the function returns its result.
+	pub fn parse_finalized_storage_proof<ParachainHeader, R>(
+		parachain: ParaId,
+		hash: ParaHash,
+		storage_proof: sp_trie::StorageProof,
+		parse: impl FnOnce(bp_runtime::StorageProofChecker<ParaHasher>) -> R,
+	) -> Result<R, sp_runtime::DispatchError>
+	where
+		ParachainHeader: HeaderT<Hash = ParaHash>,
+	{
+		let parachain_head = ImportedParaHeads::<T, I>::get(parachain, hash)
+			.ok_or(Error::<T, I>::UnknownParaHead)?;
+		let parachain_header = ParachainHeader::decode(&mut &parachain_head.0[..])
+			.map_err(|_| Error::<T, I>::FailedToDecodeParaHead)?;
+		let storage_proof_checker =
+			bp_runtime::StorageProofChecker::new(*parachain_header.state_root(), storage_proof)
+				.map_err(|_| Error::<T, I>::StorageRootMismatch)?;
+
+		Ok(parse(storage_proof_checker))
+	}
+
+	/// Read parachain head from storage proof.
+	fn read_parachain_head(
+		storage: &bp_runtime::StorageProofChecker<RelayBlockHasher<T, I>>,
+		parachain: ParaId,
+	) -> Option<ParaHead> {
+		let parachain_head_key =
+			parachain_head_storage_key_at_source(T::ParasPalletName::get(), parachain);
+		let parachain_head = storage.read_value(parachain_head_key.0.as_ref()).ok()??;
+		ParaHead::decode(&mut &parachain_head[..]).ok()
+	}
+
+	/// Try to update parachain head.
+	///
+	/// The head is only updated if it has been read at the relay chain block that is better
+	/// than the block of the previously stored head.
+	fn update_parachain_head(
+		parachain: ParaId,
+		updated_at_relay_block_number: RelayBlockNumber<T, I>,
+		updated_head: ParaHead,
+	) -> Result<(), ()> {
+		// check if head has been already updated at better relay chain block. Without this
+		// check, we may import heads in random order
+		let updated_head_hash = updated_head.hash();
+		let next_imported_hash_position = match BestParaHeads::<T, I>::get(parachain) {
+			Some(stored_best_head)
+				if stored_best_head.at_relay_block_number >= updated_at_relay_block_number =>
+			{
+				log::trace!(
+					target: "runtime::bridge-parachains",
+					"The head of parachain {:?} can't be updated to {}, because it has been already updated \
+					to {} at better relay chain block: {:?} >= {:?}",
+					parachain,
+					updated_head_hash,
+					stored_best_head.head_hash,
+					stored_best_head.at_relay_block_number,
+					updated_at_relay_block_number,
+				);
+				return Err(())
+			},
+			Some(stored_best_head) if stored_best_head.head_hash == updated_head_hash => {
+				// the head is the same => we only need to remember the new relay block number
+				BestParaHeads::<T, I>::insert(
+					parachain,
+					BestParaHead {
+						at_relay_block_number: updated_at_relay_block_number,
+						..stored_best_head
+					},
+				);
+				return Ok(())
+			},
+			Some(stored_best_head) => stored_best_head.next_imported_hash_position,
+			None => 0,
+		};
+
+		// prune the oldest head (if the ring buffer is full)
+		if let Ok(head_hash_to_prune) =
+			ImportedParaHashes::<T, I>::try_get(parachain, next_imported_hash_position)
+		{
+			log::trace!(
+				target: "runtime::bridge-parachains",
+				"Pruning old head of parachain {:?}: {}",
+				parachain,
+				head_hash_to_prune,
+			);
+			ImportedParaHeads::<T, I>::remove(parachain, head_hash_to_prune);
+		}
+
+		// insert updated best parachain head
+		BestParaHeads::<T, I>::insert(
+			parachain,
+			BestParaHead {
+				at_relay_block_number: updated_at_relay_block_number,
+				head_hash: updated_head_hash,
+				next_imported_hash_position: (next_imported_hash_position + 1) %
+					T::HeadsToKeep::get(),
+			},
+		);
+		ImportedParaHeads::<T, I>::insert(parachain, updated_head_hash, updated_head);
+		ImportedParaHashes::<T, I>::insert(
+			parachain,
+			next_imported_hash_position,
+			updated_head_hash,
+		);
+		log::trace!(
+			target: "runtime::bridge-parachains",
+			"Updated head of parachain {:?} to {}",
+			parachain,
+			updated_head_hash,
+		);
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		run_test, Origin, RelayBlockHeader, TestNumber, TestRuntime, UNTRACKED_PARACHAIN_ID,
+	};
+
+	use bp_parachains::PARAS_PALLET_NAME;
+	use bp_test_utils::{authority_list, make_default_justification};
+	use codec::Encode;
+	use frame_support::{
+		assert_noop, assert_ok, dispatch::DispatchResultWithPostInfo, traits::Get,
+	};
+	use sp_core::H256;
+	use sp_runtime::traits::BlakeTwo256;
+	use sp_trie::{record_all_keys, trie_types::TrieDBMut, Layout, MemoryDB, Recorder, TrieMut};
+
+	fn test_relay_header(num: TestNumber, state_root: H256) -> RelayBlockHeader {
+		RelayBlockHeader::new(
+			num,
+			Default::default(),
+			state_root,
+			Default::default(),
+			Default::default(),
+		)
+	}
+
+	fn initialize(state_root: H256) {
+		assert_ok!(pallet_bridge_grandpa::Pallet::<TestRuntime>::initialize(
+			Origin::root(),
+			bp_header_chain::InitializationData {
+				header: Box::new(test_relay_header(0, state_root)),
+				authority_list: authority_list(),
+				set_id: 1,
+				is_halted: false,
+			},
+		));
+	}
+
+	fn proceed(num: TestNumber, state_root: H256) {
+		let header = test_relay_header(num, state_root);
+		let justification = make_default_justification(&header);
+		assert_ok!(pallet_bridge_grandpa::Pallet::<TestRuntime>::submit_finality_proof(
+			Origin::signed(1),
+			Box::new(header),
+			justification,
+		));
+	}
+
+	fn prepare_parachain_heads_proof(heads: Vec<(u32, ParaHead)>) -> (H256, ParaHeadsProof) {
+		let mut root = Default::default();
+		let mut mdb = MemoryDB::default();
+		{
+			let mut trie = TrieDBMut::<BlakeTwo256>::new(&mut mdb, &mut root);
+			for (parachain, head) in heads {
+				let storage_key =
+					parachain_head_storage_key_at_source(PARAS_PALLET_NAME, ParaId(parachain));
+				trie.insert(&storage_key.0, &head.encode())
+					.map_err(|_| "TrieMut::insert has failed")
+					.expect("TrieMut::insert should not fail in tests");
+			}
+		}
+
+		// generate storage proof to be delivered to This chain
+		let mut proof_recorder = Recorder::<H256>::new();
+		record_all_keys::<Layout<BlakeTwo256>, _>(&mdb, &root, &mut proof_recorder)
+			.map_err(|_| "record_all_keys has failed")
+			.expect("record_all_keys should not fail in tests");
+		let storage_proof = proof_recorder.drain().into_iter().map(|n| n.data.to_vec()).collect();
+
+		(root, storage_proof)
+	}
+
+	fn head_data(parachain: u32, head_number: u32) -> ParaHead {
+		ParaHead((parachain, head_number).encode())
+	}
+
+	fn head_hash(parachain: u32, head_number: u32) -> ParaHash {
+		head_data(parachain, head_number).hash()
+	}
+
+	fn import_parachain_1_head(
+		relay_chain_block: TestNumber,
+		relay_state_root: H256,
+		proof: ParaHeadsProof,
+	) -> DispatchResultWithPostInfo {
+		Pallet::<TestRuntime>::submit_parachain_heads(
+			Origin::signed(1),
+			test_relay_header(relay_chain_block, relay_state_root).hash(),
+			vec![ParaId(1)],
+			proof,
+		)
+	}
+
+	#[test]
+	fn imports_initial_parachain_heads() {
+		let (state_root, proof) =
+			prepare_parachain_heads_proof(vec![(1, head_data(1, 0)), (3, head_data(3, 10))]);
+		run_test(|| {
+			initialize(state_root);
+
+			// we're trying to update heads of parachains 1, 2 and 3
+			assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+				Origin::signed(1),
+				test_relay_header(0, state_root).hash(),
+				vec![ParaId(1), ParaId(2), ParaId(3)],
+				proof,
+			));
+
+			// but only 1 and 3 are updated, because proof is missing head of parachain#2
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(BestParaHead {
+					at_relay_block_number: 0,
+					head_hash: head_hash(1, 0),
+					next_imported_hash_position: 1,
+				})
+			);
+			assert_eq!(BestParaHeads::<TestRuntime>::get(ParaId(2)), None);
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(3)),
+				Some(BestParaHead {
+					at_relay_block_number: 0,
+					head_hash: head_hash(3, 10),
+					next_imported_hash_position: 1,
+				})
+			);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::best_parachain_head(ParaId(1)),
+				Some(head_data(1, 0))
+			);
+			assert_eq!(Pallet::<TestRuntime>::best_parachain_head(ParaId(2)), None);
+			assert_eq!(
+				Pallet::<TestRuntime>::best_parachain_head(ParaId(3)),
+				Some(head_data(3, 10))
+			);
+		});
+	}
+
+	#[test]
+	fn imports_parachain_heads_is_able_to_progress() {
+		let (state_root_5, proof_5) = prepare_parachain_heads_proof(vec![(1, head_data(1, 5))]);
+		let (state_root_10, proof_10) = prepare_parachain_heads_proof(vec![(1, head_data(1, 10))]);
+		run_test(|| {
+			// start with relay block #0 and import head#5 of parachain#1
+			initialize(state_root_5);
+			assert_ok!(import_parachain_1_head(0, state_root_5, proof_5));
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(BestParaHead {
+					at_relay_block_number: 0,
+					head_hash: head_hash(1, 5),
+					next_imported_hash_position: 1,
+				})
+			);
+
+			// import head#10 of parachain#1 at relay block #1
+			proceed(1, state_root_10);
+			assert_ok!(import_parachain_1_head(1, state_root_10, proof_10));
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(BestParaHead {
+					at_relay_block_number: 1,
+					head_hash: head_hash(1, 10),
+					next_imported_hash_position: 2,
+				})
+			);
+
+			// both heads are available
+			assert_eq!(
+				Pallet::<TestRuntime>::parachain_head(ParaId(1), head_hash(1, 5)),
+				Some(head_data(1, 5))
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::parachain_head(ParaId(1), head_hash(1, 10)),
+				Some(head_data(1, 10))
+			);
+		});
+	}
+
+	#[test]
+	fn ignores_untracked_parachain() {
+		let (state_root, proof) = prepare_parachain_heads_proof(vec![
+			(1, head_data(1, 5)),
+			(UNTRACKED_PARACHAIN_ID, head_data(UNTRACKED_PARACHAIN_ID, 5)),
+		]);
+		run_test(|| {
+			initialize(state_root);
+			assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+				Origin::signed(1),
+				test_relay_header(0, state_root).hash(),
+				vec![ParaId(1), ParaId(UNTRACKED_PARACHAIN_ID)],
+				proof,
+			));
+
+			assert_eq!(
+				Pallet::<TestRuntime>::best_parachain_head(ParaId(1)),
+				Some(head_data(1, 5))
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::best_parachain_head(ParaId(UNTRACKED_PARACHAIN_ID)),
+				None
+			);
+		});
+	}
+
+	#[test]
+	fn only_updates_relay_block_number_when_head_is_not_changed() {
+		let (state_root, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, 0))]);
+		run_test(|| {
+			// import head#0 of parachain#1 at relay block#0
+			initialize(state_root);
+			assert_ok!(import_parachain_1_head(0, state_root, proof.clone()));
+
+			// try to import head#0 of parachain#1 at relay block#1
+			proceed(1, state_root);
+			assert_ok!(import_parachain_1_head(1, state_root, proof));
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(BestParaHead {
+					at_relay_block_number: 1,
+					head_hash: head_hash(1, 0),
+					next_imported_hash_position: 1,
+				})
+			);
+		});
+	}
+
+	#[test]
+	fn does_nothing_when_already_imported_head_at_better_relay_header() {
+		let (state_root_5, proof_5) = prepare_parachain_heads_proof(vec![(1, head_data(1, 5))]);
+		let (state_root_10, proof_10) = prepare_parachain_heads_proof(vec![(1, head_data(1, 10))]);
+		run_test(|| {
+			// start with relay block #0
+			initialize(state_root_5);
+
+			// head#10 of parachain#1 is imported at relay block#1
+			proceed(1, state_root_10);
+			assert_ok!(import_parachain_1_head(1, state_root_10, proof_10));
+
+			// now try to import head#5 at relay block#0
+			assert_ok!(import_parachain_1_head(0, state_root_5, proof_5));
+			assert_eq!(
+				BestParaHeads::<TestRuntime>::get(ParaId(1)),
+				Some(BestParaHead {
+					at_relay_block_number: 1,
+					head_hash: head_hash(1, 10),
+					next_imported_hash_position: 1,
+				})
+			);
+			assert_eq!(Pallet::<TestRuntime>::parachain_head(ParaId(1), head_hash(1, 5)), None);
+		});
+	}
+
+	#[test]
+	fn prunes_old_heads() {
+		run_test(|| {
+			let heads_to_keep = crate::mock::HeadsToKeep::get();
+
+			// import exactly `HeadsToKeep` headers
+			for i in 0..heads_to_keep {
+				let (state_root, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, i))]);
+				if i == 0 {
+					initialize(state_root);
+				} else {
+					proceed(i as _, state_root);
+				}
+
+				assert_ok!(import_parachain_1_head(i as _, state_root, proof));
+			}
+
+			// nothing is pruned yet
+			for i in 0..heads_to_keep {
+				assert!(ImportedParaHeads::<TestRuntime>::contains_key(ParaId(1), head_hash(1, i)));
+			}
+
+			// import next relay chain header and next parachain head
+			let (state_root, proof) =
+				prepare_parachain_heads_proof(vec![(1, head_data(1, heads_to_keep))]);
+			proceed(heads_to_keep as _, state_root);
+			assert_ok!(import_parachain_1_head(heads_to_keep as _, state_root, proof));
+
+			// and the head#0 is pruned
+			assert!(!ImportedParaHeads::<TestRuntime>::contains_key(ParaId(1), head_hash(1, 0)));
+			for i in 1..=heads_to_keep {
+				assert!(ImportedParaHeads::<TestRuntime>::contains_key(ParaId(1), head_hash(1, i)));
+			}
+		});
+	}
+
+	#[test]
+	fn fails_on_unknown_relay_chain_block() {
+		let (state_root, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, 5))]);
+		run_test(|| {
+			// start with relay block #0
+			initialize(state_root);
+
+			// try to import head#5 of parachain#1 at unknown relay chain block #1
+			assert_noop!(
+				import_parachain_1_head(1, state_root, proof),
+				Error::<TestRuntime>::UnknownRelayChainBlock
+			);
+		});
+	}
+
+	#[test]
+	fn fails_on_invalid_storage_proof() {
+		let (_state_root, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, 5))]);
+		run_test(|| {
+			// start with relay block #0
+			initialize(Default::default());
+
+			// try to import head#5 of parachain#1 at relay chain block #0
+			assert_noop!(
+				import_parachain_1_head(0, Default::default(), proof),
+				Error::<TestRuntime>::InvalidStorageProof
+			);
+		});
+	}
+
+	#[test]
+	fn parse_finalized_storage_proof_works() {
+		let (parachain_state_root, parachain_storage_proof) =
+			bp_runtime::craft_valid_storage_proof();
+		let parachain_head = ParaHead(test_relay_header(0, parachain_state_root).encode());
+		let parachain_head_hash = parachain_head.hash();
+		let (state_root, proof) = prepare_parachain_heads_proof(vec![(1, parachain_head)]);
+		run_test(|| {
+			initialize(state_root);
+			assert_ok!(import_parachain_1_head(0, state_root, proof));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof::<RelayBlockHeader, _>(
+					ParaId(1),
+					Default::default(),
+					parachain_storage_proof.clone(),
+					|_| (),
+				),
+				Error::<TestRuntime>::UnknownParaHead,
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof::<RelayBlockHeader, _>(
+					ParaId(1),
+					parachain_head_hash,
+					parachain_storage_proof,
+					|storage| storage.read_value(b"key1"),
+				),
+				Ok(Ok(Some(b"value1".to_vec()))),
+			);
+		});
+	}
+
+	#[test]
+	fn integrity_test_passes_with_mock_configuration() {
+		run_test(|| {
+			<Pallet<TestRuntime> as frame_support::traits::Hooks<TestNumber>>::integrity_test();
+		});
+	}
+}
@@ -0,0 +1,104 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Relayers pallet benchmarking.
+
+use crate::{Call, LaneRelayers, Pallet, RelayerRewards, RelayerStakes, StakeOf};
+
+use bp_messages::LaneId;
+use bp_runtime::ChainId;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::{Currency, Get};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+/// Bridged chain that is used in benchmarks.
+const BRIDGED_CHAIN_ID: ChainId = *b"bnch";
+/// Lane that is used in benchmarks.
+const LANE_ID: LaneId = [0, 0, 0, 0];
+
+/// Trait that must be implemented by runtime.
+pub trait Config: crate::Config {
+	/// Prepare environment for paying `reward` to the `relayer` (e.g. endow the relayers fund
+	/// account).
+	fn prepare_environment(relayer: &Self::AccountId, reward: Self::Reward);
+}
+
+benchmarks! {
+	// Benchmark `claim_rewards` call. The `claim_rewards` call doesn't depend on anything, so
+	// there's nothing special about this benchmark.
+	claim_rewards {
+		let relayer: T::AccountId = account("relayer", 0, SEED);
+		let reward = T::Reward::from(1_000_000u32);
+
+		T::prepare_environment(&relayer, reward);
+		RelayerRewards::<T>::insert(&relayer, (BRIDGED_CHAIN_ID, LANE_ID), reward);
+	}: _(RawOrigin::Signed(relayer.clone()), BRIDGED_CHAIN_ID, LANE_ID)
+	verify {
+		assert!(!RelayerRewards::<T>::contains_key(&relayer, (BRIDGED_CHAIN_ID, LANE_ID)));
+	}
+
+	// Benchmark `register` call when there are `r - 1` relayers already registered at the lane.
+	//
+	// The call reads and writes the whole lane relayers set, so its cost depends on the set size.
+	register {
+		let r in 1 .. T::MaxRelayersPerLane::get();
+
+		fill_lane_relayers::<T>(r - 1);
+		let relayer = funded_account::<T>("relayer", 0);
+	}: _(RawOrigin::Signed(relayer.clone()), LANE_ID)
+	verify {
+		assert!(RelayerStakes::<T>::contains_key(&relayer, LANE_ID));
+		assert_eq!(LaneRelayers::<T>::get(LANE_ID).len() as u32, r);
+	}
+
+	// Benchmark `deregister` call when there are `r` relayers (including the caller) registered
+	// at the lane.
+	//
+	// The caller is the last registered relayer, so the whole lane relayers set is traversed
+	// when it is removed from the set.
+	deregister {
+		let r in 1 .. T::MaxRelayersPerLane::get();
+
+		fill_lane_relayers::<T>(r - 1);
+		let relayer = funded_account::<T>("relayer", 0);
+		Pallet::<T>::register(RawOrigin::Signed(relayer.clone()).into(), LANE_ID)
+			.expect("lane relayers set is not full; relayer has enough funds; qed");
+	}: _(RawOrigin::Signed(relayer.clone()), LANE_ID)
+	verify {
+		assert!(!RelayerStakes::<T>::contains_key(&relayer, LANE_ID));
+	}
+}
+
+/// Account that has enough funds to bond registration stake.
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let account: T::AccountId = account(name, index, SEED);
+	T::StakeCurrency::make_free_balance_be(&account, StakeOf::<T>::max_value() / 2u32.into());
+	account
+}
+
+/// Register given number of relayers at the benchmark lane.
+fn fill_lane_relayers<T: Config>(relayers: u32) {
+	for index in 1..=relayers {
+		let relayer = funded_account::<T>("lane_relayer", index);
+		Pallet::<T>::register(RawOrigin::Signed(relayer).into(), LANE_ID)
+			.expect("lane relayers set is not full; relayer has enough funds; qed");
+	}
+	assert_eq!(LaneRelayers::<T>::get(LANE_ID).len() as u32, relayers);
+}
@@ -0,0 +1,184 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signed extension that prioritizes message delivery transactions of the slot relayer.
+
+use crate::{Config, Pallet};
+
+use bp_messages::LaneId;
+use codec::{Decode, Encode};
+use frame_support::traits::Get;
+use sp_runtime::{
+	traits::{DispatchInfoOf, SignedExtension},
+	transaction_validity::{
+		TransactionPriority, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+};
+use sp_std::{fmt::Debug, marker::PhantomData};
+
+/// Trait that is able to recognize message delivery transactions.
+pub trait DeliveryCallInfo<Call> {
+	/// If the call is the message delivery call, returns lane of messages that are delivered.
+	fn delivery_lane(call: &Call) -> Option<LaneId>;
+}
+
+/// Signed extension that boosts priority of message delivery transactions, submitted by the
+/// relayer that owns the current delivery slot at the lane.
+///
+/// Delivery transactions of all other relayers (and all other transactions) are not affected,
+/// so they have lower priority than the slot relayer transaction.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct PrioritizeSlotRelayer<T, CallInfo, PriorityBoost>(
+	PhantomData<(T, CallInfo, PriorityBoost)>,
+);
+
+impl<T, CallInfo, PriorityBoost> PrioritizeSlotRelayer<T, CallInfo, PriorityBoost> {
+	/// Create new signed extension.
+	pub fn new() -> Self {
+		PrioritizeSlotRelayer(PhantomData)
+	}
+}
+
+impl<T, CallInfo, PriorityBoost> Default for PrioritizeSlotRelayer<T, CallInfo, PriorityBoost> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T, CallInfo, PriorityBoost> Debug for PrioritizeSlotRelayer<T, CallInfo, PriorityBoost> {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "PrioritizeSlotRelayer")
+	}
+}
+
+impl<T, CallInfo, PriorityBoost> SignedExtension
+	for PrioritizeSlotRelayer<T, CallInfo, PriorityBoost>
+where
+	T: Config + Send + Sync,
+	CallInfo:
+		DeliveryCallInfo<<T as frame_system::Config>::Call> + Clone + Eq + Send + Sync + 'static,
+	PriorityBoost: Get<TransactionPriority> + Clone + Eq + Send + Sync + 'static,
+{
+	const IDENTIFIER: &'static str = "PrioritizeSlotRelayer";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		let lane_id = match CallInfo::delivery_lane(call) {
+			Some(lane_id) => lane_id,
+			None => return Ok(ValidTransaction::default()),
+		};
+
+		if Pallet::<T>::slot_relayer(lane_id).as_ref() != Some(who) {
+			return Ok(ValidTransaction::default())
+		}
+
+		Ok(ValidTransaction { priority: PriorityBoost::get(), ..Default::default() })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		mock::{run_test, AccountId, Call, TestRuntime, REGULAR_RELAYER, TEST_LANE_ID},
+		LaneRelayers,
+	};
+	use frame_support::parameter_types;
+
+	const OTHER_RELAYER: AccountId = 42;
+	const DELIVERY_PRIORITY_BOOST: TransactionPriority = 1_000;
+
+	parameter_types! {
+		pub const PriorityBoost: TransactionPriority = DELIVERY_PRIORITY_BOOST;
+	}
+
+	/// Every `remark` call is treated as message delivery call at the test lane.
+	#[derive(Clone, PartialEq, Eq)]
+	struct TestDeliveryCallInfo;
+
+	impl DeliveryCallInfo<Call> for TestDeliveryCallInfo {
+		fn delivery_lane(call: &Call) -> Option<LaneId> {
+			match *call {
+				Call::System(frame_system::Call::remark(_)) => Some(TEST_LANE_ID),
+				_ => None,
+			}
+		}
+	}
+
+	type TestExtension = PrioritizeSlotRelayer<TestRuntime, TestDeliveryCallInfo, PriorityBoost>;
+
+	fn validate(relayer: AccountId, call: Call) -> TransactionPriority {
+		TestExtension::new()
+			.validate(&relayer, &call, &Default::default(), 0)
+			.unwrap()
+			.priority
+	}
+
+	fn delivery_call() -> Call {
+		Call::System(frame_system::Call::remark(vec![]))
+	}
+
+	#[test]
+	fn other_calls_are_not_prioritized() {
+		run_test(|| {
+			LaneRelayers::<TestRuntime>::insert(TEST_LANE_ID, vec![REGULAR_RELAYER]);
+			assert_eq!(
+				validate(
+					REGULAR_RELAYER,
+					Call::Balances(pallet_balances::Call::transfer(OTHER_RELAYER, 1))
+				),
+				0,
+			);
+		});
+	}
+
+	#[test]
+	fn delivery_is_not_prioritized_if_there_are_no_lane_relayers() {
+		run_test(|| {
+			assert_eq!(validate(REGULAR_RELAYER, delivery_call()), 0);
+		});
+	}
+
+	#[test]
+	fn only_slot_relayer_delivery_is_prioritized() {
+		run_test(|| {
+			LaneRelayers::<TestRuntime>::insert(TEST_LANE_ID, vec![REGULAR_RELAYER, OTHER_RELAYER]);
+
+			// first slot belongs to the `REGULAR_RELAYER`
+			frame_system::Pallet::<TestRuntime>::set_block_number(0);
+			assert_eq!(validate(REGULAR_RELAYER, delivery_call()), DELIVERY_PRIORITY_BOOST);
+			assert_eq!(validate(OTHER_RELAYER, delivery_call()), 0);
+
+			// second slot belongs to the `OTHER_RELAYER`
+			frame_system::Pallet::<TestRuntime>::set_block_number(crate::mock::SLOT_LENGTH);
+			assert_eq!(validate(REGULAR_RELAYER, delivery_call()), 0);
+			assert_eq!(validate(OTHER_RELAYER, delivery_call()), DELIVERY_PRIORITY_BOOST);
+		});
+	}
+}
@@ -0,0 +1,754 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module that is used to store relayer rewards.
+//!
+//! Rewards for delivering messages are not paid immediately. Instead, they're accumulated in
+//! the runtime storage, separately for every bridge and lane. The relayer may then claim all
+//! rewards, accumulated at the given lane of the given bridge, using single `claim_rewards` call.
+//! This saves some fees (there's one transfer per claim instead of transfer per confirmation) and
+//! relayer accounts don't need to be above existential deposit when message delivery is confirmed.
+//!
+//! Relayers may also bond stake to register at the lane. Registered relayers get time-sliced
+//! delivery slots: the slot owner is the only relayer whose message delivery transactions are
+//! prioritized (see `PrioritizeSlotRelayer` signed extension). That lets rational relayers
+//! avoid racing each other with the same delivery transaction, when only one transaction is
+//! accepted and all others are failing (yet paying transaction fees).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+use bp_messages::LaneId;
+use bp_runtime::ChainId;
+use frame_support::{
+	dispatch::DispatchError,
+	traits::{Currency as CurrencyT, ExistenceRequirement, Get, ReservableCurrency},
+	weights::{Pays, PostDispatchInfo},
+};
+use sp_runtime::traits::{AtLeast32BitUnsigned, UniqueSaturatedInto, Zero};
+use sp_std::{fmt::Debug, marker::PhantomData, prelude::*};
+use weights::WeightInfo;
+
+pub use extension::{DeliveryCallInfo, PrioritizeSlotRelayer};
+pub use pallet::*;
+pub use payment_adapter::MessageDeliveryAndDispatchPaymentAdapter;
+
+mod extension;
+mod payment_adapter;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+pub mod weights;
+
+/// Reward payment procedure.
+pub trait PaymentProcedure<Relayer, Reward> {
+	/// Error that may be returned by the procedure.
+	type Error: Debug;
+
+	/// Pay reward, accumulated at the given lane of the bridge with given chain, to the relayer
+	/// account.
+	fn pay_reward(
+		relayer: &Relayer,
+		bridged_chain_id: ChainId,
+		lane_id: LaneId,
+		reward: Reward,
+	) -> Result<(), Self::Error>;
+}
+
+/// Reward payment procedure that transfers rewards from the given (relayers fund) account.
+///
+/// NOTE The fund account must always exist i.e. be over Existential Deposit.
+pub struct PayLaneRewardFromAccount<Currency, FundAccount>(PhantomData<(Currency, FundAccount)>);
+
+impl<AccountId, Currency, FundAccount> PaymentProcedure<AccountId, Currency::Balance>
+	for PayLaneRewardFromAccount<Currency, FundAccount>
+where
+	Currency: CurrencyT<AccountId>,
+	FundAccount: Get<AccountId>,
+{
+	type Error = DispatchError;
+
+	fn pay_reward(
+		relayer: &AccountId,
+		_bridged_chain_id: ChainId,
+		_lane_id: LaneId,
+		reward: Currency::Balance,
+	) -> Result<(), Self::Error> {
+		Currency::transfer(
+			&FundAccount::get(),
+			relayer,
+			reward,
+			// the relayer fund account must stay above ED (needs to be pre-funded)
+			ExistenceRequirement::KeepAlive,
+		)
+	}
+}
+
+/// Stake type used by the pallet.
+pub type StakeOf<T> =
+	<<T as Config>::StakeCurrency as CurrencyT<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Returns relayer that owns the delivery slot at given block.
+///
+/// Slots are assigned to registered lane relayers in round-robin fashion, in order of their
+/// registration. Returns `None` if there are no registered relayers at the lane, meaning that
+/// any relayer may deliver messages.
+pub fn delivery_slot_owner<AccountId: Clone>(
+	lane_relayers: &[AccountId],
+	block_number: u64,
+	slot_length: u64,
+) -> Option<AccountId> {
+	if lane_relayers.is_empty() || slot_length == 0 {
+		return None
+	}
+
+	let slot = block_number / slot_length;
+	lane_relayers.get((slot % lane_relayers.len() as u64) as usize).cloned()
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// Type of relayer reward.
+		type Reward: AtLeast32BitUnsigned + Copy + Parameter + MaybeSerializeDeserialize;
+		/// Pay rewards adapter.
+		type PaymentProcedure: PaymentProcedure<Self::AccountId, Self::Reward>;
+		/// Currency used to bond relayers stake.
+		type StakeCurrency: ReservableCurrency<Self::AccountId>;
+		/// Stake that the relayer needs to bond to register at the lane.
+		type RequiredStake: Get<StakeOf<Self>>;
+		/// Length of the delivery slot (in blocks).
+		type SlotLength: Get<Self::BlockNumber>;
+		/// Maximal number of relayers that may be registered at the single lane.
+		type MaxRelayersPerLane: Get<u32>;
+		/// Benchmarks results from runtime we're plugged into.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Claim rewards, accumulated at given lane of the bridge with given chain.
+		#[pallet::weight(T::WeightInfo::claim_rewards())]
+		pub fn claim_rewards(
+			origin: OriginFor<T>,
+			bridged_chain_id: ChainId,
+			lane_id: LaneId,
+		) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+
+			RelayerRewards::<T>::try_mutate_exists(
+				&relayer,
+				(bridged_chain_id, lane_id),
+				|maybe_reward| -> DispatchResult {
+					let reward = maybe_reward.take().ok_or(Error::<T>::NoRewardForRelayer)?;
+					T::PaymentProcedure::pay_reward(&relayer, bridged_chain_id, lane_id, reward)
+						.map_err(|e| {
+							log::trace!(
+								target: "runtime::bridge-relayers",
+								"Failed to pay {:?}/{:?} rewards to {:?}: {:?}",
+								bridged_chain_id,
+								lane_id,
+								relayer,
+								e,
+							);
+							Error::<T>::FailedToPayReward
+						})?;
+
+					Self::deposit_event(Event::<T>::RewardPaid(
+						relayer.clone(),
+						bridged_chain_id,
+						lane_id,
+						reward,
+					));
+					Ok(())
+				},
+			)
+		}
+
+		/// Register relayer at given lane.
+		///
+		/// The `RequiredStake` is reserved at the relayer account until it deregisters. The
+		/// relayer is getting delivery slots at the lane, starting from the next slot
+		/// assignment.
+		#[pallet::weight(T::WeightInfo::register(T::MaxRelayersPerLane::get()))]
+		pub fn register(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResultWithPostInfo {
+			let relayer = ensure_signed(origin)?;
+			ensure!(
+				!RelayerStakes::<T>::contains_key(&relayer, lane_id),
+				Error::<T>::AlreadyRegistered,
+			);
+
+			let lane_relayers_count = LaneRelayers::<T>::try_mutate(
+				lane_id,
+				|lane_relayers| -> Result<u32, DispatchError> {
+					ensure!(
+						lane_relayers.len() < T::MaxRelayersPerLane::get() as usize,
+						Error::<T>::TooManyRelayersAtLane,
+					);
+
+					let stake = T::RequiredStake::get();
+					T::StakeCurrency::reserve(&relayer, stake)
+						.map_err(|_| Error::<T>::FailedToReserveStake)?;
+
+					lane_relayers.push(relayer.clone());
+					RelayerStakes::<T>::insert(&relayer, lane_id, stake);
+					Ok(lane_relayers.len() as u32)
+				},
+			)?;
+
+			log::trace!(
+				target: "runtime::bridge-relayers",
+				"Relayer {:?} has been registered at lane {:?}",
+				relayer,
+				lane_id,
+			);
+
+			Self::deposit_event(Event::<T>::RelayerRegistered(relayer, lane_id));
+			Ok(PostDispatchInfo {
+				actual_weight: Some(T::WeightInfo::register(lane_relayers_count)),
+				pays_fee: Pays::Yes,
+			})
+		}
+
+		/// Deregister relayer at given lane.
+		///
+		/// The stake, reserved at registration, is unreserved.
+		#[pallet::weight(T::WeightInfo::deregister(T::MaxRelayersPerLane::get()))]
+		pub fn deregister(origin: OriginFor<T>, lane_id: LaneId) -> DispatchResultWithPostInfo {
+			let relayer = ensure_signed(origin)?;
+			let stake =
+				RelayerStakes::<T>::take(&relayer, lane_id).ok_or(Error::<T>::NotRegistered)?;
+
+			let lane_relayers_count =
+				LaneRelayers::<T>::mutate_exists(lane_id, |maybe_lane_relayers| {
+					let lane_relayers = match maybe_lane_relayers {
+						Some(lane_relayers) => lane_relayers,
+						None => return 0,
+					};
+
+					let lane_relayers_count = lane_relayers.len() as u32;
+					lane_relayers.retain(|lane_relayer| *lane_relayer != relayer);
+					if lane_relayers.is_empty() {
+						*maybe_lane_relayers = None;
+					}
+					lane_relayers_count
+				});
+			T::StakeCurrency::unreserve(&relayer, stake);
+
+			log::trace!(
+				target: "runtime::bridge-relayers",
+				"Relayer {:?} has been deregistered at lane {:?}",
+				relayer,
+				lane_id,
+			);
+
+			Self::deposit_event(Event::<T>::RelayerDeregistered(relayer, lane_id));
+			Ok(PostDispatchInfo {
+				actual_weight: Some(T::WeightInfo::deregister(lane_relayers_count)),
+				pays_fee: Pays::Yes,
+			})
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Returns relayer that owns the delivery slot at given lane at the current block.
+		///
+		/// Returns `None` if there are no relayers registered at the lane.
+		pub fn slot_relayer(lane_id: LaneId) -> Option<T::AccountId> {
+			let block_number: u64 =
+				frame_system::Pallet::<T>::block_number().unique_saturated_into();
+			let slot_length: u64 = T::SlotLength::get().unique_saturated_into();
+			delivery_slot_owner(&LaneRelayers::<T>::get(lane_id), block_number, slot_length)
+		}
+
+		/// Register reward for given relayer at given lane of the bridge with given chain.
+		///
+		/// The reward is added to the rewards that the relayer has already accumulated at
+		/// this lane of this bridge.
+		pub fn register_relayer_reward(
+			bridged_chain_id: ChainId,
+			lane_id: LaneId,
+			relayer: &T::AccountId,
+			reward: T::Reward,
+		) {
+			if reward.is_zero() {
+				return
+			}
+
+			RelayerRewards::<T>::mutate(
+				relayer,
+				(bridged_chain_id, lane_id),
+				|old_reward: &mut Option<T::Reward>| {
+					let new_reward = old_reward.unwrap_or_else(Zero::zero).saturating_add(reward);
+					*old_reward = Some(new_reward);
+
+					log::trace!(
+						target: "runtime::bridge-relayers",
+						"Relayer {:?} can now claim reward at lane {:?}/{:?}: {:?}",
+						relayer,
+						bridged_chain_id,
+						lane_id,
+						new_reward,
+					);
+				},
+			);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", T::Reward = "Reward")]
+	pub enum Event<T: Config> {
+		/// Reward, accumulated at given lane of the bridge with given chain, has been paid to
+		/// the relayer.
+		RewardPaid(T::AccountId, ChainId, LaneId, T::Reward),
+		/// Relayer has been registered at given lane.
+		RelayerRegistered(T::AccountId, LaneId),
+		/// Relayer has been deregistered at given lane.
+		RelayerDeregistered(T::AccountId, LaneId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// No reward can be claimed by given relayer at given lane of given bridge.
+		NoRewardForRelayer,
+		/// Reward payment procedure has failed.
+		FailedToPayReward,
+		/// The relayer is already registered at given lane.
+		AlreadyRegistered,
+		/// The relayer is not registered at given lane.
+		NotRegistered,
+		/// There are too many relayers registered at given lane.
+		TooManyRelayersAtLane,
+		/// Failed to reserve relayer stake.
+		FailedToReserveStake,
+	}
+
+	/// Map of the relayer => accumulated reward at given (bridged chain, lane).
+	#[pallet::storage]
+	#[pallet::getter(fn relayer_reward)]
+	pub type RelayerRewards<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Identity,
+		(ChainId, LaneId),
+		T::Reward,
+		OptionQuery,
+	>;
+
+	/// Relayers, registered at given lane, in order of their registration.
+	#[pallet::storage]
+	#[pallet::getter(fn lane_relayers)]
+	pub type LaneRelayers<T: Config> =
+		StorageMap<_, Blake2_128Concat, LaneId, Vec<T::AccountId>, ValueQuery>;
+
+	/// Stakes, reserved by relayers registered at given lane.
+	#[pallet::storage]
+	pub type RelayerStakes<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Identity,
+		LaneId,
+		StakeOf<T>,
+		OptionQuery,
+	>;
+}
+
+/// Getting storage keys for relayers pallet storage items. These keys are normally used by
+/// relayers to read delivery slots schedule.
+pub mod storage_keys {
+	use bp_messages::LaneId;
+	use sp_core::storage::StorageKey;
+
+	/// Storage key of the lane relayers set in the runtime storage.
+	pub fn lane_relayers_key(pallet_prefix: &str, lane: &LaneId) -> StorageKey {
+		bp_runtime::storage_map_final_key_blake2_128concat(pallet_prefix, "LaneRelayers", lane)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use mock::{
+		run_test, Balances, Event as TestEvent, Origin, TestRuntime, FAILING_RELAYER,
+		MAX_RELAYERS_PER_LANE, OTHER_BRIDGED_CHAIN_ID, OTHER_LANE_ID, REGULAR_RELAYER,
+		RELAYER_INITIAL_BALANCE, REQUIRED_STAKE, SLOT_LENGTH, TEST_BRIDGED_CHAIN_ID, TEST_LANE_ID,
+	};
+
+	use frame_support::{assert_noop, assert_ok};
+	use frame_system::{EventRecord, Pallet as System, Phase};
+	use sp_runtime::DispatchError;
+
+	fn get_ready_for_events() {
+		System::<TestRuntime>::set_block_number(1);
+		System::<TestRuntime>::reset_events();
+	}
+
+	#[test]
+	fn rewards_are_accumulated() {
+		run_test(|| {
+			Pallet::<TestRuntime>::register_relayer_reward(
+				TEST_BRIDGED_CHAIN_ID,
+				TEST_LANE_ID,
+				&REGULAR_RELAYER,
+				10,
+			);
+			Pallet::<TestRuntime>::register_relayer_reward(
+				TEST_BRIDGED_CHAIN_ID,
+				TEST_LANE_ID,
+				&REGULAR_RELAYER,
+				20,
+			);
+			Pallet::<TestRuntime>::register_relayer_reward(
+				TEST_BRIDGED_CHAIN_ID,
+				OTHER_LANE_ID,
+				&REGULAR_RELAYER,
+				5,
+			);
+			Pallet::<TestRuntime>::register_relayer_reward(
+				OTHER_BRIDGED_CHAIN_ID,
+				TEST_LANE_ID,
+				&REGULAR_RELAYER,
+				7,
+			);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::relayer_reward(
+					REGULAR_RELAYER,
+					(TEST_BRIDGED_CHAIN_ID, TEST_LANE_ID)
+				),
+				Some(30)
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::relayer_reward(
+					REGULAR_RELAYER,
+					(TEST_BRIDGED_CHAIN_ID, OTHER_LANE_ID)
+				),
+				Some(5)
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::relayer_reward(
+					REGULAR_RELAYER,
+					(OTHER_BRIDGED_CHAIN_ID, TEST_LANE_ID)
+				),
+				Some(7)
+			);
+		});
+	}
+
+	#[test]
+	fn zero_reward_is_not_registered() {
+		run_test(|| {
+			Pallet::<TestRuntime>::register_relayer_reward(
+				TEST_BRIDGED_CHAIN_ID,
+				TEST_LANE_ID,
+				&REGULAR_RELAYER,
+				0,
+			);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::relayer_reward(
+					REGULAR_RELAYER,
+					(TEST_BRIDGED_CHAIN_ID, TEST_LANE_ID)
+				),
+				None
+			);
+		});
+	}
+
+	#[test]
+	fn root_cant_claim_anything() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::claim_rewards(
+					Origin::root(),
+					TEST_BRIDGED_CHAIN_ID,
+					TEST_LANE_ID
+				),
+				DispatchError::BadOrigin,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_cant_claim_if_no_reward_exists() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::claim_rewards(
+					Origin::signed(REGULAR_RELAYER),
+					TEST_BRIDGED_CHAIN_ID,
+					TEST_LANE_ID
+				),
+				Error::<TestRuntime>::NoRewardForRelayer,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_cant_claim_if_payment_procedure_fails() {
+		run_test(|| {
+			RelayerRewards::<TestRuntime>::insert(
+				FAILING_RELAYER,
+				(TEST_BRIDGED_CHAIN_ID, TEST_LANE_ID),
+				100,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::claim_rewards(
+					Origin::signed(FAILING_RELAYER),
+					TEST_BRIDGED_CHAIN_ID,
+					TEST_LANE_ID
+				),
+				Error::<TestRuntime>::FailedToPayReward,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_can_claim_reward() {
+		run_test(|| {
+			get_ready_for_events();
+
+			RelayerRewards::<TestRuntime>::insert(
+				REGULAR_RELAYER,
+				(TEST_BRIDGED_CHAIN_ID, TEST_LANE_ID),
+				100,
+			);
+			RelayerRewards::<TestRuntime>::insert(
+				REGULAR_RELAYER,
+				(TEST_BRIDGED_CHAIN_ID, OTHER_LANE_ID),
+				50,
+			);
+			RelayerRewards::<TestRuntime>::insert(
+				REGULAR_RELAYER,
+				(OTHER_BRIDGED_CHAIN_ID, TEST_LANE_ID),
+				25,
+			);
+			assert_ok!(Pallet::<TestRuntime>::claim_rewards(
+				Origin::signed(REGULAR_RELAYER),
+				TEST_BRIDGED_CHAIN_ID,
+				TEST_LANE_ID
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::relayer_reward(
+					REGULAR_RELAYER,
+					(TEST_BRIDGED_CHAIN_ID, TEST_LANE_ID)
+				),
+				None
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::relayer_reward(
+					REGULAR_RELAYER,
+					(TEST_BRIDGED_CHAIN_ID, OTHER_LANE_ID)
+				),
+				Some(50)
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::relayer_reward(
+					REGULAR_RELAYER,
+					(OTHER_BRIDGED_CHAIN_ID, TEST_LANE_ID)
+				),
+				Some(25)
+			);
+			assert_eq!(Balances::free_balance(REGULAR_RELAYER), RELAYER_INITIAL_BALANCE + 100);
+
+			assert_eq!(
+				System::<TestRuntime>::events().last(),
+				Some(&EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Relayers(Event::RewardPaid(
+						REGULAR_RELAYER,
+						TEST_BRIDGED_CHAIN_ID,
+						TEST_LANE_ID,
+						100
+					)),
+					topics: vec![],
+				}),
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_can_register() {
+		run_test(|| {
+			get_ready_for_events();
+
+			assert_ok!(Pallet::<TestRuntime>::register(
+				Origin::signed(REGULAR_RELAYER),
+				TEST_LANE_ID
+			));
+			assert_eq!(Pallet::<TestRuntime>::lane_relayers(TEST_LANE_ID), vec![REGULAR_RELAYER]);
+			assert_eq!(
+				RelayerStakes::<TestRuntime>::get(REGULAR_RELAYER, TEST_LANE_ID),
+				Some(REQUIRED_STAKE)
+			);
+			assert_eq!(Balances::reserved_balance(REGULAR_RELAYER), REQUIRED_STAKE);
+
+			assert_eq!(
+				System::<TestRuntime>::events().last(),
+				Some(&EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Relayers(Event::RelayerRegistered(
+						REGULAR_RELAYER,
+						TEST_LANE_ID
+					)),
+					topics: vec![],
+				}),
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_cant_register_twice() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::register(
+				Origin::signed(REGULAR_RELAYER),
+				TEST_LANE_ID
+			));
+			assert_noop!(
+				Pallet::<TestRuntime>::register(Origin::signed(REGULAR_RELAYER), TEST_LANE_ID),
+				Error::<TestRuntime>::AlreadyRegistered,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_cant_register_if_there_are_too_many_relayers_at_lane() {
+		run_test(|| {
+			LaneRelayers::<TestRuntime>::insert(
+				TEST_LANE_ID,
+				(0..MAX_RELAYERS_PER_LANE as u64).map(|i| 1_000 + i).collect::<Vec<_>>(),
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::register(Origin::signed(REGULAR_RELAYER), TEST_LANE_ID),
+				Error::<TestRuntime>::TooManyRelayersAtLane,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_cant_register_if_it_cant_reserve_stake() {
+		run_test(|| {
+			let poor_relayer = 1_000;
+			assert_noop!(
+				Pallet::<TestRuntime>::register(Origin::signed(poor_relayer), TEST_LANE_ID),
+				Error::<TestRuntime>::FailedToReserveStake,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_cant_deregister_if_not_registered() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::deregister(Origin::signed(REGULAR_RELAYER), TEST_LANE_ID),
+				Error::<TestRuntime>::NotRegistered,
+			);
+		});
+	}
+
+	#[test]
+	fn relayer_can_deregister() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::register(
+				Origin::signed(REGULAR_RELAYER),
+				TEST_LANE_ID
+			));
+			assert_ok!(Pallet::<TestRuntime>::register(
+				Origin::signed(FAILING_RELAYER),
+				TEST_LANE_ID
+			));
+
+			get_ready_for_events();
+
+			assert_ok!(Pallet::<TestRuntime>::deregister(
+				Origin::signed(REGULAR_RELAYER),
+				TEST_LANE_ID
+			));
+			assert_eq!(Pallet::<TestRuntime>::lane_relayers(TEST_LANE_ID), vec![FAILING_RELAYER]);
+			assert_eq!(RelayerStakes::<TestRuntime>::get(REGULAR_RELAYER, TEST_LANE_ID), None);
+			assert_eq!(Balances::reserved_balance(REGULAR_RELAYER), 0);
+			assert_eq!(Balances::free_balance(REGULAR_RELAYER), RELAYER_INITIAL_BALANCE);
+
+			assert_eq!(
+				System::<TestRuntime>::events().last(),
+				Some(&EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Relayers(Event::RelayerDeregistered(
+						REGULAR_RELAYER,
+						TEST_LANE_ID
+					)),
+					topics: vec![],
+				}),
+			);
+		});
+	}
+
+	#[test]
+	fn registration_weight_depends_on_lane_relayers_count() {
+		run_test(|| {
+			let post_info =
+				Pallet::<TestRuntime>::register(Origin::signed(REGULAR_RELAYER), TEST_LANE_ID)
+					.unwrap();
+			assert_eq!(post_info.actual_weight, Some(<() as WeightInfo>::register(1)));
+
+			let post_info =
+				Pallet::<TestRuntime>::register(Origin::signed(FAILING_RELAYER), TEST_LANE_ID)
+					.unwrap();
+			assert_eq!(post_info.actual_weight, Some(<() as WeightInfo>::register(2)));
+
+			let post_info =
+				Pallet::<TestRuntime>::deregister(Origin::signed(REGULAR_RELAYER), TEST_LANE_ID)
+					.unwrap();
+			assert_eq!(post_info.actual_weight, Some(<() as WeightInfo>::deregister(2)));
+		});
+	}
+
+	#[test]
+	fn delivery_slots_are_assigned_in_round_robin_fashion() {
+		let relayers = vec![1, 2, 3];
+		assert_eq!(delivery_slot_owner(&relayers, 0, SLOT_LENGTH), Some(1));
+		assert_eq!(delivery_slot_owner(&relayers, SLOT_LENGTH - 1, SLOT_LENGTH), Some(1));
+		assert_eq!(delivery_slot_owner(&relayers, SLOT_LENGTH, SLOT_LENGTH), Some(2));
+		assert_eq!(delivery_slot_owner(&relayers, 2 * SLOT_LENGTH, SLOT_LENGTH), Some(3));
+		assert_eq!(delivery_slot_owner(&relayers, 3 * SLOT_LENGTH, SLOT_LENGTH), Some(1));
+	}
+
+	#[test]
+	fn there_are_no_slot_owners_if_no_relayers_are_registered() {
+		assert_eq!(delivery_slot_owner::<u64>(&[], 0, SLOT_LENGTH), None);
+		assert_eq!(delivery_slot_owner(&[1], 0, 0), None);
+	}
+}
@@ -0,0 +1,244 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate as pallet_bridge_relayers;
+
+use bp_messages::{
+	source_chain::ForbidOutboundMessages, target_chain::ForbidInboundMessages, LaneId, MessageNonce,
+};
+use bp_runtime::ChainId;
+use frame_support::{
+	parameter_types,
+	weights::{RuntimeDbWeight, Weight},
+};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header as SubstrateHeader,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+pub type AccountId = u64;
+pub type Balance = u64;
+
+type Block = frame_system::mocking::MockBlock<TestRuntime>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+frame_support::construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Event<T>},
+		Relayers: pallet_bridge_relayers::{Pallet, Call, Event<T>},
+		Messages: pallet_bridge_messages::{Pallet, Call, Event<T>},
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const DbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 1, write: 2 };
+	pub const ExistentialDeposit: Balance = 1;
+	pub const RelayersFundAccount: AccountId = RELAYERS_FUND_ACCOUNT;
+	pub const RequiredStake: Balance = REQUIRED_STAKE;
+	pub const SlotLength: u64 = SLOT_LENGTH;
+	pub const MaxRelayersPerLane: u32 = MAX_RELAYERS_PER_LANE;
+	pub const BridgedChainId: ChainId = TEST_BRIDGED_CHAIN_ID;
+	pub const ConfirmationFee: Balance = CONFIRMATION_FEE;
+	pub const RootAccount: Option<AccountId> = None;
+	pub const MaxMessagesToPruneAtOnce: MessageNonce = 8;
+	pub const MaxDispatchStatusesToKeep: MessageNonce = 16;
+	pub const MaxUnrewardedRelayerEntriesAtInboundLane: MessageNonce = 16;
+	pub const MaxUnconfirmedMessagesAtInboundLane: MessageNonce = 16;
+	pub const MaxInlineDispatchWeight: Weight = Weight::MAX;
+	pub const MaxAllowedSenders: u32 = 1;
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = SubstrateHeader;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = DbWeight;
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+impl pallet_balances::Config for TestRuntime {
+	type MaxLocks = ();
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = frame_system::Pallet<TestRuntime>;
+	type WeightInfo = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+}
+
+impl pallet_bridge_relayers::Config for TestRuntime {
+	type Event = Event;
+	type Reward = Balance;
+	type PaymentProcedure = TestPaymentProcedure;
+	type StakeCurrency = Balances;
+	type RequiredStake = RequiredStake;
+	type SlotLength = SlotLength;
+	type MaxRelayersPerLane = MaxRelayersPerLane;
+	type WeightInfo = ();
+}
+
+impl pallet_bridge_messages::Config for TestRuntime {
+	type Event = Event;
+	type WeightInfo = ();
+	type BridgedChainId = BridgedChainId;
+	type Parameter = ();
+	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxDispatchStatusesToKeep = MaxDispatchStatusesToKeep;
+	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
+	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxInlineDispatchWeight = MaxInlineDispatchWeight;
+	type MaxAllowedSenders = MaxAllowedSenders;
+
+	type OutboundPayload = ();
+	type OutboundMessageFee = Balance;
+
+	type InboundPayload = ();
+	type InboundMessageFee = Balance;
+	type InboundRelayer = AccountId;
+
+	type AccountIdConverter = AccountIdConverter;
+
+	type TargetHeaderChain = ForbidOutboundMessages;
+	type LaneMessageVerifier = ForbidOutboundMessages;
+	type MessageDeliveryAndDispatchPayment =
+		pallet_bridge_relayers::MessageDeliveryAndDispatchPaymentAdapter<
+			TestRuntime,
+			(),
+			Balances,
+			ConfirmationFee,
+			RootAccount,
+		>;
+	type OnMessageAccepted = ();
+	type OnDeliveryConfirmed = ();
+
+	type SourceHeaderChain = ForbidInboundMessages;
+	type MessageDispatch = ForbidInboundMessages;
+}
+
+/// Converts hash into test account id.
+pub struct AccountIdConverter;
+
+impl sp_runtime::traits::Convert<H256, AccountId> for AccountIdConverter {
+	fn convert(hash: H256) -> AccountId {
+		hash.to_low_u64_ne()
+	}
+}
+
+/// Bridged chain that we're using in tests.
+pub const TEST_BRIDGED_CHAIN_ID: ChainId = *b"test";
+
+/// Another bridged chain that we're using in tests.
+pub const OTHER_BRIDGED_CHAIN_ID: ChainId = *b"othr";
+
+/// Lane that we're using in tests.
+pub const TEST_LANE_ID: LaneId = [0, 0, 0, 1];
+
+/// Another lane that we're using in tests.
+pub const OTHER_LANE_ID: LaneId = [0, 0, 0, 2];
+
+/// Account that holds relayers rewards.
+pub const RELAYERS_FUND_ACCOUNT: AccountId = 100;
+
+/// Regular relayer that may receive rewards.
+pub const REGULAR_RELAYER: AccountId = 1;
+
+/// Relayer that can't receive rewards.
+pub const FAILING_RELAYER: AccountId = 2;
+
+/// Stake that the relayer needs to bond to register at the lane.
+pub const REQUIRED_STAKE: Balance = 100;
+
+/// Length of the delivery slot in tests.
+pub const SLOT_LENGTH: u64 = 4;
+
+/// Maximal number of relayers that may be registered at the single lane.
+pub const MAX_RELAYERS_PER_LANE: u32 = 2;
+
+/// Initial balance of relayer accounts.
+pub const RELAYER_INITIAL_BALANCE: Balance = 1_000;
+
+/// Initial balance of relayers fund account.
+pub const RELAYERS_FUND_INITIAL_BALANCE: Balance = 1_000_000;
+
+/// Fee that is paid to the delivery confirmation relayer for every confirmed message.
+pub const CONFIRMATION_FEE: Balance = 10;
+
+/// Payment procedure that rejects payments to the `FAILING_RELAYER`.
+pub struct TestPaymentProcedure;
+
+impl pallet_bridge_relayers::PaymentProcedure<AccountId, Balance> for TestPaymentProcedure {
+	type Error = sp_runtime::DispatchError;
+
+	fn pay_reward(
+		relayer: &AccountId,
+		bridged_chain_id: ChainId,
+		lane_id: LaneId,
+		reward: Balance,
+	) -> Result<(), Self::Error> {
+		if *relayer == FAILING_RELAYER {
+			return Err("Relayer can't receive rewards".into())
+		}
+
+		pallet_bridge_relayers::PayLaneRewardFromAccount::<Balances, RelayersFundAccount>::pay_reward(
+			relayer,
+			bridged_chain_id,
+			lane_id,
+			reward,
+		)
+	}
+}
+
+/// Run pallet test.
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<TestRuntime>().unwrap();
+	pallet_balances::GenesisConfig::<TestRuntime> {
+		balances: vec![
+			(RELAYERS_FUND_ACCOUNT, RELAYERS_FUND_INITIAL_BALANCE),
+			(REGULAR_RELAYER, RELAYER_INITIAL_BALANCE),
+			(FAILING_RELAYER, RELAYER_INITIAL_BALANCE),
+		],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	sp_io::TestExternalities::new(t).execute_with(test)
+}
@@ -0,0 +1,262 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Code that allows relayers pallet to be used as a delivery+dispatch payment mechanism
+//! for the messages pallet.
+
+use crate::{Config, Pallet};
+
+use bp_messages::{
+	source_chain::{MessageDeliveryAndDispatchPayment, Sender, UnspentDispatchFeeRefund},
+	LaneId, MessageNonce, UnrewardedRelayer,
+};
+use frame_support::traits::{Currency as CurrencyT, Get};
+use pallet_bridge_messages::instant_payments::{
+	cal_relayers_rewards_and_refunds, distribute_relayers_rewards,
+	pay_unspent_dispatch_fee_refunds, InstantCurrencyPayments,
+};
+use sp_std::{collections::vec_deque::VecDeque, marker::PhantomData, ops::RangeInclusive};
+
+/// Adapter that allows relayers pallet to be used as a delivery+dispatch payment mechanism
+/// for the messages pallet.
+///
+/// Message fees are paid to (and refunded from) the `relayers-fund` account, exactly like in
+/// the `InstantCurrencyPayments`. But when message delivery is confirmed, relayer rewards are
+/// not transferred to relayers. Instead, they're registered in the relayers pallet (under the
+/// `BridgedChainId` of the messages pallet instance) and may be claimed later, using
+/// `claim_rewards` call. Unspent dispatch fee refunds are still paid to message senders
+/// immediately.
+pub struct MessageDeliveryAndDispatchPaymentAdapter<
+	T,
+	MessagesInstance,
+	Currency,
+	GetConfirmationFee,
+	RootAccount,
+	UnspentFeeRefund = (),
+>(PhantomData<(T, MessagesInstance, Currency, GetConfirmationFee, RootAccount, UnspentFeeRefund)>);
+
+impl<T, MessagesInstance, Currency, GetConfirmationFee, RootAccount, UnspentFeeRefund>
+	MessageDeliveryAndDispatchPayment<T::AccountId, T::Reward>
+	for MessageDeliveryAndDispatchPaymentAdapter<
+		T,
+		MessagesInstance,
+		Currency,
+		GetConfirmationFee,
+		RootAccount,
+		UnspentFeeRefund,
+	>
+where
+	T: Config + pallet_bridge_messages::Config<MessagesInstance, OutboundMessageFee = T::Reward>,
+	MessagesInstance: 'static,
+	Currency: CurrencyT<T::AccountId, Balance = T::Reward>,
+	T::Reward: From<MessageNonce>,
+	GetConfirmationFee: Get<T::Reward>,
+	RootAccount: Get<Option<T::AccountId>>,
+	UnspentFeeRefund: UnspentDispatchFeeRefund<T::OutboundPayload, T::Reward>,
+{
+	type Error = &'static str;
+
+	fn pay_delivery_and_dispatch_fee(
+		submitter: &Sender<T::AccountId>,
+		fee: &T::Reward,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		InstantCurrencyPayments::<
+			T,
+			MessagesInstance,
+			Currency,
+			GetConfirmationFee,
+			RootAccount,
+			UnspentFeeRefund,
+		>::pay_delivery_and_dispatch_fee(submitter, fee, relayer_fund_account)
+	}
+
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &Sender<T::AccountId>,
+		fee: &T::Reward,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		InstantCurrencyPayments::<
+			T,
+			MessagesInstance,
+			Currency,
+			GetConfirmationFee,
+			RootAccount,
+			UnspentFeeRefund,
+		>::refund_delivery_and_dispatch_fee(submitter, fee, relayer_fund_account)
+	}
+
+	fn pay_relayers_rewards(
+		lane_id: LaneId,
+		messages_relayers: VecDeque<UnrewardedRelayer<T::AccountId>>,
+		confirmation_relayer: &T::AccountId,
+		received_range: &RangeInclusive<MessageNonce>,
+		relayer_fund_account: &T::AccountId,
+	) {
+		let (relayers_rewards, refunds) = cal_relayers_rewards_and_refunds::<
+			T,
+			MessagesInstance,
+			UnspentFeeRefund,
+		>(lane_id, messages_relayers, received_range);
+		if !refunds.is_empty() {
+			let root_account = RootAccount::get();
+			pay_unspent_dispatch_fee_refunds::<Currency, _>(
+				refunds,
+				root_account.as_ref(),
+				relayer_fund_account,
+			);
+		}
+		if !relayers_rewards.is_empty() {
+			let bridged_chain_id =
+				<T as pallet_bridge_messages::Config<MessagesInstance>>::BridgedChainId::get();
+			distribute_relayers_rewards(
+				confirmation_relayer,
+				relayers_rewards,
+				GetConfirmationFee::get(),
+				|relayer, reward| {
+					Pallet::<T>::register_relayer_reward(bridged_chain_id, lane_id, relayer, reward)
+				},
+			);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		run_test, AccountId, TestRuntime, CONFIRMATION_FEE, OTHER_BRIDGED_CHAIN_ID,
+		REGULAR_RELAYER, RELAYERS_FUND_ACCOUNT, TEST_BRIDGED_CHAIN_ID, TEST_LANE_ID,
+	};
+
+	use bp_messages::{DeliveredMessages, MessageData, MessageKey};
+	use codec::Encode;
+	use pallet_bridge_messages::OutboundMessages;
+
+	const OTHER_RELAYER: AccountId = 3;
+	const CONFIRMATION_RELAYER: AccountId = 4;
+
+	type TestPaymentAdapter =
+		<TestRuntime as pallet_bridge_messages::Config>::MessageDeliveryAndDispatchPayment;
+
+	fn send_messages(nonces: RangeInclusive<MessageNonce>, fee: u64) {
+		for nonce in nonces {
+			OutboundMessages::<TestRuntime>::insert(
+				MessageKey { lane_id: TEST_LANE_ID, nonce },
+				MessageData { payload: ().encode(), fee },
+			);
+		}
+	}
+
+	fn unrewarded_relayer(
+		begin: MessageNonce,
+		end: MessageNonce,
+		relayer: AccountId,
+	) -> UnrewardedRelayer<AccountId> {
+		let mut messages = DeliveredMessages::new(begin, true, 0);
+		for _ in begin + 1..=end {
+			messages.note_dispatched_message(true, 0);
+		}
+		UnrewardedRelayer { relayer, messages }
+	}
+
+	fn registered_reward(relayer: AccountId) -> Option<u64> {
+		crate::Pallet::<TestRuntime>::relayer_reward(relayer, (TEST_BRIDGED_CHAIN_ID, TEST_LANE_ID))
+	}
+
+	#[test]
+	fn pay_relayers_rewards_registers_rewards_instead_of_paying_them() {
+		run_test(|| {
+			send_messages(1..=3, 100);
+
+			TestPaymentAdapter::pay_relayers_rewards(
+				TEST_LANE_ID,
+				vec![
+					unrewarded_relayer(1, 2, REGULAR_RELAYER),
+					unrewarded_relayer(3, 3, OTHER_RELAYER),
+				]
+				.into_iter()
+				.collect(),
+				&CONFIRMATION_RELAYER,
+				&(1..=3),
+				&RELAYERS_FUND_ACCOUNT,
+			);
+
+			// every relayer pays confirmation fee for every delivered message to the
+			// confirmation relayer
+			assert_eq!(registered_reward(REGULAR_RELAYER), Some(200 - 2 * CONFIRMATION_FEE));
+			assert_eq!(registered_reward(OTHER_RELAYER), Some(100 - CONFIRMATION_FEE));
+			assert_eq!(registered_reward(CONFIRMATION_RELAYER), Some(3 * CONFIRMATION_FEE));
+
+			// rewards are registered under the chain id of the messages pallet instance
+			assert_eq!(
+				crate::Pallet::<TestRuntime>::relayer_reward(
+					REGULAR_RELAYER,
+					(OTHER_BRIDGED_CHAIN_ID, TEST_LANE_ID),
+				),
+				None,
+			);
+
+			// and nothing is transferred from the relayers fund account
+			assert_eq!(
+				pallet_balances::Pallet::<TestRuntime>::free_balance(RELAYERS_FUND_ACCOUNT),
+				crate::mock::RELAYERS_FUND_INITIAL_BALANCE,
+			);
+		});
+	}
+
+	#[test]
+	fn pay_relayers_rewards_rewards_only_messages_from_received_range() {
+		run_test(|| {
+			send_messages(1..=3, 100);
+
+			TestPaymentAdapter::pay_relayers_rewards(
+				TEST_LANE_ID,
+				vec![unrewarded_relayer(1, 3, REGULAR_RELAYER)].into_iter().collect(),
+				&REGULAR_RELAYER,
+				&(2..=3),
+				&RELAYERS_FUND_ACCOUNT,
+			);
+
+			// confirmation fee is paid to the same relayer, so it gets the full reward
+			assert_eq!(registered_reward(REGULAR_RELAYER), Some(200));
+		});
+	}
+
+	#[test]
+	fn pay_relayers_rewards_accumulates_rewards() {
+		run_test(|| {
+			send_messages(1..=2, 100);
+
+			TestPaymentAdapter::pay_relayers_rewards(
+				TEST_LANE_ID,
+				vec![unrewarded_relayer(1, 1, REGULAR_RELAYER)].into_iter().collect(),
+				&REGULAR_RELAYER,
+				&(1..=1),
+				&RELAYERS_FUND_ACCOUNT,
+			);
+			TestPaymentAdapter::pay_relayers_rewards(
+				TEST_LANE_ID,
+				vec![unrewarded_relayer(2, 2, REGULAR_RELAYER)].into_iter().collect(),
+				&REGULAR_RELAYER,
+				&(2..=2),
+				&RELAYERS_FUND_ACCOUNT,
+			);
+
+			assert_eq!(registered_reward(REGULAR_RELAYER), Some(200));
+		});
+	}
+}
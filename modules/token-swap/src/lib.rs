@@ -191,7 +191,9 @@ pub mod pallet {
 		///   chain;
 		/// - `bridged_currency_transfer_signature` - the signature of the
 		///   `swap.target_account_at_bridged_chain` for the message returned by the
-		///   `pallet_bridge_dispatch::account_ownership_digest()` function call.
+		///   `pallet_bridge_dispatch::account_ownership_digest()` function call. The digest must
+		///   include the current nonce of the `swap.target_account_at_bridged_chain` account at the
+		///   Bridged chain dispatch pallet, otherwise the transfer message will be rejected.
 		///
 		/// The `source_account_at_this_chain` MUST have enough balance to cover both token swap and
 		/// message transfer. Message fee may be estimated using corresponding `OutboundLaneApi` of
@@ -305,6 +307,7 @@ pub mod pallet {
 							bridged_currency_transfer_signature,
 						),
 						dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+						expiry: None,
 						call: bridged_currency_transfer,
 					},
 					swap_delivery_and_dispatch_fee,
@@ -672,7 +675,7 @@ mod tests {
 	fn receive_test_swap_confirmation(success: bool) {
 		Pallet::<TestRuntime, ()>::on_messages_delivered(
 			&OutboundMessageLaneId::get(),
-			&DeliveredMessages::new(MESSAGE_NONCE, success),
+			&DeliveredMessages::new(MESSAGE_NONCE, success, 0),
 		);
 	}
 
@@ -1112,8 +1115,8 @@ mod tests {
 			);
 
 			// when unrelated messages are delivered
-			let mut messages = DeliveredMessages::new(MESSAGE_NONCE - 2, true);
-			messages.note_dispatched_message(false);
+			let mut messages = DeliveredMessages::new(MESSAGE_NONCE - 2, true, 0);
+			messages.note_dispatched_message(false, 0);
 			Pallet::<TestRuntime, ()>::on_messages_delivered(
 				&OutboundMessageLaneId::get(),
 				&messages,
@@ -1128,9 +1131,9 @@ mod tests {
 			);
 
 			// when message we're interested in is accompanied by a bunch of other messages
-			let mut messages = DeliveredMessages::new(MESSAGE_NONCE - 1, false);
-			messages.note_dispatched_message(true);
-			messages.note_dispatched_message(false);
+			let mut messages = DeliveredMessages::new(MESSAGE_NONCE - 1, false, 0);
+			messages.note_dispatched_message(true, 0);
+			messages.note_dispatched_message(false, 0);
 			Pallet::<TestRuntime, ()>::on_messages_delivered(
 				&OutboundMessageLaneId::get(),
 				&messages,
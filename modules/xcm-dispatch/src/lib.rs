@@ -0,0 +1,801 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module which dispatches messages received over the bridge as XCM programs.
+//!
+//! Unlike `pallet-bridge-dispatch`, this module doesn't require the source chain to know
+//! the exact encoding of the target chain `Call`. The message carries a versioned XCM
+//! program, which is converted to the latest XCM version supported by this chain and
+//! executed by the configured `XcmExecutor`. The program is executed on behalf of the
+//! account, derived from the message sender at the source chain. In the case of a
+//! successful dispatch an event is emitted.
+//!
+//! The `FromBridgedChainXcmMessageDispatch` adapter may be used to dispatch messages, delivered
+//! by the messages pallet, using this module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+// Generated by `decl_event!`
+#![allow(clippy::unused_unit)]
+
+use bp_message_dispatch::MessageDispatch;
+use bp_messages::{
+	target_chain::{DispatchMessage, MessageDispatch as InboundMessageDispatch},
+	LaneId, MessageNonce,
+};
+use bp_runtime::{
+	derive_account_id,
+	messages::{DispatchFeePayment, MessageDispatchResult},
+	ChainId, SourceAccount,
+};
+use codec::{Decode, Encode};
+use frame_support::{traits::Get, RuntimeDebug};
+use sp_runtime::traits::{Convert, MaybeDisplay};
+use sp_std::{convert::TryFrom, fmt::Debug, marker::PhantomData, prelude::*};
+use xcm::{
+	latest::{MultiLocation, Outcome, Xcm},
+	VersionedXcm,
+};
+use xcm_executor::traits::{ExecuteXcm, WeightBounds};
+
+pub use pallet::*;
+
+/// Origin of the XCM program when it is executed on the target chain.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq)]
+pub enum XcmMessageOrigin<SourceChainAccountId> {
+	/// Message is sent by the Root origin on the source chain. On the target chain the
+	/// program is executed on behalf of the account, derived from the source Root account.
+	SourceRoot,
+	/// Message is sent by the `SourceChainAccountId` on the source chain. On the target chain
+	/// the program is executed on behalf of the account, derived from the source account ID.
+	SourceAccount(SourceChainAccountId),
+}
+
+/// Message payload type used by XCM dispatch module.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct XcmMessagePayload<SourceChainAccountId, Call> {
+	/// Origin of the message at the source chain.
+	pub origin: XcmMessageOrigin<SourceChainAccountId>,
+	/// Maximal weight of the XCM program execution at the target chain.
+	pub weight: bp_message_dispatch::Weight,
+	/// Where the message dispatch fee is paid?
+	pub dispatch_fee_payment: DispatchFeePayment,
+	/// The XCM program that is executed at the target chain.
+	pub xcm: VersionedXcm<Call>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+		/// Id of the message. Whenever message is passed to the dispatch module, it emits
+		/// event with this id + dispatch result. Could be e.g. (LaneId, MessageNonce) if
+		/// it comes from the messages module.
+		type BridgeMessageId: Parameter;
+		/// Type of account ID on source chain.
+		type SourceChainAccountId: Parameter
+			+ Member
+			+ MaybeSerializeDeserialize
+			+ Debug
+			+ MaybeDisplay
+			+ Ord
+			+ Default;
+		/// The `Call` type, that may be executed by the `Transact` XCM instruction.
+		type Call: Encode + Decode;
+		/// The XCM executor that executes programs, received over the bridge.
+		type XcmExecutor: ExecuteXcm<<Self as Config<I>>::Call>;
+		/// The type that computes weight of the XCM program.
+		type Weigher: WeightBounds<<Self as Config<I>>::Call>;
+		/// A type which can be turned into an AccountId from a 256-bit hash.
+		///
+		/// Used when deriving target chain AccountIds from source chain AccountIds.
+		type AccountIdConverter: Convert<sp_core::hash::H256, Self::AccountId>;
+		/// Converter of the derived target chain AccountId into the origin location of the
+		/// XCM program.
+		type AccountIdToMultiLocation: Convert<Self::AccountId, MultiLocation>;
+	}
+
+	type BridgeMessageIdOf<T, I> = <T as Config<I>>::BridgeMessageId;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(<T as frame_system::Config>::AccountId = "AccountId", BridgeMessageIdOf<T, I> = "BridgeMessageId")]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Message has been rejected before reaching dispatch.
+		MessageRejected(ChainId, BridgeMessageIdOf<T, I>),
+		/// Message has been rejected by dispatcher because its XCM version is not supported.
+		MessageXcmVersionUnsupported(ChainId, BridgeMessageIdOf<T, I>),
+		/// Message has been rejected by dispatcher because we have failed to compute weight
+		/// of its XCM program.
+		MessageXcmWeightUnknown(ChainId, BridgeMessageIdOf<T, I>),
+		/// Message has been rejected by dispatcher because of weight mismatch.
+		/// Last two arguments are: expected and passed XCM program weight.
+		MessageWeightMismatch(ChainId, BridgeMessageIdOf<T, I>, Weight, Weight),
+		/// The origin account has failed to pay fee for dispatching the message.
+		MessageDispatchPaymentFailed(
+			ChainId,
+			BridgeMessageIdOf<T, I>,
+			<T as frame_system::Config>::AccountId,
+			Weight,
+		),
+		/// XCM program of the message has been executed with given outcome.
+		MessageDispatched(ChainId, BridgeMessageIdOf<T, I>, Outcome),
+		/// Phantom member, never used. Needed to handle multiple pallet instances.
+		_Dummy(PhantomData<I>),
+	}
+}
+
+impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId> for Pallet<T, I> {
+	type Message = XcmMessagePayload<T::SourceChainAccountId, <T as Config<I>>::Call>;
+
+	fn dispatch_weight(message: &Self::Message) -> bp_message_dispatch::Weight {
+		message.weight
+	}
+
+	fn dispatch<P: FnOnce(&T::AccountId, bp_message_dispatch::Weight) -> Result<(), ()>>(
+		source_chain: ChainId,
+		_target_chain: ChainId,
+		id: T::BridgeMessageId,
+		message: Result<Self::Message, ()>,
+		pay_dispatch_fee: P,
+	) -> MessageDispatchResult {
+		// emit special even if message has been rejected by external component
+		let message = match message {
+			Ok(message) => message,
+			Err(_) => {
+				log::trace!(
+					target: "runtime::bridge-xcm-dispatch",
+					"Message {:?}/{:?}: rejected before actual dispatch",
+					source_chain,
+					id,
+				);
+				Self::deposit_event(Event::MessageRejected(source_chain, id));
+				return MessageDispatchResult {
+					dispatch_result: false,
+					unspent_weight: 0,
+					dispatch_fee_paid_during_dispatch: false,
+				}
+			},
+		};
+
+		// convert XCM program to the latest version
+		let mut dispatch_result = MessageDispatchResult {
+			dispatch_result: false,
+			unspent_weight: message.weight,
+			dispatch_fee_paid_during_dispatch: false,
+		};
+		let mut xcm = match Xcm::<<T as Config<I>>::Call>::try_from(message.xcm) {
+			Ok(xcm) => xcm,
+			Err(_) => {
+				log::trace!(
+					target: "runtime::bridge-xcm-dispatch",
+					"Message {:?}/{:?}: unsupported XCM version",
+					source_chain,
+					id,
+				);
+				Self::deposit_event(Event::MessageXcmVersionUnsupported(source_chain, id));
+				return dispatch_result
+			},
+		};
+
+		// verify weight
+		// (we want passed weight to be at least equal to the weight of the XCM program
+		// because otherwise programs may be executed at lower price)
+		let expected_weight = match T::Weigher::weight(&mut xcm) {
+			Ok(expected_weight) => expected_weight,
+			Err(_) => {
+				log::trace!(
+					target: "runtime::bridge-xcm-dispatch",
+					"Message {:?}/{:?}: failed to compute weight of XCM program",
+					source_chain,
+					id,
+				);
+				Self::deposit_event(Event::MessageXcmWeightUnknown(source_chain, id));
+				return dispatch_result
+			},
+		};
+		if message.weight < expected_weight {
+			log::trace!(
+				target: "runtime::bridge-xcm-dispatch",
+				"Message {:?}/{:?}: passed weight is too low. Expected at least {:?}, got {:?}",
+				source_chain,
+				id,
+				expected_weight,
+				message.weight,
+			);
+			Self::deposit_event(Event::MessageWeightMismatch(
+				source_chain,
+				id,
+				expected_weight,
+				message.weight,
+			));
+			return dispatch_result
+		}
+
+		// prepare XCM program origin
+		let source_account = match message.origin {
+			XcmMessageOrigin::SourceRoot => SourceAccount::Root,
+			XcmMessageOrigin::SourceAccount(source_account_id) =>
+				SourceAccount::Account(source_account_id),
+		};
+		let origin_account =
+			T::AccountIdConverter::convert(derive_account_id(source_chain, source_account));
+
+		// pay dispatch fee right before dispatch
+		let pay_dispatch_fee_at_target_chain =
+			message.dispatch_fee_payment == DispatchFeePayment::AtTargetChain;
+		if pay_dispatch_fee_at_target_chain &&
+			pay_dispatch_fee(&origin_account, message.weight).is_err()
+		{
+			log::trace!(
+				target: "runtime::bridge-xcm-dispatch",
+				"Failed to pay dispatch fee for dispatching message {:?}/{:?} with weight {}",
+				source_chain,
+				id,
+				message.weight,
+			);
+			Self::deposit_event(Event::MessageDispatchPaymentFailed(
+				source_chain,
+				id,
+				origin_account,
+				message.weight,
+			));
+			return dispatch_result
+		}
+		dispatch_result.dispatch_fee_paid_during_dispatch = pay_dispatch_fee_at_target_chain;
+
+		// finally execute XCM program
+		let origin_location = T::AccountIdToMultiLocation::convert(origin_account);
+		let outcome = T::XcmExecutor::execute_xcm(origin_location, xcm, message.weight);
+		dispatch_result.dispatch_result = matches!(outcome, Outcome::Complete(_));
+		dispatch_result.unspent_weight = message.weight.saturating_sub(outcome.weight_used());
+
+		log::trace!(
+			target: "runtime::bridge-xcm-dispatch",
+			"Message {:?}/{:?} has been dispatched. Weight: {} of {}. Result: {:?}. XCM outcome: {:?}",
+			source_chain,
+			id,
+			outcome.weight_used(),
+			message.weight,
+			dispatch_result,
+			outcome,
+		);
+
+		Self::deposit_event(Event::MessageDispatched(source_chain, id, outcome));
+
+		dispatch_result
+	}
+}
+
+/// Dispatching messages, delivered by the messages pallet, using this module.
+///
+/// The adapter doesn't know how to convert dispatch weight into dispatch fee, so messages with
+/// dispatch fee that is paid at this chain are rejected. Runtimes that want to support such
+/// messages need to provide their own adapter.
+pub struct FromBridgedChainXcmMessageDispatch<T, I, BridgedChainId, ThisChainId>(
+	PhantomData<(T, I, BridgedChainId, ThisChainId)>,
+);
+
+impl<T, I, Fee, BridgedChainId, ThisChainId> InboundMessageDispatch<T::AccountId, Fee>
+	for FromBridgedChainXcmMessageDispatch<T, I, BridgedChainId, ThisChainId>
+where
+	T: Config<I, BridgeMessageId = (LaneId, MessageNonce)>,
+	I: 'static,
+	BridgedChainId: Get<ChainId>,
+	ThisChainId: Get<ChainId>,
+{
+	type DispatchPayload = XcmMessagePayload<T::SourceChainAccountId, <T as Config<I>>::Call>;
+
+	fn dispatch_weight(
+		message: &DispatchMessage<Self::DispatchPayload, Fee>,
+	) -> bp_message_dispatch::Weight {
+		message.data.payload.as_ref().map(|payload| payload.weight).unwrap_or(0)
+	}
+
+	fn is_expired(_message: &DispatchMessage<Self::DispatchPayload, Fee>) -> bool {
+		// XCM messages never expire
+		false
+	}
+
+	fn dispatch_fee_payment(
+		message: &DispatchMessage<Self::DispatchPayload, Fee>,
+	) -> DispatchFeePayment {
+		message
+			.data
+			.payload
+			.as_ref()
+			.map(|payload| payload.dispatch_fee_payment)
+			.unwrap_or(DispatchFeePayment::AtSourceChain)
+	}
+
+	fn dispatch(
+		_relayer_account: &T::AccountId,
+		message: DispatchMessage<Self::DispatchPayload, Fee>,
+	) -> MessageDispatchResult {
+		let message_id = (message.key.lane_id, message.key.nonce);
+		Pallet::<T, I>::dispatch(
+			BridgedChainId::get(),
+			ThisChainId::get(),
+			message_id,
+			message.data.payload.map_err(drop),
+			|_, _| Err(()),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// From construct_runtime macro
+	#![allow(clippy::from_over_into)]
+
+	use super::*;
+	use bp_messages::{
+		source_chain::ForbidOutboundMessages,
+		target_chain::{ProvedLaneMessages, ProvedMessages, SourceHeaderChain},
+		Message, MessageData, MessageKey, Parameter as MessagesParameter,
+	};
+	use bp_runtime::Size;
+	use frame_support::{assert_ok, parameter_types, weights::Weight};
+	use frame_system::{EventRecord, Phase};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		Perbill,
+	};
+	use sp_std::cell::RefCell;
+	use xcm::latest::{Error as XcmError, Instruction, Junction, Junctions};
+
+	type AccountId = u64;
+	type BridgeMessageId = (LaneId, MessageNonce);
+	type TestMessageFee = u64;
+
+	const SOURCE_CHAIN_ID: ChainId = *b"srce";
+	const TARGET_CHAIN_ID: ChainId = *b"trgt";
+	const TEST_LANE_ID: LaneId = [0, 0, 0, 1];
+	const TEST_MESSAGE_ID: BridgeMessageId = (TEST_LANE_ID, 1);
+
+	const INSTRUCTION_WEIGHT: Weight = 1_000;
+	const TEST_WEIGHT: Weight = 10 * INSTRUCTION_WEIGHT;
+
+	thread_local! {
+		static EXECUTED_XCM_ORIGIN: RefCell<Option<MultiLocation>> = RefCell::new(None);
+	}
+
+	pub struct AccountIdConverter;
+
+	impl Convert<H256, AccountId> for AccountIdConverter {
+		fn convert(hash: H256) -> AccountId {
+			hash.to_low_u64_ne()
+		}
+	}
+
+	pub struct AccountIdToMultiLocation;
+
+	impl Convert<AccountId, MultiLocation> for AccountIdToMultiLocation {
+		fn convert(account_id: AccountId) -> MultiLocation {
+			Junctions::X1(Junction::GeneralIndex(account_id as _)).into()
+		}
+	}
+
+	pub struct TestWeigher;
+
+	impl WeightBounds<Call> for TestWeigher {
+		fn weight(message: &mut Xcm<Call>) -> Result<Weight, ()> {
+			Ok(message.0.len() as Weight * INSTRUCTION_WEIGHT)
+		}
+
+		fn instr_weight(_instruction: &Instruction<Call>) -> Result<Weight, ()> {
+			Ok(INSTRUCTION_WEIGHT)
+		}
+	}
+
+	/// Test XCM executor, that fails on the first `Trap` instruction.
+	pub struct TestXcmExecutor;
+
+	impl ExecuteXcm<Call> for TestXcmExecutor {
+		fn execute_xcm_in_credit(
+			origin: impl Into<MultiLocation>,
+			message: Xcm<Call>,
+			_weight_limit: Weight,
+			_weight_credit: Weight,
+		) -> Outcome {
+			EXECUTED_XCM_ORIGIN.with(|o| *o.borrow_mut() = Some(origin.into()));
+
+			let mut weight_used = 0;
+			for instruction in message.0 {
+				weight_used += INSTRUCTION_WEIGHT;
+				if let Instruction::Trap(code) = instruction {
+					return Outcome::Incomplete(weight_used, XcmError::Trap(code))
+				}
+			}
+			Outcome::Complete(weight_used)
+		}
+	}
+
+	#[derive(Clone, Debug, Encode, Decode, PartialEq, Eq)]
+	pub enum TestMessagesParameter {
+		Dummy,
+	}
+
+	impl MessagesParameter for TestMessagesParameter {
+		fn save(&self) {}
+	}
+
+	#[derive(Clone, Debug, Encode, Decode, PartialEq, Eq)]
+	pub struct TestMessagesProof(Vec<Message<TestMessageFee>>);
+
+	impl Size for TestMessagesProof {
+		fn size_hint(&self) -> u32 {
+			0
+		}
+	}
+
+	pub struct TestSourceHeaderChain;
+
+	impl SourceHeaderChain<TestMessageFee> for TestSourceHeaderChain {
+		type Error = &'static str;
+
+		type MessagesProof = TestMessagesProof;
+
+		fn verify_messages_proof(
+			proof: Self::MessagesProof,
+			_messages_count: u32,
+		) -> Result<ProvedMessages<Message<TestMessageFee>>, Self::Error> {
+			Ok(vec![(TEST_LANE_ID, ProvedLaneMessages { lane_state: None, messages: proof.0 })]
+				.into_iter()
+				.collect())
+		}
+	}
+
+	type Block = frame_system::mocking::MockBlock<TestRuntime>;
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+	use crate as xcm_dispatch;
+
+	frame_support::construct_runtime! {
+		pub enum TestRuntime where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			XcmDispatch: xcm_dispatch::{Pallet, Call, Event<T>},
+			Messages: pallet_bridge_messages::{Pallet, Call, Config<T>, Event<T>},
+		}
+	}
+
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::one();
+	}
+
+	impl frame_system::Config for TestRuntime {
+		type Origin = Origin;
+		type Index = u64;
+		type Call = Call;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = AccountId;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type BaseCallFilter = frame_support::traits::Everything;
+		type SystemWeightInfo = ();
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+	}
+
+	impl Config for TestRuntime {
+		type Event = Event;
+		type BridgeMessageId = BridgeMessageId;
+		type SourceChainAccountId = AccountId;
+		type Call = Call;
+		type XcmExecutor = TestXcmExecutor;
+		type Weigher = TestWeigher;
+		type AccountIdConverter = AccountIdConverter;
+		type AccountIdToMultiLocation = AccountIdToMultiLocation;
+	}
+
+	parameter_types! {
+		pub const MaxMessagesToPruneAtOnce: MessageNonce = 8;
+		pub const MaxDispatchStatusesToKeep: MessageNonce = 16;
+		pub const MaxUnrewardedRelayerEntriesAtInboundLane: MessageNonce = 16;
+		pub const MaxUnconfirmedMessagesAtInboundLane: MessageNonce = 16;
+		pub const MaxInlineDispatchWeight: Weight = 100 * TEST_WEIGHT;
+		pub const MaxAllowedSenders: u32 = 1;
+		pub const SourceChainId: ChainId = SOURCE_CHAIN_ID;
+		pub const TargetChainId: ChainId = TARGET_CHAIN_ID;
+	}
+
+	impl pallet_bridge_messages::Config for TestRuntime {
+		type Event = Event;
+		type WeightInfo = ();
+		type BridgedChainId = SourceChainId;
+		type Parameter = TestMessagesParameter;
+		type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+		type MaxDispatchStatusesToKeep = MaxDispatchStatusesToKeep;
+		type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
+		type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+		type MaxInlineDispatchWeight = MaxInlineDispatchWeight;
+		type MaxAllowedSenders = MaxAllowedSenders;
+
+		type OutboundPayload = ();
+		type OutboundMessageFee = TestMessageFee;
+
+		type InboundPayload = XcmMessagePayload<AccountId, Call>;
+		type InboundMessageFee = TestMessageFee;
+		type InboundRelayer = AccountId;
+
+		type AccountIdConverter = AccountIdConverter;
+
+		type TargetHeaderChain = ForbidOutboundMessages;
+		type LaneMessageVerifier = ForbidOutboundMessages;
+		type MessageDeliveryAndDispatchPayment = ForbidOutboundMessages;
+		type OnMessageAccepted = ();
+		type OnDeliveryConfirmed = ();
+
+		type SourceHeaderChain = TestSourceHeaderChain;
+		type MessageDispatch =
+			FromBridgedChainXcmMessageDispatch<TestRuntime, (), SourceChainId, TargetChainId>;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<TestRuntime>().unwrap();
+		pallet_bridge_messages::GenesisConfig::<TestRuntime> {
+			opened_lanes: vec![TEST_LANE_ID],
+			..Default::default()
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		sp_io::TestExternalities::new(t)
+	}
+
+	fn prepare_message(instructions: Vec<Instruction<Call>>) -> XcmMessagePayload<AccountId, Call> {
+		XcmMessagePayload {
+			origin: XcmMessageOrigin::SourceAccount(1),
+			weight: TEST_WEIGHT,
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			xcm: VersionedXcm::from(Xcm(instructions)),
+		}
+	}
+
+	fn dispatch(
+		message: Result<XcmMessagePayload<AccountId, Call>, ()>,
+		pay_dispatch_fee: impl FnOnce(&AccountId, Weight) -> Result<(), ()>,
+	) -> MessageDispatchResult {
+		System::set_block_number(1);
+		XcmDispatch::dispatch(
+			SOURCE_CHAIN_ID,
+			TARGET_CHAIN_ID,
+			TEST_MESSAGE_ID,
+			message,
+			pay_dispatch_fee,
+		)
+	}
+
+	fn assert_single_event(event: xcm_dispatch::Event<TestRuntime>) {
+		assert_eq!(
+			System::events(),
+			vec![EventRecord {
+				phase: Phase::Initialization,
+				event: Event::XcmDispatch(event),
+				topics: vec![],
+			}],
+		);
+	}
+
+	#[test]
+	fn should_emit_event_for_rejected_messages() {
+		new_test_ext().execute_with(|| {
+			let result = dispatch(Err(()), |_, _| unreachable!());
+			assert_eq!(result.unspent_weight, 0);
+			assert!(!result.dispatch_result);
+
+			assert_single_event(xcm_dispatch::Event::MessageRejected(
+				SOURCE_CHAIN_ID,
+				TEST_MESSAGE_ID,
+			));
+		});
+	}
+
+	#[test]
+	fn should_fail_on_weight_mismatch() {
+		new_test_ext().execute_with(|| {
+			let mut message = prepare_message(vec![Instruction::ClearOrigin]);
+			message.weight = INSTRUCTION_WEIGHT - 1;
+
+			let result = dispatch(Ok(message), |_, _| unreachable!());
+			assert_eq!(result.unspent_weight, INSTRUCTION_WEIGHT - 1);
+			assert!(!result.dispatch_result);
+			assert_eq!(EXECUTED_XCM_ORIGIN.with(|o| o.borrow().clone()), None);
+
+			assert_single_event(xcm_dispatch::Event::MessageWeightMismatch(
+				SOURCE_CHAIN_ID,
+				TEST_MESSAGE_ID,
+				INSTRUCTION_WEIGHT,
+				INSTRUCTION_WEIGHT - 1,
+			));
+		});
+	}
+
+	#[test]
+	fn should_emit_event_for_unpaid_messages() {
+		new_test_ext().execute_with(|| {
+			let mut message = prepare_message(vec![Instruction::ClearOrigin]);
+			message.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
+
+			let result = dispatch(Ok(message), |_, _| Err(()));
+			assert_eq!(result.unspent_weight, TEST_WEIGHT);
+			assert!(!result.dispatch_result);
+			assert!(!result.dispatch_fee_paid_during_dispatch);
+
+			let origin_account = AccountIdConverter::convert(derive_account_id::<AccountId>(
+				SOURCE_CHAIN_ID,
+				SourceAccount::Account(1),
+			));
+			assert_single_event(xcm_dispatch::Event::MessageDispatchPaymentFailed(
+				SOURCE_CHAIN_ID,
+				TEST_MESSAGE_ID,
+				origin_account,
+				TEST_WEIGHT,
+			));
+		});
+	}
+
+	#[test]
+	fn should_execute_xcm_program_on_behalf_of_derived_account() {
+		new_test_ext().execute_with(|| {
+			let mut message =
+				prepare_message(vec![Instruction::ClearOrigin, Instruction::ClearOrigin]);
+			message.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
+
+			let result = dispatch(Ok(message), |_, weight| {
+				assert_eq!(weight, TEST_WEIGHT);
+				Ok(())
+			});
+			assert_eq!(result.unspent_weight, TEST_WEIGHT - 2 * INSTRUCTION_WEIGHT);
+			assert!(result.dispatch_result);
+			assert!(result.dispatch_fee_paid_during_dispatch);
+
+			let origin_account = AccountIdConverter::convert(derive_account_id::<AccountId>(
+				SOURCE_CHAIN_ID,
+				SourceAccount::Account(1),
+			));
+			assert_eq!(
+				EXECUTED_XCM_ORIGIN.with(|o| o.borrow().clone()),
+				Some(AccountIdToMultiLocation::convert(origin_account)),
+			);
+
+			assert_single_event(xcm_dispatch::Event::MessageDispatched(
+				SOURCE_CHAIN_ID,
+				TEST_MESSAGE_ID,
+				Outcome::Complete(2 * INSTRUCTION_WEIGHT),
+			));
+		});
+	}
+
+	#[test]
+	fn should_return_dispatch_failed_flag_if_xcm_program_is_not_completed() {
+		new_test_ext().execute_with(|| {
+			let message = prepare_message(vec![
+				Instruction::ClearOrigin,
+				Instruction::Trap(42),
+				Instruction::ClearOrigin,
+			]);
+
+			let result = dispatch(Ok(message), |_, _| unreachable!());
+			assert_eq!(result.unspent_weight, TEST_WEIGHT - 2 * INSTRUCTION_WEIGHT);
+			assert!(!result.dispatch_result);
+			assert!(!result.dispatch_fee_paid_during_dispatch);
+
+			assert_single_event(xcm_dispatch::Event::MessageDispatched(
+				SOURCE_CHAIN_ID,
+				TEST_MESSAGE_ID,
+				Outcome::Incomplete(2 * INSTRUCTION_WEIGHT, XcmError::Trap(42)),
+			));
+		});
+	}
+
+	#[test]
+	fn should_dispatch_messages_delivered_over_bridge() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			let message = Message {
+				key: MessageKey { lane_id: TEST_LANE_ID, nonce: 1 },
+				data: MessageData {
+					payload: prepare_message(vec![Instruction::ClearOrigin]).encode(),
+					fee: 0,
+				},
+			};
+			assert_ok!(Messages::receive_messages_proof(
+				Origin::signed(1),
+				2,
+				TestMessagesProof(vec![message]),
+				1,
+				TEST_WEIGHT,
+			));
+
+			assert!(System::events().into_iter().any(|record| record.event ==
+				Event::XcmDispatch(xcm_dispatch::Event::MessageDispatched(
+					SOURCE_CHAIN_ID,
+					TEST_MESSAGE_ID,
+					Outcome::Complete(INSTRUCTION_WEIGHT),
+				))));
+			assert_eq!(
+				pallet_bridge_messages::Pallet::<TestRuntime>::inbound_latest_received_nonce(
+					TEST_LANE_ID
+				),
+				1,
+			);
+		});
+	}
+
+	#[test]
+	fn should_reject_messages_delivered_over_bridge_if_dispatch_fee_is_paid_at_target_chain() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			let mut payload = prepare_message(vec![Instruction::ClearOrigin]);
+			payload.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
+			let message = Message {
+				key: MessageKey { lane_id: TEST_LANE_ID, nonce: 1 },
+				data: MessageData { payload: payload.encode(), fee: 0 },
+			};
+			assert_ok!(Messages::receive_messages_proof(
+				Origin::signed(1),
+				2,
+				TestMessagesProof(vec![message]),
+				1,
+				TEST_WEIGHT,
+			));
+
+			assert!(System::events().into_iter().any(|record| matches!(
+				record.event,
+				Event::XcmDispatch(xcm_dispatch::Event::MessageDispatchPaymentFailed(..))
+			)));
+		});
+	}
+}
@@ -22,7 +22,9 @@
 
 mod millau_hash;
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce, UnrewardedRelayersState};
+use bp_messages::{
+	LaneId, LaneInfo, MessageDetails, MessageDispatchStatus, MessageNonce, UnrewardedRelayersState,
+};
 use bp_runtime::Chain;
 use frame_support::{
 	weights::{constants::WEIGHT_PER_SECOND, DispatchClass, IdentityFee, Weight},
@@ -111,6 +113,12 @@ pub const PAY_INBOUND_DISPATCH_FEE_WEIGHT: Weight = 600_000_000;
 /// conditions.
 pub const SESSION_LENGTH: BlockNumber = 5 * time_units::MINUTES;
 
+/// Length of the message delivery slot at Millau, measured in number of blocks.
+///
+/// During the slot, message delivery transactions of the slot owner (one of relayers registered
+/// at the lane) are prioritized over delivery transactions of other relayers.
+pub const DELIVERY_SLOT_LENGTH: BlockNumber = time_units::MINUTES;
+
 /// Re-export `time_units` to make usage easier.
 pub use time_units::*;
 
@@ -253,6 +261,12 @@ pub fn max_extrinsic_weight() -> Weight {
 		.unwrap_or(Weight::MAX)
 }
 
+/// Get the maximal dispatch weight of the message that is dispatched during delivery to the Millau
+/// chain. Heavier messages are put into the overweight queue.
+pub fn max_inline_dispatch_weight() -> Weight {
+	max_extrinsic_weight() / 4
+}
+
 /// Get the maximum length in bytes that a Normal extrinsic on the Millau chain requires.
 pub fn max_extrinsic_size() -> u32 {
 	*BlockLength::get().max.get(DispatchClass::Normal)
@@ -260,11 +274,21 @@ pub fn max_extrinsic_size() -> u32 {
 
 /// Name of the With-Rialto messages pallet instance in the Millau runtime.
 pub const WITH_RIALTO_MESSAGES_PALLET_NAME: &str = "BridgeRialtoMessages";
+/// Name of the With-Rialto dispatch pallet instance in the Millau runtime.
+pub const WITH_RIALTO_DISPATCH_PALLET_NAME: &str = "BridgeDispatch";
+/// Name of the relayers pallet in the Millau runtime.
+pub const RELAYERS_PALLET_NAME: &str = "BridgeRelayers";
 /// Name of the With-Rialto token swap pallet instance in the Millau runtime.
 pub const WITH_RIALTO_TOKEN_SWAP_PALLET_NAME: &str = "BridgeRialtoTokenSwap";
+/// Name of the With-Rialto parachains pallet instance in the Millau runtime.
+pub const WITH_RIALTO_PARACHAINS_PALLET_NAME: &str = "BridgeRialtoParachains";
 
 /// Name of the `MillauFinalityApi::best_finalized` runtime method.
 pub const BEST_FINALIZED_MILLAU_HEADER_METHOD: &str = "MillauFinalityApi_best_finalized";
+/// Name of the `MillauFinalityApi::authority_set` runtime method.
+pub const MILLAU_AUTHORITY_SET_METHOD: &str = "MillauFinalityApi_authority_set";
+/// Name of the `MillauFinalityApi::header_set_id` runtime method.
+pub const MILLAU_HEADER_SET_ID_METHOD: &str = "MillauFinalityApi_header_set_id";
 
 /// Name of the `ToMillauOutboundLaneApi::estimate_message_delivery_and_dispatch_fee` runtime
 /// method.
@@ -278,6 +302,11 @@ pub const TO_MILLAU_LATEST_RECEIVED_NONCE_METHOD: &str =
 /// Name of the `ToMillauOutboundLaneApi::latest_generated_nonce` runtime method.
 pub const TO_MILLAU_LATEST_GENERATED_NONCE_METHOD: &str =
 	"ToMillauOutboundLaneApi_latest_generated_nonce";
+/// Name of the `ToMillauOutboundLaneApi::message_dispatch_status` runtime method.
+pub const TO_MILLAU_MESSAGE_DISPATCH_STATUS_METHOD: &str =
+	"ToMillauOutboundLaneApi_message_dispatch_status";
+/// Name of the `ToMillauOutboundLaneApi::lanes` runtime method.
+pub const TO_MILLAU_LANES_METHOD: &str = "ToMillauOutboundLaneApi_lanes";
 
 /// Name of the `FromMillauInboundLaneApi::latest_received_nonce` runtime method.
 pub const FROM_MILLAU_LATEST_RECEIVED_NONCE_METHOD: &str =
@@ -299,6 +328,11 @@ sp_api::decl_runtime_apis! {
 		fn best_finalized() -> (BlockNumber, Hash);
 		/// Returns true if the header is known to the runtime.
 		fn is_known_header(hash: Hash) -> bool;
+		/// Returns GRANDPA authority set with given id, if it is known to the runtime.
+		fn authority_set(set_id: bp_header_chain::SetId) -> Option<bp_header_chain::AuthoritySet>;
+		/// Returns id of GRANDPA authority set that has finalized the header, if the header is
+		/// known to the runtime.
+		fn header_set_id(hash: Hash) -> Option<bp_header_chain::SetId>;
 	}
 
 	/// Outbound message lane API for messages that are sent to Millau chain.
@@ -333,6 +367,13 @@ sp_api::decl_runtime_apis! {
 		fn latest_received_nonce(lane: LaneId) -> MessageNonce;
 		/// Returns nonce of the latest message, generated by given lane.
 		fn latest_generated_nonce(lane: LaneId) -> MessageNonce;
+		/// Returns dispatch status of the message with given nonce, reported by the bridged chain.
+		///
+		/// Returns `None` if message delivery is not yet confirmed, or if the status has already
+		/// been pruned from the storage.
+		fn message_dispatch_status(lane: LaneId, nonce: MessageNonce) -> Option<MessageDispatchStatus>;
+		/// Returns all lanes that are registered at this chain, with their states.
+		fn lanes() -> Vec<(LaneId, LaneInfo)>;
 	}
 
 	/// Inbound message lane API for messages sent by Millau chain.
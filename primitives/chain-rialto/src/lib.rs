@@ -20,7 +20,9 @@
 // Runtime-generated DecodeLimit::decode_all_With_depth_limit
 #![allow(clippy::unnecessary_mut_passed)]
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce, UnrewardedRelayersState};
+use bp_messages::{
+	LaneId, LaneInfo, MessageDetails, MessageDispatchStatus, MessageNonce, UnrewardedRelayersState,
+};
 use bp_runtime::Chain;
 use frame_support::{
 	weights::{constants::WEIGHT_PER_SECOND, DispatchClass, IdentityFee, Weight},
@@ -103,6 +105,12 @@ pub const PAY_INBOUND_DISPATCH_FEE_WEIGHT: Weight = 600_000_000;
 /// conditions.
 pub const SESSION_LENGTH: BlockNumber = 4;
 
+/// Length of the message delivery slot at Rialto, measured in number of blocks.
+///
+/// During the slot, message delivery transactions of the slot owner (one of relayers registered
+/// at the lane) are prioritized over delivery transactions of other relayers.
+pub const DELIVERY_SLOT_LENGTH: BlockNumber = 4;
+
 /// Re-export `time_units` to make usage easier.
 pub use time_units::*;
 
@@ -223,6 +231,12 @@ pub fn max_extrinsic_weight() -> Weight {
 		.unwrap_or(Weight::MAX)
 }
 
+/// Get the maximal dispatch weight of the message that is dispatched during delivery to the Rialto
+/// chain. Heavier messages are put into the overweight queue.
+pub fn max_inline_dispatch_weight() -> Weight {
+	max_extrinsic_weight() / 4
+}
+
 /// Get the maximum length in bytes that a Normal extrinsic on the Millau chain requires.
 pub fn max_extrinsic_size() -> u32 {
 	*BlockLength::get().max.get(DispatchClass::Normal)
@@ -230,6 +244,10 @@ pub fn max_extrinsic_size() -> u32 {
 
 /// Name of the With-Millau messages pallet instance in the Rialto runtime.
 pub const WITH_MILLAU_MESSAGES_PALLET_NAME: &str = "BridgeMillauMessages";
+/// Name of the With-Millau dispatch pallet instance in the Rialto runtime.
+pub const WITH_MILLAU_DISPATCH_PALLET_NAME: &str = "BridgeDispatch";
+/// Name of the relayers pallet in the Rialto runtime.
+pub const RELAYERS_PALLET_NAME: &str = "BridgeRelayers";
 
 /// Name of the parachain registrar pallet in the Rialto runtime.
 pub const PARAS_REGISTRAR_PALLET_NAME: &str = "Registrar";
@@ -239,6 +257,10 @@ pub const PARAS_PALLET_NAME: &str = "Paras";
 
 /// Name of the `RialtoFinalityApi::best_finalized` runtime method.
 pub const BEST_FINALIZED_RIALTO_HEADER_METHOD: &str = "RialtoFinalityApi_best_finalized";
+/// Name of the `RialtoFinalityApi::authority_set` runtime method.
+pub const RIALTO_AUTHORITY_SET_METHOD: &str = "RialtoFinalityApi_authority_set";
+/// Name of the `RialtoFinalityApi::header_set_id` runtime method.
+pub const RIALTO_HEADER_SET_ID_METHOD: &str = "RialtoFinalityApi_header_set_id";
 
 /// Name of the `ToRialtoOutboundLaneApi::estimate_message_delivery_and_dispatch_fee` runtime
 /// method.
@@ -249,6 +271,11 @@ pub const TO_RIALTO_MESSAGE_DETAILS_METHOD: &str = "ToRialtoOutboundLaneApi_mess
 /// Name of the `ToRialtoOutboundLaneApi::latest_generated_nonce` runtime method.
 pub const TO_RIALTO_LATEST_GENERATED_NONCE_METHOD: &str =
 	"ToRialtoOutboundLaneApi_latest_generated_nonce";
+/// Name of the `ToRialtoOutboundLaneApi::message_dispatch_status` runtime method.
+pub const TO_RIALTO_MESSAGE_DISPATCH_STATUS_METHOD: &str =
+	"ToRialtoOutboundLaneApi_message_dispatch_status";
+/// Name of the `ToRialtoOutboundLaneApi::lanes` runtime method.
+pub const TO_RIALTO_LANES_METHOD: &str = "ToRialtoOutboundLaneApi_lanes";
 /// Name of the `ToRialtoOutboundLaneApi::latest_received_nonce` runtime method.
 pub const TO_RIALTO_LATEST_RECEIVED_NONCE_METHOD: &str =
 	"ToRialtoOutboundLaneApi_latest_received_nonce";
@@ -273,6 +300,11 @@ sp_api::decl_runtime_apis! {
 		fn best_finalized() -> (BlockNumber, Hash);
 		/// Returns true if the header is known to the runtime.
 		fn is_known_header(hash: Hash) -> bool;
+		/// Returns GRANDPA authority set with given id, if it is known to the runtime.
+		fn authority_set(set_id: bp_header_chain::SetId) -> Option<bp_header_chain::AuthoritySet>;
+		/// Returns id of GRANDPA authority set that has finalized the header, if the header is
+		/// known to the runtime.
+		fn header_set_id(hash: Hash) -> Option<bp_header_chain::SetId>;
 	}
 
 	/// Outbound message lane API for messages that are sent to Rialto chain.
@@ -307,6 +339,13 @@ sp_api::decl_runtime_apis! {
 		fn latest_received_nonce(lane: LaneId) -> MessageNonce;
 		/// Returns nonce of the latest message, generated by given lane.
 		fn latest_generated_nonce(lane: LaneId) -> MessageNonce;
+		/// Returns dispatch status of the message with given nonce, reported by the bridged chain.
+		///
+		/// Returns `None` if message delivery is not yet confirmed, or if the status has already
+		/// been pruned from the storage.
+		fn message_dispatch_status(lane: LaneId, nonce: MessageNonce) -> Option<MessageDispatchStatus>;
+		/// Returns all lanes that are registered at this chain, with their states.
+		fn lanes() -> Vec<(LaneId, LaneInfo)>;
 	}
 
 	/// Inbound message lane API for messages sent by Rialto chain.
@@ -20,7 +20,9 @@
 // Runtime-generated DecodeLimit::decode_all_with_depth_limit
 #![allow(clippy::unnecessary_mut_passed)]
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce, UnrewardedRelayersState};
+use bp_messages::{
+	LaneId, LaneInfo, MessageDetails, MessageDispatchStatus, MessageNonce, UnrewardedRelayersState,
+};
 use frame_support::weights::{
 	Weight, WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial,
 };
@@ -79,6 +81,10 @@ pub const WITH_WOCOCO_MESSAGES_PALLET_NAME: &str = "BridgeWococoMessages";
 
 /// Name of the `RococoFinalityApi::best_finalized` runtime method.
 pub const BEST_FINALIZED_ROCOCO_HEADER_METHOD: &str = "RococoFinalityApi_best_finalized";
+/// Name of the `RococoFinalityApi::authority_set` runtime method.
+pub const ROCOCO_AUTHORITY_SET_METHOD: &str = "RococoFinalityApi_authority_set";
+/// Name of the `RococoFinalityApi::header_set_id` runtime method.
+pub const ROCOCO_HEADER_SET_ID_METHOD: &str = "RococoFinalityApi_header_set_id";
 /// Name of the `RococoFinalityApi::is_known_header` runtime method.
 pub const IS_KNOWN_ROCOCO_HEADER_METHOD: &str = "RococoFinalityApi_is_known_header";
 
@@ -91,6 +97,11 @@ pub const TO_ROCOCO_MESSAGE_DETAILS_METHOD: &str = "ToRococoOutboundLaneApi_mess
 /// Name of the `ToRococoOutboundLaneApi::latest_generated_nonce` runtime method.
 pub const TO_ROCOCO_LATEST_GENERATED_NONCE_METHOD: &str =
 	"ToRococoOutboundLaneApi_latest_generated_nonce";
+/// Name of the `ToRococoOutboundLaneApi::message_dispatch_status` runtime method.
+pub const TO_ROCOCO_MESSAGE_DISPATCH_STATUS_METHOD: &str =
+	"ToRococoOutboundLaneApi_message_dispatch_status";
+/// Name of the `ToRococoOutboundLaneApi::lanes` runtime method.
+pub const TO_ROCOCO_LANES_METHOD: &str = "ToRococoOutboundLaneApi_lanes";
 /// Name of the `ToRococoOutboundLaneApi::latest_received_nonce` runtime method.
 pub const TO_ROCOCO_LATEST_RECEIVED_NONCE_METHOD: &str =
 	"ToRococoOutboundLaneApi_latest_received_nonce";
@@ -124,6 +135,11 @@ sp_api::decl_runtime_apis! {
 		fn best_finalized() -> (BlockNumber, Hash);
 		/// Returns true if the header is known to the runtime.
 		fn is_known_header(hash: Hash) -> bool;
+		/// Returns GRANDPA authority set with given id, if it is known to the runtime.
+		fn authority_set(set_id: bp_header_chain::SetId) -> Option<bp_header_chain::AuthoritySet>;
+		/// Returns id of GRANDPA authority set that has finalized the header, if the header is
+		/// known to the runtime.
+		fn header_set_id(hash: Hash) -> Option<bp_header_chain::SetId>;
 	}
 
 	/// Outbound message lane API for messages that are sent to Rococo chain.
@@ -158,6 +174,13 @@ sp_api::decl_runtime_apis! {
 		fn latest_received_nonce(lane: LaneId) -> MessageNonce;
 		/// Returns nonce of the latest message, generated by given lane.
 		fn latest_generated_nonce(lane: LaneId) -> MessageNonce;
+		/// Returns dispatch status of the message with given nonce, reported by the bridged chain.
+		///
+		/// Returns `None` if message delivery is not yet confirmed, or if the status has already
+		/// been pruned from the storage.
+		fn message_dispatch_status(lane: LaneId, nonce: MessageNonce) -> Option<MessageDispatchStatus>;
+		/// Returns all lanes that are registered at this chain, with their states.
+		fn lanes() -> Vec<(LaneId, LaneInfo)>;
 	}
 
 	/// Inbound message lane API for messages sent by Rococo chain.
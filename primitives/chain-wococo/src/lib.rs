@@ -20,7 +20,9 @@
 // Runtime-generated DecodeLimit::decode_all_with_depth_limit
 #![allow(clippy::unnecessary_mut_passed)]
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce, UnrewardedRelayersState};
+use bp_messages::{
+	LaneId, LaneInfo, MessageDetails, MessageDispatchStatus, MessageNonce, UnrewardedRelayersState,
+};
 use sp_std::prelude::*;
 
 pub use bp_polkadot_core::*;
@@ -42,6 +44,10 @@ pub const WITH_ROCOCO_MESSAGES_PALLET_NAME: &str = "BridgeRococoMessages";
 
 /// Name of the `WococoFinalityApi::best_finalized` runtime method.
 pub const BEST_FINALIZED_WOCOCO_HEADER_METHOD: &str = "WococoFinalityApi_best_finalized";
+/// Name of the `WococoFinalityApi::authority_set` runtime method.
+pub const WOCOCO_AUTHORITY_SET_METHOD: &str = "WococoFinalityApi_authority_set";
+/// Name of the `WococoFinalityApi::header_set_id` runtime method.
+pub const WOCOCO_HEADER_SET_ID_METHOD: &str = "WococoFinalityApi_header_set_id";
 /// Name of the `WococoFinalityApi::is_known_header` runtime method.
 pub const IS_KNOWN_WOCOCO_HEADER_METHOD: &str = "WococoFinalityApi_is_known_header";
 
@@ -54,6 +60,11 @@ pub const TO_WOCOCO_MESSAGE_DETAILS_METHOD: &str = "ToWococoOutboundLaneApi_mess
 /// Name of the `ToWococoOutboundLaneApi::latest_generated_nonce` runtime method.
 pub const TO_WOCOCO_LATEST_GENERATED_NONCE_METHOD: &str =
 	"ToWococoOutboundLaneApi_latest_generated_nonce";
+/// Name of the `ToWococoOutboundLaneApi::message_dispatch_status` runtime method.
+pub const TO_WOCOCO_MESSAGE_DISPATCH_STATUS_METHOD: &str =
+	"ToWococoOutboundLaneApi_message_dispatch_status";
+/// Name of the `ToWococoOutboundLaneApi::lanes` runtime method.
+pub const TO_WOCOCO_LANES_METHOD: &str = "ToWococoOutboundLaneApi_lanes";
 /// Name of the `ToWococoOutboundLaneApi::latest_received_nonce` runtime method.
 pub const TO_WOCOCO_LATEST_RECEIVED_NONCE_METHOD: &str =
 	"ToWococoOutboundLaneApi_latest_received_nonce";
@@ -78,6 +89,11 @@ sp_api::decl_runtime_apis! {
 		fn best_finalized() -> (BlockNumber, Hash);
 		/// Returns true if the header is known to the runtime.
 		fn is_known_header(hash: Hash) -> bool;
+		/// Returns GRANDPA authority set with given id, if it is known to the runtime.
+		fn authority_set(set_id: bp_header_chain::SetId) -> Option<bp_header_chain::AuthoritySet>;
+		/// Returns id of GRANDPA authority set that has finalized the header, if the header is
+		/// known to the runtime.
+		fn header_set_id(hash: Hash) -> Option<bp_header_chain::SetId>;
 	}
 
 	/// Outbound message lane API for messages that are sent to Wococo chain.
@@ -112,6 +128,13 @@ sp_api::decl_runtime_apis! {
 		fn latest_received_nonce(lane: LaneId) -> MessageNonce;
 		/// Returns nonce of the latest message, generated by given lane.
 		fn latest_generated_nonce(lane: LaneId) -> MessageNonce;
+		/// Returns dispatch status of the message with given nonce, reported by the bridged chain.
+		///
+		/// Returns `None` if message delivery is not yet confirmed, or if the status has already
+		/// been pruned from the storage.
+		fn message_dispatch_status(lane: LaneId, nonce: MessageNonce) -> Option<MessageDispatchStatus>;
+		/// Returns all lanes that are registered at this chain, with their states.
+		fn lanes() -> Vec<(LaneId, LaneInfo)>;
 	}
 
 	/// Inbound message lane API for messages sent by Wococo chain.
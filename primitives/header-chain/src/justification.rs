@@ -51,6 +51,28 @@ impl<H: HeaderT> crate::FinalityProof<H::Number> for GrandpaJustification<H> {
 	}
 }
 
+/// Signed GRANDPA precommit.
+pub type SignedPrecommit<Header> = finality_grandpa::SignedPrecommit<
+	<Header as HeaderT>::Hash,
+	<Header as HeaderT>::Number,
+	AuthoritySignature,
+	AuthorityId,
+>;
+
+/// A proof that the GRANDPA authority has signed two different precommits in the same round.
+///
+/// Honest authorities never do that, so the proof means that the authorities set of the bridged
+/// chain (or, at least, one of its members) is misbehaving.
+#[derive(Encode, Decode, RuntimeDebug, Clone, PartialEq, Eq)]
+pub struct GrandpaEquivocationProof<Header: HeaderT> {
+	/// The round both precommits have been signed in.
+	pub round: u64,
+	/// First precommit of the authority.
+	pub first: SignedPrecommit<Header>,
+	/// Second precommit of the same authority, conflicting with the first one.
+	pub second: SignedPrecommit<Header>,
+}
+
 /// Justification verification error.
 #[derive(RuntimeDebug, PartialEq)]
 pub enum Error {
@@ -68,6 +90,15 @@ pub enum Error {
 	TooLowCumulativeWeight,
 	/// The justification contains extra (unused) headers in its `votes_ancestries` field.
 	ExtraHeadersInVotesAncestries,
+	/// The justification contains precommits that are not required to justify commit header
+	/// finalization.
+	RedundantVotesInJustification,
+	/// The equivocation proof contains precommits signed by different authorities.
+	EquivocationAuthoritiesMismatch,
+	/// The equivocation proof contains two identical precommits.
+	NotAnEquivocation,
+	/// The equivocation proof is signed by authority that is not in the set.
+	UnknownAuthority,
 }
 
 /// Decode justification target.
@@ -80,12 +111,83 @@ pub fn decode_justification_target<Header: HeaderT>(
 }
 
 /// Verify that justification, that is generated by given authority set, finalizes given header.
+///
+/// The justification must not contain any redundant data: precommits of unknown authorities,
+/// duplicate precommits, precommits that are not required to reach the threshold and unused
+/// headers in the `votes_ancestries` are rejected. Justifications that are generated by GRANDPA
+/// voters may be stripped of redundant data using `verify_and_optimize_justification`.
 pub fn verify_justification<Header: HeaderT>(
 	finalized_target: (Header::Hash, Header::Number),
 	authorities_set_id: SetId,
 	authorities_set: &VoterSet<AuthorityId>,
 	justification: &GrandpaJustification<Header>,
 ) -> Result<(), Error>
+where
+	Header::Number: finality_grandpa::BlockNumberOps,
+{
+	verify_justification_impl(
+		finalized_target,
+		authorities_set_id,
+		authorities_set,
+		justification,
+		false,
+	)
+	.map(drop)
+}
+
+/// Verify that justification, that is generated by given authority set, finalizes given header
+/// and remove all redundant precommits and unused `votes_ancestries` headers from it.
+///
+/// The optimized justification is the smallest valid justification that may be built from
+/// the original justification. It is accepted by the `verify_justification`.
+pub fn verify_and_optimize_justification<Header: HeaderT>(
+	finalized_target: (Header::Hash, Header::Number),
+	authorities_set_id: SetId,
+	authorities_set: &VoterSet<AuthorityId>,
+	justification: &mut GrandpaJustification<Header>,
+) -> Result<(), Error>
+where
+	Header::Number: finality_grandpa::BlockNumberOps,
+{
+	let redundant_data = verify_justification_impl(
+		finalized_target,
+		authorities_set_id,
+		authorities_set,
+		justification,
+		true,
+	)?;
+
+	let mut precommit_index = 0;
+	justification.commit.precommits.retain(|_| {
+		let is_redundant = redundant_data.precommits.contains(&precommit_index);
+		precommit_index += 1;
+		!is_redundant
+	});
+	justification
+		.votes_ancestries
+		.retain(|header| !redundant_data.votes_ancestries.contains(&header.hash()));
+
+	Ok(())
+}
+
+/// Redundant data of the valid justification.
+struct RedundantJustificationData<Hash> {
+	/// Indices of redundant precommits.
+	precommits: BTreeSet<usize>,
+	/// Hashes of unused `votes_ancestries` headers.
+	votes_ancestries: BTreeSet<Hash>,
+}
+
+/// Verify justification and return its redundant data.
+///
+/// If `allow_redundant_data` is false, the justification with redundant data is rejected.
+fn verify_justification_impl<Header: HeaderT>(
+	finalized_target: (Header::Hash, Header::Number),
+	authorities_set_id: SetId,
+	authorities_set: &VoterSet<AuthorityId>,
+	justification: &GrandpaJustification<Header>,
+	allow_redundant_data: bool,
+) -> Result<RedundantJustificationData<Header::Hash>, Error>
 where
 	Header::Number: finality_grandpa::BlockNumberOps,
 {
@@ -94,30 +196,41 @@ where
 		return Err(Error::InvalidJustificationTarget)
 	}
 
+	let threshold = authorities_set.threshold().0.into();
 	let mut chain = AncestryChain::new(&justification.votes_ancestries);
 	let mut signature_buffer = Vec::new();
 	let mut votes = BTreeSet::new();
 	let mut cumulative_weight = 0u64;
-	for signed in &justification.commit.precommits {
-		// authority must be in the set
+	let mut redundant_precommits = BTreeSet::new();
+	for (precommit_index, signed) in justification.commit.precommits.iter().enumerate() {
+		// the precommit is redundant if:
+		//
+		// - the justification target already has enough weight;
+		//
+		// - the precommit is signed by the authority that is not in the set.
+		// `finality_grandpa::import_precommit` just ignores such precommits;
+		//
+		// - the authority has already voted in the same round. There's a lot of code in
+		// `validate_commit` and `import_precommit` functions inside `finality-grandpa` crate
+		// (mostly related to reporing equivocations). But the only thing that we care about is
+		// that only first vote from the authority is accepted.
+		//
+		// Redundant precommits are making justification verification more expensive, so we
+		// don't accept them
 		let authority_info = match authorities_set.get(&signed.id) {
-			Some(authority_info) => authority_info,
-			None => {
-				// just ignore precommit from unknown authority as
-				// `finality_grandpa::import_precommit` does
+			Some(authority_info)
+				if cumulative_weight < threshold && votes.insert(signed.id.clone()) =>
+				authority_info,
+			_ => {
+				if !allow_redundant_data {
+					return Err(Error::RedundantVotesInJustification)
+				}
+
+				redundant_precommits.insert(precommit_index);
 				continue
 			},
 		};
 
-		// check if authority has already voted in the same round.
-		//
-		// there's a lot of code in `validate_commit` and `import_precommit` functions inside
-		// `finality-grandpa` crate (mostly related to reporing equivocations). But the only thing
-		// that we care about is that only first vote from the authority is accepted
-		if !votes.insert(signed.id.clone()) {
-			continue
-		}
-
 		// everything below this line can't just `continue`, because state is already altered
 
 		// all precommits must be for block higher than the target
@@ -155,20 +268,60 @@ where
 	}
 
 	// check that there are no extra headers in the justification
-	if !chain.unvisited.is_empty() {
+	if !allow_redundant_data && !chain.unvisited.is_empty() {
 		return Err(Error::ExtraHeadersInVotesAncestries)
 	}
 
 	// check that the cumulative weight of validators voted for the justification target (or one
 	// of its descendents) is larger than required threshold.
-	let threshold = authorities_set.threshold().0.into();
 	if cumulative_weight >= threshold {
-		Ok(())
+		Ok(RedundantJustificationData {
+			precommits: redundant_precommits,
+			votes_ancestries: chain.unvisited,
+		})
 	} else {
 		Err(Error::TooLowCumulativeWeight)
 	}
 }
 
+/// Verify that the proof shows that the authority from given set has signed two different
+/// precommits in the same round.
+pub fn verify_equivocation_proof<Header: HeaderT>(
+	authorities_set_id: SetId,
+	authorities_set: &VoterSet<AuthorityId>,
+	proof: &GrandpaEquivocationProof<Header>,
+) -> Result<(), Error> {
+	// both precommits must be signed by the same authority
+	if proof.first.id != proof.second.id {
+		return Err(Error::EquivocationAuthoritiesMismatch)
+	}
+	// ... and they must be different
+	if proof.first.precommit == proof.second.precommit {
+		return Err(Error::NotAnEquivocation)
+	}
+	// the authority must be in the set
+	if authorities_set.get(&proof.first.id).is_none() {
+		return Err(Error::UnknownAuthority)
+	}
+
+	// verify both signatures
+	let mut signature_buffer = Vec::new();
+	for signed in &[&proof.first, &proof.second] {
+		if !sp_finality_grandpa::check_message_signature_with_buffer(
+			&finality_grandpa::Message::Precommit(signed.precommit.clone()),
+			&signed.id,
+			&signed.signature,
+			proof.round,
+			authorities_set_id,
+			&mut signature_buffer,
+		) {
+			return Err(Error::InvalidAuthoritySignature)
+		}
+	}
+
+	Ok(())
+}
+
 /// Votes ancestries with useful methods.
 #[derive(RuntimeDebug)]
 pub struct AncestryChain<Header: HeaderT> {
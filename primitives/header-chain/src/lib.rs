@@ -23,12 +23,14 @@ use codec::{Codec, Decode, Encode, EncodeLike};
 use core::{clone::Clone, cmp::Eq, default::Default, fmt::Debug};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
-use sp_finality_grandpa::{AuthorityList, ConsensusLog, SetId, GRANDPA_ENGINE_ID};
+use sp_finality_grandpa::{AuthorityList, ConsensusLog, GRANDPA_ENGINE_ID};
 use sp_runtime::{generic::OpaqueDigestItemId, traits::Header as HeaderT, RuntimeDebug};
 use sp_std::boxed::Box;
 
 pub mod justification;
 
+pub use sp_finality_grandpa::SetId;
+
 /// A type that can be used as a parameter in a dispatchable function.
 ///
 /// When using `decl_module` all arguments for call functions must implement this trait.
@@ -52,6 +54,31 @@ impl AuthoritySet {
 	}
 }
 
+/// GRANDPA authority set change, that has been signalled by some header, but is not yet enacted.
+///
+/// Standard GRANDPA changes with non-zero delay are enacted when the header with `enact_at` number
+/// is finalized.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Clone)]
+pub struct ScheduledAuthoritySetChange<Number> {
+	/// List of GRANDPA authorities of the next set.
+	pub authorities: AuthorityList,
+	/// Number of the header that enacts the change.
+	pub enact_at: Number,
+}
+
+/// GRANDPA forced authority set change, that has been approved, but is not yet enacted.
+///
+/// Forced changes are issued when GRANDPA is stalled. Authorities of the next set start voting
+/// from the `median_last_finalized` header, so justifications of all headers after it are signed
+/// by the next set.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Clone)]
+pub struct ForcedAuthoritySetChange<Number> {
+	/// List of GRANDPA authorities of the next set.
+	pub authorities: AuthorityList,
+	/// Number of the last header, finalized by the current set.
+	pub median_last_finalized: Number,
+}
+
 /// Data required for initializing the bridge pallet.
 ///
 /// The bridge needs to know where to start its sync from, and this provides that initial context.
@@ -84,28 +111,25 @@ pub trait InclusionProofVerifier {
 }
 
 /// A trait for pallets which want to keep track of finalized headers from a bridged chain.
-pub trait HeaderChain<H, E> {
+///
+/// The trait is implemented by all bridge finality pallets (GRANDPA, BEEFY, ...), so that
+/// higher-level pallets may verify storage proofs of the bridged chain without knowing
+/// which finality gadget has been used to finalize its headers.
+pub trait HeaderChain<H: HeaderT> {
 	/// Get the best finalized header known to the header chain.
 	fn best_finalized() -> H;
 
-	/// Get the best authority set known to the header chain.
-	fn authority_set() -> AuthoritySet;
-
-	/// Write a header finalized by GRANDPA to the underlying pallet storage.
-	fn append_header(header: H) -> Result<(), E>;
+	/// Get finalized header with given hash, if it is known to the header chain.
+	fn finalized_header(hash: H::Hash) -> Option<H>;
 }
 
-impl<H: Default, E> HeaderChain<H, E> for () {
+impl<H: HeaderT + Default> HeaderChain<H> for () {
 	fn best_finalized() -> H {
 		H::default()
 	}
 
-	fn authority_set() -> AuthoritySet {
-		AuthoritySet::default()
-	}
-
-	fn append_header(_header: H) -> Result<(), E> {
-		Ok(())
+	fn finalized_header(_hash: H::Hash) -> Option<H> {
+		None
 	}
 }
 
@@ -130,3 +154,19 @@ pub fn find_grandpa_authorities_scheduled_change<H: HeaderT>(
 	// the right kind of consensus log.
 	header.digest().convert_first(|l| l.try_to(id).and_then(filter_log))
 }
+
+/// Find header digest that signals **forced** change of GRANDPA authorities set.
+pub fn find_grandpa_authorities_forced_change<H: HeaderT>(
+	header: &H,
+) -> Option<(H::Number, sp_finality_grandpa::ScheduledChange<H::Number>)> {
+	let id = OpaqueDigestItemId::Consensus(&GRANDPA_ENGINE_ID);
+
+	let filter_log = |log: ConsensusLog<H::Number>| match log {
+		ConsensusLog::ForcedChange(delay, change) => Some((delay, change)),
+		_ => None,
+	};
+
+	// find the first consensus digest with the right ID which converts to
+	// the right kind of consensus log.
+	header.digest().convert_first(|l| l.try_to(id).and_then(filter_log))
+}
@@ -16,14 +16,16 @@
 
 //! Tests for Grandpa Justification code.
 
-use bp_header_chain::justification::{verify_justification, Error};
+use bp_header_chain::justification::{
+	verify_and_optimize_justification, verify_equivocation_proof, verify_justification, Error,
+};
 use bp_test_utils::*;
 
 type TestHeader = sp_runtime::testing::Header;
 
 #[test]
 fn valid_justification_accepted() {
-	let authorities = vec![(ALICE, 1), (BOB, 1), (CHARLIE, 1), (DAVE, 1)];
+	let authorities = vec![(ALICE, 1), (BOB, 1), (CHARLIE, 1)];
 	let params = JustificationGeneratorParams {
 		header: test_header(1),
 		round: TEST_GRANDPA_ROUND,
@@ -54,7 +56,7 @@ fn valid_justification_accepted_with_single_fork() {
 		header: test_header(1),
 		round: TEST_GRANDPA_ROUND,
 		set_id: TEST_GRANDPA_SET_ID,
-		authorities: vec![(ALICE, 1), (BOB, 1), (CHARLIE, 1), (DAVE, 1), (EVE, 1)],
+		authorities: vec![(ALICE, 1), (BOB, 1), (CHARLIE, 1)],
 		ancestors: 5,
 		forks: 1,
 	};
@@ -75,16 +77,18 @@ fn valid_justification_accepted_with_arbitrary_number_of_authorities() {
 	use finality_grandpa::voter_set::VoterSet;
 	use sp_finality_grandpa::AuthorityId;
 
+	// 11 of 15 authorities is exactly the threshold
 	let n = 15;
+	let signers = 11;
 	let authorities = accounts(n).iter().map(|k| (*k, 1)).collect::<Vec<_>>();
 
 	let params = JustificationGeneratorParams {
 		header: test_header(1),
 		round: TEST_GRANDPA_ROUND,
 		set_id: TEST_GRANDPA_SET_ID,
-		authorities: authorities.clone(),
-		ancestors: n.into(),
-		forks: n.into(),
+		authorities: authorities[..signers].to_vec(),
+		ancestors: signers as u32,
+		forks: signers as u32,
 	};
 
 	let authorities = authorities
@@ -189,3 +193,222 @@ fn justification_is_invalid_if_we_dont_meet_threshold() {
 		Err(Error::TooLowCumulativeWeight),
 	);
 }
+
+#[test]
+fn justification_with_precommit_of_unknown_authority_rejected() {
+	let mut justification = make_default_justification::<TestHeader>(&test_header(1));
+	justification.commit.precommits[0] = signed_precommit::<TestHeader>(
+		&DAVE,
+		header_id::<TestHeader>(1),
+		justification.round,
+		TEST_GRANDPA_SET_ID,
+	);
+
+	assert_eq!(
+		verify_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			TEST_GRANDPA_SET_ID,
+			&voter_set(),
+			&justification,
+		),
+		Err(Error::RedundantVotesInJustification),
+	);
+}
+
+#[test]
+fn justification_with_duplicate_precommit_rejected() {
+	let mut justification = make_default_justification::<TestHeader>(&test_header(1));
+	justification.commit.precommits.push(justification.commit.precommits[0].clone());
+
+	assert_eq!(
+		verify_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			TEST_GRANDPA_SET_ID,
+			&voter_set(),
+			&justification,
+		),
+		Err(Error::RedundantVotesInJustification),
+	);
+}
+
+#[test]
+fn justification_with_precommits_above_threshold_rejected() {
+	// 3 of 4 authorities is enough to reach the threshold
+	let authorities = vec![(ALICE, 1), (BOB, 1), (CHARLIE, 1), (DAVE, 1)];
+	let params = JustificationGeneratorParams {
+		header: test_header(1),
+		round: TEST_GRANDPA_ROUND,
+		set_id: TEST_GRANDPA_SET_ID,
+		authorities: authorities.clone(),
+		ancestors: 4,
+		forks: 4,
+	};
+	let voter_set = finality_grandpa::voter_set::VoterSet::new(
+		authorities
+			.iter()
+			.map(|(id, w)| (sp_finality_grandpa::AuthorityId::from(*id), *w)),
+	)
+	.unwrap();
+
+	assert_eq!(
+		verify_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			TEST_GRANDPA_SET_ID,
+			&voter_set,
+			&make_justification_for_header::<TestHeader>(params),
+		),
+		Err(Error::RedundantVotesInJustification),
+	);
+}
+
+#[test]
+fn optimizer_removes_redundant_precommits_and_unused_ancestry_headers() {
+	// every authority votes for its own fork, so redundant precommits are also making some
+	// headers of `votes_ancestries` redundant
+	let authorities = vec![(ALICE, 1), (BOB, 1), (CHARLIE, 1), (DAVE, 1)];
+	let params = JustificationGeneratorParams {
+		header: test_header(1),
+		round: TEST_GRANDPA_ROUND,
+		set_id: TEST_GRANDPA_SET_ID,
+		authorities: authorities.clone(),
+		ancestors: 8,
+		forks: 4,
+	};
+	let voter_set = finality_grandpa::voter_set::VoterSet::new(
+		authorities
+			.iter()
+			.map(|(id, w)| (sp_finality_grandpa::AuthorityId::from(*id), *w)),
+	)
+	.unwrap();
+
+	// also add precommit of unknown authority and duplicate precommit
+	let mut justification = make_justification_for_header::<TestHeader>(params);
+	justification.commit.precommits.insert(
+		0,
+		signed_precommit::<TestHeader>(
+			&EVE,
+			header_id::<TestHeader>(1),
+			justification.round,
+			TEST_GRANDPA_SET_ID,
+		),
+	);
+	justification
+		.commit
+		.precommits
+		.insert(2, justification.commit.precommits[1].clone());
+
+	assert_eq!(
+		verify_and_optimize_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			TEST_GRANDPA_SET_ID,
+			&voter_set,
+			&mut justification,
+		),
+		Ok(()),
+	);
+	assert_eq!(justification.commit.precommits.len(), 3);
+	assert_eq!(justification.votes_ancestries.len(), 6);
+	assert_eq!(
+		verify_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			TEST_GRANDPA_SET_ID,
+			&voter_set,
+			&justification,
+		),
+		Ok(()),
+	);
+}
+
+#[test]
+fn optimizer_does_not_change_optimal_justification() {
+	let mut justification = make_default_justification::<TestHeader>(&test_header(1));
+	let original_justification = justification.clone();
+
+	assert_eq!(
+		verify_and_optimize_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			TEST_GRANDPA_SET_ID,
+			&voter_set(),
+			&mut justification,
+		),
+		Ok(()),
+	);
+	assert_eq!(justification, original_justification);
+}
+
+#[test]
+fn optimizer_rejects_invalid_justification() {
+	let mut justification = make_default_justification::<TestHeader>(&test_header(1));
+	justification.commit.precommits[0].signature = Default::default();
+
+	assert_eq!(
+		verify_and_optimize_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			TEST_GRANDPA_SET_ID,
+			&voter_set(),
+			&mut justification,
+		),
+		Err(Error::InvalidAuthoritySignature),
+	);
+}
+
+#[test]
+fn valid_equivocation_proof_accepted() {
+	assert_eq!(
+		verify_equivocation_proof::<TestHeader>(
+			TEST_GRANDPA_SET_ID,
+			&voter_set(),
+			&make_equivocation_proof(&ALICE, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID),
+		),
+		Ok(()),
+	);
+}
+
+#[test]
+fn equivocation_proof_with_precommits_of_different_authorities_rejected() {
+	let mut proof =
+		make_equivocation_proof::<TestHeader>(&ALICE, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID);
+	proof.second =
+		make_equivocation_proof::<TestHeader>(&BOB, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID).second;
+
+	assert_eq!(
+		verify_equivocation_proof::<TestHeader>(TEST_GRANDPA_SET_ID, &voter_set(), &proof),
+		Err(Error::EquivocationAuthoritiesMismatch),
+	);
+}
+
+#[test]
+fn equivocation_proof_with_identical_precommits_rejected() {
+	let mut proof =
+		make_equivocation_proof::<TestHeader>(&ALICE, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID);
+	proof.second = proof.first.clone();
+
+	assert_eq!(
+		verify_equivocation_proof::<TestHeader>(TEST_GRANDPA_SET_ID, &voter_set(), &proof),
+		Err(Error::NotAnEquivocation),
+	);
+}
+
+#[test]
+fn equivocation_proof_of_unknown_authority_rejected() {
+	assert_eq!(
+		verify_equivocation_proof::<TestHeader>(
+			TEST_GRANDPA_SET_ID,
+			&voter_set(),
+			&make_equivocation_proof(&FERDIE, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID),
+		),
+		Err(Error::UnknownAuthority),
+	);
+}
+
+#[test]
+fn equivocation_proof_signed_in_other_round_rejected() {
+	let mut proof =
+		make_equivocation_proof::<TestHeader>(&ALICE, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID);
+	proof.round += 1;
+
+	assert_eq!(
+		verify_equivocation_proof::<TestHeader>(TEST_GRANDPA_SET_ID, &voter_set(), &proof),
+		Err(Error::InvalidAuthoritySignature),
+	);
+}
@@ -33,6 +33,13 @@ pub type Weight = u64;
 /// Spec version type.
 pub type SpecVersion = u32;
 
+/// Nonce of the target account, that is used to protect `CallOrigin::TargetAccount` messages
+/// from being replayed.
+pub type TargetAccountNonce = u64;
+
+/// Number of the target chain block, after which the message is considered expired.
+pub type ExpiryBlockNumber = u64;
+
 /// A generic trait to dispatch arbitrary messages delivered over the bridge.
 pub trait MessageDispatch<AccountId, BridgeMessageId> {
 	/// A type of the message to be dispatched.
@@ -86,17 +93,13 @@ pub enum CallOrigin<SourceChainAccountId, TargetChainAccountPublic, TargetChainS
 	///
 	/// The account can be identified by `TargetChainAccountPublic`. The proof that the
 	/// `SourceChainAccountId` controls `TargetChainAccountPublic` is the `TargetChainSignature`
-	/// over `(Call, SourceChainAccountId, TargetChainSpecVersion, SourceChainBridgeId).encode()`.
+	/// over `(Call, SourceChainAccountId, TargetChainSpecVersion, SourceChainBridgeId,
+	/// TargetChainBridgeId, TargetAccountNonce).encode()`.
 	///
-	/// NOTE sending messages using this origin (or any other) does not have replay protection!
-	/// The assumption is that both the source account and the target account is controlled by
-	/// the same entity, so source-chain replay protection is sufficient.
-	/// As a consequence, it's extremely important for the target chain user to never produce
-	/// a signature with their target-private key on something that could be sent over the bridge,
-	/// i.e. if the target user signs `(<some-source-account-id>, Call::Transfer(X, 5))`
-	/// The owner of `some-source-account-id` can send that message multiple times, which would
-	/// result with multiple transfer calls being dispatched on the target chain.
-	/// So please, NEVER USE YOUR PRIVATE KEY TO SIGN SOMETHING YOU DON'T FULLY UNDERSTAND!
+	/// The `TargetAccountNonce` is the nonce of the target account, tracked by the dispatcher at
+	/// the target chain. It is incremented every time the signature of the target account is
+	/// verified, so every signed message may only be dispatched once. Messages signed with the
+	/// stale nonce are rejected with signature mismatch.
 	TargetAccount(SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature),
 
 	/// Call is sent by the `SourceChainAccountId` on the source chain. On the target chain it is
@@ -111,6 +114,11 @@ pub enum CallOrigin<SourceChainAccountId, TargetChainAccountPublic, TargetChainS
 }
 
 /// Message payload type used by dispatch module.
+///
+/// The `expiry` field has been added after the payload format has been deployed, so it is the
+/// last field of the payload. Payloads that have been encoded before it has been added, end right
+/// after the `call` and can't be decoded. Queued outbound messages are upgraded by the messages
+/// pallet storage migration.
 #[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq)]
 pub struct MessagePayload<
 	SourceChainAccountId,
@@ -130,6 +138,9 @@ pub struct MessagePayload<
 	pub dispatch_fee_payment: DispatchFeePayment,
 	/// The call itself.
 	pub call: Call,
+	/// Number of the target chain block, after which the message is not dispatched. If the
+	/// message is delivered after this block, the message fee is refunded to the sender.
+	pub expiry: Option<ExpiryBlockNumber>,
 }
 
 impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature> Size
@@ -139,3 +150,43 @@ impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature> Size
 		self.call.len() as _
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	type TestPayload = MessagePayload<u64, u64, u64, Vec<u8>>;
+
+	fn payload(expiry: Option<ExpiryBlockNumber>) -> TestPayload {
+		MessagePayload {
+			spec_version: 1,
+			weight: 100,
+			origin: CallOrigin::SourceAccount(42),
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			call: vec![1, 2, 3],
+			expiry,
+		}
+	}
+
+	#[test]
+	fn payload_without_expiry_is_not_decoded() {
+		let payload = payload(None);
+		let encoded_without_expiry = (
+			payload.spec_version,
+			payload.weight,
+			&payload.origin,
+			payload.dispatch_fee_payment,
+			&payload.call,
+		)
+			.encode();
+
+		assert!(TestPayload::decode(&mut &encoded_without_expiry[..]).is_err());
+	}
+
+	#[test]
+	fn payload_with_expiry_is_decoded() {
+		let payload = payload(Some(77));
+
+		assert_eq!(TestPayload::decode(&mut &payload.encode()[..]).unwrap(), payload);
+	}
+}
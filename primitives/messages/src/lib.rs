@@ -24,7 +24,7 @@
 
 use bitvec::prelude::*;
 use bp_runtime::messages::DispatchFeePayment;
-use codec::{Decode, Encode};
+use codec::{Compact, Decode, Encode};
 use frame_support::RuntimeDebug;
 use sp_std::{collections::vec_deque::VecDeque, prelude::*};
 
@@ -71,6 +71,36 @@ impl Parameter for () {
 /// Lane identifier.
 pub type LaneId = [u8; 4];
 
+/// Lane lifecycle state.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum LaneState {
+	/// The lane is opened and all operations are allowed.
+	Opened,
+	/// The lane is being closed. New outbound messages are rejected, but already queued
+	/// messages are still delivered and their delivery is still confirmed.
+	Closing,
+	/// The lane is closed. All operations are prohibited.
+	Closed,
+}
+
+/// Lane information, stored in the lanes registry.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct LaneInfo {
+	/// Lane lifecycle state.
+	pub state: LaneState,
+	/// Lane operating mode. It is checked in addition to the pallet operating mode.
+	pub operating_mode: OperatingMode,
+}
+
+impl LaneInfo {
+	/// Returns info of just opened lane.
+	pub fn opened() -> Self {
+		LaneInfo { state: LaneState::Opened, operating_mode: OperatingMode::Normal }
+	}
+}
+
 /// Message nonce. Valid messages will never have 0 nonce.
 pub type MessageNonce = u64;
 
@@ -162,9 +192,15 @@ impl<RelayerId> InboundLaneData<RelayerId> {
 		let dispatch_results_per_byte = 8;
 		let dispatch_result_size =
 			sp_std::cmp::max(relayers_entries, messages_count / dispatch_results_per_byte);
+		// unspent weights are compact-encoded, so we're using the maximal size of encoded `u64`
+		let unspent_weight_size = Compact(Weight::MAX).encoded_size() as u32;
+		let unspent_weights_size = messages_count.checked_mul(unspent_weight_size)?;
 		relayers_size
 			.checked_add(message_nonce_size)
 			.and_then(|result| result.checked_add(dispatch_result_size))
+			.and_then(|result| result.checked_add(unspent_weights_size))
+			// expired messages bitvec has the same size as the dispatch results bitvec
+			.and_then(|result| result.checked_add(dispatch_result_size))
 	}
 
 	/// Nonce of the last message that has been delivered to this (target) chain.
@@ -194,6 +230,23 @@ pub struct MessageDetails<OutboundMessageFee> {
 /// Bit vector of message dispatch results.
 pub type DispatchResultsBitVec = BitVec<Msb0, u8>;
 
+/// Dispatch status of the outbound message, as it has been reported by the bridged chain.
+///
+/// The status is stored at the source chain when message delivery is confirmed, so that
+/// message senders may inspect results of their messages dispatch.
+#[derive(Clone, Copy, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+pub struct MessageDispatchStatus {
+	/// Dispatch result flag, returned by the message dispatcher at the bridged chain. See
+	/// `dispatch_result` field of the `bp_runtime::messages::MessageDispatchResult` structure
+	/// for more information.
+	pub dispatch_result: bool,
+	/// Part of the declared dispatch weight that has not been spent by the dispatcher.
+	pub unspent_weight: Weight,
+	/// True if the message has expired before it has been delivered to the bridged chain. Expired
+	/// messages are not dispatched and their fee may be refunded to the sender.
+	pub expired: bool,
+}
+
 /// Unrewarded relayer entry stored in the inbound lane data.
 ///
 /// This struct represents a continuous range of messages that have been delivered by the same
@@ -217,16 +270,39 @@ pub struct DeliveredMessages {
 	/// message in the `[begin; end]` range. See `dispatch_result` field of the
 	/// `bp_runtime::messages::MessageDispatchResult` structure for more information.
 	pub dispatch_results: DispatchResultsBitVec,
+	/// Unspent dispatch weight, returned by the message dispatcher for every message in
+	/// the `[begin; end]` range. See `unspent_weight` field of the
+	/// `bp_runtime::messages::MessageDispatchResult` structure for more information.
+	///
+	/// Unspent weight of most messages is zero or close to zero, so we're using compact encoding
+	/// here to keep the delivery proof small.
+	pub unspent_weights: Vec<Compact<Weight>>,
+	/// Expiration flag (`false`/`true`) for every message in the `[begin; end]` range. Expired
+	/// messages are delivered, but not dispatched.
+	pub expired_messages: DispatchResultsBitVec,
 }
 
 impl DeliveredMessages {
 	/// Create new `DeliveredMessages` struct that confirms delivery of single nonce with given
-	/// dispatch result.
-	pub fn new(nonce: MessageNonce, dispatch_result: bool) -> Self {
+	/// dispatch result and unspent dispatch weight.
+	pub fn new(nonce: MessageNonce, dispatch_result: bool, unspent_weight: Weight) -> Self {
 		DeliveredMessages {
 			begin: nonce,
 			end: nonce,
 			dispatch_results: bitvec![Msb0, u8; if dispatch_result { 1 } else { 0 }],
+			unspent_weights: vec![Compact(unspent_weight)],
+			expired_messages: bitvec![Msb0, u8; 0],
+		}
+	}
+
+	/// Create new `DeliveredMessages` struct that confirms delivery of single expired nonce.
+	pub fn new_expired(nonce: MessageNonce) -> Self {
+		DeliveredMessages {
+			begin: nonce,
+			end: nonce,
+			dispatch_results: bitvec![Msb0, u8; 0],
+			unspent_weights: vec![Compact(0)],
+			expired_messages: bitvec![Msb0, u8; 1],
 		}
 	}
 
@@ -240,9 +316,19 @@ impl DeliveredMessages {
 	}
 
 	/// Note new dispatched message.
-	pub fn note_dispatched_message(&mut self, dispatch_result: bool) {
+	pub fn note_dispatched_message(&mut self, dispatch_result: bool, unspent_weight: Weight) {
 		self.end += 1;
 		self.dispatch_results.push(dispatch_result);
+		self.unspent_weights.push(Compact(unspent_weight));
+		self.expired_messages.push(false);
+	}
+
+	/// Note new expired message.
+	pub fn note_expired_message(&mut self) {
+		self.end += 1;
+		self.dispatch_results.push(false);
+		self.unspent_weights.push(Compact(0));
+		self.expired_messages.push(true);
 	}
 
 	/// Returns true if delivered messages contain message with given nonce.
@@ -264,6 +350,60 @@ impl DeliveredMessages {
 		let index = nonce.checked_sub(self.begin).expect(INVALID_NONCE) as usize;
 		*self.dispatch_results.get(index).expect(INVALID_NONCE)
 	}
+
+	/// Get unspent dispatch weight by message nonce.
+	///
+	/// Panics if message nonce is not in the `begin..=end` range. Typically you'll first
+	/// check if message is within the range by calling `contains_message`.
+	pub fn message_unspent_weight(&self, nonce: MessageNonce) -> Weight {
+		const INVALID_NONCE: &str = "Invalid nonce used to index unspent_weights";
+
+		let index = nonce.checked_sub(self.begin).expect(INVALID_NONCE) as usize;
+		self.unspent_weights.get(index).expect(INVALID_NONCE).0
+	}
+
+	/// Returns true if message with given nonce has expired before it has been delivered.
+	///
+	/// Panics if message nonce is not in the `begin..=end` range. Typically you'll first
+	/// check if message is within the range by calling `contains_message`.
+	pub fn message_expired(&self, nonce: MessageNonce) -> bool {
+		const INVALID_NONCE: &str = "Invalid nonce used to index expired_messages";
+
+		let index = nonce.checked_sub(self.begin).expect(INVALID_NONCE) as usize;
+		*self.expired_messages.get(index).expect(INVALID_NONCE)
+	}
+
+	/// Update dispatch result and unspent dispatch weight of the message with given nonce.
+	///
+	/// This is used when the message is dispatched after it has been delivered (e.g. from the
+	/// overweight messages queue).
+	///
+	/// Panics if message nonce is not in the `begin..=end` range. Typically you'll first
+	/// check if message is within the range by calling `contains_message`.
+	pub fn set_message_dispatch_result(
+		&mut self,
+		nonce: MessageNonce,
+		dispatch_result: bool,
+		unspent_weight: Weight,
+	) {
+		const INVALID_NONCE: &str = "Invalid nonce used to index dispatch_results";
+
+		let index = nonce.checked_sub(self.begin).expect(INVALID_NONCE) as usize;
+		self.dispatch_results.set(index, dispatch_result);
+		*self.unspent_weights.get_mut(index).expect(INVALID_NONCE) = Compact(unspent_weight);
+	}
+
+	/// Get dispatch status of the message with given nonce.
+	///
+	/// Panics if message nonce is not in the `begin..=end` range. Typically you'll first
+	/// check if message is within the range by calling `contains_message`.
+	pub fn message_dispatch_status(&self, nonce: MessageNonce) -> MessageDispatchStatus {
+		MessageDispatchStatus {
+			dispatch_result: self.message_dispatch_result(nonce),
+			unspent_weight: self.message_unspent_weight(nonce),
+			expired: self.message_expired(nonce),
+		}
+	}
 }
 
 /// Gist of `InboundLaneData::relayers` field used by runtime APIs.
@@ -329,10 +469,10 @@ mod tests {
 		assert_eq!(
 			total_unrewarded_messages(
 				&vec![
-					UnrewardedRelayer { relayer: 1, messages: DeliveredMessages::new(0, true) },
+					UnrewardedRelayer { relayer: 1, messages: DeliveredMessages::new(0, true, 0) },
 					UnrewardedRelayer {
 						relayer: 2,
-						messages: DeliveredMessages::new(MessageNonce::MAX, true)
+						messages: DeliveredMessages::new(MessageNonce::MAX, true, 0)
 					},
 				]
 				.into_iter()
@@ -363,13 +503,20 @@ mod tests {
 					.map(|i| {
 						let mut entry = UnrewardedRelayer {
 							relayer: i,
-							messages: DeliveredMessages::new(i as _, true),
+							messages: DeliveredMessages::new(i as _, true, 0),
 						};
 						entry.messages.dispatch_results = bitvec![
 							Msb0, u8;
 							1;
 							(messages_count / relayer_entries) as _
 						];
+						entry.messages.unspent_weights =
+							vec![Compact(Weight::MAX); (messages_count / relayer_entries) as _];
+						entry.messages.expired_messages = bitvec![
+							Msb0, u8;
+							0;
+							(messages_count / relayer_entries) as _
+						];
 						entry
 					})
 					.collect(),
@@ -391,8 +538,13 @@ mod tests {
 
 	#[test]
 	fn message_dispatch_result_works() {
-		let delivered_messages =
-			DeliveredMessages { begin: 100, end: 150, dispatch_results: bitvec![Msb0, u8; 1; 151] };
+		let delivered_messages = DeliveredMessages {
+			begin: 100,
+			end: 150,
+			dispatch_results: bitvec![Msb0, u8; 1; 151],
+			unspent_weights: (0..151).map(Compact).collect(),
+			expired_messages: bitvec![Msb0, u8; 0; 151],
+		};
 
 		assert!(!delivered_messages.contains_message(99));
 		assert!(delivered_messages.contains_message(100));
@@ -400,5 +552,39 @@ mod tests {
 		assert!(!delivered_messages.contains_message(151));
 
 		assert!(delivered_messages.message_dispatch_result(125));
+		assert_eq!(delivered_messages.message_unspent_weight(125), 25);
+		assert_eq!(
+			delivered_messages.message_dispatch_status(125),
+			MessageDispatchStatus { dispatch_result: true, unspent_weight: 25, expired: false },
+		);
+	}
+
+	#[test]
+	fn expired_messages_are_noted() {
+		let mut delivered_messages = DeliveredMessages::new(100, true, 10);
+		delivered_messages.note_expired_message();
+		delivered_messages.note_dispatched_message(false, 20);
+
+		assert!(!delivered_messages.message_expired(100));
+		assert!(delivered_messages.message_expired(101));
+		assert!(!delivered_messages.message_expired(102));
+		assert_eq!(
+			delivered_messages.message_dispatch_status(101),
+			MessageDispatchStatus { dispatch_result: false, unspent_weight: 0, expired: true },
+		);
+	}
+
+	#[test]
+	fn message_dispatch_result_may_be_updated() {
+		let mut delivered_messages = DeliveredMessages::new(100, true, 10);
+		delivered_messages.note_dispatched_message(false, 0);
+		delivered_messages.note_dispatched_message(true, 30);
+
+		delivered_messages.set_message_dispatch_result(101, true, 20);
+
+		assert!(delivered_messages.message_dispatch_result(100));
+		assert!(delivered_messages.message_dispatch_result(101));
+		assert!(delivered_messages.message_dispatch_result(102));
+		assert_eq!(delivered_messages.unspent_weights, vec![Compact(10), Compact(20), Compact(30)],);
 	}
 }
@@ -25,6 +25,7 @@ use sp_std::{
 	collections::{btree_map::BTreeMap, vec_deque::VecDeque},
 	fmt::Debug,
 	ops::RangeInclusive,
+	vec::Vec,
 };
 
 /// The sender of the message on the source chain.
@@ -122,6 +123,17 @@ pub trait MessageDeliveryAndDispatchPayment<AccountId, Balance> {
 		relayer_fund_account: &AccountId,
 	) -> Result<(), Self::Error>;
 
+	/// Refund delivery_and_dispatch_fee from the relayers-fund account back to the submitter
+	/// account.
+	///
+	/// This is called for messages that have expired before they have been delivered, so
+	/// relayers aren't rewarded for their delivery.
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &Sender<AccountId>,
+		fee: &Balance,
+		relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error>;
+
 	/// Pay rewards for delivering messages to the given relayers.
 	///
 	/// The implementation may also choose to pay reward to the `confirmation_relayer`, which is
@@ -135,6 +147,24 @@ pub trait MessageDeliveryAndDispatchPayment<AccountId, Balance> {
 	);
 }
 
+/// Computes the part of the message fee that corresponds to the unspent dispatch weight.
+///
+/// When dispatch fee is paid at the source chain, the submitter prepays dispatch of the whole
+/// declared message weight. If the dispatch at the target chain has spent less weight than
+/// declared, the difference is reported back with the delivery confirmation and the submitter
+/// may be refunded with the amount, returned by this trait.
+pub trait UnspentDispatchFeeRefund<Payload, Balance> {
+	/// Returns the amount that should be refunded to the message submitter, given the unspent
+	/// dispatch weight of the message.
+	fn unspent_dispatch_fee(payload: &Payload, unspent_weight: Weight) -> Balance;
+}
+
+impl<Payload, Balance: Default> UnspentDispatchFeeRefund<Payload, Balance> for () {
+	fn unspent_dispatch_fee(_payload: &Payload, _unspent_weight: Weight) -> Balance {
+		Default::default()
+	}
+}
+
 /// Send message artifacts.
 #[derive(RuntimeDebug, PartialEq)]
 pub struct SendMessageArtifacts {
@@ -158,6 +188,25 @@ pub trait MessagesBridge<AccountId, Balance, Payload> {
 		message: Payload,
 		delivery_and_dispatch_fee: Balance,
 	) -> Result<SendMessageArtifacts, Self::Error>;
+
+	/// Send multiple messages over the same lane.
+	///
+	/// Returns artifacts of every sent message. The default implementation sends messages
+	/// one-by-one, so if some message can't be sent, previous messages stay sent. Implementations
+	/// that need to send either all messages, or none of them, shall override this method.
+	fn send_messages(
+		sender: Sender<AccountId>,
+		lane: LaneId,
+		messages: Vec<(Payload, Balance)>,
+	) -> Result<Vec<SendMessageArtifacts>, Self::Error>
+	where
+		AccountId: Clone,
+	{
+		messages
+			.into_iter()
+			.map(|(message, fee)| Self::send_message(sender.clone(), lane, message, fee))
+			.collect()
+	}
 }
 
 /// Bridge that does nothing when message is being sent.
@@ -274,6 +323,14 @@ impl<AccountId, Balance> MessageDeliveryAndDispatchPayment<AccountId, Balance>
 		Err(ALL_OUTBOUND_MESSAGES_REJECTED)
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		_submitter: &Sender<AccountId>,
+		_fee: &Balance,
+		_relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error> {
+		Err(ALL_OUTBOUND_MESSAGES_REJECTED)
+	}
+
 	fn pay_relayers_rewards(
 		_lane_id: LaneId,
 		_messages_relayers: VecDeque<UnrewardedRelayer<AccountId>>,
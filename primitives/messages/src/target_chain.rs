@@ -18,7 +18,10 @@
 
 use crate::{LaneId, Message, MessageData, MessageKey, OutboundLaneData};
 
-use bp_runtime::{messages::MessageDispatchResult, Size};
+use bp_runtime::{
+	messages::{DispatchFeePayment, MessageDispatchResult},
+	Size,
+};
 use codec::{Decode, Encode, Error as CodecError};
 use frame_support::{weights::Weight, Parameter, RuntimeDebug};
 use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, prelude::*};
@@ -96,6 +99,21 @@ pub trait MessageDispatch<AccountId, Fee> {
 	/// of dispatch weight.
 	fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, Fee>) -> Weight;
 
+	/// Returns true if the message has expired and must not be dispatched.
+	///
+	/// Expired messages are still delivered (so that the lane isn't blocked), but they're not
+	/// dispatched. The source chain learns about that from the delivery confirmation and may
+	/// refund the message fee to the sender.
+	fn is_expired(message: &DispatchMessage<Self::DispatchPayload, Fee>) -> bool;
+
+	/// Returns where the dispatch fee of the message is paid.
+	///
+	/// Messages with invalid payload should be treated as prepaid at the source chain, because
+	/// nobody pays dispatch fee for them at this chain.
+	fn dispatch_fee_payment(
+		message: &DispatchMessage<Self::DispatchPayload, Fee>,
+	) -> DispatchFeePayment;
+
 	/// Called when inbound message is received.
 	///
 	/// It is up to the implementers of this trait to determine whether the message
@@ -159,6 +177,16 @@ impl<AccountId, Fee> MessageDispatch<AccountId, Fee> for ForbidInboundMessages {
 		Weight::MAX
 	}
 
+	fn is_expired(_message: &DispatchMessage<Self::DispatchPayload, Fee>) -> bool {
+		false
+	}
+
+	fn dispatch_fee_payment(
+		_message: &DispatchMessage<Self::DispatchPayload, Fee>,
+	) -> DispatchFeePayment {
+		DispatchFeePayment::AtSourceChain
+	}
+
 	fn dispatch(
 		_: &AccountId,
 		_: DispatchMessage<Self::DispatchPayload, Fee>,
@@ -0,0 +1,72 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Primitives of parachains module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bp_polkadot_core::parachains::{ParaHash, ParaId};
+use codec::{Decode, Encode};
+use frame_support::RuntimeDebug;
+use sp_core::storage::StorageKey;
+
+/// Name of the `paras` pallet at Polkadot-like relay chains.
+pub const PARAS_PALLET_NAME: &str = "Paras";
+
+/// Name of the `Heads` map of the `paras` pallet.
+pub const PARAS_HEADS_MAP_NAME: &str = "Heads";
+
+/// Name of the `BestParaHeads` map of the bridge parachains pallet.
+pub const BEST_PARA_HEADS_MAP_NAME: &str = "BestParaHeads";
+
+/// Best known parachain head as it is stored in the runtime storage of the bridge parachains
+/// pallet.
+#[derive(Decode, Encode, PartialEq, RuntimeDebug)]
+pub struct BestParaHead<RelayBlockNumber> {
+	/// Number of relay block where this head has been updated.
+	pub at_relay_block_number: RelayBlockNumber,
+	/// Hash of parachain head.
+	pub head_hash: ParaHash,
+	/// Current ring buffer position for this parachain.
+	pub next_imported_hash_position: u32,
+}
+
+/// Returns runtime storage key of given parachain head at the source (relay) chain.
+///
+/// The key is used to build (at the relay chain) and verify (at the bridged chain) proofs of
+/// parachain heads.
+pub fn parachain_head_storage_key_at_source(
+	paras_pallet_name: &str,
+	para_id: ParaId,
+) -> StorageKey {
+	bp_runtime::storage_map_final_key_twox64_concat(
+		paras_pallet_name,
+		PARAS_HEADS_MAP_NAME,
+		&para_id.encode(),
+	)
+}
+
+/// Returns runtime storage key of best known parachain head at the target (bridged) chain.
+pub fn best_parachain_head_storage_key_at_target(
+	bridge_parachains_pallet_name: &str,
+	para_id: ParaId,
+) -> StorageKey {
+	bp_runtime::storage_map_final_key_blake2_128concat(
+		bridge_parachains_pallet_name,
+		BEST_PARA_HEADS_MAP_NAME,
+		&para_id.encode(),
+	)
+}
@@ -0,0 +1,66 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Primitives of polkadot-like chains, that are related to parachains functionality.
+//!
+//! Even though this (bridges) repository references polkadot repository, we can't
+//! reference polkadot crates from pallets. That's because bridges repository is
+//! included in the polkadot repository and included pallets are used by polkadot
+//! chains. Having pallets that are referencing polkadot, would mean that there may
+//! be two versions of polkadot crates included in the runtime. Which is bad.
+
+use frame_support::RuntimeDebug;
+use parity_scale_codec::{Decode, Encode};
+use sp_core::Hasher;
+use sp_std::vec::Vec;
+
+/// Parachain id.
+///
+/// This is an equivalent of the `polkadot_parachain::Id`, which is a plain `u32` wrapper.
+#[derive(
+	Clone, Copy, Decode, Default, Encode, Eq, Hash, Ord, PartialEq, PartialOrd, RuntimeDebug,
+)]
+pub struct ParaId(pub u32);
+
+impl From<u32> for ParaId {
+	fn from(id: u32) -> Self {
+		ParaId(id)
+	}
+}
+
+/// Parachain head.
+///
+/// This is an equivalent of the `polkadot_parachain::HeadData`.
+///
+/// The parachain head means (at least in Cumulus) a SCALE-encoded parachain header.
+#[derive(Clone, Decode, Default, Encode, Eq, PartialEq, RuntimeDebug)]
+pub struct ParaHead(pub Vec<u8>);
+
+impl ParaHead {
+	/// Returns the hash of this head data.
+	pub fn hash(&self) -> ParaHash {
+		ParaHasher::hash(&self.0)
+	}
+}
+
+/// Parachain head hash.
+pub type ParaHash = crate::Hash;
+
+/// Parachain head hasher.
+pub type ParaHasher = crate::Hasher;
+
+/// Raw storage proof of parachain heads, stored in polkadot-like chain runtime.
+pub type ParaHeadsProof = Vec<Vec<u8>>;
@@ -18,7 +18,7 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use bp_header_chain::justification::GrandpaJustification;
+use bp_header_chain::justification::{GrandpaEquivocationProof, GrandpaJustification};
 use codec::Encode;
 use sp_application_crypto::TryFrom;
 use sp_finality_grandpa::{AuthorityId, AuthoritySignature, AuthorityWeight, SetId};
@@ -190,6 +190,29 @@ pub fn signed_precommit<H: HeaderT>(
 	finality_grandpa::SignedPrecommit { precommit, signature, id }
 }
 
+/// Make equivocation proof: two precommits of given authority for two different (competing)
+/// headers, signed in the same round.
+pub fn make_equivocation_proof<H: HeaderT>(
+	signer: &Account,
+	round: u64,
+	set_id: SetId,
+) -> GrandpaEquivocationProof<H> {
+	let header = test_header::<H>(One::one());
+	let mut fork_header = header.clone();
+	fork_header.set_parent_hash(Default::default());
+
+	GrandpaEquivocationProof {
+		round,
+		first: signed_precommit::<H>(signer, (header.hash(), *header.number()), round, set_id),
+		second: signed_precommit::<H>(
+			signer,
+			(fork_header.hash(), *fork_header.number()),
+			round,
+			set_id,
+		),
+	}
+}
+
 /// Get a header for testing.
 ///
 /// The correct parent hash will be used if given a non-zero header.
@@ -60,6 +60,8 @@ impl SubstrateFinalitySyncPipeline for KusamaFinalityToPolkadot {
 
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str =
 		bp_kusama::BEST_FINALIZED_KUSAMA_HEADER_METHOD;
+	const AUTHORITY_SET_AT_TARGET: &'static str = bp_kusama::KUSAMA_AUTHORITY_SET_METHOD;
+	const HEADER_SET_ID_AT_TARGET: &'static str = bp_kusama::KUSAMA_HEADER_SET_ID_METHOD;
 
 	type TargetChain = Polkadot;
 
@@ -109,6 +111,50 @@ impl SubstrateFinalitySyncPipeline for KusamaFinalityToPolkadot {
 
 		Bytes(transaction.encode())
 	}
+
+	fn make_submit_finality_proofs_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Polkadot>,
+		transaction_nonce: bp_runtime::IndexOf<Polkadot>,
+		finality_proofs: Vec<(KusamaSyncHeader, GrandpaJustification<bp_kusama::Header>)>,
+	) -> Bytes {
+		let call = relay_polkadot_client::runtime::Call::BridgeKusamaGrandpa(
+			relay_polkadot_client::runtime::BridgeKusamaGrandpaCall::submit_finality_proofs(
+				finality_proofs
+					.into_iter()
+					.map(|(header, proof)| (header.into_inner(), proof))
+					.collect(),
+			),
+		);
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Polkadot::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
+
+	fn make_halt_bridge_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Polkadot>,
+		transaction_nonce: bp_runtime::IndexOf<Polkadot>,
+	) -> Bytes {
+		let call = relay_polkadot_client::runtime::Call::BridgeKusamaGrandpa(
+			relay_polkadot_client::runtime::BridgeKusamaGrandpaCall::set_operational(false),
+		);
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Polkadot::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
 }
 
 #[cfg(test)]
@@ -47,6 +47,8 @@ impl SubstrateFinalitySyncPipeline for MillauFinalityToRialto {
 
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str =
 		bp_millau::BEST_FINALIZED_MILLAU_HEADER_METHOD;
+	const AUTHORITY_SET_AT_TARGET: &'static str = bp_millau::MILLAU_AUTHORITY_SET_METHOD;
+	const HEADER_SET_ID_AT_TARGET: &'static str = bp_millau::MILLAU_HEADER_SET_ID_METHOD;
 
 	type TargetChain = Rialto;
 
@@ -77,4 +79,47 @@ impl SubstrateFinalitySyncPipeline for MillauFinalityToRialto {
 
 		Bytes(transaction.encode())
 	}
+
+	fn make_submit_finality_proofs_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Rialto>,
+		transaction_nonce: IndexOf<Rialto>,
+		finality_proofs: Vec<(MillauSyncHeader, GrandpaJustification<bp_millau::Header>)>,
+	) -> Bytes {
+		let call = rialto_runtime::BridgeGrandpaMillauCall::submit_finality_proofs(
+			finality_proofs
+				.into_iter()
+				.map(|(header, proof)| (header.into_inner(), proof))
+				.collect(),
+		)
+		.into();
+
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Rialto::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
+
+	fn make_halt_bridge_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Rialto>,
+		transaction_nonce: IndexOf<Rialto>,
+	) -> Bytes {
+		let call = rialto_runtime::BridgeGrandpaMillauCall::set_operational(false).into();
+
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Rialto::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
 }
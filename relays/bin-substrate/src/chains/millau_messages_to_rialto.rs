@@ -39,7 +39,7 @@ use substrate_relay_helper::{
 		select_delivery_transaction_limits, MessagesRelayParams, StandaloneMessagesMetrics,
 		SubstrateMessageLane, SubstrateMessageLaneToSubstrate,
 	},
-	messages_source::SubstrateMessagesSource,
+	messages_source::{select_lanes_to_serve, SubstrateMessagesSource},
 	messages_target::SubstrateMessagesTarget,
 };
 
@@ -61,6 +61,7 @@ impl SubstrateMessageLane for MillauMessagesToRialto {
 		bp_rialto::TO_RIALTO_LATEST_GENERATED_NONCE_METHOD;
 	const OUTBOUND_LANE_LATEST_RECEIVED_NONCE_METHOD: &'static str =
 		bp_rialto::TO_RIALTO_LATEST_RECEIVED_NONCE_METHOD;
+	const OUTBOUND_LANE_LANES_METHOD: &'static str = bp_rialto::TO_RIALTO_LANES_METHOD;
 
 	const INBOUND_LANE_LATEST_RECEIVED_NONCE_METHOD: &'static str =
 		bp_millau::FROM_MILLAU_LATEST_RECEIVED_NONCE_METHOD;
@@ -80,6 +81,10 @@ impl SubstrateMessageLane for MillauMessagesToRialto {
 	const PAY_INBOUND_DISPATCH_FEE_WEIGHT_AT_TARGET_CHAIN: Weight =
 		bp_rialto::PAY_INBOUND_DISPATCH_FEE_WEIGHT;
 
+	const RELAYERS_PALLET_NAME_AT_TARGET: Option<&'static str> =
+		Some(bp_rialto::RELAYERS_PALLET_NAME);
+	const DELIVERY_SLOT_LENGTH_AT_TARGET: u64 = bp_rialto::DELIVERY_SLOT_LENGTH as _;
+
 	type SourceChain = Millau;
 	type TargetChain = Rialto;
 
@@ -170,8 +175,13 @@ pub async fn run(
 	let stall_timeout = Duration::from_secs(5 * 60);
 	let relayer_id_at_millau = (*params.source_sign.public().as_array_ref()).into();
 
-	let lane_id = params.lane_id;
 	let source_client = params.source_client;
+	let lanes = select_lanes_to_serve::<MillauMessagesToRialto>(&source_client, params.lanes)
+		.await
+		.map_err(|e| anyhow::format_err!("{}", e))?;
+	let base_lane = *lanes
+		.first()
+		.ok_or_else(|| anyhow::format_err!("At least one messages lane must be specified"))?;
 	let lane = MillauMessagesToRialto {
 		message_lane: SubstrateMessageLaneToSubstrate {
 			source_client: source_client.clone(),
@@ -209,15 +219,15 @@ pub async fn run(
 	);
 
 	let (metrics_params, metrics_values) = add_standalone_metrics(
-		Some(messages_relay::message_lane_loop::metrics_prefix::<
+		Some(messages_relay::message_lanes_loop::metrics_prefix::<
 			<MillauMessagesToRialto as SubstrateMessageLane>::MessageLane,
-		>(&lane_id)),
+		>(&lanes)),
 		params.metrics_params,
 		source_client.clone(),
 	)?;
-	messages_relay::message_lane_loop::run(
-		messages_relay::message_lane_loop::Params {
-			lane: lane_id,
+	messages_relay::message_lanes_loop::run(
+		messages_relay::message_lanes_loop::Params {
+			lanes,
 			source_tick: Millau::AVERAGE_BLOCK_INTERVAL,
 			target_tick: Rialto::AVERAGE_BLOCK_INTERVAL,
 			reconnect_delay: relay_utils::relay_loop::RECONNECT_DELAY,
@@ -229,20 +239,26 @@ pub async fn run(
 					bp_rialto::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
 				max_messages_in_single_batch,
 				max_messages_weight_in_single_batch,
+				max_inline_dispatch_weight: bp_rialto::max_inline_dispatch_weight(),
 				max_messages_size_in_single_batch,
+				// give the slot owner a whole slot to deliver messages
+				delivery_slot_grace_period: Rialto::AVERAGE_BLOCK_INTERVAL *
+					bp_rialto::DELIVERY_SLOT_LENGTH,
 				relayer_mode: params.relayer_mode,
 			},
+			scheduling: params.lanes_scheduling,
 		},
+		// clients of other lanes are derived from these clients by the multi-lane loop
 		MillauSourceClient::new(
 			source_client.clone(),
 			lane.clone(),
-			lane_id,
+			base_lane,
 			params.target_to_source_headers_relay,
 		),
 		RialtoTargetClient::new(
 			params.target_client,
 			lane,
-			lane_id,
+			base_lane,
 			metrics_values,
 			params.source_to_target_headers_relay,
 		),
@@ -24,6 +24,7 @@ pub mod polkadot_headers_to_kusama;
 pub mod polkadot_messages_to_kusama;
 pub mod rialto_headers_to_millau;
 pub mod rialto_messages_to_millau;
+pub mod rialto_parachains_to_millau;
 pub mod rococo_headers_to_wococo;
 pub mod rococo_messages_to_wococo;
 pub mod westend_headers_to_millau;
@@ -91,6 +92,7 @@ mod tests {
 			&call,
 			millau_account_id,
 			rialto_runtime::VERSION.spec_version,
+			0,
 		);
 
 		let rialto_signer =
@@ -113,6 +115,7 @@ mod tests {
 			&call,
 			rialto_account_id,
 			millau_runtime::VERSION.spec_version,
+			0,
 		);
 
 		let millau_signer =
@@ -303,6 +306,46 @@ mod rococo_tests {
 			actual, expected
 		);
 	}
+
+	#[test]
+	fn scale_compatibility_of_bridges_batch_call() {
+		// given
+		let header = sp_runtime::generic::Header {
+			parent_hash: Default::default(),
+			number: Default::default(),
+			state_root: Default::default(),
+			extrinsics_root: Default::default(),
+			digest: sp_runtime::generic::Digest { logs: vec![] },
+		};
+
+		let justification = GrandpaJustification {
+			round: 0,
+			commit: finality_grandpa::Commit {
+				target_hash: Default::default(),
+				target_number: Default::default(),
+				precommits: vec![],
+			},
+			votes_ancestries: vec![],
+		};
+
+		let actual = relay_rococo_client::runtime::BridgeGrandpaWococoCall::submit_finality_proofs(
+			vec![(header.clone(), justification.clone())],
+		);
+		let expected = millau_runtime::BridgeGrandpaRialtoCall::<millau_runtime::Runtime>::submit_finality_proofs(
+			vec![(header, justification)],
+		);
+
+		// when
+		let actual_encoded = actual.encode();
+		let expected_encoded = expected.encode();
+
+		// then
+		assert_eq!(
+			actual_encoded, expected_encoded,
+			"\n\nEncoding difference.\nGot {:#?} \nExpected: {:#?}",
+			actual, expected
+		);
+	}
 }
 
 #[cfg(test)]
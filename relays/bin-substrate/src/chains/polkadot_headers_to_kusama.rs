@@ -60,6 +60,8 @@ impl SubstrateFinalitySyncPipeline for PolkadotFinalityToKusama {
 
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str =
 		bp_polkadot::BEST_FINALIZED_POLKADOT_HEADER_METHOD;
+	const AUTHORITY_SET_AT_TARGET: &'static str = bp_polkadot::POLKADOT_AUTHORITY_SET_METHOD;
+	const HEADER_SET_ID_AT_TARGET: &'static str = bp_polkadot::POLKADOT_HEADER_SET_ID_METHOD;
 
 	type TargetChain = Kusama;
 
@@ -109,6 +111,50 @@ impl SubstrateFinalitySyncPipeline for PolkadotFinalityToKusama {
 
 		Bytes(transaction.encode())
 	}
+
+	fn make_submit_finality_proofs_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Kusama>,
+		transaction_nonce: bp_runtime::IndexOf<Kusama>,
+		finality_proofs: Vec<(PolkadotSyncHeader, GrandpaJustification<bp_polkadot::Header>)>,
+	) -> Bytes {
+		let call = relay_kusama_client::runtime::Call::BridgePolkadotGrandpa(
+			relay_kusama_client::runtime::BridgePolkadotGrandpaCall::submit_finality_proofs(
+				finality_proofs
+					.into_iter()
+					.map(|(header, proof)| (header.into_inner(), proof))
+					.collect(),
+			),
+		);
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Kusama::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
+
+	fn make_halt_bridge_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Kusama>,
+		transaction_nonce: bp_runtime::IndexOf<Kusama>,
+	) -> Bytes {
+		let call = relay_kusama_client::runtime::Call::BridgePolkadotGrandpa(
+			relay_kusama_client::runtime::BridgePolkadotGrandpaCall::set_operational(false),
+		);
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Kusama::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
 }
 
 #[cfg(test)]
@@ -52,6 +52,8 @@ impl SubstrateFinalitySyncPipeline for RialtoFinalityToMillau {
 
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str =
 		bp_rialto::BEST_FINALIZED_RIALTO_HEADER_METHOD;
+	const AUTHORITY_SET_AT_TARGET: &'static str = bp_rialto::RIALTO_AUTHORITY_SET_METHOD;
+	const HEADER_SET_ID_AT_TARGET: &'static str = bp_rialto::RIALTO_HEADER_SET_ID_METHOD;
 
 	type TargetChain = Millau;
 
@@ -82,4 +84,54 @@ impl SubstrateFinalitySyncPipeline for RialtoFinalityToMillau {
 
 		Bytes(transaction.encode())
 	}
+
+	fn make_submit_finality_proofs_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Millau>,
+		transaction_nonce: IndexOf<Millau>,
+		finality_proofs: Vec<(RialtoSyncHeader, GrandpaJustification<bp_rialto::Header>)>,
+	) -> Bytes {
+		let call = millau_runtime::BridgeGrandpaRialtoCall::<
+			millau_runtime::Runtime,
+			millau_runtime::RialtoGrandpaInstance,
+		>::submit_finality_proofs(
+			finality_proofs
+				.into_iter()
+				.map(|(header, proof)| (header.into_inner(), proof))
+				.collect(),
+		)
+		.into();
+
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Millau::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
+
+	fn make_halt_bridge_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Millau>,
+		transaction_nonce: IndexOf<Millau>,
+	) -> Bytes {
+		let call = millau_runtime::BridgeGrandpaRialtoCall::<
+			millau_runtime::Runtime,
+			millau_runtime::RialtoGrandpaInstance,
+		>::set_operational(false)
+		.into();
+
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Millau::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
 }
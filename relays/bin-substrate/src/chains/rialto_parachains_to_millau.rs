@@ -0,0 +1,81 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Rialto-to-Millau parachain heads sync entrypoint.
+
+use codec::Encode;
+use sp_core::{Bytes, Pair};
+
+use bp_polkadot_core::parachains::{ParaHeadsProof, ParaId};
+use relay_millau_client::{Millau, SigningParams as MillauSigningParams};
+use relay_rialto_client::Rialto;
+use relay_substrate_client::{
+	Client, HeaderIdOf, IndexOf, TransactionSignScheme, UnsignedTransaction,
+};
+use substrate_relay_helper::parachains_pipeline::SubstrateParachainsPipeline;
+
+/// Rialto-to-Millau parachain heads sync pipeline.
+#[derive(Clone, Debug)]
+pub struct RialtoParachainsToMillau {
+	target_client: Client<Millau>,
+	target_sign: MillauSigningParams,
+}
+
+impl RialtoParachainsToMillau {
+	pub fn new(target_client: Client<Millau>, target_sign: MillauSigningParams) -> Self {
+		Self { target_client, target_sign }
+	}
+}
+
+impl SubstrateParachainsPipeline for RialtoParachainsToMillau {
+	type SourceRelayChain = Rialto;
+	type TargetChain = Millau;
+
+	const SOURCE_PARAS_PALLET_NAME: &'static str = bp_rialto::PARAS_PALLET_NAME;
+	const TARGET_PARACHAINS_PALLET_NAME: &'static str =
+		bp_millau::WITH_RIALTO_PARACHAINS_PALLET_NAME;
+	const BEST_FINALIZED_SOURCE_RELAY_HEADER_ID_AT_TARGET: &'static str =
+		bp_rialto::BEST_FINALIZED_RIALTO_HEADER_METHOD;
+
+	fn transactions_author(&self) -> bp_millau::AccountId {
+		(*self.target_sign.public().as_array_ref()).into()
+	}
+
+	fn make_submit_parachain_heads_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Millau>,
+		transaction_nonce: IndexOf<Millau>,
+		at_relay_block: HeaderIdOf<Rialto>,
+		parachains: Vec<ParaId>,
+		parachain_heads_proof: ParaHeadsProof,
+	) -> Bytes {
+		let call = millau_runtime::BridgeParachainsCall::<
+			millau_runtime::Runtime,
+			millau_runtime::WithRialtoParachainsInstance,
+		>::submit_parachain_heads(at_relay_block.1, parachains, parachain_heads_proof)
+		.into();
+
+		let genesis_hash = *self.target_client.genesis_hash();
+		let transaction = Millau::sign_transaction(
+			genesis_hash,
+			&self.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
+}
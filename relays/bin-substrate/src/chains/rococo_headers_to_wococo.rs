@@ -55,6 +55,8 @@ impl SubstrateFinalitySyncPipeline for RococoFinalityToWococo {
 
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str =
 		bp_rococo::BEST_FINALIZED_ROCOCO_HEADER_METHOD;
+	const AUTHORITY_SET_AT_TARGET: &'static str = bp_rococo::ROCOCO_AUTHORITY_SET_METHOD;
+	const HEADER_SET_ID_AT_TARGET: &'static str = bp_rococo::ROCOCO_HEADER_SET_ID_METHOD;
 
 	type TargetChain = Wococo;
 
@@ -104,4 +106,48 @@ impl SubstrateFinalitySyncPipeline for RococoFinalityToWococo {
 
 		Bytes(transaction.encode())
 	}
+
+	fn make_submit_finality_proofs_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Wococo>,
+		transaction_nonce: IndexOf<Wococo>,
+		finality_proofs: Vec<(RococoSyncHeader, GrandpaJustification<bp_rococo::Header>)>,
+	) -> Bytes {
+		let call = relay_wococo_client::runtime::Call::BridgeGrandpaRococo(
+			relay_wococo_client::runtime::BridgeGrandpaRococoCall::submit_finality_proofs(
+				finality_proofs
+					.into_iter()
+					.map(|(header, proof)| (header.into_inner(), proof))
+					.collect(),
+			),
+		);
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Wococo::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
+
+	fn make_halt_bridge_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Wococo>,
+		transaction_nonce: IndexOf<Wococo>,
+	) -> Bytes {
+		let call = relay_wococo_client::runtime::Call::BridgeGrandpaRococo(
+			relay_wococo_client::runtime::BridgeGrandpaRococoCall::set_operational(false),
+		);
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Wococo::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
 }
@@ -38,7 +38,7 @@ use substrate_relay_helper::{
 		select_delivery_transaction_limits, MessagesRelayParams, StandaloneMessagesMetrics,
 		SubstrateMessageLane, SubstrateMessageLaneToSubstrate,
 	},
-	messages_source::SubstrateMessagesSource,
+	messages_source::{select_lanes_to_serve, SubstrateMessagesSource},
 	messages_target::SubstrateMessagesTarget,
 };
 
@@ -60,6 +60,7 @@ impl SubstrateMessageLane for RococoMessagesToWococo {
 		bp_wococo::TO_WOCOCO_LATEST_GENERATED_NONCE_METHOD;
 	const OUTBOUND_LANE_LATEST_RECEIVED_NONCE_METHOD: &'static str =
 		bp_wococo::TO_WOCOCO_LATEST_RECEIVED_NONCE_METHOD;
+	const OUTBOUND_LANE_LANES_METHOD: &'static str = bp_wococo::TO_WOCOCO_LANES_METHOD;
 
 	const INBOUND_LANE_LATEST_RECEIVED_NONCE_METHOD: &'static str =
 		bp_rococo::FROM_ROCOCO_LATEST_RECEIVED_NONCE_METHOD;
@@ -170,8 +171,13 @@ pub async fn run(
 	let stall_timeout = Duration::from_secs(5 * 60);
 	let relayer_id_at_rococo = (*params.source_sign.public().as_array_ref()).into();
 
-	let lane_id = params.lane_id;
 	let source_client = params.source_client;
+	let lanes = select_lanes_to_serve::<RococoMessagesToWococo>(&source_client, params.lanes)
+		.await
+		.map_err(|e| anyhow::format_err!("{}", e))?;
+	let base_lane = *lanes
+		.first()
+		.ok_or_else(|| anyhow::format_err!("At least one messages lane must be specified"))?;
 	let lane = RococoMessagesToWococo {
 		message_lane: SubstrateMessageLaneToSubstrate {
 			source_client: source_client.clone(),
@@ -212,15 +218,15 @@ pub async fn run(
 	);
 
 	let (metrics_params, metrics_values) = add_standalone_metrics(
-		Some(messages_relay::message_lane_loop::metrics_prefix::<
+		Some(messages_relay::message_lanes_loop::metrics_prefix::<
 			<RococoMessagesToWococo as SubstrateMessageLane>::MessageLane,
-		>(&lane_id)),
+		>(&lanes)),
 		params.metrics_params,
 		source_client.clone(),
 	)?;
-	messages_relay::message_lane_loop::run(
-		messages_relay::message_lane_loop::Params {
-			lane: lane_id,
+	messages_relay::message_lanes_loop::run(
+		messages_relay::message_lanes_loop::Params {
+			lanes,
 			source_tick: Rococo::AVERAGE_BLOCK_INTERVAL,
 			target_tick: Wococo::AVERAGE_BLOCK_INTERVAL,
 			reconnect_delay: relay_utils::relay_loop::RECONNECT_DELAY,
@@ -232,20 +238,27 @@ pub async fn run(
 					bp_wococo::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
 				max_messages_in_single_batch,
 				max_messages_weight_in_single_batch,
+				// we don't know the target runtime configuration, so assume that all messages
+				// are dispatched during delivery
+				max_inline_dispatch_weight: Weight::MAX,
 				max_messages_size_in_single_batch,
+				// there are no delivery slots at the target chain
+				delivery_slot_grace_period: Duration::from_secs(0),
 				relayer_mode: params.relayer_mode,
 			},
+			scheduling: params.lanes_scheduling,
 		},
+		// clients of other lanes are derived from these clients by the multi-lane loop
 		RococoSourceClient::new(
 			source_client.clone(),
 			lane.clone(),
-			lane_id,
+			base_lane,
 			params.target_to_source_headers_relay,
 		),
 		WococoTargetClient::new(
 			params.target_client,
 			lane,
-			lane_id,
+			base_lane,
 			metrics_values,
 			params.source_to_target_headers_relay,
 		),
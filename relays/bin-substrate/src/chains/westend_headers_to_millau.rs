@@ -53,6 +53,8 @@ impl SubstrateFinalitySyncPipeline for WestendFinalityToMillau {
 
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str =
 		bp_westend::BEST_FINALIZED_WESTEND_HEADER_METHOD;
+	const AUTHORITY_SET_AT_TARGET: &'static str = bp_westend::WESTEND_AUTHORITY_SET_METHOD;
+	const HEADER_SET_ID_AT_TARGET: &'static str = bp_westend::WESTEND_HEADER_SET_ID_METHOD;
 
 	type TargetChain = Millau;
 
@@ -90,4 +92,54 @@ impl SubstrateFinalitySyncPipeline for WestendFinalityToMillau {
 
 		Bytes(transaction.encode())
 	}
+
+	fn make_submit_finality_proofs_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Millau>,
+		transaction_nonce: IndexOf<Millau>,
+		finality_proofs: Vec<(WestendSyncHeader, GrandpaJustification<bp_westend::Header>)>,
+	) -> Bytes {
+		let call = millau_runtime::BridgeGrandpaWestendCall::<
+			millau_runtime::Runtime,
+			millau_runtime::WestendGrandpaInstance,
+		>::submit_finality_proofs(
+			finality_proofs
+				.into_iter()
+				.map(|(header, proof)| (header.into_inner(), proof))
+				.collect(),
+		)
+		.into();
+
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Millau::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
+
+	fn make_halt_bridge_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Millau>,
+		transaction_nonce: IndexOf<Millau>,
+	) -> Bytes {
+		let call = millau_runtime::BridgeGrandpaWestendCall::<
+			millau_runtime::Runtime,
+			millau_runtime::WestendGrandpaInstance,
+		>::set_operational(false)
+		.into();
+
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Millau::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
 }
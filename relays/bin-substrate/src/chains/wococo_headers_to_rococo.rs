@@ -60,6 +60,8 @@ impl SubstrateFinalitySyncPipeline for WococoFinalityToRococo {
 
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str =
 		bp_wococo::BEST_FINALIZED_WOCOCO_HEADER_METHOD;
+	const AUTHORITY_SET_AT_TARGET: &'static str = bp_wococo::WOCOCO_AUTHORITY_SET_METHOD;
+	const HEADER_SET_ID_AT_TARGET: &'static str = bp_wococo::WOCOCO_HEADER_SET_ID_METHOD;
 
 	type TargetChain = Rococo;
 
@@ -109,6 +111,50 @@ impl SubstrateFinalitySyncPipeline for WococoFinalityToRococo {
 
 		Bytes(transaction.encode())
 	}
+
+	fn make_submit_finality_proofs_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Rococo>,
+		transaction_nonce: IndexOf<Rococo>,
+		finality_proofs: Vec<(WococoSyncHeader, GrandpaJustification<bp_wococo::Header>)>,
+	) -> Bytes {
+		let call = relay_rococo_client::runtime::Call::BridgeGrandpaWococo(
+			relay_rococo_client::runtime::BridgeGrandpaWococoCall::submit_finality_proofs(
+				finality_proofs
+					.into_iter()
+					.map(|(header, proof)| (header.into_inner(), proof))
+					.collect(),
+			),
+		);
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Rococo::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
+
+	fn make_halt_bridge_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Rococo>,
+		transaction_nonce: IndexOf<Rococo>,
+	) -> Bytes {
+		let call = relay_rococo_client::runtime::Call::BridgeGrandpaWococo(
+			relay_rococo_client::runtime::BridgeGrandpaWococoCall::set_operational(false),
+		);
+		let genesis_hash = *self.finality_pipeline.target_client.genesis_hash();
+		let transaction = Rococo::sign_transaction(
+			genesis_hash,
+			&self.finality_pipeline.target_sign,
+			era,
+			UnsignedTransaction::new(call, transaction_nonce),
+		);
+
+		Bytes(transaction.encode())
+	}
 }
 
 #[cfg(test)]
@@ -76,6 +76,11 @@ macro_rules! select_full_bridge {
 				// Send-message
 				#[allow(unused_imports)]
 				use millau_runtime::millau_to_rialto_account_ownership_digest as account_ownership_digest;
+				#[allow(unused_imports)]
+				use rialto_runtime::DbWeight as TargetDbWeight;
+				#[allow(dead_code)]
+				const TARGET_DISPATCH_PALLET_NAME: Option<&str> =
+					Some(bp_rialto::WITH_MILLAU_DISPATCH_PALLET_NAME);
 
 				$generic
 			}
@@ -99,6 +104,11 @@ macro_rules! select_full_bridge {
 				// Send-message
 				#[allow(unused_imports)]
 				use rialto_runtime::rialto_to_millau_account_ownership_digest as account_ownership_digest;
+				#[allow(unused_imports)]
+				use millau_runtime::DbWeight as TargetDbWeight;
+				#[allow(dead_code)]
+				const TARGET_DISPATCH_PALLET_NAME: Option<&str> =
+					Some(bp_millau::WITH_RIALTO_DISPATCH_PALLET_NAME);
 
 				$generic
 			}
@@ -121,6 +131,10 @@ macro_rules! select_full_bridge {
 				// Send-message
 				#[allow(unused_imports)]
 				use relay_rococo_client::runtime::rococo_to_wococo_account_ownership_digest as account_ownership_digest;
+				#[allow(unused_imports)]
+				use frame_support::weights::constants::RocksDbWeight as TargetDbWeight;
+				#[allow(dead_code)]
+				const TARGET_DISPATCH_PALLET_NAME: Option<&str> = None;
 
 				$generic
 			}
@@ -143,6 +157,10 @@ macro_rules! select_full_bridge {
 				// Send-message
 				#[allow(unused_imports)]
 				use relay_wococo_client::runtime::wococo_to_rococo_account_ownership_digest as account_ownership_digest;
+				#[allow(unused_imports)]
+				use frame_support::weights::constants::RocksDbWeight as TargetDbWeight;
+				#[allow(dead_code)]
+				const TARGET_DISPATCH_PALLET_NAME: Option<&str> = None;
 
 				$generic
 			}
@@ -165,6 +183,10 @@ macro_rules! select_full_bridge {
 				// Send-message
 				#[allow(unused_imports)]
 				use relay_kusama_client::runtime::kusama_to_polkadot_account_ownership_digest as account_ownership_digest;
+				#[allow(unused_imports)]
+				use frame_support::weights::constants::RocksDbWeight as TargetDbWeight;
+				#[allow(dead_code)]
+				const TARGET_DISPATCH_PALLET_NAME: Option<&str> = None;
 
 				$generic
 			}
@@ -187,6 +209,10 @@ macro_rules! select_full_bridge {
 				// Send-message
 				#[allow(unused_imports)]
 				use relay_polkadot_client::runtime::polkadot_to_kusama_account_ownership_digest as account_ownership_digest;
+				#[allow(unused_imports)]
+				use frame_support::weights::constants::RocksDbWeight as TargetDbWeight;
+				#[allow(dead_code)]
+				const TARGET_DISPATCH_PALLET_NAME: Option<&str> = None;
 
 				$generic
 			}
@@ -325,6 +325,8 @@ mod tests {
 			"//Alice",
 			"--origin",
 			"Target",
+			"--target-account-nonce",
+			"0",
 			"remark",
 		])
 		.encode_payload()
@@ -345,7 +347,7 @@ mod tests {
 
 		// then
 		assert!(format!("{:?}", call_hex).starts_with(
-			"0x11030000000001000000381409000000000001d43593c715fdd31c61141abd04a99fd6822c8558854cc\
+			"0x11030000000001000000385d880f0000000001d43593c715fdd31c61141abd04a99fd6822c8558854cc\
 			de39a5684e7a56da27d01d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d01"
 		))
 	}
@@ -36,6 +36,7 @@ mod register_parachain;
 mod relay_headers;
 mod relay_headers_and_messages;
 mod relay_messages;
+mod relay_parachains;
 mod resubmit_transactions;
 mod swap_tokens;
 
@@ -65,6 +66,12 @@ pub enum Command {
 	/// the message relays - i.e. when there are messages or confirmations that needs to be
 	/// relayed between chains.
 	RelayHeadersAndMessages(relay_headers_and_messages::RelayHeadersAndMessages),
+	/// Start parachain heads relay between relay chain and the chain with bridge parachains
+	/// pallet.
+	///
+	/// The bridge GRANDPA pallet, used by the parachains pallet, should be kept in sync by
+	/// the `relay-headers` sub-command.
+	RelayParachains(relay_parachains::RelayParachains),
 	/// Initialize on-chain bridge pallet with current header data.
 	///
 	/// Sends initialization transaction to bootstrap the bridge with current finalized block data.
@@ -107,6 +114,7 @@ impl Command {
 			Self::RelayHeaders(_) |
 			Self::RelayMessages(_) |
 			Self::RelayHeadersAndMessages(_) |
+			Self::RelayParachains(_) |
 			Self::InitBridge(_) => {
 				initialize_relay();
 			},
@@ -123,6 +131,7 @@ impl Command {
 			Self::RelayHeaders(arg) => arg.run().await?,
 			Self::RelayMessages(arg) => arg.run().await?,
 			Self::RelayHeadersAndMessages(arg) => arg.run().await?,
+			Self::RelayParachains(arg) => arg.run().await?,
 			Self::InitBridge(arg) => arg.run().await?,
 			Self::SendMessage(arg) => arg.run().await?,
 			Self::EncodeCall(arg) => arg.run().await?,
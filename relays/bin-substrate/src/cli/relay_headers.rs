@@ -33,6 +33,15 @@ pub struct RelayHeaders {
 	/// are relayed.
 	#[structopt(long)]
 	only_mandatory_headers: bool,
+	/// What to do if the best finalized header at the target chain is not canonical at the
+	/// source chain.
+	#[structopt(
+		long,
+		possible_values = ForkDetectionMode::VARIANTS,
+		case_insensitive = true,
+		default_value = "disabled"
+	)]
+	fork_detection: ForkDetectionMode,
 	#[structopt(flatten)]
 	source: SourceConnectionParams,
 	#[structopt(flatten)]
@@ -43,6 +52,28 @@ pub struct RelayHeaders {
 	prometheus_params: PrometheusParams,
 }
 
+/// Fork detection mode of the headers relay.
+#[derive(Debug, EnumString, EnumVariantNames, Clone, Copy, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum ForkDetectionMode {
+	/// Fork detection is disabled.
+	Disabled,
+	/// Detected forks are only reported (logged and exposed as metric).
+	Report,
+	/// Detected forks are reported and the bridge pallet at the target chain is halted.
+	Halt,
+}
+
+impl From<ForkDetectionMode> for finality_relay::ForkDetectionMode {
+	fn from(mode: ForkDetectionMode) -> Self {
+		match mode {
+			ForkDetectionMode::Disabled => finality_relay::ForkDetectionMode::Disabled,
+			ForkDetectionMode::Report => finality_relay::ForkDetectionMode::Report,
+			ForkDetectionMode::Halt => finality_relay::ForkDetectionMode::Halt,
+		}
+	}
+}
+
 #[derive(Debug, EnumString, EnumVariantNames)]
 #[strum(serialize_all = "kebab_case")]
 /// Headers relay bridge.
@@ -129,6 +160,7 @@ impl RelayHeaders {
 				source_client,
 				target_client,
 				self.only_mandatory_headers,
+				self.fork_detection.into(),
 				target_transactions_mortality,
 				metrics_params,
 			)
@@ -33,12 +33,15 @@ use relay_substrate_client::{
 use relay_utils::metrics::MetricsParams;
 use sp_core::{Bytes, Pair};
 use substrate_relay_helper::{
-	messages_lane::{MessagesRelayParams, SubstrateMessageLane},
+	messages_lane::{MessagesRelayParams, OnDemandRelay, SubstrateMessageLane},
 	on_demand_headers::OnDemandHeadersRelay,
 };
 
 use crate::{
-	cli::{relay_messages::RelayerMode, CliChain, HexLaneId, PrometheusParams},
+	cli::{
+		relay_messages::{LanesScheduling, RelayerMode},
+		CliChain, HexLaneId, PrometheusParams,
+	},
 	declare_chain_options,
 };
 
@@ -64,6 +67,10 @@ pub struct HeadersAndMessagesSharedParams {
 	/// Hex-encoded lane identifiers that should be served by the complex relay.
 	#[structopt(long, default_value = "00000000")]
 	lane: Vec<HexLaneId>,
+	/// Strategy that is used to select lane, which messages are delivered next. All lanes are
+	/// served by the same relay, so their delivery transactions are submitted one-by-one.
+	#[structopt(long, possible_values = LanesScheduling::VARIANTS, case_insensitive = true, default_value = "round-robin")]
+	lanes_scheduling: LanesScheduling,
 	#[structopt(long, possible_values = RelayerMode::VARIANTS, case_insensitive = true, default_value = "rational")]
 	relayer_mode: RelayerMode,
 	/// Create relayers fund accounts on both chains, if it does not exists yet.
@@ -488,65 +495,65 @@ impl RelayHeadersAndMessages {
 				}
 			}
 
-			let left_to_right_on_demand_headers = OnDemandHeadersRelay::new(
-				left_client.clone(),
-				right_client.clone(),
-				right_transactions_mortality,
-				LeftToRightFinality::new(right_client.clone(), right_sign.clone()),
-				MAX_MISSING_LEFT_HEADERS_AT_RIGHT,
-				params.shared.only_mandatory_headers,
-			);
-			let right_to_left_on_demand_headers = OnDemandHeadersRelay::new(
-				right_client.clone(),
-				left_client.clone(),
-				left_transactions_mortality,
-				RightToLeftFinality::new(left_client.clone(), left_sign.clone()),
-				MAX_MISSING_RIGHT_HEADERS_AT_LEFT,
-				params.shared.only_mandatory_headers,
-			);
-
-			// Need 2x capacity since we consider both directions for each lane
-			let mut message_relays = Vec::with_capacity(lanes.len() * 2);
-			for lane in lanes {
-				let lane = lane.into();
-				let left_to_right_messages = left_to_right_messages(MessagesRelayParams {
-					source_client: left_client.clone(),
-					source_sign: left_sign.clone(),
-					target_client: right_client.clone(),
-					target_sign: right_sign.clone(),
-					source_to_target_headers_relay: Some(left_to_right_on_demand_headers.clone()),
-					target_to_source_headers_relay: Some(right_to_left_on_demand_headers.clone()),
-					lane_id: lane,
-					relayer_mode,
-					metrics_params: metrics_params.clone().disable().metrics_prefix(
-						messages_relay::message_lane_loop::metrics_prefix::<
-							<LeftToRightMessages as SubstrateMessageLane>::MessageLane,
-						>(&lane),
-					),
-				})
-				.map_err(|e| anyhow::format_err!("{}", e))
-				.boxed();
-				let right_to_left_messages = right_to_left_messages(MessagesRelayParams {
-					source_client: right_client.clone(),
-					source_sign: right_sign.clone(),
-					target_client: left_client.clone(),
-					target_sign: left_sign.clone(),
-					source_to_target_headers_relay: Some(right_to_left_on_demand_headers.clone()),
-					target_to_source_headers_relay: Some(left_to_right_on_demand_headers.clone()),
-					lane_id: lane,
-					relayer_mode,
-					metrics_params: metrics_params.clone().disable().metrics_prefix(
-						messages_relay::message_lane_loop::metrics_prefix::<
-							<RightToLeftMessages as SubstrateMessageLane>::MessageLane,
-						>(&lane),
-					),
-				})
-				.map_err(|e| anyhow::format_err!("{}", e))
-				.boxed();
-
-				message_relays.push(left_to_right_messages);
-				message_relays.push(right_to_left_messages);
-			}
+			let left_to_right_on_demand_headers =
+				OnDemandRelay::Headers(OnDemandHeadersRelay::new(
+					left_client.clone(),
+					right_client.clone(),
+					right_transactions_mortality,
+					LeftToRightFinality::new(right_client.clone(), right_sign.clone()),
+					MAX_MISSING_LEFT_HEADERS_AT_RIGHT,
+					params.shared.only_mandatory_headers,
+				));
+			let right_to_left_on_demand_headers =
+				OnDemandRelay::Headers(OnDemandHeadersRelay::new(
+					right_client.clone(),
+					left_client.clone(),
+					left_transactions_mortality,
+					RightToLeftFinality::new(left_client.clone(), left_sign.clone()),
+					MAX_MISSING_RIGHT_HEADERS_AT_LEFT,
+					params.shared.only_mandatory_headers,
+				));
+
+			// all lanes of the same direction are served by the single multi-lane relay
+			let lanes = lanes.into_iter().map(Into::into).collect::<Vec<_>>();
+			let lanes_scheduling = params.shared.lanes_scheduling.into();
+			let left_to_right_messages = left_to_right_messages(MessagesRelayParams {
+				source_client: left_client.clone(),
+				source_sign: left_sign.clone(),
+				target_client: right_client.clone(),
+				target_sign: right_sign.clone(),
+				source_to_target_headers_relay: Some(left_to_right_on_demand_headers.clone()),
+				target_to_source_headers_relay: Some(right_to_left_on_demand_headers.clone()),
+				lanes: lanes.clone(),
+				lanes_scheduling,
+				relayer_mode,
+				metrics_params: metrics_params.clone().disable().metrics_prefix(
+					messages_relay::message_lanes_loop::metrics_prefix::<
+						<LeftToRightMessages as SubstrateMessageLane>::MessageLane,
+					>(&lanes),
+				),
+			})
+			.map_err(|e| anyhow::format_err!("{}", e))
+			.boxed();
+			let right_to_left_messages = right_to_left_messages(MessagesRelayParams {
+				source_client: right_client.clone(),
+				source_sign: right_sign.clone(),
+				target_client: left_client.clone(),
+				target_sign: left_sign.clone(),
+				source_to_target_headers_relay: Some(right_to_left_on_demand_headers.clone()),
+				target_to_source_headers_relay: Some(left_to_right_on_demand_headers.clone()),
+				lanes: lanes.clone(),
+				lanes_scheduling,
+				relayer_mode,
+				metrics_params: metrics_params.clone().disable().metrics_prefix(
+					messages_relay::message_lanes_loop::metrics_prefix::<
+						<RightToLeftMessages as SubstrateMessageLane>::MessageLane,
+					>(&lanes),
+				),
+			})
+			.map_err(|e| anyhow::format_err!("{}", e))
+			.boxed();
+			let message_relays = vec![left_to_right_messages, right_to_left_messages];
 
 			relay_utils::relay_metrics(None, metrics_params)
 				.expose()
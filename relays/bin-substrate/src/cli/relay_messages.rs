@@ -47,6 +47,25 @@ impl From<RelayerMode> for messages_relay::message_lane_loop::RelayerMode {
 	}
 }
 
+/// Strategy that is used to select lane, which messages are delivered next.
+#[derive(Debug, EnumString, EnumVariantNames, Clone, Copy, PartialEq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum LanesScheduling {
+	/// Lanes are served in round-robin order.
+	RoundRobin,
+	/// The lane with maximal reward of queued messages is served first.
+	QueuedReward,
+}
+
+impl From<LanesScheduling> for messages_relay::message_lanes_loop::LaneSchedulingStrategy {
+	fn from(scheduling: LanesScheduling) -> Self {
+		match scheduling {
+			LanesScheduling::RoundRobin => Self::RoundRobin,
+			LanesScheduling::QueuedReward => Self::QueuedReward,
+		}
+	}
+}
+
 /// Start messages relayer process.
 #[derive(StructOpt)]
 pub struct RelayMessages {
@@ -86,7 +105,8 @@ impl RelayMessages {
 				target_sign,
 				source_to_target_headers_relay: None,
 				target_to_source_headers_relay: None,
-				lane_id: self.lane.into(),
+				lanes: vec![self.lane.into()],
+				lanes_scheduling: LanesScheduling::RoundRobin.into(),
 				relayer_mode: self.relayer_mode.into(),
 				metrics_params: self.prometheus_params.into(),
 			})
@@ -0,0 +1,91 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+use bp_polkadot_core::parachains::ParaId;
+use substrate_relay_helper::parachains_pipeline::SubstrateParachainsPipeline;
+
+use crate::cli::{
+	PrometheusParams, SourceConnectionParams, TargetConnectionParams, TargetSigningParams,
+};
+
+/// Start parachain heads relayer process.
+#[derive(StructOpt)]
+pub struct RelayParachains {
+	/// A bridge instance to relay parachain heads for.
+	#[structopt(possible_values = RelayParachainsBridge::VARIANTS, case_insensitive = true)]
+	bridge: RelayParachainsBridge,
+	/// Identifiers of parachains, which heads are relayed.
+	#[structopt(long = "parachain", required = true)]
+	parachains: Vec<u32>,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+	#[structopt(flatten)]
+	target_sign: TargetSigningParams,
+	#[structopt(flatten)]
+	prometheus_params: PrometheusParams,
+}
+
+#[derive(Debug, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+/// Parachain heads relay bridge.
+pub enum RelayParachainsBridge {
+	RialtoToMillau,
+}
+
+macro_rules! select_bridge {
+	($bridge: expr, $generic: tt) => {
+		match $bridge {
+			RelayParachainsBridge::RialtoToMillau => {
+				type Source = relay_rialto_client::Rialto;
+				type Target = relay_millau_client::Millau;
+				type Parachains =
+					crate::chains::rialto_parachains_to_millau::RialtoParachainsToMillau;
+
+				$generic
+			},
+		}
+	};
+}
+
+impl RelayParachains {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		select_bridge!(self.bridge, {
+			let source_client = self.source.to_client::<Source>().await?;
+			let target_client = self.target.to_client::<Target>().await?;
+			let target_transactions_mortality = self.target_sign.target_transactions_mortality;
+			let target_sign = self.target_sign.to_keypair::<Target>()?;
+			let metrics_params = Parachains::customize_metrics(self.prometheus_params.into())?;
+			let parachains = Parachains::new(target_client.clone(), target_sign);
+			parachains.start_relay_guards();
+
+			substrate_relay_helper::parachains_pipeline::run(
+				parachains,
+				source_client,
+				target_client,
+				self.parachains.into_iter().map(ParaId).collect(),
+				target_transactions_mortality,
+				metrics_params,
+			)
+			.await
+		})
+	}
+}
@@ -19,12 +19,12 @@ use crate::cli::{
 	encode_call::{self, CliEncodeCall},
 	estimate_fee::estimate_message_delivery_and_dispatch_fee,
 	Balance, CliChain, ExplicitOrMaximal, HexBytes, HexLaneId, Origins, SourceConnectionParams,
-	SourceSigningParams, TargetSigningParams,
+	SourceSigningParams, TargetConnectionParams, TargetSigningParams,
 };
-use bp_message_dispatch::{CallOrigin, MessagePayload};
+use bp_message_dispatch::{CallOrigin, MessagePayload, TargetAccountNonce};
 use bp_runtime::BalanceOf;
 use codec::Encode;
-use frame_support::weights::Weight;
+use frame_support::{traits::Get, weights::Weight};
 use relay_substrate_client::{Chain, TransactionSignScheme, UnsignedTransaction};
 use sp_core::{Bytes, Pair};
 use sp_runtime::{traits::IdentifyAccount, AccountId32, MultiSignature, MultiSigner};
@@ -88,6 +88,20 @@ pub struct SendMessage {
 	/// `SourceAccount`.
 	#[structopt(long, possible_values = &Origins::variants(), default_value = "Source")]
 	origin: Origins,
+	/// Current nonce of the target account in the target chain dispatch pallet. Only used when
+	/// `--origin Target` is selected. If not passed, it is read from the target chain node.
+	#[structopt(long)]
+	target_account_nonce: Option<TargetAccountNonce>,
+	/// Connect to Target node at given host. Only used to read the target account nonce.
+	#[structopt(long, default_value = "127.0.0.1")]
+	target_host: String,
+	/// Connect to Target node websocket server at given port. Only used to read the target account
+	/// nonce.
+	#[structopt(long)]
+	target_port: Option<u16>,
+	/// Use secure websocket connection to the Target node.
+	#[structopt(long)]
+	target_secure: bool,
 }
 
 impl SendMessage {
@@ -102,6 +116,7 @@ impl SendMessage {
 				dispatch_fee_payment,
 				dispatch_weight,
 				origin,
+				target_account_nonce,
 				bridge,
 				..
 			} = self;
@@ -112,9 +127,19 @@ impl SendMessage {
 			let target_call = Target::encode_call(message)?;
 
 			let payload = {
+				// the `TargetAccount` origin verification isn't free at the target chain
+				let origin_overhead_weight = match origin {
+					Origins::Source => 0,
+					Origins::Target =>
+						pallet_bridge_dispatch::target_account_origin_overhead(TargetDbWeight::get()),
+				};
 				let target_call_weight = prepare_call_dispatch_weight(
 					dispatch_weight,
-					ExplicitOrMaximal::Explicit(Target::get_dispatch_info(&target_call)?.weight),
+					ExplicitOrMaximal::Explicit(
+						Target::get_dispatch_info(&target_call)?
+							.weight
+							.saturating_add(origin_overhead_weight),
+					),
 					compute_maximal_message_dispatch_weight(Target::max_extrinsic_weight()),
 				);
 				let source_sender_public: MultiSigner = source_sign.public().into();
@@ -127,10 +152,14 @@ impl SendMessage {
 						Origins::Source => CallOrigin::SourceAccount(source_account_id),
 						Origins::Target => {
 							let target_sign = target_sign.to_keypair::<Target>()?;
+							let target_account_nonce = target_account_nonce.ok_or_else(|| {
+								anyhow::format_err!("Target account nonce is not specified")
+							})?;
 							let digest = account_ownership_digest(
 								&target_call,
 								source_account_id.clone(),
 								Target::RUNTIME_VERSION.spec_version,
+								target_account_nonce,
 							);
 							let target_origin_public = target_sign.public();
 							let digest_signature = target_sign.sign(&digest);
@@ -152,6 +181,40 @@ impl SendMessage {
 	/// Run the command.
 	pub async fn run(mut self) -> anyhow::Result<()> {
 		crate::select_full_bridge!(self.bridge, {
+			if let (Origins::Target, None) = (&self.origin, self.target_account_nonce) {
+				let dispatch_pallet_name = TARGET_DISPATCH_PALLET_NAME.ok_or_else(|| {
+					anyhow::format_err!(
+						"Target account nonce can't be read from {}. Use --target-account-nonce",
+						Target::NAME,
+					)
+				})?;
+				let target_port = self.target_port.ok_or_else(|| {
+					anyhow::format_err!(
+						"Either --target-account-nonce or --target-port must be specified"
+					)
+				})?;
+				let target_client = TargetConnectionParams {
+					target_host: self.target_host.clone(),
+					target_port,
+					target_secure: self.target_secure,
+				}
+				.to_client::<Target>()
+				.await?;
+				let target_public: MultiSigner =
+					self.target_sign.to_keypair::<Target>()?.public().into();
+				let target_account_nonce = target_client
+					.storage_value::<TargetAccountNonce>(
+						pallet_bridge_dispatch::storage_keys::target_account_nonce_key(
+							dispatch_pallet_name,
+							&target_public.into_account(),
+						),
+						None,
+					)
+					.await?
+					.unwrap_or(0);
+				self.target_account_nonce = Some(target_account_nonce);
+			}
+
 			let payload = self.encode_payload()?;
 
 			let source_client = self.source.to_client::<Source>().await?;
@@ -263,6 +326,7 @@ where
 		weight,
 		origin,
 		dispatch_fee_payment: dispatch_fee_payment.into(),
+		expiry: None,
 		call: HexBytes::encode(call),
 	};
 
@@ -270,8 +334,9 @@ where
 	log::info!(target: "bridge", "Encoded Message Payload: {:?}", HexBytes::encode(&payload));
 
 	// re-pack to return `Vec<u8>`
-	let MessagePayload { spec_version, weight, origin, dispatch_fee_payment, call } = payload;
-	MessagePayload { spec_version, weight, origin, dispatch_fee_payment, call: call.0 }
+	let MessagePayload { spec_version, weight, origin, dispatch_fee_payment, expiry, call } =
+		payload;
+	MessagePayload { spec_version, weight, origin, dispatch_fee_payment, expiry, call: call.0 }
 }
 
 pub(crate) fn compute_maximal_message_dispatch_weight(maximal_extrinsic_weight: Weight) -> Weight {
@@ -313,6 +378,7 @@ mod tests {
 					sp_keyring::AccountKeyring::Alice.to_account_id()
 				),
 				dispatch_fee_payment: bp_runtime::messages::DispatchFeePayment::AtSourceChain,
+				expiry: None,
 				call: hex!("0001081234").to_vec(),
 			}
 		);
@@ -332,6 +398,8 @@ mod tests {
 			"Target",
 			"--target-signer",
 			"//Bob",
+			"--target-account-nonce",
+			"0",
 			"remark",
 			"--remark-payload",
 			"1234",
@@ -350,13 +418,14 @@ mod tests {
 			payload,
 			MessagePayload {
 				spec_version: relay_millau_client::Millau::RUNTIME_VERSION.spec_version,
-				weight: 576000,
+				weight: 576000 + 260_000_000,
 				origin: CallOrigin::TargetAccount(
 					sp_keyring::AccountKeyring::Alice.to_account_id(),
 					sp_keyring::AccountKeyring::Bob.into(),
 					signature,
 				),
 				dispatch_fee_payment: bp_runtime::messages::DispatchFeePayment::AtSourceChain,
+				expiry: None,
 				call: hex!("0001081234").to_vec(),
 			}
 		);
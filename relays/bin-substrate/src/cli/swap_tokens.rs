@@ -26,7 +26,7 @@ use rand::random;
 use structopt::StructOpt;
 use strum::{EnumString, EnumVariantNames, VariantNames};
 
-use frame_support::dispatch::GetDispatchInfo;
+use frame_support::{dispatch::GetDispatchInfo, traits::Get};
 use relay_substrate_client::{
 	AccountIdOf, AccountPublicOf, BalanceOf, BlockNumberOf, CallOf, Chain, ChainWithBalances,
 	Client, Error as SubstrateError, HashOf, SignatureOf, Subscription, TransactionSignScheme,
@@ -109,8 +109,11 @@ macro_rules! select_bridge {
 				use bp_rialto::{
 					derive_account_from_millau_id as derive_target_account_from_source_account,
 					TO_RIALTO_ESTIMATE_MESSAGE_FEE_METHOD as ESTIMATE_SOURCE_TO_TARGET_MESSAGE_FEE_METHOD,
+					WITH_MILLAU_DISPATCH_PALLET_NAME as TARGET_DISPATCH_PALLET_NAME,
 				};
 
+				use rialto_runtime::DbWeight as TargetDbWeight;
+
 				const SOURCE_CHAIN_ID: bp_runtime::ChainId = bp_runtime::MILLAU_CHAIN_ID;
 				const TARGET_CHAIN_ID: bp_runtime::ChainId = bp_runtime::RIALTO_CHAIN_ID;
 
@@ -181,17 +184,32 @@ impl SwapTokens {
 				token_swap.target_balance_at_bridged_chain,
 			)
 			.into();
+			// the message is dispatched with `TargetAccount` origin, so the origin verification
+			// overhead must be covered by the declared weight
 			let bridged_currency_transfer_weight =
-				bridged_currency_transfer.get_dispatch_info().weight;
+				bridged_currency_transfer.get_dispatch_info().weight.saturating_add(
+					pallet_bridge_dispatch::target_account_origin_overhead(TargetDbWeight::get()),
+				);
 
 			// sign message
 			let bridged_chain_spec_version = TARGET_SPEC_VERSION;
+			let target_account_nonce = target_client
+				.storage_value::<bp_message_dispatch::TargetAccountNonce>(
+					pallet_bridge_dispatch::storage_keys::target_account_nonce_key(
+						TARGET_DISPATCH_PALLET_NAME,
+						&accounts.target_account_at_bridged_chain,
+					),
+					None,
+				)
+				.await?
+				.unwrap_or(0);
 			let signature_payload = pallet_bridge_dispatch::account_ownership_digest(
 				&bridged_currency_transfer,
 				&accounts.swap_account,
 				&bridged_chain_spec_version,
 				SOURCE_CHAIN_ID,
 				TARGET_CHAIN_ID,
+				target_account_nonce,
 			);
 			let bridged_currency_transfer_signature: SignatureOf<Target> =
 				target_sign.sign(&signature_payload).into();
@@ -214,6 +232,7 @@ impl SwapTokens {
 						),
 						dispatch_fee_payment:
 							bp_runtime::messages::DispatchFeePayment::AtTargetChain,
+						expiry: None,
 						call: bridged_currency_transfer.encode(),
 					},
 				)
@@ -347,6 +366,7 @@ impl SwapTokens {
 						accounts.target_account_at_bridged_chain.clone(),
 					),
 					dispatch_fee_payment: bp_runtime::messages::DispatchFeePayment::AtSourceChain,
+					expiry: None,
 					call: claim_swap_call.encode(),
 				};
 				let claim_swap_delivery_and_dispatch_fee: BalanceOf<Target> =
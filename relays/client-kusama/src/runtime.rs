@@ -30,11 +30,13 @@ pub type UncheckedExtrinsic = bp_polkadot_core::UncheckedExtrinsic<Call>;
 ///
 /// The byte vector returned by this function should be signed with a Polkadot account private key.
 /// This way, the owner of `kusama_account_id` on Kusama proves that the Polkadot account private
-/// key is also under his control.
+/// key is also under his control. The `polkadot_account_nonce` must match the current nonce of the
+/// Polkadot account, stored by the Polkadot dispatch pallet.
 pub fn kusama_to_polkadot_account_ownership_digest<Call, AccountId, SpecVersion>(
 	polkadot_call: &Call,
 	kusama_account_id: AccountId,
 	polkadot_spec_version: SpecVersion,
+	polkadot_account_nonce: bp_message_dispatch::TargetAccountNonce,
 ) -> Vec<u8>
 where
 	Call: codec::Encode,
@@ -47,6 +49,7 @@ where
 		polkadot_spec_version,
 		bp_runtime::KUSAMA_CHAIN_ID,
 		bp_runtime::POLKADOT_CHAIN_ID,
+		polkadot_account_nonce,
 	)
 }
 
@@ -101,6 +104,15 @@ pub enum BridgePolkadotGrandpaCall {
 	),
 	#[codec(index = 1)]
 	initialize(bp_header_chain::InitializationData<<PolkadotLike as Chain>::Header>),
+	#[codec(index = 3)]
+	set_operational(bool),
+	#[codec(index = 6)]
+	submit_finality_proofs(
+		Vec<(
+			<PolkadotLike as Chain>::Header,
+			bp_header_chain::justification::GrandpaJustification<<PolkadotLike as Chain>::Header>,
+		)>,
+	),
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
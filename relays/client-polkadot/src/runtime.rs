@@ -30,11 +30,13 @@ pub type UncheckedExtrinsic = bp_polkadot_core::UncheckedExtrinsic<Call>;
 ///
 /// The byte vector returned by this function should be signed with a Kusama account private key.
 /// This way, the owner of `kusam_account_id` on Polkadot proves that the Kusama account private key
-/// is also under his control.
+/// is also under his control. The `kusama_account_nonce` must match the current nonce of the
+/// Kusama account, stored by the Kusama dispatch pallet.
 pub fn polkadot_to_kusama_account_ownership_digest<Call, AccountId, SpecVersion>(
 	kusama_call: &Call,
 	kusam_account_id: AccountId,
 	kusama_spec_version: SpecVersion,
+	kusama_account_nonce: bp_message_dispatch::TargetAccountNonce,
 ) -> Vec<u8>
 where
 	Call: codec::Encode,
@@ -47,6 +49,7 @@ where
 		kusama_spec_version,
 		bp_runtime::POLKADOT_CHAIN_ID,
 		bp_runtime::KUSAMA_CHAIN_ID,
+		kusama_account_nonce,
 	)
 }
 
@@ -101,6 +104,15 @@ pub enum BridgeKusamaGrandpaCall {
 	),
 	#[codec(index = 1)]
 	initialize(bp_header_chain::InitializationData<<PolkadotLike as Chain>::Header>),
+	#[codec(index = 3)]
+	set_operational(bool),
+	#[codec(index = 6)]
+	submit_finality_proofs(
+		Vec<(
+			<PolkadotLike as Chain>::Header,
+			bp_header_chain::justification::GrandpaJustification<<PolkadotLike as Chain>::Header>,
+		)>,
+	),
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
@@ -85,6 +85,7 @@ impl TransactionSignScheme for Rialto {
 				frame_system::CheckNonce::<rialto_runtime::Runtime>::from(unsigned.nonce),
 				frame_system::CheckWeight::<rialto_runtime::Runtime>::new(),
 				pallet_transaction_payment::ChargeTransactionPayment::<rialto_runtime::Runtime>::from(unsigned.tip),
+				rialto_runtime::PrioritizeSlotRelayer::new(),
 			),
 			(
 				rialto_runtime::VERSION.spec_version,
@@ -94,6 +95,7 @@ impl TransactionSignScheme for Rialto {
 				(),
 				(),
 				(),
+				(),
 			),
 		);
 		let signature = raw_payload.using_encoded(|payload| signer.sign(payload));
@@ -29,11 +29,13 @@ pub type UncheckedExtrinsic = bp_polkadot_core::UncheckedExtrinsic<Call>;
 ///
 /// The byte vector returned by this function should be signed with a Wococo account private key.
 /// This way, the owner of `rococo_account_id` on Rococo proves that the Wococo account private key
-/// is also under his control.
+/// is also under his control. The `wococo_account_nonce` must match the current nonce of the
+/// Wococo account, stored by the Wococo dispatch pallet.
 pub fn rococo_to_wococo_account_ownership_digest<Call, AccountId, SpecVersion>(
 	wococo_call: &Call,
 	rococo_account_id: AccountId,
 	wococo_spec_version: SpecVersion,
+	wococo_account_nonce: bp_message_dispatch::TargetAccountNonce,
 ) -> Vec<u8>
 where
 	Call: codec::Encode,
@@ -46,6 +48,7 @@ where
 		wococo_spec_version,
 		bp_runtime::ROCOCO_CHAIN_ID,
 		bp_runtime::WOCOCO_CHAIN_ID,
+		wococo_account_nonce,
 	)
 }
 
@@ -90,6 +93,15 @@ pub enum BridgeGrandpaWococoCall {
 	),
 	#[codec(index = 1)]
 	initialize(bp_header_chain::InitializationData<<PolkadotLike as Chain>::Header>),
+	#[codec(index = 3)]
+	set_operational(bool),
+	#[codec(index = 6)]
+	submit_finality_proofs(
+		Vec<(
+			<PolkadotLike as Chain>::Header,
+			bp_header_chain::justification::GrandpaJustification<<PolkadotLike as Chain>::Header>,
+		)>,
+	),
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
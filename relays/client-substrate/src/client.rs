@@ -19,6 +19,7 @@
 use crate::{
 	chain::{Chain, ChainWithBalances, TransactionStatusOf},
 	rpc::Substrate,
+	transaction_tracker::TransactionTracker,
 	ConnectionParams, Error, HashOf, HeaderIdOf, Result,
 };
 
@@ -48,7 +49,7 @@ use sp_runtime::{
 };
 use sp_trie::StorageProof;
 use sp_version::RuntimeVersion;
-use std::{convert::TryFrom, future::Future};
+use std::{convert::TryFrom, future::Future, time::Duration};
 
 const SUB_API_GRANDPA_AUTHORITIES: &str = "GrandpaApi_grandpa_authorities";
 const SUB_API_TXPOOL_VALIDATE_TRANSACTION: &str = "TaggedTransactionQueue_validate_transaction";
@@ -366,11 +367,41 @@ impl<C: Chain> Client<C> {
 		extrinsic_signer: C::AccountId,
 		prepare_extrinsic: impl FnOnce(HeaderIdOf<C>, C::Index) -> Bytes + Send + 'static,
 	) -> Result<Subscription<TransactionStatusOf<C>>> {
+		self.submit_and_watch_signed_extrinsic_with_hash(extrinsic_signer, prepare_extrinsic)
+			.await
+			.map(|(_, subscription)| subscription)
+	}
+
+	/// Does exactly the same as `submit_signed_extrinsic`, but returns tracker of the submitted
+	/// transaction.
+	///
+	/// If the transaction is neither finalized, nor rejected during `stall_timeout`, the tracker
+	/// considers it lost.
+	pub async fn submit_and_track_signed_extrinsic(
+		&self,
+		extrinsic_signer: C::AccountId,
+		stall_timeout: Duration,
+		prepare_extrinsic: impl FnOnce(HeaderIdOf<C>, C::Index) -> Bytes + Send + 'static,
+	) -> Result<TransactionTracker<C>> {
+		let (transaction_hash, subscription) = self
+			.submit_and_watch_signed_extrinsic_with_hash(extrinsic_signer, prepare_extrinsic)
+			.await?;
+		Ok(TransactionTracker::new(stall_timeout, transaction_hash, subscription))
+	}
+
+	/// Submit an extrinsic signed by given account and subscribe to its status updates.
+	///
+	/// Returns hash of the submitted extrinsic and the status subscription.
+	async fn submit_and_watch_signed_extrinsic_with_hash(
+		&self,
+		extrinsic_signer: C::AccountId,
+		prepare_extrinsic: impl FnOnce(HeaderIdOf<C>, C::Index) -> Bytes + Send + 'static,
+	) -> Result<(C::Hash, Subscription<TransactionStatusOf<C>>)> {
 		let _guard = self.submit_signed_extrinsic_lock.lock().await;
 		let transaction_nonce = self.next_account_index(extrinsic_signer).await?;
 		let best_header = self.best_header().await?;
 		let best_header_id = HeaderId(*best_header.number(), best_header.hash());
-		let subscription = self
+		let (tx_hash, subscription) = self
 			.jsonrpsee_execute(move |client| async move {
 				let extrinsic = prepare_extrinsic(best_header_id, transaction_nonce);
 				let tx_hash = C::Hasher::hash(&extrinsic.0);
@@ -383,7 +414,7 @@ impl<C: Chain> Client<C> {
 					)
 					.await?;
 				log::trace!(target: "bridge", "Sent transaction to {} node: {:?}", C::NAME, tx_hash);
-				Ok(subscription)
+				Ok((tx_hash, subscription))
 			})
 			.await?;
 		let (sender, receiver) = futures::channel::mpsc::channel(MAX_SUBSCRIPTION_CAPACITY);
@@ -393,7 +424,7 @@ impl<C: Chain> Client<C> {
 			subscription,
 			sender,
 		));
-		Ok(Subscription(Mutex::new(receiver)))
+		Ok((tx_hash, Subscription(Mutex::new(receiver))))
 	}
 
 	/// Returns pending extrinsics from transaction pool.
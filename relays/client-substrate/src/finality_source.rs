@@ -97,7 +97,7 @@ where
 		Header = SyncHeader<C::Header>,
 		FinalityProof = GrandpaJustification<C::Header>,
 	>,
-	P::Header: SourceHeader<C::BlockNumber>,
+	P::Header: SourceHeader<C::Hash, C::BlockNumber>,
 {
 	type FinalityProofsStream = Pin<Box<dyn Stream<Item = GrandpaJustification<C::Header>> + Send>>;
 
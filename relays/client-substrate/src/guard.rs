@@ -167,7 +167,7 @@ impl<C: ChainWithBalances> Environment<C> for Client<C> {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
 	use super::*;
 	use frame_support::weights::IdentityFee;
 	use futures::{
@@ -178,7 +178,7 @@ mod tests {
 	};
 
 	#[derive(Debug, Clone)]
-	struct TestChain;
+	pub(crate) struct TestChain;
 
 	impl bp_runtime::Chain for TestChain {
 		type BlockNumber = u32;
@@ -28,6 +28,7 @@ pub mod finality_source;
 pub mod guard;
 pub mod headers_source;
 pub mod metrics;
+pub mod transaction_tracker;
 
 use std::time::Duration;
 
@@ -39,6 +40,7 @@ pub use crate::{
 	client::{Client, OpaqueGrandpaAuthoritiesSet, Subscription},
 	error::{Error, Result},
 	sync_header::SyncHeader,
+	transaction_tracker::TransactionTracker,
 };
 pub use bp_runtime::{
 	AccountIdOf, AccountPublicOf, BalanceOf, BlockNumberOf, Chain as ChainBase, HashOf, HeaderOf,
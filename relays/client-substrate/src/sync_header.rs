@@ -17,7 +17,7 @@
 use bp_header_chain::find_grandpa_authorities_scheduled_change;
 use finality_relay::SourceHeader as FinalitySourceHeader;
 use headers_relay::sync_types::SourceHeader;
-use num_traits::{CheckedSub, One};
+use num_traits::{CheckedSub, One, Zero};
 use relay_utils::HeaderId;
 use sp_runtime::traits::Header as HeaderT;
 
@@ -62,12 +62,27 @@ impl<Header: HeaderT> SourceHeader<Header::Hash, Header::Number> for SyncHeader<
 	}
 }
 
-impl<Header: HeaderT> FinalitySourceHeader<Header::Number> for SyncHeader<Header> {
+impl<Header: HeaderT> FinalitySourceHeader<Header::Hash, Header::Number> for SyncHeader<Header> {
+	fn hash(&self) -> Header::Hash {
+		self.0.hash()
+	}
+
 	fn number(&self) -> Header::Number {
 		*self.0.number()
 	}
 
 	fn is_mandatory(&self) -> bool {
+		// the header that signals scheduled authorities set change must be imported, because the
+		// bridge pallet learns next authorities from its digest. Forced changes are approved by
+		// the pallet owner along with the header, so the header itself isn't mandatory
 		find_grandpa_authorities_scheduled_change(&self.0).is_some()
 	}
+
+	fn delayed_change_enactment(&self) -> Option<Header::Number> {
+		// delayed change is enacted when the enactment header is finalized by the current
+		// authorities set, so the pallet must import exactly this header to switch the set
+		find_grandpa_authorities_scheduled_change(&self.0)
+			.filter(|change| !change.delay.is_zero())
+			.map(|change| *self.0.number() + change.delay)
+	}
 }
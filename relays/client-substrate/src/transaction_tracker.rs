@@ -0,0 +1,200 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracker of transactions, submitted by the relay.
+
+use crate::{Chain, HashOf, Subscription, TransactionStatusOf};
+
+use async_trait::async_trait;
+use futures::{future::FutureExt, select};
+use relay_utils::{TrackedTransactionStatus, TransactionTracker as RelayTransactionTracker};
+use sc_transaction_pool_api::TransactionStatus;
+use std::time::Duration;
+
+/// Substrate transaction tracker implementation.
+///
+/// Substrate node provides RPC API to submit and watch for transaction events. This way
+/// we may know when transaction is included into block, finalized or rejected. There are
+/// some edge cases, when we can't fully trust this mechanism - e.g. transaction may broadcasted
+/// and then dropped out of node transaction pool (some other cases are also possible - node
+/// restarts, connection lost, ...). Then we can't know for sure - what is currently happening
+/// with our transaction. Is the transaction really lost? Is it still alive on the chain
+/// network?
+///
+/// So the tracker also uses the stall timeout. If we haven't seen the final status of the
+/// transaction during this period, we consider it lost.
+pub struct TransactionTracker<C: Chain> {
+	transaction_hash: HashOf<C>,
+	stall_timeout: Duration,
+	subscription: Subscription<TransactionStatusOf<C>>,
+}
+
+impl<C: Chain> TransactionTracker<C> {
+	/// Create transaction tracker.
+	pub fn new(
+		stall_timeout: Duration,
+		transaction_hash: HashOf<C>,
+		subscription: Subscription<TransactionStatusOf<C>>,
+	) -> Self {
+		Self { stall_timeout, transaction_hash, subscription }
+	}
+
+	/// Returns hash of the tracked transaction.
+	pub fn transaction_hash(&self) -> HashOf<C> {
+		self.transaction_hash
+	}
+}
+
+#[async_trait]
+impl<C: Chain> RelayTransactionTracker for TransactionTracker<C> {
+	async fn wait(self) -> TrackedTransactionStatus {
+		let transaction_hash = self.transaction_hash;
+		let wait_for_stall_timeout = async_std::task::sleep(self.stall_timeout).fuse();
+		let wait_for_final_status =
+			watch_transaction_status::<C>(transaction_hash, self.subscription).fuse();
+		futures::pin_mut!(wait_for_stall_timeout, wait_for_final_status);
+
+		select! {
+			_ = wait_for_stall_timeout => {
+				log::trace!(
+					target: "bridge",
+					"{} transaction {:?} is considered lost after timeout",
+					C::NAME,
+					transaction_hash,
+				);
+
+				TrackedTransactionStatus::Lost
+			},
+			status = wait_for_final_status => status,
+		}
+	}
+}
+
+/// Watch for transaction status updates until the final status is received.
+async fn watch_transaction_status<C: Chain>(
+	transaction_hash: HashOf<C>,
+	subscription: Subscription<TransactionStatusOf<C>>,
+) -> TrackedTransactionStatus {
+	loop {
+		match subscription.next().await {
+			Ok(Some(status)) => match on_transaction_status::<C>(transaction_hash, status) {
+				Some(final_status) => return final_status,
+				None => continue,
+			},
+			Ok(None) | Err(_) => {
+				// we have been unable to track the transaction => let's assume it is lost
+				log::trace!(
+					target: "bridge",
+					"{} transaction {:?} is considered lost, because its status subscription has ended",
+					C::NAME,
+					transaction_hash,
+				);
+
+				return TrackedTransactionStatus::Lost
+			},
+		}
+	}
+}
+
+/// Process transaction status update.
+///
+/// Returns `Some(_)` if the status is final and we do not need to watch transaction anymore.
+fn on_transaction_status<C: Chain>(
+	transaction_hash: HashOf<C>,
+	status: TransactionStatusOf<C>,
+) -> Option<TrackedTransactionStatus> {
+	match status {
+		TransactionStatus::Finalized(block_hash) => {
+			log::trace!(
+				target: "bridge",
+				"{} transaction {:?} has been finalized at block: {:?}",
+				C::NAME,
+				transaction_hash,
+				block_hash,
+			);
+			Some(TrackedTransactionStatus::Finalized)
+		},
+		TransactionStatus::Invalid |
+		TransactionStatus::Usurped(_) |
+		TransactionStatus::Dropped |
+		TransactionStatus::FinalityTimeout(_) => {
+			log::trace!(
+				target: "bridge",
+				"{} transaction {:?} has been lost: {:?}",
+				C::NAME,
+				transaction_hash,
+				status,
+			);
+			Some(TrackedTransactionStatus::Lost)
+		},
+		TransactionStatus::InBlock(block_hash) => {
+			log::trace!(
+				target: "bridge",
+				"{} transaction {:?} has been included in block: {:?}",
+				C::NAME,
+				transaction_hash,
+				block_hash,
+			);
+			None
+		},
+		TransactionStatus::Future |
+		TransactionStatus::Ready |
+		TransactionStatus::Broadcast(_) |
+		TransactionStatus::Retracted(_) => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::guard::tests::TestChain;
+	use sp_core::H256;
+
+	fn on_status(status: TransactionStatusOf<TestChain>) -> Option<TrackedTransactionStatus> {
+		on_transaction_status::<TestChain>(Default::default(), status)
+	}
+
+	#[test]
+	fn finalized_transaction_is_reported() {
+		assert_eq!(
+			on_status(TransactionStatus::Finalized(H256::default())),
+			Some(TrackedTransactionStatus::Finalized),
+		);
+	}
+
+	#[test]
+	fn lost_transaction_is_reported() {
+		assert_eq!(on_status(TransactionStatus::Invalid), Some(TrackedTransactionStatus::Lost));
+		assert_eq!(on_status(TransactionStatus::Dropped), Some(TrackedTransactionStatus::Lost));
+		assert_eq!(
+			on_status(TransactionStatus::Usurped(H256::default())),
+			Some(TrackedTransactionStatus::Lost),
+		);
+		assert_eq!(
+			on_status(TransactionStatus::FinalityTimeout(H256::default())),
+			Some(TrackedTransactionStatus::Lost),
+		);
+	}
+
+	#[test]
+	fn intermediate_statuses_are_ignored() {
+		assert_eq!(on_status(TransactionStatus::Future), None);
+		assert_eq!(on_status(TransactionStatus::Ready), None);
+		assert_eq!(on_status(TransactionStatus::Broadcast(vec![])), None);
+		assert_eq!(on_status(TransactionStatus::InBlock(H256::default())), None);
+		assert_eq!(on_status(TransactionStatus::Retracted(H256::default())), None);
+	}
+}
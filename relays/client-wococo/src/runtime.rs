@@ -29,11 +29,13 @@ pub type UncheckedExtrinsic = bp_polkadot_core::UncheckedExtrinsic<Call>;
 ///
 /// The byte vector returned by this function should be signed with a Rococo account private key.
 /// This way, the owner of `wococo_account_id` on Rococo proves that the Rococo account private key
-/// is also under his control.
+/// is also under his control. The `rococo_account_nonce` must match the current nonce of the
+/// Rococo account, stored by the Rococo dispatch pallet.
 pub fn wococo_to_rococo_account_ownership_digest<Call, AccountId, SpecVersion>(
 	rococo_call: &Call,
 	wococo_account_id: AccountId,
 	rococo_spec_version: SpecVersion,
+	rococo_account_nonce: bp_message_dispatch::TargetAccountNonce,
 ) -> Vec<u8>
 where
 	Call: codec::Encode,
@@ -46,6 +48,7 @@ where
 		rococo_spec_version,
 		bp_runtime::WOCOCO_CHAIN_ID,
 		bp_runtime::ROCOCO_CHAIN_ID,
+		rococo_account_nonce,
 	)
 }
 
@@ -90,6 +93,15 @@ pub enum BridgeGrandpaRococoCall {
 	),
 	#[codec(index = 1)]
 	initialize(bp_header_chain::InitializationData<<PolkadotLike as Chain>::Header>),
+	#[codec(index = 3)]
+	set_operational(bool),
+	#[codec(index = 6)]
+	submit_finality_proofs(
+		Vec<(
+			<PolkadotLike as Chain>::Header,
+			bp_header_chain::justification::GrandpaJustification<<PolkadotLike as Chain>::Header>,
+		)>,
+	),
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
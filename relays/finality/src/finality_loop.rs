@@ -17,22 +17,29 @@
 //! The loop basically reads all missing headers and their finality proofs from the source client.
 //! The proof for the best possible header is then submitted to the target node. The only exception
 //! is the mandatory headers, which we always submit to the target node. For such headers, we
-//! assume that the persistent proof either exists, or will eventually become available.
+//! assume that the persistent proof either exists, or will eventually become available. If
+//! there are several mandatory headers missing from the target node, their proofs are submitted
+//! in a single transaction.
 
 use crate::{FinalityProof, FinalitySyncPipeline, SourceHeader};
 
 use async_trait::async_trait;
 use backoff::backoff::Backoff;
-use futures::{select, Future, FutureExt, Stream, StreamExt};
+use futures::{future::BoxFuture, select, Future, FutureExt, Stream, StreamExt};
 use headers_relay::sync_loop_metrics::SyncLoopMetrics;
 use num_traits::{One, Saturating};
 use relay_utils::{
-	metrics::{GlobalMetrics, MetricsParams},
+	metrics::{
+		metric_name, register, Counter, GlobalMetrics, MetricsParams, Opts, PrometheusError,
+		Registry, U64,
+	},
 	relay_loop::Client as RelayClient,
-	retry_backoff, FailedClient, MaybeConnectionError,
+	retry_backoff, FailedClient, HeaderId, MaybeConnectionError, TrackedTransactionStatus,
+	TransactionTracker,
 };
 use std::{
 	pin::Pin,
+	sync::{Arc, Mutex},
 	time::{Duration, Instant},
 };
 
@@ -61,6 +68,38 @@ pub struct FinalitySyncParams {
 	pub stall_timeout: Duration,
 	/// If true, only mandatory headers are relayed.
 	pub only_mandatory_headers: bool,
+	/// Maximal number of mandatory headers that may be submitted in a single transaction.
+	///
+	/// When the relay is far behind the source chain (e.g. after downtime), there may be many
+	/// mandatory headers that we need to submit. Submitting them in a single transaction is
+	/// cheaper. The target pallet still counts every header against its rate limit, so it must
+	/// not exceed the `MaxRequests` of the pallet. If it is `1`, headers are always submitted
+	/// one-by-one.
+	pub max_mandatory_headers_per_transaction: usize,
+	/// Maximal number of headers that we read after the first mandatory header, when looking for
+	/// next mandatory headers to submit in the same transaction.
+	pub mandatory_headers_look_ahead: usize,
+	/// What to do if the best source header, known to the target node, is not the canonical
+	/// source header.
+	pub fork_detection: ForkDetectionMode,
+}
+
+/// Fork detection mode.
+///
+/// The target node may only import headers that are justified by the current GRANDPA authorities
+/// set. If the best header, known to the target node, is not in the canonical source chain, it
+/// means that the source chain authorities have finalized conflicting forks (or that the bridge
+/// has been compromised in some other way).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ForkDetectionMode {
+	/// Headers, imported by the target node, are not verified.
+	Disabled,
+	/// If non-canonical header is detected, the loop stops relaying headers and keeps reporting
+	/// the fork.
+	Report,
+	/// Same as `Report`, but the loop also submits transaction that halts the bridge pallet at the
+	/// target node. The transaction will only succeed if the relayer is the pallet owner.
+	Halt,
 }
 
 /// Source client used in finality synchronization loop.
@@ -86,15 +125,32 @@ pub trait SourceClient<P: FinalitySyncPipeline>: RelayClient {
 /// Target client used in finality synchronization loop.
 #[async_trait]
 pub trait TargetClient<P: FinalitySyncPipeline>: RelayClient {
-	/// Get best finalized source block number.
-	async fn best_finalized_source_block_number(&self) -> Result<P::Number, Self::Error>;
+	/// Transaction tracker to track submitted transactions.
+	type TransactionTracker: TransactionTracker;
+
+	/// Get best finalized source block id.
+	async fn best_finalized_source_block_id(
+		&self,
+	) -> Result<HeaderId<P::Hash, P::Number>, Self::Error>;
 
 	/// Submit header finality proof.
 	async fn submit_finality_proof(
 		&self,
 		header: P::Header,
 		proof: P::FinalityProof,
-	) -> Result<(), Self::Error>;
+	) -> Result<Self::TransactionTracker, Self::Error>;
+
+	/// Submit finality proofs of multiple headers in a single transaction.
+	///
+	/// Headers are ordered by their numbers. Proof of every header may only be verified after
+	/// all previous headers are imported.
+	async fn submit_finality_proofs(
+		&self,
+		finality_proofs: Vec<(P::Header, P::FinalityProof)>,
+	) -> Result<Self::TransactionTracker, Self::Error>;
+
+	/// Submit transaction that halts all operations of the bridge pallet.
+	async fn halt_bridge(&self) -> Result<(), Self::Error>;
 }
 
 /// Return prefix that will be used by default to expose Prometheus metrics of the finality proofs
@@ -112,9 +168,12 @@ pub async fn run<P: FinalitySyncPipeline>(
 	exit_signal: impl Future<Output = ()> + 'static + Send,
 ) -> anyhow::Result<()> {
 	let exit_signal = exit_signal.shared();
+	// fork detection state must survive reconnects, otherwise the same fork would be reported
+	// (and the bridge halted) again after every reconnect
+	let last_verified_header_at_target = Arc::new(Mutex::new(None));
 	relay_utils::relay_loop(source_client, target_client)
 		.with_metrics(Some(metrics_prefix::<P>()), metrics_params)
-		.loop_metric(|registry, prefix| SyncLoopMetrics::new(registry, prefix))?
+		.loop_metric(|registry, prefix| FinalityLoopMetrics::new(registry, prefix))?
 		.standalone_metric(|registry, prefix| GlobalMetrics::new(registry, prefix))?
 		.expose()
 		.await?
@@ -124,12 +183,38 @@ pub async fn run<P: FinalitySyncPipeline>(
 				target_client,
 				sync_params.clone(),
 				metrics,
+				last_verified_header_at_target.clone(),
 				exit_signal.clone(),
 			)
 		})
 		.await
 }
 
+/// Finality synchronization loop metrics.
+#[derive(Clone)]
+pub(crate) struct FinalityLoopMetrics {
+	/// Headers synchronization metrics.
+	sync: SyncLoopMetrics,
+	/// Number of times we have seen non-canonical source header at the target node.
+	detected_forks: Counter<U64>,
+}
+
+impl FinalityLoopMetrics {
+	/// Create and register finality loop metrics.
+	pub fn new(registry: &Registry, prefix: Option<&str>) -> Result<Self, PrometheusError> {
+		Ok(FinalityLoopMetrics {
+			sync: SyncLoopMetrics::new(registry, prefix)?,
+			detected_forks: register(
+				Counter::with_opts(Opts::new(
+					metric_name(prefix, "detected_forks"),
+					"Number of times non-canonical source header has been seen at the target node",
+				))?,
+				registry,
+			)?,
+		})
+	}
+}
+
 /// Unjustified headers container. Ordered by header number.
 pub(crate) type UnjustifiedHeaders<H> = Vec<H>;
 /// Finality proofs container. Ordered by target header number.
@@ -148,6 +233,8 @@ pub(crate) enum Error<P: FinalitySyncPipeline, SourceError, TargetError> {
 	Target(TargetError),
 	/// Finality proof for mandatory header is missing from the source node.
 	MissingMandatoryFinalityProof(P::Number),
+	/// Best source header, known to the target node, is not in the canonical source chain.
+	ForkDetected(HeaderId<P::Hash, P::Number>),
 	/// The synchronization has stalled.
 	Stalled,
 }
@@ -169,12 +256,28 @@ where
 }
 
 /// Information about transaction that we have submitted.
-#[derive(Debug, Clone)]
 struct Transaction<Number> {
 	/// Time when we have submitted this transaction.
 	pub time: Instant,
 	/// The number of the header we have submitted.
 	pub submitted_header_number: Number,
+	/// Future that resolves when the transaction is finalized or lost.
+	tracker: BoxFuture<'static, TrackedTransactionStatus>,
+	/// Final transaction status, if it is already known.
+	status: Option<TrackedTransactionStatus>,
+}
+
+impl<Number> Transaction<Number> {
+	/// Return final status of the transaction, if it is already known.
+	///
+	/// The function never blocks - if the tracker has not yet reported the final status,
+	/// `None` is returned.
+	fn status(&mut self) -> Option<TrackedTransactionStatus> {
+		if self.status.is_none() {
+			self.status = (&mut self.tracker).now_or_never();
+		}
+		self.status
+	}
 }
 
 /// Finality proofs stream that may be restarted.
@@ -192,6 +295,18 @@ impl<S> From<S> for RestartableFinalityProofsStream<S> {
 	}
 }
 
+/// Last best source header at the target node that we have compared with the canonical source
+/// header, and the comparison result.
+type VerifiedHeaderAtTarget<P> = Option<(
+	HeaderId<<P as FinalitySyncPipeline>::Hash, <P as FinalitySyncPipeline>::Number>,
+	bool,
+)>;
+
+/// Number of the best source header at the target node and number of the header that enacts
+/// the delayed change, signalled by this header (if any).
+type DelayedChangeAtTarget<P> =
+	Option<(<P as FinalitySyncPipeline>::Number, Option<<P as FinalitySyncPipeline>::Number>)>;
+
 /// Finality synchronization loop state.
 struct FinalityLoopState<'a, P: FinalitySyncPipeline, FinalityProofsStream> {
 	/// Synchronization loop progress.
@@ -201,14 +316,20 @@ struct FinalityLoopState<'a, P: FinalitySyncPipeline, FinalityProofsStream> {
 	/// Recent finality proofs that we have read from the stream.
 	recent_finality_proofs: &'a mut FinalityProofs<P>,
 	/// Last transaction that we have submitted to the target node.
-	last_transaction: Option<Transaction<P::Number>>,
+	last_transaction: &'a mut Option<Transaction<P::Number>>,
+	/// Last best source header at the target node that we have compared with the canonical
+	/// source header, and the comparison result.
+	last_verified_header_at_target: &'a mut VerifiedHeaderAtTarget<P>,
+	/// Delayed change, signalled by the best source header at the target node.
+	delayed_change_at_target: &'a mut DelayedChangeAtTarget<P>,
 }
 
 async fn run_until_connection_lost<P: FinalitySyncPipeline>(
 	source_client: impl SourceClient<P>,
 	target_client: impl TargetClient<P>,
 	sync_params: FinalitySyncParams,
-	metrics_sync: Option<SyncLoopMetrics>,
+	metrics: Option<FinalityLoopMetrics>,
+	shared_last_verified_header_at_target: Arc<Mutex<VerifiedHeaderAtTarget<P>>>,
 	exit_signal: impl Future<Output = ()>,
 ) -> Result<(), FailedClient> {
 	let restart_finality_proofs_stream = || async {
@@ -232,10 +353,14 @@ async fn run_until_connection_lost<P: FinalitySyncPipeline>(
 		stream: Box::pin(restart_finality_proofs_stream().await?),
 	};
 	let mut recent_finality_proofs = Vec::new();
+	let mut delayed_change_at_target = None;
 
 	let mut progress = (Instant::now(), None);
 	let mut retry_backoff = retry_backoff();
 	let mut last_transaction = None;
+	let mut last_verified_header_at_target = *shared_last_verified_header_at_target
+		.lock()
+		.expect("the mutex is only locked by the finality loop; qed");
 
 	loop {
 		// run loop iteration
@@ -246,17 +371,21 @@ async fn run_until_connection_lost<P: FinalitySyncPipeline>(
 				progress: &mut progress,
 				finality_proofs_stream: &mut finality_proofs_stream,
 				recent_finality_proofs: &mut recent_finality_proofs,
-				last_transaction: last_transaction.clone(),
+				last_transaction: &mut last_transaction,
+				last_verified_header_at_target: &mut last_verified_header_at_target,
+				delayed_change_at_target: &mut delayed_change_at_target,
 			},
 			&sync_params,
-			&metrics_sync,
+			&metrics,
 		)
 		.await;
+		*shared_last_verified_header_at_target
+			.lock()
+			.expect("the mutex is only locked by the finality loop; qed") = last_verified_header_at_target;
 
 		// deal with errors
 		let next_tick = match iteration_result {
-			Ok(updated_last_transaction) => {
-				last_transaction = updated_last_transaction;
+			Ok(()) => {
 				retry_backoff.reset();
 				sync_params.tick
 			},
@@ -286,8 +415,8 @@ async fn run_loop_iteration<P, SC, TC>(
 	target_client: &TC,
 	state: FinalityLoopState<'_, P, SC::FinalityProofsStream>,
 	sync_params: &FinalitySyncParams,
-	metrics_sync: &Option<SyncLoopMetrics>,
-) -> Result<Option<Transaction<P::Number>>, Error<P, SC::Error, TC::Error>>
+	metrics: &Option<FinalityLoopMetrics>,
+) -> Result<(), Error<P, SC::Error, TC::Error>>
 where
 	P: FinalitySyncPipeline,
 	SC: SourceClient<P>,
@@ -296,22 +425,48 @@ where
 	// read best source headers ids from source and target nodes
 	let best_number_at_source =
 		source_client.best_finalized_block_number().await.map_err(Error::Source)?;
-	let best_number_at_target = target_client
-		.best_finalized_source_block_number()
-		.await
-		.map_err(Error::Target)?;
-	if let Some(ref metrics_sync) = *metrics_sync {
-		metrics_sync.update_best_block_at_source(best_number_at_source);
-		metrics_sync.update_best_block_at_target(best_number_at_target);
+	let best_id_at_target =
+		target_client.best_finalized_source_block_id().await.map_err(Error::Target)?;
+	let best_number_at_target = best_id_at_target.0;
+	if let Some(ref metrics) = *metrics {
+		metrics.sync.update_best_block_at_source(best_number_at_source);
+		metrics.sync.update_best_block_at_target(best_number_at_target);
 	}
 	*state.progress =
 		print_sync_progress::<P>(*state.progress, best_number_at_source, best_number_at_target);
 
+	// if we're watching for forks, ensure that the best header at target is the canonical header
+	if sync_params.fork_detection != ForkDetectionMode::Disabled {
+		ensure_best_header_at_target_is_canonical(
+			source_client,
+			target_client,
+			state.last_verified_header_at_target,
+			best_number_at_source,
+			best_id_at_target,
+			sync_params.fork_detection,
+			metrics,
+		)
+		.await?;
+	}
+
 	// if we have already submitted header, then we just need to wait for it
+	// if the transaction has been lost, we may submit new header immediately
 	// if we're waiting too much, then we believe our transaction has been lost and restart sync
-	if let Some(last_transaction) = state.last_transaction {
+	if let Some(last_transaction) = state.last_transaction.as_mut() {
 		if best_number_at_target >= last_transaction.submitted_header_number {
 			// transaction has been mined && we can continue
+			*state.last_transaction = None;
+		} else if last_transaction.status() == Some(TrackedTransactionStatus::Lost) {
+			log::warn!(
+				target: "bridge",
+				"Finality synchronization from {} to {} has lost transaction with header #{:?}. \
+				Going to resubmit",
+				P::SOURCE_NAME,
+				P::TARGET_NAME,
+				last_transaction.submitted_header_number,
+			);
+
+			*state.last_transaction = None;
 		} else if last_transaction.time.elapsed() > sync_params.stall_timeout {
 			log::error!(
 				target: "bridge",
@@ -322,7 +477,7 @@ where
 
 			return Err(Error::Stalled)
 		} else {
-			return Ok(Some(last_transaction))
+			return Ok(())
 		}
 	}
 
@@ -332,32 +487,130 @@ where
 		target_client,
 		state.finality_proofs_stream,
 		state.recent_finality_proofs,
+		state.delayed_change_at_target,
 		best_number_at_source,
 		best_number_at_target,
 		sync_params,
 	)
 	.await?
 	{
-		Some((header, justification)) => {
-			let new_transaction =
-				Transaction { time: Instant::now(), submitted_header_number: header.number() };
+		Some(mut finality_proofs) => {
+			let submitted_header_number = match finality_proofs.last() {
+				Some((header, _)) => header.number(),
+				None => return Ok(()),
+			};
 
 			log::debug!(
 				target: "bridge",
-				"Going to submit finality proof of {} header #{:?} to {}",
+				"Going to submit finality proofs of {} {} header(s) (up to #{:?}) to {}",
+				finality_proofs.len(),
 				P::SOURCE_NAME,
-				new_transaction.submitted_header_number,
+				submitted_header_number,
 				P::TARGET_NAME,
 			);
 
-			target_client
-				.submit_finality_proof(header, justification)
-				.await
-				.map_err(Error::Target)?;
-			Ok(Some(new_transaction))
+			let tracker = match finality_proofs.len() {
+				1 => {
+					let (header, justification) = finality_proofs.remove(0);
+					target_client.submit_finality_proof(header, justification).await
+				},
+				_ => target_client.submit_finality_proofs(finality_proofs).await,
+			}
+			.map_err(Error::Target)?;
+			*state.last_transaction = Some(Transaction {
+				time: Instant::now(),
+				submitted_header_number,
+				tracker: tracker.wait(),
+				status: None,
+			});
+			Ok(())
 		},
-		None => Ok(None),
+		None => Ok(()),
+	}
+}
+
+/// Ensure that the best source header, known to the target node, is in the canonical source chain.
+///
+/// Every header is only compared once. If the header is not canonical, the function reports
+/// the fork and (if requested) submits transaction that halts the bridge at the target node. The
+/// halt transaction is only submitted once - if it fails (e.g. because the relayer is not the
+/// pallet owner), the failure is logged and the fork keeps being reported.
+pub(crate) async fn ensure_best_header_at_target_is_canonical<P, SC, TC>(
+	source_client: &SC,
+	target_client: &TC,
+	last_verified_header_at_target: &mut VerifiedHeaderAtTarget<P>,
+	best_number_at_source: P::Number,
+	best_id_at_target: HeaderId<P::Hash, P::Number>,
+	fork_detection: ForkDetectionMode,
+	metrics: &Option<FinalityLoopMetrics>,
+) -> Result<(), Error<P, SC::Error, TC::Error>>
+where
+	P: FinalitySyncPipeline,
+	SC: SourceClient<P>,
+	TC: TargetClient<P>,
+{
+	// if we have already verified this header, the fork (if any) has been already reported
+	match *last_verified_header_at_target {
+		Some((verified_id, true)) if verified_id == best_id_at_target => return Ok(()),
+		Some((verified_id, false)) if verified_id == best_id_at_target =>
+			return Err(Error::ForkDetected(best_id_at_target)),
+		_ => (),
 	}
+
+	// the source node may be (temporarily) behind the target node => we can't verify the header
+	// until the source node is synced
+	if best_id_at_target.0 > best_number_at_source {
+		return Ok(())
+	}
+
+	let (canonical_header, _) = source_client
+		.header_and_finality_proof(best_id_at_target.0)
+		.await
+		.map_err(Error::Source)?;
+	let canonical_hash = canonical_header.hash();
+	let is_canonical = canonical_hash == best_id_at_target.1;
+	if is_canonical {
+		*last_verified_header_at_target = Some((best_id_at_target, true));
+		return Ok(())
+	}
+
+	log::error!(
+		target: "bridge",
+		"Best {} header at {} is {:?}, but canonical {} header #{:?} is {:?}. The bridge is compromised!",
+		P::SOURCE_NAME,
+		P::TARGET_NAME,
+		best_id_at_target,
+		P::SOURCE_NAME,
+		best_id_at_target.0,
+		canonical_hash,
+	);
+	if let Some(ref metrics) = *metrics {
+		metrics.detected_forks.inc();
+	}
+
+	if fork_detection == ForkDetectionMode::Halt {
+		log::warn!(
+			target: "bridge",
+			"Going to halt {} bridge pallet at {}",
+			P::SOURCE_NAME,
+			P::TARGET_NAME,
+		);
+
+		// the halt transaction is not retried: if the relayer is not allowed to halt the bridge,
+		// retrying won't help
+		if let Err(error) = target_client.halt_bridge().await {
+			log::error!(
+				target: "bridge",
+				"Failed to halt {} bridge pallet at {}: {:?}",
+				P::SOURCE_NAME,
+				P::TARGET_NAME,
+				error,
+			);
+		}
+	}
+
+	*last_verified_header_at_target = Some((best_id_at_target, false));
+	Err(Error::ForkDetected(best_id_at_target))
 }
 
 pub(crate) async fn select_header_to_submit<P, SC, TC>(
@@ -365,10 +618,11 @@ pub(crate) async fn select_header_to_submit<P, SC, TC>(
 	target_client: &TC,
 	finality_proofs_stream: &mut RestartableFinalityProofsStream<SC::FinalityProofsStream>,
 	recent_finality_proofs: &mut FinalityProofs<P>,
+	delayed_change_at_target: &mut DelayedChangeAtTarget<P>,
 	best_number_at_source: P::Number,
 	best_number_at_target: P::Number,
 	sync_params: &FinalitySyncParams,
-) -> Result<Option<(P::Header, P::FinalityProof)>, Error<P, SC::Error, TC::Error>>
+) -> Result<Option<Vec<(P::Header, P::FinalityProof)>>, Error<P, SC::Error, TC::Error>>
 where
 	P: FinalitySyncPipeline,
 	SC: SourceClient<P>,
@@ -383,17 +637,19 @@ where
 	);
 
 	// read missing headers. if we see that the header schedules GRANDPA change, we need to
-	// submit this header
+	// submit this header (and maybe few next mandatory headers)
 	let selected_finality_proof = read_missing_headers::<P, SC, TC>(
 		source_client,
 		target_client,
+		delayed_change_at_target,
 		best_number_at_source,
 		best_number_at_target,
+		sync_params.max_mandatory_headers_per_transaction,
+		sync_params.mandatory_headers_look_ahead,
 	)
 	.await?;
 	let (mut unjustified_headers, mut selected_finality_proof) = match selected_finality_proof {
-		SelectedFinalityProof::Mandatory(header, finality_proof) =>
-			return Ok(Some((header, finality_proof))),
+		SelectedFinalityProof::Mandatory(finality_proofs) => return Ok(Some(finality_proofs)),
 		_ if sync_params.only_mandatory_headers => {
 			// we are not reading finality proofs from the stream, so eventually it'll break
 			// but we don't care about transient proofs at all, so it is acceptable
@@ -425,13 +681,14 @@ where
 		sync_params.recent_finality_proofs_limit,
 	);
 
-	Ok(selected_finality_proof)
+	Ok(selected_finality_proof.map(|finality_proof| vec![finality_proof]))
 }
 
 /// Finality proof that has been selected by the `read_missing_headers` function.
 pub(crate) enum SelectedFinalityProof<Header, FinalityProof> {
-	/// Mandatory header and its proof has been selected. We shall submit proof for this header.
-	Mandatory(Header, FinalityProof),
+	/// Mandatory headers and their proofs have been selected. We shall submit proofs for these
+	/// headers. There's always at least one header.
+	Mandatory(Vec<(Header, FinalityProof)>),
 	/// Regular header and its proof has been selected. We may submit this proof, or proof for
 	/// some better header.
 	Regular(UnjustifiedHeaders<Header>, Header, FinalityProof),
@@ -444,7 +701,17 @@ pub(crate) enum SelectedFinalityProof<Header, FinalityProof> {
 /// If we have found some header with known proof, it is returned.
 /// Otherwise, `SelectedFinalityProof::None` is returned.
 ///
-/// Unless we have found mandatory header, all missing headers are collected and returned.
+/// If we have found mandatory header, we keep reading headers to find next mandatory headers
+/// (up to `max_mandatory_headers` in total), so that they could be submitted in a single
+/// transaction. Non-mandatory headers between them are ignored. We read at most
+/// `mandatory_headers_look_ahead` headers after the first mandatory header. Otherwise, all
+/// missing headers are collected and returned.
+///
+/// If the best header at the target client (or any of selected mandatory headers) has signalled
+/// delayed change, the header that enacts this change is also mandatory. Headers before it are
+/// ignored, because the target client won't accept them. The change, signalled by the best header
+/// at the target client, is only read once per best header and then cached in
+/// `delayed_change_at_target`.
 pub(crate) async fn read_missing_headers<
 	P: FinalitySyncPipeline,
 	SC: SourceClient<P>,
@@ -452,25 +719,67 @@ pub(crate) async fn read_missing_headers<
 >(
 	source_client: &SC,
 	_target_client: &TC,
+	delayed_change_at_target: &mut DelayedChangeAtTarget<P>,
 	best_number_at_source: P::Number,
 	best_number_at_target: P::Number,
+	max_mandatory_headers: usize,
+	mandatory_headers_look_ahead: usize,
 ) -> Result<SelectedFinalityProof<P::Header, P::FinalityProof>, Error<P, SC::Error, TC::Error>> {
+	let mut mandatory_headers = Vec::new();
+	let mut headers_read_after_mandatory = 0;
 	let mut unjustified_headers = Vec::new();
 	let mut selected_finality_proof = None;
+	let mut delayed_change_enactment = match *delayed_change_at_target {
+		Some((number, delayed_change_enactment)) if number == best_number_at_target =>
+			delayed_change_enactment,
+		_ => {
+			let delayed_change_enactment = source_client
+				.header_and_finality_proof(best_number_at_target)
+				.await
+				.map_err(Error::Source)?
+				.0
+				.delayed_change_enactment();
+			*delayed_change_at_target = Some((best_number_at_target, delayed_change_enactment));
+			delayed_change_enactment
+		},
+	};
 	let mut header_number = best_number_at_target + One::one();
 	while header_number <= best_number_at_source {
+		if let Some(enact_at) =
+			delayed_change_enactment.filter(|enact_at| *enact_at > header_number)
+		{
+			header_number = enact_at;
+			continue
+		}
+
+		// we don't want to read all headers up to the best source header only to find that there
+		// are no more mandatory headers there
+		if !mandatory_headers.is_empty() {
+			if headers_read_after_mandatory >= mandatory_headers_look_ahead {
+				break
+			}
+			headers_read_after_mandatory += 1;
+		}
+
 		let (header, finality_proof) = source_client
 			.header_and_finality_proof(header_number)
 			.await
 			.map_err(Error::Source)?;
-		let is_mandatory = header.is_mandatory();
+		let is_mandatory = header.is_mandatory() || delayed_change_enactment == Some(header_number);
+		delayed_change_enactment = header.delayed_change_enactment();
 
 		match (is_mandatory, finality_proof) {
 			(true, Some(finality_proof)) => {
 				log::trace!(target: "bridge", "Header {:?} is mandatory", header_number);
-				return Ok(SelectedFinalityProof::Mandatory(header, finality_proof))
+				mandatory_headers.push((header, finality_proof));
+				if mandatory_headers.len() >= max_mandatory_headers {
+					break
+				}
 			},
+			// we may still submit mandatory headers that we have found before
+			(true, None) if !mandatory_headers.is_empty() => break,
 			(true, None) => return Err(Error::MissingMandatoryFinalityProof(header.number())),
+			(false, _) if !mandatory_headers.is_empty() => (),
 			(false, Some(finality_proof)) => {
 				log::trace!(target: "bridge", "Header {:?} has persistent finality proof", header_number);
 				unjustified_headers.clear();
@@ -484,6 +793,10 @@ pub(crate) async fn read_missing_headers<
 		header_number = header_number + One::one();
 	}
 
+	if !mandatory_headers.is_empty() {
+		return Ok(SelectedFinalityProof::Mandatory(mandatory_headers))
+	}
+
 	Ok(match selected_finality_proof {
 		Some((header, proof)) => SelectedFinalityProof::Regular(unjustified_headers, header, proof),
 		None => SelectedFinalityProof::None(unjustified_headers),
@@ -20,9 +20,10 @@
 
 use crate::{
 	finality_loop::{
-		prune_recent_finality_proofs, read_finality_proofs_from_stream, run,
-		select_better_recent_finality_proof, select_header_to_submit, FinalityProofs,
-		FinalitySyncParams, RestartableFinalityProofsStream, SourceClient, TargetClient,
+		ensure_best_header_at_target_is_canonical, prune_recent_finality_proofs,
+		read_finality_proofs_from_stream, run, select_better_recent_finality_proof,
+		select_header_to_submit, Error, FinalityProofs, FinalitySyncParams, ForkDetectionMode,
+		RestartableFinalityProofsStream, SourceClient, TargetClient,
 	},
 	FinalityProof, FinalitySyncPipeline, SourceHeader,
 };
@@ -31,12 +32,15 @@ use async_trait::async_trait;
 use futures::{FutureExt, Stream, StreamExt};
 use parking_lot::Mutex;
 use relay_utils::{
-	metrics::MetricsParams, relay_loop::Client as RelayClient, MaybeConnectionError,
+	metrics::MetricsParams, relay_loop::Client as RelayClient, HeaderId, MaybeConnectionError,
+	TrackedTransactionStatus, TransactionTracker,
 };
 use std::{collections::HashMap, pin::Pin, sync::Arc, time::Duration};
 
 type IsMandatory = bool;
+type DelayedChangeEnactment = Option<TestNumber>;
 type TestNumber = u64;
+type TestHash = u64;
 
 #[derive(Debug, Clone)]
 enum TestError {
@@ -49,6 +53,22 @@ impl MaybeConnectionError for TestError {
 	}
 }
 
+#[derive(Debug, Clone)]
+struct TestTransactionTracker(TrackedTransactionStatus);
+
+impl Default for TestTransactionTracker {
+	fn default() -> TestTransactionTracker {
+		TestTransactionTracker(TrackedTransactionStatus::Finalized)
+	}
+}
+
+#[async_trait]
+impl TransactionTracker for TestTransactionTracker {
+	async fn wait(self) -> TrackedTransactionStatus {
+		self.0
+	}
+}
+
 #[derive(Debug, Clone)]
 struct TestFinalitySyncPipeline;
 
@@ -56,16 +76,20 @@ impl FinalitySyncPipeline for TestFinalitySyncPipeline {
 	const SOURCE_NAME: &'static str = "TestSource";
 	const TARGET_NAME: &'static str = "TestTarget";
 
-	type Hash = u64;
+	type Hash = TestHash;
 	type Number = TestNumber;
 	type Header = TestSourceHeader;
 	type FinalityProof = TestFinalityProof;
 }
 
 #[derive(Debug, Clone, PartialEq)]
-struct TestSourceHeader(IsMandatory, TestNumber);
+struct TestSourceHeader(IsMandatory, TestNumber, TestHash, DelayedChangeEnactment);
+
+impl SourceHeader<TestHash, TestNumber> for TestSourceHeader {
+	fn hash(&self) -> TestHash {
+		self.2
+	}
 
-impl SourceHeader<TestNumber> for TestSourceHeader {
 	fn number(&self) -> TestNumber {
 		self.1
 	}
@@ -73,6 +97,10 @@ impl SourceHeader<TestNumber> for TestSourceHeader {
 	fn is_mandatory(&self) -> bool {
 		self.0
 	}
+
+	fn delayed_change_enactment(&self) -> Option<TestNumber> {
+		self.3
+	}
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -91,7 +119,11 @@ struct ClientsData {
 	source_proofs: Vec<TestFinalityProof>,
 
 	target_best_block_number: TestNumber,
+	target_best_block_hash: TestHash,
 	target_headers: Vec<(TestSourceHeader, TestFinalityProof)>,
+	target_batches: usize,
+	target_is_halted: bool,
+	target_fails_to_halt: bool,
 }
 
 #[derive(Clone)]
@@ -152,21 +184,51 @@ impl RelayClient for TestTargetClient {
 
 #[async_trait]
 impl TargetClient<TestFinalitySyncPipeline> for TestTargetClient {
-	async fn best_finalized_source_block_number(&self) -> Result<TestNumber, TestError> {
+	type TransactionTracker = TestTransactionTracker;
+
+	async fn best_finalized_source_block_id(
+		&self,
+	) -> Result<HeaderId<TestHash, TestNumber>, TestError> {
 		let mut data = self.data.lock();
 		(self.on_method_call)(&mut *data);
-		Ok(data.target_best_block_number)
+		Ok(HeaderId(data.target_best_block_number, data.target_best_block_hash))
 	}
 
 	async fn submit_finality_proof(
 		&self,
 		header: TestSourceHeader,
 		proof: TestFinalityProof,
-	) -> Result<(), TestError> {
+	) -> Result<TestTransactionTracker, TestError> {
 		let mut data = self.data.lock();
 		(self.on_method_call)(&mut *data);
 		data.target_best_block_number = header.number();
+		data.target_best_block_hash = header.hash();
 		data.target_headers.push((header, proof));
+		Ok(TestTransactionTracker::default())
+	}
+
+	async fn submit_finality_proofs(
+		&self,
+		finality_proofs: Vec<(TestSourceHeader, TestFinalityProof)>,
+	) -> Result<TestTransactionTracker, TestError> {
+		let mut data = self.data.lock();
+		(self.on_method_call)(&mut *data);
+		if let Some((header, _)) = finality_proofs.last() {
+			data.target_best_block_number = header.number();
+			data.target_best_block_hash = header.hash();
+		}
+		data.target_headers.extend(finality_proofs);
+		data.target_batches += 1;
+		Ok(TestTransactionTracker::default())
+	}
+
+	async fn halt_bridge(&self) -> Result<(), TestError> {
+		let mut data = self.data.lock();
+		(self.on_method_call)(&mut *data);
+		if data.target_fails_to_halt {
+			return Err(TestError::NonConnection)
+		}
+		data.target_is_halted = true;
 		Ok(())
 	}
 }
@@ -188,7 +250,11 @@ fn prepare_test_clients(
 		source_proofs: vec![TestFinalityProof(12), TestFinalityProof(14)],
 
 		target_best_block_number: 5,
+		target_best_block_hash: 5,
 		target_headers: vec![],
+		target_batches: 0,
+		target_is_halted: false,
+		target_fails_to_halt: false,
 	}));
 	(
 		TestSourceClient {
@@ -201,17 +267,26 @@ fn prepare_test_clients(
 
 fn run_sync_loop(
 	state_function: impl Fn(&mut ClientsData) -> bool + Send + Sync + 'static,
+) -> ClientsData {
+	run_sync_loop_with_fork_detection(ForkDetectionMode::Disabled, 5, state_function)
+}
+
+fn run_sync_loop_with_fork_detection(
+	fork_detection: ForkDetectionMode,
+	target_best_block_hash: TestHash,
+	state_function: impl Fn(&mut ClientsData) -> bool + Send + Sync + 'static,
 ) -> ClientsData {
 	let (exit_sender, exit_receiver) = futures::channel::mpsc::unbounded();
 	let (source_client, target_client) = prepare_test_clients(
 		exit_sender,
 		state_function,
 		vec![
-			(6, (TestSourceHeader(false, 6), None)),
-			(7, (TestSourceHeader(false, 7), Some(TestFinalityProof(7)))),
-			(8, (TestSourceHeader(true, 8), Some(TestFinalityProof(8)))),
-			(9, (TestSourceHeader(false, 9), Some(TestFinalityProof(9)))),
-			(10, (TestSourceHeader(false, 10), None)),
+			(5, (TestSourceHeader(false, 5, 5, None), None)),
+			(6, (TestSourceHeader(false, 6, 6, None), None)),
+			(7, (TestSourceHeader(false, 7, 7, None), Some(TestFinalityProof(7)))),
+			(8, (TestSourceHeader(true, 8, 8, None), Some(TestFinalityProof(8)))),
+			(9, (TestSourceHeader(false, 9, 9, None), Some(TestFinalityProof(9)))),
+			(10, (TestSourceHeader(false, 10, 10, None), None)),
 		]
 		.into_iter()
 		.collect(),
@@ -221,9 +296,13 @@ fn run_sync_loop(
 		recent_finality_proofs_limit: 1024,
 		stall_timeout: Duration::from_secs(1),
 		only_mandatory_headers: false,
+		max_mandatory_headers_per_transaction: 1,
+		mandatory_headers_look_ahead: 1024,
+		fork_detection,
 	};
 
 	let clients_data = source_client.data.clone();
+	clients_data.lock().target_best_block_hash = target_best_block_hash;
 	let _ = async_std::task::block_on(run(
 		source_client,
 		target_client,
@@ -248,20 +327,20 @@ fn finality_sync_loop_works() {
 		// the stream
 		if data.target_best_block_number == 9 {
 			data.source_best_block_number = 14;
-			data.source_headers.insert(11, (TestSourceHeader(false, 11), None));
+			data.source_headers.insert(11, (TestSourceHeader(false, 11, 11, None), None));
 			data.source_headers
-				.insert(12, (TestSourceHeader(false, 12), Some(TestFinalityProof(12))));
-			data.source_headers.insert(13, (TestSourceHeader(false, 13), None));
+				.insert(12, (TestSourceHeader(false, 12, 12, None), Some(TestFinalityProof(12))));
+			data.source_headers.insert(13, (TestSourceHeader(false, 13, 13, None), None));
 			data.source_headers
-				.insert(14, (TestSourceHeader(false, 14), Some(TestFinalityProof(14))));
+				.insert(14, (TestSourceHeader(false, 14, 14, None), Some(TestFinalityProof(14))));
 		}
 		// once this ^^^ is done, we generate more blocks && read persistent proof for block 16
 		if data.target_best_block_number == 14 {
 			data.source_best_block_number = 17;
-			data.source_headers.insert(15, (TestSourceHeader(false, 15), None));
+			data.source_headers.insert(15, (TestSourceHeader(false, 15, 15, None), None));
 			data.source_headers
-				.insert(16, (TestSourceHeader(false, 16), Some(TestFinalityProof(16))));
-			data.source_headers.insert(17, (TestSourceHeader(false, 17), None));
+				.insert(16, (TestSourceHeader(false, 16, 16, None), Some(TestFinalityProof(16))));
+			data.source_headers.insert(17, (TestSourceHeader(false, 17, 17, None), None));
 		}
 
 		data.target_best_block_number == 16
@@ -271,13 +350,13 @@ fn finality_sync_loop_works() {
 		client_data.target_headers,
 		vec![
 			// before adding 11..14: finality proof for mandatory header#8
-			(TestSourceHeader(true, 8), TestFinalityProof(8)),
+			(TestSourceHeader(true, 8, 8, None), TestFinalityProof(8)),
 			// before adding 11..14: persistent finality proof for non-mandatory header#9
-			(TestSourceHeader(false, 9), TestFinalityProof(9)),
+			(TestSourceHeader(false, 9, 9, None), TestFinalityProof(9)),
 			// after adding 11..14: ephemeral finality proof for non-mandatory header#14
-			(TestSourceHeader(false, 14), TestFinalityProof(14)),
+			(TestSourceHeader(false, 14, 14, None), TestFinalityProof(14)),
 			// after adding 15..17: persistent finality proof for non-mandatory header#16
-			(TestSourceHeader(false, 16), TestFinalityProof(16)),
+			(TestSourceHeader(false, 16, 16, None), TestFinalityProof(16)),
 		],
 	);
 }
@@ -285,17 +364,18 @@ fn finality_sync_loop_works() {
 fn run_only_mandatory_headers_mode_test(
 	only_mandatory_headers: bool,
 	has_mandatory_headers: bool,
-) -> Option<(TestSourceHeader, TestFinalityProof)> {
+) -> Option<Vec<(TestSourceHeader, TestFinalityProof)>> {
 	let (exit_sender, _) = futures::channel::mpsc::unbounded();
 	let (source_client, target_client) = prepare_test_clients(
 		exit_sender,
 		|_| false,
 		vec![
-			(6, (TestSourceHeader(false, 6), Some(TestFinalityProof(6)))),
-			(7, (TestSourceHeader(false, 7), Some(TestFinalityProof(7)))),
-			(8, (TestSourceHeader(has_mandatory_headers, 8), Some(TestFinalityProof(8)))),
-			(9, (TestSourceHeader(false, 9), Some(TestFinalityProof(9)))),
-			(10, (TestSourceHeader(false, 10), Some(TestFinalityProof(10)))),
+			(5, (TestSourceHeader(false, 5, 5, None), None)),
+			(6, (TestSourceHeader(false, 6, 6, None), Some(TestFinalityProof(6)))),
+			(7, (TestSourceHeader(false, 7, 7, None), Some(TestFinalityProof(7)))),
+			(8, (TestSourceHeader(has_mandatory_headers, 8, 8, None), Some(TestFinalityProof(8)))),
+			(9, (TestSourceHeader(false, 9, 9, None), Some(TestFinalityProof(9)))),
+			(10, (TestSourceHeader(false, 10, 10, None), Some(TestFinalityProof(10)))),
 		]
 		.into_iter()
 		.collect(),
@@ -305,6 +385,7 @@ fn run_only_mandatory_headers_mode_test(
 		&target_client,
 		&mut RestartableFinalityProofsStream::from(futures::stream::empty().boxed()),
 		&mut vec![],
+		&mut None,
 		10,
 		5,
 		&FinalitySyncParams {
@@ -312,6 +393,9 @@ fn run_only_mandatory_headers_mode_test(
 			recent_finality_proofs_limit: 0,
 			stall_timeout: Duration::from_secs(0),
 			only_mandatory_headers,
+			max_mandatory_headers_per_transaction: 1,
+			mandatory_headers_look_ahead: 1024,
+			fork_detection: ForkDetectionMode::Disabled,
 		},
 	))
 	.unwrap()
@@ -322,7 +406,7 @@ fn select_header_to_submit_skips_non_mandatory_headers_when_only_mandatory_heade
 	assert_eq!(run_only_mandatory_headers_mode_test(true, false), None);
 	assert_eq!(
 		run_only_mandatory_headers_mode_test(false, false),
-		Some((TestSourceHeader(false, 10), TestFinalityProof(10))),
+		Some(vec![(TestSourceHeader(false, 10, 10, None), TestFinalityProof(10))]),
 	);
 }
 
@@ -330,12 +414,246 @@ fn select_header_to_submit_skips_non_mandatory_headers_when_only_mandatory_heade
 fn select_header_to_submit_selects_mandatory_headers_when_only_mandatory_headers_are_required() {
 	assert_eq!(
 		run_only_mandatory_headers_mode_test(true, true),
-		Some((TestSourceHeader(true, 8), TestFinalityProof(8))),
+		Some(vec![(TestSourceHeader(true, 8, 8, None), TestFinalityProof(8))]),
 	);
 	assert_eq!(
 		run_only_mandatory_headers_mode_test(false, true),
-		Some((TestSourceHeader(true, 8), TestFinalityProof(8))),
+		Some(vec![(TestSourceHeader(true, 8, 8, None), TestFinalityProof(8))]),
+	);
+}
+
+fn run_mandatory_headers_batch_test(
+	max_mandatory_headers_per_transaction: usize,
+	mandatory_headers_look_ahead: usize,
+	has_last_mandatory_finality_proof: bool,
+) -> Result<Option<Vec<(TestSourceHeader, TestFinalityProof)>>, ()> {
+	let (exit_sender, _) = futures::channel::mpsc::unbounded();
+	let (source_client, target_client) = prepare_test_clients(
+		exit_sender,
+		|_| false,
+		vec![
+			(5, (TestSourceHeader(false, 5, 5, None), None)),
+			(6, (TestSourceHeader(false, 6, 6, None), Some(TestFinalityProof(6)))),
+			(7, (TestSourceHeader(true, 7, 7, None), Some(TestFinalityProof(7)))),
+			(8, (TestSourceHeader(false, 8, 8, None), Some(TestFinalityProof(8)))),
+			(9, (TestSourceHeader(true, 9, 9, None), Some(TestFinalityProof(9)))),
+			(
+				10,
+				(
+					TestSourceHeader(true, 10, 10, None),
+					if has_last_mandatory_finality_proof {
+						Some(TestFinalityProof(10))
+					} else {
+						None
+					},
+				),
+			),
+		]
+		.into_iter()
+		.collect(),
 	);
+	async_std::task::block_on(select_header_to_submit(
+		&source_client,
+		&target_client,
+		&mut RestartableFinalityProofsStream::from(futures::stream::empty().boxed()),
+		&mut vec![],
+		&mut None,
+		10,
+		5,
+		&FinalitySyncParams {
+			tick: Duration::from_secs(0),
+			recent_finality_proofs_limit: 0,
+			stall_timeout: Duration::from_secs(0),
+			only_mandatory_headers: false,
+			max_mandatory_headers_per_transaction,
+			mandatory_headers_look_ahead,
+			fork_detection: ForkDetectionMode::Disabled,
+		},
+	))
+	.map_err(drop)
+}
+
+#[test]
+fn select_header_to_submit_selects_multiple_mandatory_headers() {
+	assert_eq!(
+		run_mandatory_headers_batch_test(1, 1024, true),
+		Ok(Some(vec![(TestSourceHeader(true, 7, 7, None), TestFinalityProof(7))])),
+	);
+	assert_eq!(
+		run_mandatory_headers_batch_test(2, 1024, true),
+		Ok(Some(vec![
+			(TestSourceHeader(true, 7, 7, None), TestFinalityProof(7)),
+			(TestSourceHeader(true, 9, 9, None), TestFinalityProof(9)),
+		])),
+	);
+	assert_eq!(
+		run_mandatory_headers_batch_test(16, 1024, true),
+		Ok(Some(vec![
+			(TestSourceHeader(true, 7, 7, None), TestFinalityProof(7)),
+			(TestSourceHeader(true, 9, 9, None), TestFinalityProof(9)),
+			(TestSourceHeader(true, 10, 10, None), TestFinalityProof(10)),
+		])),
+	);
+}
+
+#[test]
+fn select_header_to_submit_stops_batch_at_mandatory_header_without_finality_proof() {
+	assert_eq!(
+		run_mandatory_headers_batch_test(16, 1024, false),
+		Ok(Some(vec![
+			(TestSourceHeader(true, 7, 7, None), TestFinalityProof(7)),
+			(TestSourceHeader(true, 9, 9, None), TestFinalityProof(9)),
+		])),
+	);
+}
+
+#[test]
+fn select_header_to_submit_stops_looking_for_mandatory_headers_after_look_ahead() {
+	// header#8 is read after header#7, but header#9 is not
+	assert_eq!(
+		run_mandatory_headers_batch_test(16, 1, true),
+		Ok(Some(vec![(TestSourceHeader(true, 7, 7, None), TestFinalityProof(7))])),
+	);
+	assert_eq!(
+		run_mandatory_headers_batch_test(16, 2, true),
+		Ok(Some(vec![
+			(TestSourceHeader(true, 7, 7, None), TestFinalityProof(7)),
+			(TestSourceHeader(true, 9, 9, None), TestFinalityProof(9)),
+		])),
+	);
+}
+
+fn run_delayed_change_test(
+	best_header_at_target: TestSourceHeader,
+	best_number_at_source: TestNumber,
+	mut delayed_change_at_target: Option<(TestNumber, Option<TestNumber>)>,
+) -> Option<Vec<(TestSourceHeader, TestFinalityProof)>> {
+	let (exit_sender, _) = futures::channel::mpsc::unbounded();
+	let (source_client, target_client) = prepare_test_clients(
+		exit_sender,
+		|_| false,
+		vec![
+			(5, (best_header_at_target, None)),
+			(6, (TestSourceHeader(true, 6, 6, Some(8)), Some(TestFinalityProof(6)))),
+			(7, (TestSourceHeader(false, 7, 7, None), Some(TestFinalityProof(7)))),
+			(8, (TestSourceHeader(false, 8, 8, None), Some(TestFinalityProof(8)))),
+			(9, (TestSourceHeader(false, 9, 9, None), Some(TestFinalityProof(9)))),
+			(10, (TestSourceHeader(false, 10, 10, None), Some(TestFinalityProof(10)))),
+		]
+		.into_iter()
+		.collect(),
+	);
+	async_std::task::block_on(select_header_to_submit(
+		&source_client,
+		&target_client,
+		&mut RestartableFinalityProofsStream::from(futures::stream::empty().boxed()),
+		&mut vec![],
+		&mut delayed_change_at_target,
+		best_number_at_source,
+		5,
+		&FinalitySyncParams {
+			tick: Duration::from_secs(0),
+			recent_finality_proofs_limit: 0,
+			stall_timeout: Duration::from_secs(0),
+			only_mandatory_headers: false,
+			max_mandatory_headers_per_transaction: 16,
+			mandatory_headers_look_ahead: 1024,
+			fork_detection: ForkDetectionMode::Disabled,
+		},
+	))
+	.unwrap()
+}
+
+#[test]
+fn select_header_to_submit_selects_enactment_header_of_delayed_change() {
+	// header#6 signals delayed change, that is enacted by header#8
+	assert_eq!(
+		run_delayed_change_test(TestSourceHeader(false, 5, 5, None), 10, None),
+		Some(vec![
+			(TestSourceHeader(true, 6, 6, Some(8)), TestFinalityProof(6)),
+			(TestSourceHeader(false, 8, 8, None), TestFinalityProof(8)),
+		]),
+	);
+}
+
+#[test]
+fn select_header_to_submit_selects_enactment_header_of_change_signalled_by_best_target_header() {
+	// header#5 is already known to the target and it signals delayed change, that is enacted by
+	// header#7. Header#6 is skipped
+	assert_eq!(
+		run_delayed_change_test(TestSourceHeader(true, 5, 5, Some(7)), 10, None),
+		Some(vec![(TestSourceHeader(false, 7, 7, None), TestFinalityProof(7))]),
+	);
+
+	// if the enactment header is not yet finalized, nothing is selected
+	assert_eq!(run_delayed_change_test(TestSourceHeader(true, 5, 5, Some(12)), 10, None), None);
+}
+
+#[test]
+fn select_header_to_submit_uses_cached_delayed_change_of_best_target_header() {
+	// header#5 doesn't signal delayed change, but the cached value says that it does => the cached
+	// value is used instead of reading header#5 again
+	assert_eq!(
+		run_delayed_change_test(TestSourceHeader(true, 5, 5, None), 10, Some((5, Some(7)))),
+		Some(vec![(TestSourceHeader(false, 7, 7, None), TestFinalityProof(7))]),
+	);
+
+	// the cached value of the previous best header is ignored
+	assert_eq!(
+		run_delayed_change_test(TestSourceHeader(true, 5, 5, None), 10, Some((4, Some(7)))),
+		Some(vec![
+			(TestSourceHeader(true, 6, 6, Some(8)), TestFinalityProof(6)),
+			(TestSourceHeader(false, 8, 8, None), TestFinalityProof(8))
+		]),
+	);
+}
+
+#[test]
+fn finality_sync_loop_submits_multiple_mandatory_headers_in_single_transaction() {
+	let (exit_sender, exit_receiver) = futures::channel::mpsc::unbounded();
+	let (source_client, target_client) = prepare_test_clients(
+		exit_sender,
+		|data| data.target_best_block_number == 10,
+		vec![
+			(5, (TestSourceHeader(false, 5, 5, None), None)),
+			(6, (TestSourceHeader(true, 6, 6, None), Some(TestFinalityProof(6)))),
+			(7, (TestSourceHeader(false, 7, 7, None), None)),
+			(8, (TestSourceHeader(true, 8, 8, None), Some(TestFinalityProof(8)))),
+			(9, (TestSourceHeader(false, 9, 9, None), None)),
+			(10, (TestSourceHeader(false, 10, 10, None), Some(TestFinalityProof(10)))),
+		]
+		.into_iter()
+		.collect(),
+	);
+	let sync_params = FinalitySyncParams {
+		tick: Duration::from_secs(0),
+		recent_finality_proofs_limit: 1024,
+		stall_timeout: Duration::from_secs(1),
+		only_mandatory_headers: false,
+		max_mandatory_headers_per_transaction: 16,
+		mandatory_headers_look_ahead: 1024,
+		fork_detection: ForkDetectionMode::Disabled,
+	};
+
+	let clients_data = source_client.data.clone();
+	let _ = async_std::task::block_on(run(
+		source_client,
+		target_client,
+		sync_params,
+		MetricsParams::disabled(),
+		exit_receiver.into_future().map(|(_, _)| ()),
+	));
+
+	let clients_data = clients_data.lock().clone();
+	assert_eq!(
+		clients_data.target_headers,
+		vec![
+			(TestSourceHeader(true, 6, 6, None), TestFinalityProof(6)),
+			(TestSourceHeader(true, 8, 8, None), TestFinalityProof(8)),
+			(TestSourceHeader(false, 10, 10, None), TestFinalityProof(10)),
+		],
+	);
+	assert_eq!(clients_data.target_batches, 1);
 }
 
 #[test]
@@ -345,63 +663,74 @@ fn select_better_recent_finality_proof_works() {
 		select_better_recent_finality_proof::<TestFinalitySyncPipeline>(
 			&[(5, TestFinalityProof(5))],
 			&mut vec![],
-			Some((TestSourceHeader(false, 2), TestFinalityProof(2))),
+			Some((TestSourceHeader(false, 2, 2, None), TestFinalityProof(2))),
 		),
-		Some((TestSourceHeader(false, 2), TestFinalityProof(2))),
+		Some((TestSourceHeader(false, 2, 2, None), TestFinalityProof(2))),
 	);
 
 	// if there are no recent finality proofs, nothing is changed
 	assert_eq!(
 		select_better_recent_finality_proof::<TestFinalitySyncPipeline>(
 			&[],
-			&mut vec![TestSourceHeader(false, 5)],
-			Some((TestSourceHeader(false, 2), TestFinalityProof(2))),
+			&mut vec![TestSourceHeader(false, 5, 5, None)],
+			Some((TestSourceHeader(false, 2, 2, None), TestFinalityProof(2))),
 		),
-		Some((TestSourceHeader(false, 2), TestFinalityProof(2))),
+		Some((TestSourceHeader(false, 2, 2, None), TestFinalityProof(2))),
 	);
 
 	// if there's no intersection between recent finality proofs and unjustified headers, nothing is
 	// changed
-	let mut unjustified_headers = vec![TestSourceHeader(false, 9), TestSourceHeader(false, 10)];
+	let mut unjustified_headers =
+		vec![TestSourceHeader(false, 9, 9, None), TestSourceHeader(false, 10, 10, None)];
 	assert_eq!(
 		select_better_recent_finality_proof::<TestFinalitySyncPipeline>(
 			&[(1, TestFinalityProof(1)), (4, TestFinalityProof(4))],
 			&mut unjustified_headers,
-			Some((TestSourceHeader(false, 2), TestFinalityProof(2))),
+			Some((TestSourceHeader(false, 2, 2, None), TestFinalityProof(2))),
 		),
-		Some((TestSourceHeader(false, 2), TestFinalityProof(2))),
+		Some((TestSourceHeader(false, 2, 2, None), TestFinalityProof(2))),
 	);
 
 	// if there's intersection between recent finality proofs and unjustified headers, but there are
 	// no proofs in this intersection, nothing is changed
-	let mut unjustified_headers =
-		vec![TestSourceHeader(false, 8), TestSourceHeader(false, 9), TestSourceHeader(false, 10)];
+	let mut unjustified_headers = vec![
+		TestSourceHeader(false, 8, 8, None),
+		TestSourceHeader(false, 9, 9, None),
+		TestSourceHeader(false, 10, 10, None),
+	];
 	assert_eq!(
 		select_better_recent_finality_proof::<TestFinalitySyncPipeline>(
 			&[(7, TestFinalityProof(7)), (11, TestFinalityProof(11))],
 			&mut unjustified_headers,
-			Some((TestSourceHeader(false, 2), TestFinalityProof(2))),
+			Some((TestSourceHeader(false, 2, 2, None), TestFinalityProof(2))),
 		),
-		Some((TestSourceHeader(false, 2), TestFinalityProof(2))),
+		Some((TestSourceHeader(false, 2, 2, None), TestFinalityProof(2))),
 	);
 	assert_eq!(
 		unjustified_headers,
-		vec![TestSourceHeader(false, 8), TestSourceHeader(false, 9), TestSourceHeader(false, 10)]
+		vec![
+			TestSourceHeader(false, 8, 8, None),
+			TestSourceHeader(false, 9, 9, None),
+			TestSourceHeader(false, 10, 10, None)
+		]
 	);
 
 	// if there's intersection between recent finality proofs and unjustified headers and there's
 	// a proof in this intersection:
 	// - this better (last from intersection) proof is selected;
 	// - 'obsolete' unjustified headers are pruned.
-	let mut unjustified_headers =
-		vec![TestSourceHeader(false, 8), TestSourceHeader(false, 9), TestSourceHeader(false, 10)];
+	let mut unjustified_headers = vec![
+		TestSourceHeader(false, 8, 8, None),
+		TestSourceHeader(false, 9, 9, None),
+		TestSourceHeader(false, 10, 10, None),
+	];
 	assert_eq!(
 		select_better_recent_finality_proof::<TestFinalitySyncPipeline>(
 			&[(7, TestFinalityProof(7)), (9, TestFinalityProof(9))],
 			&mut unjustified_headers,
-			Some((TestSourceHeader(false, 2), TestFinalityProof(2))),
+			Some((TestSourceHeader(false, 2, 2, None), TestFinalityProof(2))),
 		),
-		Some((TestSourceHeader(false, 9), TestFinalityProof(9))),
+		Some((TestSourceHeader(false, 9, 9, None), TestFinalityProof(9))),
 	);
 }
 
@@ -475,3 +804,167 @@ fn prune_recent_finality_proofs_works() {
 	prune_recent_finality_proofs::<TestFinalitySyncPipeline>(20, &mut recent_finality_proofs, 2);
 	assert_eq!(&original_recent_finality_proofs[5..], recent_finality_proofs,);
 }
+
+fn run_fork_detection_test(
+	fork_detection: ForkDetectionMode,
+	best_number_at_source: TestNumber,
+	best_hash_at_target: TestHash,
+	last_verified_header_at_target: &mut Option<(HeaderId<TestHash, TestNumber>, bool)>,
+) -> (Result<(), Error<TestFinalitySyncPipeline, TestError, TestError>>, ClientsData) {
+	run_fork_detection_test_with_halt_result(
+		fork_detection,
+		best_number_at_source,
+		best_hash_at_target,
+		last_verified_header_at_target,
+		true,
+	)
+}
+
+fn run_fork_detection_test_with_halt_result(
+	fork_detection: ForkDetectionMode,
+	best_number_at_source: TestNumber,
+	best_hash_at_target: TestHash,
+	last_verified_header_at_target: &mut Option<(HeaderId<TestHash, TestNumber>, bool)>,
+	halt_succeeds: bool,
+) -> (Result<(), Error<TestFinalitySyncPipeline, TestError, TestError>>, ClientsData) {
+	let (exit_sender, _) = futures::channel::mpsc::unbounded();
+	let (source_client, target_client) = prepare_test_clients(
+		exit_sender,
+		|_| false,
+		vec![(5, (TestSourceHeader(false, 5, 5, None), Some(TestFinalityProof(5))))]
+			.into_iter()
+			.collect(),
+	);
+	source_client.data.lock().target_fails_to_halt = !halt_succeeds;
+	let result = async_std::task::block_on(ensure_best_header_at_target_is_canonical(
+		&source_client,
+		&target_client,
+		last_verified_header_at_target,
+		best_number_at_source,
+		HeaderId(5, best_hash_at_target),
+		fork_detection,
+		&None,
+	));
+	let clients_data = source_client.data.lock().clone();
+	(result, clients_data)
+}
+
+#[test]
+fn fork_detection_accepts_canonical_header_at_target() {
+	let mut last_verified_header_at_target = None;
+	let (result, clients_data) = run_fork_detection_test(
+		ForkDetectionMode::Halt,
+		10,
+		5,
+		&mut last_verified_header_at_target,
+	);
+	assert!(result.is_ok());
+	assert!(!clients_data.target_is_halted);
+	assert_eq!(last_verified_header_at_target, Some((HeaderId(5, 5), true)));
+}
+
+#[test]
+fn fork_detection_ignores_header_that_is_not_yet_known_to_source() {
+	let mut last_verified_header_at_target = None;
+	let (result, clients_data) = run_fork_detection_test(
+		ForkDetectionMode::Halt,
+		4,
+		42,
+		&mut last_verified_header_at_target,
+	);
+	assert!(result.is_ok());
+	assert!(!clients_data.target_is_halted);
+	assert_eq!(last_verified_header_at_target, None);
+}
+
+#[test]
+fn fork_detection_reports_non_canonical_header_at_target() {
+	let mut last_verified_header_at_target = None;
+	let (result, clients_data) = run_fork_detection_test(
+		ForkDetectionMode::Report,
+		10,
+		42,
+		&mut last_verified_header_at_target,
+	);
+	assert!(matches!(result, Err(Error::ForkDetected(HeaderId(5, 42)))));
+	assert!(!clients_data.target_is_halted);
+	assert_eq!(last_verified_header_at_target, Some((HeaderId(5, 42), false)));
+}
+
+#[test]
+fn fork_detection_halts_bridge_if_non_canonical_header_is_at_target() {
+	let mut last_verified_header_at_target = None;
+	let (result, clients_data) = run_fork_detection_test(
+		ForkDetectionMode::Halt,
+		10,
+		42,
+		&mut last_verified_header_at_target,
+	);
+	assert!(matches!(result, Err(Error::ForkDetected(HeaderId(5, 42)))));
+	assert!(clients_data.target_is_halted);
+	assert_eq!(last_verified_header_at_target, Some((HeaderId(5, 42), false)));
+}
+
+#[test]
+fn fork_detection_does_not_retry_to_halt_bridge_if_previous_attempt_has_failed() {
+	let mut last_verified_header_at_target = None;
+	let (result, clients_data) = run_fork_detection_test_with_halt_result(
+		ForkDetectionMode::Halt,
+		10,
+		42,
+		&mut last_verified_header_at_target,
+		false,
+	);
+	assert!(matches!(result, Err(Error::ForkDetected(HeaderId(5, 42)))));
+	assert!(!clients_data.target_is_halted);
+	assert_eq!(last_verified_header_at_target, Some((HeaderId(5, 42), false)));
+
+	let (result, clients_data) = run_fork_detection_test(
+		ForkDetectionMode::Halt,
+		10,
+		42,
+		&mut last_verified_header_at_target,
+	);
+	assert!(matches!(result, Err(Error::ForkDetected(HeaderId(5, 42)))));
+	assert!(!clients_data.target_is_halted);
+	assert_eq!(last_verified_header_at_target, Some((HeaderId(5, 42), false)));
+}
+
+#[test]
+fn fork_detection_does_not_halt_bridge_twice() {
+	let mut last_verified_header_at_target = Some((HeaderId(5, 42), false));
+	let (result, clients_data) = run_fork_detection_test(
+		ForkDetectionMode::Halt,
+		10,
+		42,
+		&mut last_verified_header_at_target,
+	);
+	assert!(matches!(result, Err(Error::ForkDetected(HeaderId(5, 42)))));
+	assert!(!clients_data.target_is_halted);
+}
+
+#[test]
+fn finality_sync_loop_works_with_fork_detection() {
+	let client_data = run_sync_loop_with_fork_detection(ForkDetectionMode::Halt, 5, |data| {
+		data.target_best_block_number == 9
+	});
+
+	assert!(!client_data.target_is_halted);
+	assert_eq!(
+		client_data.target_headers,
+		vec![
+			(TestSourceHeader(true, 8, 8, None), TestFinalityProof(8)),
+			(TestSourceHeader(false, 9, 9, None), TestFinalityProof(9)),
+		],
+	);
+}
+
+#[test]
+fn finality_sync_loop_halts_bridge_when_fork_is_detected() {
+	let client_data = run_sync_loop_with_fork_detection(ForkDetectionMode::Halt, 42, |data| {
+		data.target_is_halted
+	});
+
+	assert!(client_data.target_is_halted);
+	assert!(client_data.target_headers.is_empty());
+}
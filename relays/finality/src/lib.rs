@@ -20,7 +20,7 @@
 //! to submit all source headers to the target node.
 
 pub use crate::finality_loop::{
-	metrics_prefix, run, FinalitySyncParams, SourceClient, TargetClient,
+	metrics_prefix, run, FinalitySyncParams, ForkDetectionMode, SourceClient, TargetClient,
 };
 
 use bp_header_chain::FinalityProof;
@@ -41,15 +41,22 @@ pub trait FinalitySyncPipeline: 'static + Clone + Debug + Send + Sync {
 	/// Headers we're syncing are identified by this number.
 	type Number: relay_utils::BlockNumberBase;
 	/// Type of header that we're syncing.
-	type Header: SourceHeader<Self::Number>;
+	type Header: SourceHeader<Self::Hash, Self::Number>;
 	/// Finality proof type.
 	type FinalityProof: FinalityProof<Self::Number>;
 }
 
 /// Header that we're receiving from source node.
-pub trait SourceHeader<Number>: Clone + Debug + PartialEq + Send + Sync {
+pub trait SourceHeader<Hash, Number>: Clone + Debug + PartialEq + Send + Sync {
+	/// Returns hash of header.
+	fn hash(&self) -> Hash;
 	/// Returns number of header.
 	fn number(&self) -> Number;
 	/// Returns true if this header needs to be submitted to target node.
 	fn is_mandatory(&self) -> bool;
+	/// Returns number of the header that enacts delayed change, signalled by this header.
+	///
+	/// The enactment header is mandatory and the target node won't accept any headers between
+	/// this header and the enactment header.
+	fn delayed_change_enactment(&self) -> Option<Number>;
 }
@@ -20,7 +20,7 @@ use crate::finality_target::SubstrateFinalityTarget;
 
 use bp_header_chain::justification::GrandpaJustification;
 use bp_runtime::AccountIdOf;
-use finality_relay::{FinalitySyncParams, FinalitySyncPipeline};
+use finality_relay::{FinalitySyncParams, FinalitySyncPipeline, ForkDetectionMode};
 use relay_substrate_client::{
 	finality_source::FinalitySource, BlockNumberOf, Chain, Client, HashOf, SyncHeader,
 };
@@ -41,6 +41,18 @@ pub(crate) const STALL_TIMEOUT: Duration = Duration::from_secs(60 * 60);
 /// Finality delay of 4096 blocks is unlikely to happen in practice in
 /// Substrate+GRANDPA based chains (good to know).
 pub(crate) const RECENT_FINALITY_PROOFS_LIMIT: usize = 4096;
+/// Default maximal number of mandatory headers that are submitted in a single transaction.
+///
+/// Every header in the batch adds justification verification cost to the transaction, so it
+/// should be small enough for the transaction to fit into the target chain block. It also must
+/// not exceed the `MaxRequests` of the target pallet.
+pub(crate) const MAX_MANDATORY_HEADERS_PER_TRANSACTION: usize = 8;
+/// Default maximal number of headers that are read after the first mandatory header, when
+/// looking for next mandatory headers.
+///
+/// Every header is read with a separate call to the source node, so if mandatory headers are
+/// rare, they are submitted in separate transactions.
+pub(crate) const MANDATORY_HEADERS_LOOK_AHEAD: usize = 1024;
 
 /// Headers sync pipeline for Substrate <-> Substrate relays.
 pub trait SubstrateFinalitySyncPipeline: 'static + Clone + Debug + Send + Sync {
@@ -49,6 +61,12 @@ pub trait SubstrateFinalitySyncPipeline: 'static + Clone + Debug + Send + Sync {
 
 	/// Name of the runtime method that returns id of best finalized source header at target chain.
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str;
+	/// Name of the runtime method that returns GRANDPA authority set with given id at target
+	/// chain.
+	const AUTHORITY_SET_AT_TARGET: &'static str;
+	/// Name of the runtime method that returns id of GRANDPA authority set, that has finalized
+	/// given source header, at target chain.
+	const HEADER_SET_ID_AT_TARGET: &'static str;
 
 	/// Chain with GRANDPA bridge pallet.
 	type TargetChain: Chain;
@@ -76,6 +94,28 @@ pub trait SubstrateFinalitySyncPipeline: 'static + Clone + Debug + Send + Sync {
 		header: <Self::FinalitySyncPipeline as FinalitySyncPipeline>::Header,
 		proof: <Self::FinalitySyncPipeline as FinalitySyncPipeline>::FinalityProof,
 	) -> Bytes;
+
+	/// Make transaction that submits finality proofs of multiple headers.
+	fn make_submit_finality_proofs_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Self::TargetChain>,
+		transaction_nonce: bp_runtime::IndexOf<Self::TargetChain>,
+		finality_proofs: Vec<(
+			<Self::FinalitySyncPipeline as FinalitySyncPipeline>::Header,
+			<Self::FinalitySyncPipeline as FinalitySyncPipeline>::FinalityProof,
+		)>,
+	) -> Bytes;
+
+	/// Make transaction that halts all operations of the GRANDPA pallet at the target chain.
+	///
+	/// This transaction is submitted when the relay detects that the pallet has imported
+	/// non-canonical source header. It'll only succeed if `transactions_author()` is the pallet
+	/// owner.
+	fn make_halt_bridge_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Self::TargetChain>,
+		transaction_nonce: bp_runtime::IndexOf<Self::TargetChain>,
+	) -> Bytes;
 }
 
 /// Substrate-to-Substrate finality proof pipeline.
@@ -131,6 +171,7 @@ pub async fn run<SourceChain, TargetChain, P>(
 	source_client: Client<SourceChain>,
 	target_client: Client<TargetChain>,
 	only_mandatory_headers: bool,
+	fork_detection: ForkDetectionMode,
 	transactions_mortality: Option<u32>,
 	metrics_params: MetricsParams,
 ) -> anyhow::Result<()>
@@ -168,6 +209,9 @@ where
 			)
 			.unwrap_or(STALL_TIMEOUT),
 			only_mandatory_headers,
+			max_mandatory_headers_per_transaction: MAX_MANDATORY_HEADERS_PER_TRANSACTION,
+			mandatory_headers_look_ahead: MANDATORY_HEADERS_LOOK_AHEAD,
+			fork_detection,
 		},
 		metrics_params,
 		futures::future::pending(),
@@ -18,25 +18,190 @@
 //! runtime that implements `<BridgedChainName>FinalityApi` to allow bridging with
 //! <BridgedName> chain.
 
-use crate::finality_pipeline::SubstrateFinalitySyncPipeline;
+use crate::finality_pipeline::{SubstrateFinalitySyncPipeline, STALL_TIMEOUT};
 
+use async_std::sync::{Arc, Mutex};
 use async_trait::async_trait;
-use codec::Decode;
-use finality_relay::{FinalitySyncPipeline, TargetClient};
-use relay_substrate_client::{Chain, Client, Error as SubstrateError};
-use relay_utils::relay_loop::Client as RelayClient;
+use bp_header_chain::{
+	find_grandpa_authorities_forced_change, find_grandpa_authorities_scheduled_change,
+	justification::{verify_and_optimize_justification, GrandpaJustification},
+	AuthoritySet, SetId,
+};
+use codec::{Decode, Encode};
+use finality_grandpa::voter_set::VoterSet;
+use finality_relay::{FinalitySyncPipeline, SourceHeader, TargetClient};
+use num_traits::Zero;
+use relay_substrate_client::{
+	BalanceOf, Chain, Client, Error as SubstrateError, SyncHeader, TransactionEra,
+	TransactionTracker,
+};
+use relay_utils::{relay_loop::Client as RelayClient, HeaderId};
+use sp_core::Bytes;
+use sp_finality_grandpa::AuthorityList;
+use sp_runtime::traits::Header as HeaderT;
+
+/// Shared reference to the cost of the latest header submission transaction.
+pub(crate) type HeaderSubmissionCostRef<C> = Arc<Mutex<Option<BalanceOf<C>>>>;
+
+/// Finality proof that may contain redundant data, which is not required to prove header
+/// finality.
+pub trait OptimizableFinalityProof<Hash, Number> {
+	/// Verify the proof, generated by given authority set, and remove all redundant data from it.
+	fn optimize(
+		&mut self,
+		target: (Hash, Number),
+		authority_set: &AuthoritySet,
+	) -> Result<(), String>;
+}
+
+impl<Header: HeaderT> OptimizableFinalityProof<Header::Hash, Header::Number>
+	for GrandpaJustification<Header>
+where
+	Header::Number: finality_grandpa::BlockNumberOps,
+{
+	fn optimize(
+		&mut self,
+		target: (Header::Hash, Header::Number),
+		authority_set: &AuthoritySet,
+	) -> Result<(), String> {
+		let voter_set = VoterSet::new(authority_set.authorities.iter().cloned())
+			.ok_or_else(|| format!("Invalid authorities set: {:?}", authority_set.authorities))?;
+		verify_and_optimize_justification::<Header>(target, authority_set.set_id, &voter_set, self)
+			.map_err(|e| format!("{:?}", e))
+	}
+}
+
+/// Source header that may signal GRANDPA authorities set change.
+pub trait AuthoritySetChangeHeader {
+	/// Returns authorities of the next set, if the header signals (scheduled or forced) authorities
+	/// set change.
+	fn signalled_authorities(&self) -> Option<AuthorityList>;
+}
+
+impl<Header: HeaderT> AuthoritySetChangeHeader for SyncHeader<Header> {
+	fn signalled_authorities(&self) -> Option<AuthorityList> {
+		find_grandpa_authorities_forced_change(&**self)
+			.map(|(_, change)| change.next_authorities)
+			.or_else(|| {
+				find_grandpa_authorities_scheduled_change(&**self)
+					.map(|change| change.next_authorities)
+			})
+	}
+}
 
 /// Substrate client as Substrate finality target.
 pub struct SubstrateFinalityTarget<C: Chain, P> {
 	client: Client<C>,
 	pipeline: P,
 	transactions_mortality: Option<u32>,
+	header_submission_cost: Option<HeaderSubmissionCostRef<C>>,
 }
 
 impl<C: Chain, P> SubstrateFinalityTarget<C, P> {
 	/// Create new Substrate headers target.
 	pub fn new(client: Client<C>, pipeline: P, transactions_mortality: Option<u32>) -> Self {
-		SubstrateFinalityTarget { client, pipeline, transactions_mortality }
+		SubstrateFinalityTarget {
+			client,
+			pipeline,
+			transactions_mortality,
+			header_submission_cost: None,
+		}
+	}
+
+	/// Estimate cost of every submitted header and save it to the given reference.
+	pub(crate) fn with_header_submission_cost(
+		mut self,
+		header_submission_cost: HeaderSubmissionCostRef<C>,
+	) -> Self {
+		self.header_submission_cost = Some(header_submission_cost);
+		self
+	}
+}
+
+impl<C, P> SubstrateFinalityTarget<C, P>
+where
+	C: Chain,
+	P: SubstrateFinalitySyncPipeline<TargetChain = C>,
+	<P::FinalitySyncPipeline as FinalitySyncPipeline>::Number: Decode,
+	<P::FinalitySyncPipeline as FinalitySyncPipeline>::Hash: Decode + Encode,
+	<P::FinalitySyncPipeline as FinalitySyncPipeline>::FinalityProof: OptimizableFinalityProof<
+		<P::FinalitySyncPipeline as FinalitySyncPipeline>::Hash,
+		<P::FinalitySyncPipeline as FinalitySyncPipeline>::Number,
+	>,
+	<P::FinalitySyncPipeline as FinalitySyncPipeline>::Header: AuthoritySetChangeHeader,
+{
+	/// Returns GRANDPA authority sets that may have signed justifications of source headers that
+	/// are not yet known to the target chain.
+	///
+	/// It is the set that has finalized the best source header, known to the target chain, and
+	/// the next set, if the best header has enacted authorities set change. Sets are ordered by
+	/// their ids.
+	async fn authority_sets_at_target(&self) -> Result<Vec<AuthoritySet>, SubstrateError> {
+		let best_finalized_id = self.best_finalized_source_block_id().await?;
+		let encoded_best_finalized_set_id = self
+			.client
+			.state_call(
+				P::HEADER_SET_ID_AT_TARGET.into(),
+				Bytes(best_finalized_id.1.encode()),
+				None,
+			)
+			.await?;
+		let best_finalized_set_id: Option<SetId> =
+			Decode::decode(&mut &encoded_best_finalized_set_id.0[..])
+				.map_err(SubstrateError::ResponseParseFailed)?;
+		let best_finalized_set_id = match best_finalized_set_id {
+			Some(best_finalized_set_id) => best_finalized_set_id,
+			None => return Ok(Vec::new()),
+		};
+
+		let mut authority_sets = Vec::new();
+		for set_id in [best_finalized_set_id, best_finalized_set_id + 1] {
+			let encoded_authority_set = self
+				.client
+				.state_call(P::AUTHORITY_SET_AT_TARGET.into(), Bytes(set_id.encode()), None)
+				.await?;
+			let authority_set: Option<AuthoritySet> =
+				Decode::decode(&mut &encoded_authority_set.0[..])
+					.map_err(SubstrateError::ResponseParseFailed)?;
+			authority_sets.extend(authority_set);
+		}
+
+		Ok(authority_sets)
+	}
+
+	/// Remove redundant data from the finality proof of given header.
+	///
+	/// The proof is verified by the given authority sets, starting from the set with the largest
+	/// id. If the proof can't be verified by any of these sets, it is returned unchanged.
+	fn optimize_finality_proof(
+		header: &<P::FinalitySyncPipeline as FinalitySyncPipeline>::Header,
+		proof: <P::FinalitySyncPipeline as FinalitySyncPipeline>::FinalityProof,
+		authority_sets: &[AuthoritySet],
+	) -> <P::FinalitySyncPipeline as FinalitySyncPipeline>::FinalityProof {
+		let header_id = (header.hash(), header.number());
+		for authority_set in authority_sets.iter().rev() {
+			let mut optimized_proof = proof.clone();
+			match optimized_proof.optimize(header_id, authority_set) {
+				Ok(()) => return optimized_proof,
+				Err(error) => log::trace!(
+					target: "bridge",
+					"Failed to optimize {} finality proof of header {:?} using authority set {}: {}",
+					<P::FinalitySyncPipeline as FinalitySyncPipeline>::SOURCE_NAME,
+					header_id,
+					authority_set.set_id,
+					error,
+				),
+			}
+		}
+
+		log::debug!(
+			target: "bridge",
+			"Failed to optimize {} finality proof of header {:?}. Submitting it as is",
+			<P::FinalitySyncPipeline as FinalitySyncPipeline>::SOURCE_NAME,
+			header_id,
+		);
+
+		proof
 	}
 }
 
@@ -46,6 +211,7 @@ impl<C: Chain, P: SubstrateFinalitySyncPipeline> Clone for SubstrateFinalityTarg
 			client: self.client.clone(),
 			pipeline: self.pipeline.clone(),
 			transactions_mortality: self.transactions_mortality,
+			header_submission_cost: self.header_submission_cost.clone(),
 		}
 	}
 }
@@ -65,11 +231,24 @@ where
 	C: Chain,
 	P: SubstrateFinalitySyncPipeline<TargetChain = C>,
 	<P::FinalitySyncPipeline as FinalitySyncPipeline>::Number: Decode,
-	<P::FinalitySyncPipeline as FinalitySyncPipeline>::Hash: Decode,
+	<P::FinalitySyncPipeline as FinalitySyncPipeline>::Hash: Decode + Encode,
+	<P::FinalitySyncPipeline as FinalitySyncPipeline>::FinalityProof: OptimizableFinalityProof<
+		<P::FinalitySyncPipeline as FinalitySyncPipeline>::Hash,
+		<P::FinalitySyncPipeline as FinalitySyncPipeline>::Number,
+	>,
+	<P::FinalitySyncPipeline as FinalitySyncPipeline>::Header: AuthoritySetChangeHeader,
 {
-	async fn best_finalized_source_block_number(
+	type TransactionTracker = TransactionTracker<C>;
+
+	async fn best_finalized_source_block_id(
 		&self,
-	) -> Result<<P::FinalitySyncPipeline as FinalitySyncPipeline>::Number, SubstrateError> {
+	) -> Result<
+		HeaderId<
+			<P::FinalitySyncPipeline as FinalitySyncPipeline>::Hash,
+			<P::FinalitySyncPipeline as FinalitySyncPipeline>::Number,
+		>,
+		SubstrateError,
+	> {
 		// we can't continue to relay finality if target node is out of sync, because
 		// it may have already received (some of) headers that we're going to relay
 		self.client.ensure_synced().await?;
@@ -80,21 +259,52 @@ where
 			<P::FinalitySyncPipeline as FinalitySyncPipeline>::Number,
 		>(&self.client, P::BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET)
 		.await?
-		.best_finalized_peer_at_best_self
-		.0)
+		.best_finalized_peer_at_best_self)
 	}
 
 	async fn submit_finality_proof(
 		&self,
 		header: <P::FinalitySyncPipeline as FinalitySyncPipeline>::Header,
 		proof: <P::FinalitySyncPipeline as FinalitySyncPipeline>::FinalityProof,
-	) -> Result<(), SubstrateError> {
+	) -> Result<TransactionTracker<C>, SubstrateError> {
+		// justification, generated by GRANDPA voter, may contain redundant votes and headers,
+		// which are rejected by the pallet
+		let authority_sets = self.authority_sets_at_target().await?;
+		let proof = Self::optimize_finality_proof(&header, proof, &authority_sets);
+
+		// if someone (e.g. messages relay) is interested in the cost of header submission, let's
+		// estimate it using the transaction we're going to submit
+		if let Some(ref header_submission_cost) = self.header_submission_cost {
+			let transaction = self.pipeline.make_submit_finality_proof_transaction(
+				TransactionEra::Immortal,
+				Zero::zero(),
+				header.clone(),
+				proof.clone(),
+			);
+			match self.client.estimate_extrinsic_fee(transaction).await {
+				Ok(fee) => *header_submission_cost.lock().await = Some(fee.inclusion_fee()),
+				Err(error) => log::debug!(
+					target: "bridge",
+					"Failed to estimate {} header submission cost at {}: {:?}",
+					<P::FinalitySyncPipeline as FinalitySyncPipeline>::SOURCE_NAME,
+					C::NAME,
+					error,
+				),
+			}
+		}
+
 		let transactions_author = self.pipeline.transactions_author();
 		let pipeline = self.pipeline.clone();
 		let transactions_mortality = self.transactions_mortality;
+		let stall_timeout = relay_substrate_client::transaction_stall_timeout(
+			transactions_mortality,
+			C::AVERAGE_BLOCK_INTERVAL,
+		)
+		.unwrap_or(STALL_TIMEOUT);
 		self.client
-			.submit_signed_extrinsic(
+			.submit_and_track_signed_extrinsic(
 				transactions_author,
+				stall_timeout,
 				move |best_block_id, transaction_nonce| {
 					pipeline.make_submit_finality_proof_transaction(
 						relay_substrate_client::TransactionEra::new(
@@ -109,6 +319,77 @@ where
 				},
 			)
 			.await
+	}
+
+	async fn submit_finality_proofs(
+		&self,
+		finality_proofs: Vec<(
+			<P::FinalitySyncPipeline as FinalitySyncPipeline>::Header,
+			<P::FinalitySyncPipeline as FinalitySyncPipeline>::FinalityProof,
+		)>,
+	) -> Result<TransactionTracker<C>, SubstrateError> {
+		// justifications of all headers, except the first one, are signed by authority sets that
+		// are not yet known to the target chain. So we're learning them from headers that we're
+		// going to submit
+		let mut authority_sets = self.authority_sets_at_target().await?;
+		let mut optimized_finality_proofs = Vec::with_capacity(finality_proofs.len());
+		for (header, proof) in finality_proofs {
+			let proof = Self::optimize_finality_proof(&header, proof, &authority_sets);
+			if let (Some(next_authorities), Some(last_authority_set)) =
+				(header.signalled_authorities(), authority_sets.last())
+			{
+				let next_set_id = last_authority_set.set_id + 1;
+				authority_sets.push(AuthoritySet::new(next_authorities, next_set_id));
+			}
+			optimized_finality_proofs.push((header, proof));
+		}
+
+		let transactions_author = self.pipeline.transactions_author();
+		let pipeline = self.pipeline.clone();
+		let transactions_mortality = self.transactions_mortality;
+		let stall_timeout = relay_substrate_client::transaction_stall_timeout(
+			transactions_mortality,
+			C::AVERAGE_BLOCK_INTERVAL,
+		)
+		.unwrap_or(STALL_TIMEOUT);
+		self.client
+			.submit_and_track_signed_extrinsic(
+				transactions_author,
+				stall_timeout,
+				move |best_block_id, transaction_nonce| {
+					pipeline.make_submit_finality_proofs_transaction(
+						relay_substrate_client::TransactionEra::new(
+							best_block_id.0,
+							best_block_id.1,
+							transactions_mortality,
+						),
+						transaction_nonce,
+						optimized_finality_proofs,
+					)
+				},
+			)
+			.await
+	}
+
+	async fn halt_bridge(&self) -> Result<(), SubstrateError> {
+		let transactions_author = self.pipeline.transactions_author();
+		let pipeline = self.pipeline.clone();
+		let transactions_mortality = self.transactions_mortality;
+		self.client
+			.submit_signed_extrinsic(
+				transactions_author,
+				move |best_block_id, transaction_nonce| {
+					pipeline.make_halt_bridge_transaction(
+						relay_substrate_client::TransactionEra::new(
+							best_block_id.0,
+							best_block_id.1,
+							transactions_mortality,
+						),
+						transaction_nonce,
+					)
+				},
+			)
+			.await
 			.map(drop)
 	}
 }
@@ -23,7 +23,7 @@
 
 use bp_header_chain::{
 	find_grandpa_authorities_scheduled_change,
-	justification::{verify_justification, GrandpaJustification},
+	justification::{verify_and_optimize_justification, GrandpaJustification},
 	InitializationData,
 };
 use codec::Decode;
@@ -187,11 +187,12 @@ async fn prepare_initialization_data<SourceChain: Chain>(
 			initial_authorities_set_id,
 		);
 
-		let is_valid_set_id = verify_justification::<SourceChain::Header>(
+		// justification, generated by GRANDPA voter, may contain redundant votes and headers
+		let is_valid_set_id = verify_and_optimize_justification::<SourceChain::Header>(
 			(initial_header_hash, initial_header_number),
 			initial_authorities_set_id,
 			&authorities_for_verification,
-			&justification,
+			&mut justification.clone(),
 		)
 		.is_ok();
 
@@ -27,3 +27,5 @@ pub mod messages_lane;
 pub mod messages_source;
 pub mod messages_target;
 pub mod on_demand_headers;
+pub mod on_demand_parachains;
+pub mod parachains_pipeline;
@@ -18,7 +18,7 @@
 
 use crate::{
 	messages_source::SubstrateMessagesProof, messages_target::SubstrateMessagesReceivingProof,
-	on_demand_headers::OnDemandHeadersRelay,
+	on_demand_headers::OnDemandHeadersRelay, on_demand_parachains::OnDemandParachainsRelay,
 };
 
 use async_trait::async_trait;
@@ -28,7 +28,7 @@ use frame_support::weights::Weight;
 use messages_relay::message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf};
 use relay_substrate_client::{
 	metrics::{FloatStorageValueMetric, StorageProofOverheadMetric},
-	BlockNumberOf, Chain, Client, HashOf,
+	BalanceOf, BlockNumberOf, Chain, Client, HashOf, HeaderIdOf,
 };
 use relay_utils::{
 	metrics::{F64SharedRef, MetricsParams},
@@ -49,17 +49,58 @@ pub struct MessagesRelayParams<SC: Chain, SS, TC: Chain, TS> {
 	/// Sign parameters for messages target chain.
 	pub target_sign: TS,
 	/// Optional on-demand source to target headers relay.
-	pub source_to_target_headers_relay: Option<OnDemandHeadersRelay<SC>>,
+	pub source_to_target_headers_relay: Option<OnDemandRelay<SC, TC>>,
 	/// Optional on-demand target to source headers relay.
-	pub target_to_source_headers_relay: Option<OnDemandHeadersRelay<TC>>,
-	/// Identifier of lane that needs to be served.
-	pub lane_id: LaneId,
+	pub target_to_source_headers_relay: Option<OnDemandRelay<TC, SC>>,
+	/// Identifiers of lanes that need to be served.
+	pub lanes: Vec<LaneId>,
+	/// Strategy that is used to select lane, which delivery transaction is submitted next.
+	pub lanes_scheduling: messages_relay::message_lanes_loop::LaneSchedulingStrategy,
 	/// Relayer operating mode.
 	pub relayer_mode: messages_relay::message_lane_loop::RelayerMode,
 	/// Metrics parameters.
 	pub metrics_params: MetricsParams,
 }
 
+/// On-demand relay that is used by the messages relay to sync headers of the bridged chain.
+pub enum OnDemandRelay<SourceChain: Chain, TargetChain: Chain> {
+	/// Source chain has its own finality, so its headers are relayed directly.
+	Headers(OnDemandHeadersRelay<SourceChain, TargetChain>),
+	/// Source chain is a parachain, so its heads are relayed from the relay chain (which headers
+	/// are, in turn, relayed by the on-demand relay chain headers relay).
+	Parachains(OnDemandParachainsRelay<SourceChain>),
+}
+
+impl<SourceChain: Chain, TargetChain: Chain> Clone for OnDemandRelay<SourceChain, TargetChain> {
+	fn clone(&self) -> Self {
+		match *self {
+			OnDemandRelay::Headers(ref relay) => OnDemandRelay::Headers(relay.clone()),
+			OnDemandRelay::Parachains(ref relay) => OnDemandRelay::Parachains(relay.clone()),
+		}
+	}
+}
+
+impl<SourceChain: Chain, TargetChain: Chain> OnDemandRelay<SourceChain, TargetChain> {
+	/// Someone is asking us to relay given finalized header.
+	pub async fn require_finalized_header(&self, header_id: HeaderIdOf<SourceChain>) {
+		match *self {
+			OnDemandRelay::Headers(ref relay) => relay.require_finalized_header(header_id).await,
+			OnDemandRelay::Parachains(ref relay) => relay.require_finalized_header(header_id).await,
+		}
+	}
+
+	/// Returns cost of the latest header submission transaction at the target chain.
+	///
+	/// Returns `None` if no headers have been submitted by this relay yet. The parachains relay
+	/// doesn't track its transactions cost, so it always returns `None`.
+	pub async fn header_submission_cost(&self) -> Option<BalanceOf<TargetChain>> {
+		match *self {
+			OnDemandRelay::Headers(ref relay) => relay.header_submission_cost().await,
+			OnDemandRelay::Parachains(_) => None,
+		}
+	}
+}
+
 /// Message sync pipeline for Substrate <-> Substrate relays.
 #[async_trait]
 pub trait SubstrateMessageLane: 'static + Clone + Send + Sync {
@@ -74,6 +115,8 @@ pub trait SubstrateMessageLane: 'static + Clone + Send + Sync {
 	/// Name of the runtime method that returns latest received (confirmed) nonce at the the source
 	/// chain.
 	const OUTBOUND_LANE_LATEST_RECEIVED_NONCE_METHOD: &'static str;
+	/// Name of the runtime method that returns all lanes, registered at the source chain.
+	const OUTBOUND_LANE_LANES_METHOD: &'static str;
 
 	/// Name of the runtime method that returns latest received nonce at the target chain.
 	const INBOUND_LANE_LATEST_RECEIVED_NONCE_METHOD: &'static str;
@@ -101,6 +144,13 @@ pub trait SubstrateMessageLane: 'static + Clone + Send + Sync {
 	/// delivery transaction.
 	const PAY_INBOUND_DISPATCH_FEE_WEIGHT_AT_TARGET_CHAIN: Weight;
 
+	/// Name of the relayers pallet as it is declared in the `construct_runtime!()` at target chain.
+	///
+	/// If it is `None`, the target chain has no relayers pallet and delivery slots are not used.
+	const RELAYERS_PALLET_NAME_AT_TARGET: Option<&'static str> = None;
+	/// Length of the message delivery slot (in blocks) at the target chain.
+	const DELIVERY_SLOT_LENGTH_AT_TARGET: u64 = 0;
+
 	/// Source chain.
 	type SourceChain: Chain;
 	/// Target chain.
@@ -19,12 +19,13 @@
 //! <BridgedName> chain.
 
 use crate::{
-	messages_lane::SubstrateMessageLane, messages_target::SubstrateMessagesReceivingProof,
-	on_demand_headers::OnDemandHeadersRelay,
+	finality_pipeline::STALL_TIMEOUT,
+	messages_lane::{OnDemandRelay, SubstrateMessageLane},
+	messages_target::SubstrateMessagesReceivingProof,
 };
 
 use async_trait::async_trait;
-use bp_messages::{LaneId, MessageNonce, UnrewardedRelayersState};
+use bp_messages::{LaneId, LaneInfo, LaneState, MessageNonce, UnrewardedRelayersState};
 use bridge_runtime_common::messages::{
 	source::FromBridgedChainMessagesDeliveryProof, target::FromBridgedChainMessagesProof,
 };
@@ -36,11 +37,12 @@ use messages_relay::{
 		ClientState, MessageDetails, MessageDetailsMap, MessageProofParameters, SourceClient,
 		SourceClientState,
 	},
+	message_lanes_loop::MultiLaneSourceClient,
 };
 use num_traits::{Bounded, Zero};
 use relay_substrate_client::{
 	BalanceOf, BlockNumberOf, Chain, Client, Error as SubstrateError, HashOf, HeaderIdOf, HeaderOf,
-	IndexOf,
+	IndexOf, TransactionTracker,
 };
 use relay_utils::{relay_loop::Client as RelayClient, BlockNumberBase, HeaderId};
 use sp_core::Bytes;
@@ -60,7 +62,7 @@ pub struct SubstrateMessagesSource<P: SubstrateMessageLane> {
 	client: Client<P::SourceChain>,
 	lane: P,
 	lane_id: LaneId,
-	target_to_source_headers_relay: Option<OnDemandHeadersRelay<P::TargetChain>>,
+	target_to_source_headers_relay: Option<OnDemandRelay<P::TargetChain, P::SourceChain>>,
 }
 
 impl<P: SubstrateMessageLane> SubstrateMessagesSource<P> {
@@ -69,7 +71,7 @@ impl<P: SubstrateMessageLane> SubstrateMessagesSource<P> {
 		client: Client<P::SourceChain>,
 		lane: P,
 		lane_id: LaneId,
-		target_to_source_headers_relay: Option<OnDemandHeadersRelay<P::TargetChain>>,
+		target_to_source_headers_relay: Option<OnDemandRelay<P::TargetChain, P::SourceChain>>,
 	) -> Self {
 		SubstrateMessagesSource { client, lane, lane_id, target_to_source_headers_relay }
 	}
@@ -95,6 +97,16 @@ impl<P: SubstrateMessageLane> RelayClient for SubstrateMessagesSource<P> {
 	}
 }
 
+impl<P> MultiLaneSourceClient<P::MessageLane> for SubstrateMessagesSource<P>
+where
+	P: SubstrateMessageLane,
+	Self: SourceClient<P::MessageLane>,
+{
+	fn lane_client(&self, lane: LaneId) -> Self {
+		Self { lane_id: lane, ..self.clone() }
+	}
+}
+
 #[async_trait]
 impl<P> SourceClient<P::MessageLane> for SubstrateMessagesSource<P>
 where
@@ -122,6 +134,8 @@ where
 	<P::MessageLane as MessageLane>::TargetHeaderHash: Decode,
 	<P::MessageLane as MessageLane>::SourceChainBalance: AtLeast32BitUnsigned,
 {
+	type TransactionTracker = TransactionTracker<P::SourceChain>;
+
 	async fn state(&self) -> Result<SourceClientState<P::MessageLane>, SubstrateError> {
 		// we can't continue to deliver confirmations if source node is out of sync, because
 		// it may have already received confirmations that we're going to deliver
@@ -240,11 +254,12 @@ where
 		&self,
 		generated_at_block: TargetHeaderIdOf<P::MessageLane>,
 		proof: <P::MessageLane as MessageLane>::MessagesReceivingProof,
-	) -> Result<(), SubstrateError> {
+	) -> Result<TransactionTracker<P::SourceChain>, SubstrateError> {
 		let lane = self.lane.clone();
 		self.client
-			.submit_signed_extrinsic(
+			.submit_and_track_signed_extrinsic(
 				self.lane.source_transactions_author(),
+				STALL_TIMEOUT,
 				move |_, transaction_nonce| {
 					lane.make_messages_receiving_proof_transaction(
 						transaction_nonce,
@@ -253,8 +268,7 @@ where
 					)
 				},
 			)
-			.await?;
-		Ok(())
+			.await
 	}
 
 	async fn require_target_header_on_source(&self, id: TargetHeaderIdOf<P::MessageLane>) {
@@ -276,6 +290,64 @@ where
 			.map(|fee| fee.inclusion_fee())
 			.unwrap_or_else(|_| BalanceOf::<P::SourceChain>::max_value())
 	}
+
+	async fn estimate_target_header_submission(
+		&self,
+	) -> <P::MessageLane as MessageLane>::SourceChainBalance {
+		match self.target_to_source_headers_relay {
+			Some(ref target_to_source_headers_relay) => target_to_source_headers_relay
+				.header_submission_cost()
+				.await
+				.unwrap_or_else(Zero::zero),
+			None => Zero::zero(),
+		}
+	}
+}
+
+/// Read all lanes, registered at the source chain, with their states.
+pub async fn read_lanes<P: SubstrateMessageLane>(
+	source_client: &Client<P::SourceChain>,
+) -> Result<Vec<(LaneId, LaneInfo)>, SubstrateError> {
+	let encoded_lanes = source_client
+		.state_call(P::OUTBOUND_LANE_LANES_METHOD.into(), Bytes(Vec::new()), None)
+		.await?;
+	Decode::decode(&mut &encoded_lanes.0[..]).map_err(SubstrateError::ResponseParseFailed)
+}
+
+/// Select lanes that the messages relay is going to serve.
+///
+/// If no lanes are requested, all opened lanes of the source chain are served. Otherwise the
+/// requested lanes are served, but we warn about lanes that are not opened at the source chain.
+pub async fn select_lanes_to_serve<P: SubstrateMessageLane>(
+	source_client: &Client<P::SourceChain>,
+	requested_lanes: Vec<LaneId>,
+) -> Result<Vec<LaneId>, SubstrateError> {
+	let lanes = read_lanes::<P>(source_client).await?;
+	if requested_lanes.is_empty() {
+		return Ok(lanes
+			.into_iter()
+			.filter(|(_, lane_info)| lane_info.state == LaneState::Opened)
+			.map(|(lane_id, _)| lane_id)
+			.collect())
+	}
+
+	for requested_lane in &requested_lanes {
+		let lane_state = lanes
+			.iter()
+			.find(|(lane_id, _)| lane_id == requested_lane)
+			.map(|(_, lane_info)| lane_info.state);
+		if lane_state != Some(LaneState::Opened) {
+			log::warn!(
+				target: "bridge",
+				"Lane {:?} is not opened at {}: {:?}",
+				requested_lane,
+				P::SourceChain::NAME,
+				lane_state,
+			);
+		}
+	}
+
+	Ok(requested_lanes)
 }
 
 /// Prepare 'dummy' messages delivery proof that will compose the delivery confirmation transaction.
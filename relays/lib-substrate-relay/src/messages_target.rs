@@ -19,9 +19,9 @@
 //! <BridgedName> chain.
 
 use crate::{
-	messages_lane::{StandaloneMessagesMetrics, SubstrateMessageLane},
+	finality_pipeline::STALL_TIMEOUT,
+	messages_lane::{OnDemandRelay, StandaloneMessagesMetrics, SubstrateMessageLane},
 	messages_source::{read_client_state, SubstrateMessagesProof},
-	on_demand_headers::OnDemandHeadersRelay,
 };
 
 use async_trait::async_trait;
@@ -34,12 +34,14 @@ use codec::{Decode, Encode};
 use frame_support::weights::{Weight, WeightToFeePolynomial};
 use messages_relay::{
 	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
-	message_lane_loop::{TargetClient, TargetClientState},
+	message_lane_loop::{NoncesSubmitArtifacts, TargetClient, TargetClientState},
+	message_lanes_loop::MultiLaneTargetClient,
 };
 use num_traits::{Bounded, Zero};
+use pallet_bridge_relayers::{delivery_slot_owner, storage_keys::lane_relayers_key};
 use relay_substrate_client::{
-	BalanceOf, BlockNumberOf, Chain, Client, Error as SubstrateError, HashOf, HeaderOf, IndexOf,
-	WeightToFeeOf,
+	AccountIdOf, BalanceOf, BlockNumberOf, Chain, Client, Error as SubstrateError, HashOf,
+	HeaderOf, IndexOf, TransactionTracker, WeightToFeeOf,
 };
 use relay_utils::{relay_loop::Client as RelayClient, BlockNumberBase, HeaderId};
 use sp_core::Bytes;
@@ -56,7 +58,7 @@ pub struct SubstrateMessagesTarget<P: SubstrateMessageLane> {
 	lane: P,
 	lane_id: LaneId,
 	metric_values: StandaloneMessagesMetrics,
-	source_to_target_headers_relay: Option<OnDemandHeadersRelay<P::SourceChain>>,
+	source_to_target_headers_relay: Option<OnDemandRelay<P::SourceChain, P::TargetChain>>,
 }
 
 impl<P: SubstrateMessageLane> SubstrateMessagesTarget<P> {
@@ -66,7 +68,7 @@ impl<P: SubstrateMessageLane> SubstrateMessagesTarget<P> {
 		lane: P,
 		lane_id: LaneId,
 		metric_values: StandaloneMessagesMetrics,
-		source_to_target_headers_relay: Option<OnDemandHeadersRelay<P::SourceChain>>,
+		source_to_target_headers_relay: Option<OnDemandRelay<P::SourceChain, P::TargetChain>>,
 	) -> Self {
 		SubstrateMessagesTarget {
 			client,
@@ -99,6 +101,16 @@ impl<P: SubstrateMessageLane> RelayClient for SubstrateMessagesTarget<P> {
 	}
 }
 
+impl<P> MultiLaneTargetClient<P::MessageLane> for SubstrateMessagesTarget<P>
+where
+	P: SubstrateMessageLane,
+	Self: TargetClient<P::MessageLane>,
+{
+	fn lane_client(&self, lane: LaneId) -> Self {
+		Self { lane_id: lane, ..self.clone() }
+	}
+}
+
 #[async_trait]
 impl<P> TargetClient<P::MessageLane> for SubstrateMessagesTarget<P>
 where
@@ -125,6 +137,8 @@ where
 	<P::MessageLane as MessageLane>::SourceHeaderNumber: Decode,
 	<P::MessageLane as MessageLane>::SourceHeaderHash: Decode,
 {
+	type TransactionTracker = TransactionTracker<P::TargetChain>;
+
 	async fn state(&self) -> Result<TargetClientState<P::MessageLane>, SubstrateError> {
 		// we can't continue to deliver messages if target node is out of sync, because
 		// it may have already received (some of) messages that we're going to deliver
@@ -190,6 +204,29 @@ where
 		Ok((id, unrewarded_relayers_state))
 	}
 
+	async fn is_delivery_slot_available(
+		&self,
+		id: TargetHeaderIdOf<P::MessageLane>,
+	) -> Result<(TargetHeaderIdOf<P::MessageLane>, bool), SubstrateError> {
+		let relayers_pallet_name = match P::RELAYERS_PALLET_NAME_AT_TARGET {
+			Some(relayers_pallet_name) => relayers_pallet_name,
+			None => return Ok((id, true)),
+		};
+
+		let lane_relayers: Vec<AccountIdOf<P::TargetChain>> = self
+			.client
+			.storage_value(lane_relayers_key(relayers_pallet_name, &self.lane_id), Some(id.1))
+			.await?
+			.unwrap_or_default();
+		let slot_owner =
+			delivery_slot_owner(&lane_relayers, id.0.into(), P::DELIVERY_SLOT_LENGTH_AT_TARGET);
+		let is_delivery_slot_available = match slot_owner {
+			Some(slot_owner) => slot_owner == self.lane.target_transactions_author(),
+			None => true,
+		};
+		Ok((id, is_delivery_slot_available))
+	}
+
 	async fn prove_messages_receiving(
 		&self,
 		id: TargetHeaderIdOf<P::MessageLane>,
@@ -221,12 +258,14 @@ where
 		generated_at_header: SourceHeaderIdOf<P::MessageLane>,
 		nonces: RangeInclusive<MessageNonce>,
 		proof: <P::MessageLane as MessageLane>::MessagesProof,
-	) -> Result<RangeInclusive<MessageNonce>, SubstrateError> {
+	) -> Result<NoncesSubmitArtifacts<TransactionTracker<P::TargetChain>>, SubstrateError> {
 		let lane = self.lane.clone();
 		let nonces_clone = nonces.clone();
-		self.client
-			.submit_signed_extrinsic(
+		let tx_tracker = self
+			.client
+			.submit_and_track_signed_extrinsic(
 				self.lane.target_transactions_author(),
+				STALL_TIMEOUT,
 				move |_, transaction_nonce| {
 					lane.make_messages_delivery_transaction(
 						transaction_nonce,
@@ -237,7 +276,7 @@ where
 				},
 			)
 			.await?;
-		Ok(nonces)
+		Ok(NoncesSubmitArtifacts { nonces, tx_tracker })
 	}
 
 	async fn require_source_header_on_target(&self, id: SourceHeaderIdOf<P::MessageLane>) {
@@ -354,6 +393,36 @@ where
 
 		Ok(delivery_fee_in_source_tokens)
 	}
+
+	async fn estimate_source_header_submission_in_source_tokens(
+		&self,
+	) -> Result<<P::MessageLane as MessageLane>::SourceChainBalance, SubstrateError> {
+		let header_submission_cost_in_target_tokens = match self.source_to_target_headers_relay {
+			Some(ref source_to_target_headers_relay) =>
+				source_to_target_headers_relay.header_submission_cost().await,
+			None => None,
+		};
+		let header_submission_cost_in_target_tokens = match header_submission_cost_in_target_tokens
+		{
+			Some(header_submission_cost_in_target_tokens) =>
+				header_submission_cost_in_target_tokens,
+			None => return Ok(Zero::zero()),
+		};
+
+		let conversion_rate =
+			self.metric_values.target_to_source_conversion_rate().await.ok_or_else(|| {
+				SubstrateError::Custom(format!(
+					"Failed to compute conversion rate from {} to {}",
+					P::TargetChain::NAME,
+					P::SourceChain::NAME,
+				))
+			})?;
+
+		Ok(convert_target_tokens_to_source_tokens::<P::SourceChain, P::TargetChain>(
+			FixedU128::from_float(conversion_rate),
+			header_submission_cost_in_target_tokens,
+		))
+	}
 }
 
 /// Prepare 'dummy' messages proof that will compose the delivery transaction.
@@ -451,6 +520,7 @@ mod tests {
 		const OUTBOUND_LANE_MESSAGE_DETAILS_METHOD: &'static str = "";
 		const OUTBOUND_LANE_LATEST_GENERATED_NONCE_METHOD: &'static str = "";
 		const OUTBOUND_LANE_LATEST_RECEIVED_NONCE_METHOD: &'static str = "";
+		const OUTBOUND_LANE_LANES_METHOD: &'static str = "";
 
 		const INBOUND_LANE_LATEST_RECEIVED_NONCE_METHOD: &'static str = "";
 		const INBOUND_LANE_LATEST_CONFIRMED_NONCE_METHOD: &'static str = "";
@@ -23,12 +23,12 @@ use futures::{select, FutureExt};
 use num_traits::{CheckedSub, One, Zero};
 
 use finality_relay::{
-	FinalitySyncParams, FinalitySyncPipeline, SourceClient as FinalitySourceClient, SourceHeader,
-	TargetClient as FinalityTargetClient,
+	FinalitySyncParams, FinalitySyncPipeline, ForkDetectionMode,
+	SourceClient as FinalitySourceClient, SourceHeader, TargetClient as FinalityTargetClient,
 };
 use relay_substrate_client::{
 	finality_source::{FinalitySource as SubstrateFinalitySource, RequiredHeaderNumberRef},
-	Chain, Client, HeaderIdOf, SyncHeader,
+	BalanceOf, Chain, Client, HeaderIdOf, SyncHeader,
 };
 use relay_utils::{
 	metrics::MetricsParams, relay_loop::Client as RelayClient, BlockNumberBase, FailedClient,
@@ -37,10 +37,10 @@ use relay_utils::{
 
 use crate::{
 	finality_pipeline::{
-		SubstrateFinalitySyncPipeline, SubstrateFinalityToSubstrate, RECENT_FINALITY_PROOFS_LIMIT,
-		STALL_TIMEOUT,
+		SubstrateFinalitySyncPipeline, SubstrateFinalityToSubstrate, MANDATORY_HEADERS_LOOK_AHEAD,
+		MAX_MANDATORY_HEADERS_PER_TRANSACTION, RECENT_FINALITY_PROOFS_LIMIT, STALL_TIMEOUT,
 	},
-	finality_target::SubstrateFinalityTarget,
+	finality_target::{HeaderSubmissionCostRef, SubstrateFinalityTarget},
 };
 
 /// On-demand Substrate <-> Substrate headers relay.
@@ -49,16 +49,18 @@ use crate::{
 /// relay) needs it to continue its regular work. When enough headers are relayed, on-demand stops
 /// syncing headers.
 #[derive(Clone)]
-pub struct OnDemandHeadersRelay<SourceChain: Chain> {
+pub struct OnDemandHeadersRelay<SourceChain: Chain, TargetChain: Chain> {
 	/// Relay task name.
 	relay_task_name: String,
 	/// Shared reference to maximal required finalized header number.
 	required_header_number: RequiredHeaderNumberRef<SourceChain>,
+	/// Shared reference to the cost of the latest header submission transaction.
+	header_submission_cost: HeaderSubmissionCostRef<TargetChain>,
 }
 
-impl<SourceChain: Chain> OnDemandHeadersRelay<SourceChain> {
+impl<SourceChain: Chain, TargetChain: Chain> OnDemandHeadersRelay<SourceChain, TargetChain> {
 	/// Create new on-demand headers relay.
-	pub fn new<TargetChain: Chain, TargetSign, P>(
+	pub fn new<TargetSign, P>(
 		source_client: Client<SourceChain>,
 		target_client: Client<TargetChain>,
 		target_transactions_mortality: Option<u32>,
@@ -82,9 +84,11 @@ impl<SourceChain: Chain> OnDemandHeadersRelay<SourceChain> {
 		>,
 	{
 		let required_header_number = Arc::new(Mutex::new(Zero::zero()));
+		let header_submission_cost = Arc::new(Mutex::new(None));
 		let this = OnDemandHeadersRelay {
 			relay_task_name: on_demand_headers_relay_name::<SourceChain, TargetChain>(),
 			required_header_number: required_header_number.clone(),
+			header_submission_cost: header_submission_cost.clone(),
 		};
 		async_std::task::spawn(async move {
 			background_task(
@@ -95,6 +99,7 @@ impl<SourceChain: Chain> OnDemandHeadersRelay<SourceChain> {
 				maximal_headers_difference,
 				only_mandatory_headers,
 				required_header_number,
+				header_submission_cost,
 			)
 			.await;
 		});
@@ -117,6 +122,13 @@ impl<SourceChain: Chain> OnDemandHeadersRelay<SourceChain> {
 			*required_header_number = header_id.0;
 		}
 	}
+
+	/// Returns cost of the latest header submission transaction at the target chain.
+	///
+	/// Returns `None` if no headers have been submitted by this relay yet.
+	pub async fn header_submission_cost(&self) -> Option<BalanceOf<TargetChain>> {
+		*self.header_submission_cost.lock().await
+	}
 }
 
 /// Background task that is responsible for starting headers relay.
@@ -128,6 +140,7 @@ async fn background_task<SourceChain, TargetChain, TargetSign, P>(
 	maximal_headers_difference: SourceChain::BlockNumber,
 	only_mandatory_headers: bool,
 	required_header_number: RequiredHeaderNumberRef<SourceChain>,
+	header_submission_cost: HeaderSubmissionCostRef<TargetChain>,
 ) where
 	SourceChain: Chain + Debug,
 	SourceChain::BlockNumber: BlockNumberBase,
@@ -148,7 +161,8 @@ async fn background_task<SourceChain, TargetChain, TargetSign, P>(
 		target_client.clone(),
 		pipeline.clone(),
 		target_transactions_mortality,
-	);
+	)
+	.with_header_submission_cost(header_submission_cost);
 	let mut latest_non_mandatory_at_source = Zero::zero();
 
 	let mut restart_relay = true;
@@ -253,6 +267,10 @@ async fn background_task<SourceChain, TargetChain, TargetSign, P>(
 						recent_finality_proofs_limit: RECENT_FINALITY_PROOFS_LIMIT,
 						stall_timeout: STALL_TIMEOUT,
 						only_mandatory_headers,
+						max_mandatory_headers_per_transaction:
+							MAX_MANDATORY_HEADERS_PER_TRANSACTION,
+						mandatory_headers_look_ahead: MANDATORY_HEADERS_LOOK_AHEAD,
+						fork_detection: ForkDetectionMode::Disabled,
 					},
 					MetricsParams::disabled(),
 					futures::future::pending(),
@@ -393,16 +411,20 @@ where
 	P: SubstrateFinalitySyncPipeline,
 	P::FinalitySyncPipeline: FinalitySyncPipeline<Number = SourceChain::BlockNumber>,
 {
-	finality_target.best_finalized_source_block_number().await.map_err(|error| {
-		log::error!(
-			target: "bridge",
-			"Failed to read best finalized source header from target in {} relay: {:?}",
-			relay_task_name,
-			error,
-		);
+	finality_target
+		.best_finalized_source_block_id()
+		.await
+		.map(|id| id.0)
+		.map_err(|error| {
+			log::error!(
+				target: "bridge",
+				"Failed to read best finalized source header from target in {} relay: {:?}",
+				relay_task_name,
+				error,
+			);
 
-		error
-	})
+			error
+		})
 }
 
 /// Read first mandatory header in given inclusive range.
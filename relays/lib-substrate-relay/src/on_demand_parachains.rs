@@ -0,0 +1,295 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! On-demand Substrate -> Substrate parachain heads relay.
+
+use std::{fmt::Debug, time::Instant};
+
+use async_std::sync::{Arc, Mutex};
+use codec::Decode;
+use num_traits::Zero;
+
+use bp_polkadot_core::parachains::{ParaHash, ParaId};
+use relay_substrate_client::{
+	finality_source::RequiredHeaderNumberRef, BlockNumberOf, Chain, Client,
+	Error as SubstrateError, HeaderIdOf,
+};
+use relay_utils::{FailedClient, HeaderId, MaybeConnectionError};
+use sp_runtime::traits::Header as HeaderT;
+
+use crate::{
+	finality_pipeline::STALL_TIMEOUT,
+	on_demand_headers::OnDemandHeadersRelay,
+	parachains_pipeline::{
+		best_finalized_relay_header_at_target, best_parachain_head_at_target,
+		parachain_head_at_source, submit_parachain_heads, SubstrateParachainsPipeline,
+	},
+};
+
+/// On-demand Substrate <-> Substrate parachain heads relay.
+///
+/// This relay may be requested to sync more parachain heads, whenever some other relay (e.g.
+/// messages relay) needs it to continue its regular work. When the required head is relayed,
+/// on-demand stops syncing heads. If the relay chain header, required to prove the parachain
+/// head, is not yet known to the target chain, it is requested from the on-demand relay chain
+/// headers relay.
+#[derive(Clone)]
+pub struct OnDemandParachainsRelay<SourceParachain: Chain> {
+	/// Relay task name.
+	relay_task_name: String,
+	/// Shared reference to maximal required finalized parachain header number.
+	required_header_number: RequiredHeaderNumberRef<SourceParachain>,
+}
+
+impl<SourceParachain: Chain> OnDemandParachainsRelay<SourceParachain> {
+	/// Create new on-demand parachain heads relay.
+	pub fn new<P: SubstrateParachainsPipeline>(
+		pipeline: P,
+		source_relay_client: Client<P::SourceRelayChain>,
+		target_client: Client<P::TargetChain>,
+		target_transactions_mortality: Option<u32>,
+		on_demand_source_relay_to_target_headers: OnDemandHeadersRelay<
+			P::SourceRelayChain,
+			P::TargetChain,
+		>,
+		parachain: ParaId,
+	) -> Self
+	where
+		SourceParachain: Chain<Hash = ParaHash> + Debug,
+	{
+		let required_header_number = Arc::new(Mutex::new(Zero::zero()));
+		let this = OnDemandParachainsRelay {
+			relay_task_name: on_demand_parachains_relay_name::<SourceParachain, P::TargetChain>(),
+			required_header_number: required_header_number.clone(),
+		};
+		async_std::task::spawn(async move {
+			background_task::<SourceParachain, P>(
+				pipeline,
+				source_relay_client,
+				target_client,
+				target_transactions_mortality,
+				on_demand_source_relay_to_target_headers,
+				parachain,
+				required_header_number,
+			)
+			.await;
+		});
+
+		this
+	}
+
+	/// Someone is asking us to relay given finalized parachain header.
+	pub async fn require_finalized_header(&self, header_id: HeaderIdOf<SourceParachain>) {
+		let mut required_header_number = self.required_header_number.lock().await;
+		if header_id.0 > *required_header_number {
+			log::trace!(
+				target: "bridge",
+				"More {} headers required in {} relay. Going to sync up to the {}",
+				SourceParachain::NAME,
+				self.relay_task_name,
+				header_id.0,
+			);
+
+			*required_header_number = header_id.0;
+		}
+	}
+}
+
+/// Background task that is responsible for starting parachain heads relay.
+async fn background_task<SourceParachain, P>(
+	pipeline: P,
+	mut source_relay_client: Client<P::SourceRelayChain>,
+	mut target_client: Client<P::TargetChain>,
+	target_transactions_mortality: Option<u32>,
+	on_demand_source_relay_to_target_headers: OnDemandHeadersRelay<
+		P::SourceRelayChain,
+		P::TargetChain,
+	>,
+	parachain: ParaId,
+	required_header_number: RequiredHeaderNumberRef<SourceParachain>,
+) where
+	SourceParachain: Chain<Hash = ParaHash> + Debug,
+	P: SubstrateParachainsPipeline,
+{
+	let relay_task_name = on_demand_parachains_relay_name::<SourceParachain, P::TargetChain>();
+	let stall_timeout = relay_substrate_client::transaction_stall_timeout(
+		target_transactions_mortality,
+		P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+	)
+	.unwrap_or(STALL_TIMEOUT);
+	let mut last_submitted_at: Option<(BlockNumberOf<P::SourceRelayChain>, Instant)> = None;
+
+	loop {
+		async_std::task::sleep(P::TargetChain::AVERAGE_BLOCK_INTERVAL).await;
+
+		let required_header_number = *required_header_number.lock().await;
+		let iteration_result = run_background_task_iteration::<SourceParachain, P>(
+			&pipeline,
+			&source_relay_client,
+			&target_client,
+			target_transactions_mortality,
+			&on_demand_source_relay_to_target_headers,
+			parachain,
+			required_header_number,
+			stall_timeout,
+			&mut last_submitted_at,
+		)
+		.await;
+
+		if let Err((failed_client, error)) = iteration_result {
+			log::error!(
+				target: "bridge",
+				"Failed to relay {} head in {} relay: {:?}",
+				SourceParachain::NAME,
+				relay_task_name,
+				error,
+			);
+
+			if error.is_connection_error() {
+				relay_utils::relay_loop::reconnect_failed_client(
+					failed_client,
+					relay_utils::relay_loop::RECONNECT_DELAY,
+					&mut source_relay_client,
+					&mut target_client,
+				)
+				.await;
+			}
+		}
+	}
+}
+
+/// Single iteration of the on-demand parachain heads relay background task.
+#[allow(clippy::too_many_arguments)]
+async fn run_background_task_iteration<SourceParachain, P>(
+	pipeline: &P,
+	source_relay_client: &Client<P::SourceRelayChain>,
+	target_client: &Client<P::TargetChain>,
+	target_transactions_mortality: Option<u32>,
+	on_demand_source_relay_to_target_headers: &OnDemandHeadersRelay<
+		P::SourceRelayChain,
+		P::TargetChain,
+	>,
+	parachain: ParaId,
+	required_header_number: BlockNumberOf<SourceParachain>,
+	stall_timeout: std::time::Duration,
+	last_submitted_at: &mut Option<(BlockNumberOf<P::SourceRelayChain>, Instant)>,
+) -> Result<(), (FailedClient, SubstrateError)>
+where
+	SourceParachain: Chain<Hash = ParaHash> + Debug,
+	P: SubstrateParachainsPipeline,
+{
+	if required_header_number.is_zero() {
+		return Ok(())
+	}
+
+	// parachain head may only be proved at relay chain block, that is known to the target chain
+	let best_relay_block_at_target = best_finalized_relay_header_at_target::<P>(target_client)
+		.await
+		.map_err(|e| (FailedClient::Target, e))?;
+	let head_at_source =
+		parachain_head_at_source::<P>(source_relay_client, best_relay_block_at_target.1, parachain)
+			.await
+			.map_err(|e| (FailedClient::Source, e))?;
+	let head_at_source = match head_at_source {
+		Some(head_at_source) => head_at_source,
+		None => {
+			// parachain is not yet registered at the relay chain block that is known to the
+			// target => we need more relay chain headers
+			require_best_finalized_relay_header::<P>(
+				source_relay_client,
+				on_demand_source_relay_to_target_headers,
+			)
+			.await?;
+			return Ok(())
+		},
+	};
+	let head_hash_at_source = head_at_source.hash();
+	let header_at_source = SourceParachain::Header::decode(&mut &head_at_source.0[..])
+		.map_err(|e| (FailedClient::Source, SubstrateError::ResponseParseFailed(e)))?;
+	let header_number_at_source = *header_at_source.number();
+
+	// if parachain head at the relay chain block, known to the target, is not enough, we need
+	// more relay chain headers
+	if header_number_at_source < required_header_number {
+		require_best_finalized_relay_header::<P>(
+			source_relay_client,
+			on_demand_source_relay_to_target_headers,
+		)
+		.await?;
+		return Ok(())
+	}
+
+	// if target already has this head, there's nothing to do
+	let best_head_at_target = best_parachain_head_at_target::<P>(target_client, parachain)
+		.await
+		.map_err(|e| (FailedClient::Target, e))?;
+	if best_head_at_target.map(|best_head| best_head.head_hash) == Some(head_hash_at_source) {
+		return Ok(())
+	}
+
+	// if we have recently submitted the same head, let's wait until it is imported or the
+	// transaction is considered lost
+	if let Some((submitted_at_relay_block, submitted_at)) = *last_submitted_at {
+		if submitted_at_relay_block >= best_relay_block_at_target.0 &&
+			submitted_at.elapsed() < stall_timeout
+		{
+			return Ok(())
+		}
+	}
+
+	submit_parachain_heads(
+		pipeline,
+		source_relay_client,
+		target_client,
+		best_relay_block_at_target,
+		vec![parachain],
+		target_transactions_mortality,
+	)
+	.await?;
+	*last_submitted_at = Some((best_relay_block_at_target.0, Instant::now()));
+
+	Ok(())
+}
+
+/// Ask on-demand relay chain headers relay to sync best finalized relay chain header.
+async fn require_best_finalized_relay_header<P: SubstrateParachainsPipeline>(
+	source_relay_client: &Client<P::SourceRelayChain>,
+	on_demand_source_relay_to_target_headers: &OnDemandHeadersRelay<
+		P::SourceRelayChain,
+		P::TargetChain,
+	>,
+) -> Result<(), (FailedClient, SubstrateError)> {
+	let best_finalized_relay_hash = source_relay_client
+		.best_finalized_header_hash()
+		.await
+		.map_err(|e| (FailedClient::Source, e))?;
+	let best_finalized_relay_header = source_relay_client
+		.header_by_hash(best_finalized_relay_hash)
+		.await
+		.map_err(|e| (FailedClient::Source, e))?;
+	on_demand_source_relay_to_target_headers
+		.require_finalized_header(HeaderId(
+			*best_finalized_relay_header.number(),
+			best_finalized_relay_hash,
+		))
+		.await;
+	Ok(())
+}
+
+/// On-demand parachains relay task name.
+fn on_demand_parachains_relay_name<SourceChain: Chain, TargetChain: Chain>() -> String {
+	format!("on-demand-{}-to-{}", SourceChain::NAME, TargetChain::NAME)
+}
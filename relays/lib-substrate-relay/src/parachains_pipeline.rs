@@ -0,0 +1,404 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Substrate-to-Substrate parachain heads relay entrypoint.
+//!
+//! The relay watches `Heads` map of the `paras` pallet at the source relay chain and submits
+//! proofs of updated parachain heads to the bridge parachains pallet at the target chain. Proofs
+//! are always generated at the relay chain block that is already known to the target chain (i.e.
+//! imported by the GRANDPA pallet, used by the bridge parachains pallet).
+
+use crate::{finality_pipeline::STALL_TIMEOUT, messages_source::read_client_state};
+
+use bp_parachains::{
+	best_parachain_head_storage_key_at_target, parachain_head_storage_key_at_source, BestParaHead,
+};
+use bp_polkadot_core::parachains::{ParaHash, ParaHead, ParaHeadsProof, ParaId};
+use bp_runtime::AccountIdOf;
+use relay_substrate_client::{
+	BlockNumberOf, Chain, Client, Error as SubstrateError, HashOf, HeaderIdOf, TransactionEra,
+};
+use relay_utils::{metrics::MetricsParams, FailedClient, MaybeConnectionError};
+use sp_core::Bytes;
+use std::{
+	fmt::Debug,
+	time::{Duration, Instant},
+};
+
+/// Parachain heads relay pipeline for Substrate <-> Substrate relays.
+pub trait SubstrateParachainsPipeline: 'static + Clone + Debug + Send + Sync {
+	/// Relay chain, which is storing parachain heads.
+	type SourceRelayChain: Chain;
+	/// Chain with the bridge parachains pallet.
+	type TargetChain: Chain;
+
+	/// Name of the `paras` pallet at the source relay chain.
+	const SOURCE_PARAS_PALLET_NAME: &'static str = bp_parachains::PARAS_PALLET_NAME;
+	/// Name of the bridge parachains pallet at the target chain.
+	const TARGET_PARACHAINS_PALLET_NAME: &'static str;
+	/// Name of the runtime method that returns id of best finalized source relay chain header at
+	/// target chain.
+	const BEST_FINALIZED_SOURCE_RELAY_HEADER_ID_AT_TARGET: &'static str;
+
+	/// Customize metrics exposed by parachain heads relay loop.
+	fn customize_metrics(params: MetricsParams) -> anyhow::Result<MetricsParams> {
+		Ok(params)
+	}
+
+	/// Start parachain heads relay guards.
+	///
+	/// Different parachain bridges may have different set of guards. So the implementation is left
+	/// to the specific bridges.
+	fn start_relay_guards(&self) {}
+
+	/// Returns id of account that we're using to sign transactions at target chain.
+	fn transactions_author(&self) -> AccountIdOf<Self::TargetChain>;
+
+	/// Make submit parachain heads transaction.
+	fn make_submit_parachain_heads_transaction(
+		&self,
+		era: bp_runtime::TransactionEraOf<Self::TargetChain>,
+		transaction_nonce: bp_runtime::IndexOf<Self::TargetChain>,
+		at_relay_block: HeaderIdOf<Self::SourceRelayChain>,
+		parachains: Vec<ParaId>,
+		parachain_heads_proof: ParaHeadsProof,
+	) -> Bytes;
+}
+
+/// Last parachain heads submission.
+#[derive(Clone, Copy, Debug)]
+struct SubmittedHeads<RelayBlockNumber> {
+	/// Number of relay chain block, used to generate heads proof.
+	at_relay_block_number: RelayBlockNumber,
+	/// Submission time.
+	submitted_at: Instant,
+}
+
+/// Returns prefix of parachain heads relay metrics.
+pub fn metrics_prefix<P: SubstrateParachainsPipeline>() -> String {
+	format!("{}_to_{}_Parachains", P::SourceRelayChain::NAME, P::TargetChain::NAME)
+}
+
+/// Run Substrate-to-Substrate parachain heads relay.
+pub async fn run<P: SubstrateParachainsPipeline>(
+	pipeline: P,
+	source_client: Client<P::SourceRelayChain>,
+	target_client: Client<P::TargetChain>,
+	parachains: Vec<ParaId>,
+	transactions_mortality: Option<u32>,
+	metrics_params: MetricsParams,
+) -> anyhow::Result<()> {
+	log::info!(
+		target: "bridge",
+		"Starting {} -> {} parachain heads relay. Relayed parachains: {:?}",
+		P::SourceRelayChain::NAME,
+		P::TargetChain::NAME,
+		parachains,
+	);
+
+	let stall_timeout = relay_substrate_client::transaction_stall_timeout(
+		transactions_mortality,
+		P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+	)
+	.unwrap_or(STALL_TIMEOUT);
+
+	relay_utils::relay_loop(source_client, target_client)
+		.with_metrics(Some(metrics_prefix::<P>()), metrics_params)
+		.expose()
+		.await?
+		.run(metrics_prefix::<P>(), move |source_client, target_client, _| {
+			run_until_connection_lost(
+				pipeline.clone(),
+				source_client,
+				target_client,
+				parachains.clone(),
+				transactions_mortality,
+				stall_timeout,
+			)
+		})
+		.await
+}
+
+/// Run parachain heads relay until connection to one of nodes is lost.
+async fn run_until_connection_lost<P: SubstrateParachainsPipeline>(
+	pipeline: P,
+	source_client: Client<P::SourceRelayChain>,
+	target_client: Client<P::TargetChain>,
+	parachains: Vec<ParaId>,
+	transactions_mortality: Option<u32>,
+	stall_timeout: Duration,
+) -> Result<(), FailedClient> {
+	let tick = std::cmp::max(
+		P::SourceRelayChain::AVERAGE_BLOCK_INTERVAL,
+		P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+	);
+	let mut last_submitted_heads = None;
+	loop {
+		let iteration_result = run_loop_iteration(
+			&pipeline,
+			&source_client,
+			&target_client,
+			&parachains,
+			transactions_mortality,
+			stall_timeout,
+			&mut last_submitted_heads,
+		)
+		.await;
+
+		match iteration_result {
+			Ok(()) => (),
+			Err((failed_client, error)) => {
+				log::error!(
+					target: "bridge",
+					"{} -> {} parachain heads relay has failed: {:?}",
+					P::SourceRelayChain::NAME,
+					P::TargetChain::NAME,
+					error,
+				);
+
+				if error.is_connection_error() {
+					return Err(failed_client)
+				}
+			},
+		}
+
+		async_std::task::sleep(tick).await;
+	}
+}
+
+/// Single iteration of the parachain heads relay loop.
+async fn run_loop_iteration<P: SubstrateParachainsPipeline>(
+	pipeline: &P,
+	source_client: &Client<P::SourceRelayChain>,
+	target_client: &Client<P::TargetChain>,
+	parachains: &[ParaId],
+	transactions_mortality: Option<u32>,
+	stall_timeout: Duration,
+	last_submitted_heads: &mut Option<SubmittedHeads<BlockNumberOf<P::SourceRelayChain>>>,
+) -> Result<(), (FailedClient, SubstrateError)> {
+	// we may only prove parachain heads at relay chain block, that is known to the target chain
+	let best_relay_block_at_target = best_finalized_relay_header_at_target::<P>(target_client)
+		.await
+		.map_err(|e| (FailedClient::Target, e))?;
+
+	// if we have recently submitted heads at the same relay block, let's wait until they're
+	// imported or the transaction is considered lost
+	if let Some(last_submitted_heads) = *last_submitted_heads {
+		if last_submitted_heads.at_relay_block_number >= best_relay_block_at_target.0 &&
+			last_submitted_heads.submitted_at.elapsed() < stall_timeout
+		{
+			return Ok(())
+		}
+	}
+
+	// select parachains, which heads need to be updated
+	let mut parachains_to_update = Vec::new();
+	for parachain in parachains {
+		let head_at_source =
+			parachain_head_at_source::<P>(source_client, best_relay_block_at_target.1, *parachain)
+				.await
+				.map_err(|e| (FailedClient::Source, e))?;
+		let best_head_at_target = best_parachain_head_at_target::<P>(target_client, *parachain)
+			.await
+			.map_err(|e| (FailedClient::Target, e))?;
+		if is_update_required(
+			best_relay_block_at_target.0,
+			head_at_source.map(|head| head.hash()),
+			best_head_at_target,
+		) {
+			parachains_to_update.push(*parachain);
+		}
+	}
+
+	if parachains_to_update.is_empty() {
+		return Ok(())
+	}
+
+	submit_parachain_heads(
+		pipeline,
+		source_client,
+		target_client,
+		best_relay_block_at_target,
+		parachains_to_update,
+		transactions_mortality,
+	)
+	.await?;
+	*last_submitted_heads = Some(SubmittedHeads {
+		at_relay_block_number: best_relay_block_at_target.0,
+		submitted_at: Instant::now(),
+	});
+
+	Ok(())
+}
+
+/// Returns true if parachain head at the target chain needs to be updated.
+fn is_update_required<RelayBlockNumber: PartialOrd>(
+	at_relay_block_number: RelayBlockNumber,
+	head_hash_at_source: Option<ParaHash>,
+	best_head_at_target: Option<BestParaHead<RelayBlockNumber>>,
+) -> bool {
+	match (head_hash_at_source, best_head_at_target) {
+		// parachain is unknown to the source relay chain (or it has been deregistered) => there's
+		// nothing to relay
+		(None, _) => false,
+		// parachain head is unknown to the target chain => let's relay it
+		(Some(_), None) => true,
+		// parachain head has been updated at the source relay chain => let's relay it
+		(Some(head_hash_at_source), Some(best_head_at_target)) =>
+			best_head_at_target.at_relay_block_number < at_relay_block_number &&
+				best_head_at_target.head_hash != head_hash_at_source,
+	}
+}
+
+/// Read id of the best finalized source relay chain header, known to the target chain.
+pub(crate) async fn best_finalized_relay_header_at_target<P: SubstrateParachainsPipeline>(
+	target_client: &Client<P::TargetChain>,
+) -> Result<HeaderIdOf<P::SourceRelayChain>, SubstrateError> {
+	Ok(read_client_state::<
+		P::TargetChain,
+		HashOf<P::SourceRelayChain>,
+		BlockNumberOf<P::SourceRelayChain>,
+	>(target_client, P::BEST_FINALIZED_SOURCE_RELAY_HEADER_ID_AT_TARGET)
+	.await?
+	.best_finalized_peer_at_best_self)
+}
+
+/// Read parachain head at given source relay chain block.
+pub(crate) async fn parachain_head_at_source<P: SubstrateParachainsPipeline>(
+	source_client: &Client<P::SourceRelayChain>,
+	at_relay_block: HashOf<P::SourceRelayChain>,
+	parachain: ParaId,
+) -> Result<Option<ParaHead>, SubstrateError> {
+	source_client
+		.storage_value(
+			parachain_head_storage_key_at_source(P::SOURCE_PARAS_PALLET_NAME, parachain),
+			Some(at_relay_block),
+		)
+		.await
+}
+
+/// Read best parachain head, known to the target chain.
+pub(crate) async fn best_parachain_head_at_target<P: SubstrateParachainsPipeline>(
+	target_client: &Client<P::TargetChain>,
+	parachain: ParaId,
+) -> Result<Option<BestParaHead<BlockNumberOf<P::SourceRelayChain>>>, SubstrateError> {
+	target_client
+		.storage_value(
+			best_parachain_head_storage_key_at_target(P::TARGET_PARACHAINS_PALLET_NAME, parachain),
+			None,
+		)
+		.await
+}
+
+/// Prove heads of given parachains at given relay chain block and submit proof to the target
+/// chain.
+pub(crate) async fn submit_parachain_heads<P: SubstrateParachainsPipeline>(
+	pipeline: &P,
+	source_client: &Client<P::SourceRelayChain>,
+	target_client: &Client<P::TargetChain>,
+	at_relay_block: HeaderIdOf<P::SourceRelayChain>,
+	parachains: Vec<ParaId>,
+	transactions_mortality: Option<u32>,
+) -> Result<(), (FailedClient, SubstrateError)> {
+	let storage_keys = parachains
+		.iter()
+		.map(|parachain| {
+			parachain_head_storage_key_at_source(P::SOURCE_PARAS_PALLET_NAME, *parachain)
+		})
+		.collect();
+	let parachain_heads_proof = source_client
+		.prove_storage(storage_keys, at_relay_block.1)
+		.await
+		.map_err(|e| (FailedClient::Source, e))?
+		.iter_nodes()
+		.collect::<Vec<_>>();
+
+	log::info!(
+		target: "bridge",
+		"Submitting heads of parachains {:?} at {} block {:?} to {}",
+		parachains,
+		P::SourceRelayChain::NAME,
+		at_relay_block,
+		P::TargetChain::NAME,
+	);
+
+	let transactions_author = pipeline.transactions_author();
+	let pipeline = pipeline.clone();
+	target_client
+		.submit_signed_extrinsic(transactions_author, move |best_block_id, transaction_nonce| {
+			pipeline.make_submit_parachain_heads_transaction(
+				TransactionEra::new(best_block_id.0, best_block_id.1, transactions_mortality),
+				transaction_nonce,
+				at_relay_block,
+				parachains,
+				parachain_heads_proof,
+			)
+		})
+		.await
+		.map(drop)
+		.map_err(|e| (FailedClient::Target, e))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn best_head_at_target(at_relay_block_number: u32, head_hash: ParaHash) -> BestParaHead<u32> {
+		BestParaHead { at_relay_block_number, head_hash, next_imported_hash_position: 0 }
+	}
+
+	#[test]
+	fn update_is_not_required_if_parachain_is_unknown_to_source() {
+		assert!(!is_update_required::<u32>(10, None, None));
+		assert!(!is_update_required(
+			10,
+			None,
+			Some(best_head_at_target(5, ParaHash::repeat_byte(1)))
+		));
+	}
+
+	#[test]
+	fn update_is_required_if_parachain_is_unknown_to_target() {
+		assert!(is_update_required(10, Some(ParaHash::repeat_byte(1)), None));
+	}
+
+	#[test]
+	fn update_is_required_if_parachain_head_has_changed() {
+		assert!(is_update_required(
+			10,
+			Some(ParaHash::repeat_byte(2)),
+			Some(best_head_at_target(5, ParaHash::repeat_byte(1)))
+		));
+	}
+
+	#[test]
+	fn update_is_not_required_if_parachain_head_is_the_same() {
+		assert!(!is_update_required(
+			10,
+			Some(ParaHash::repeat_byte(1)),
+			Some(best_head_at_target(5, ParaHash::repeat_byte(1)))
+		));
+	}
+
+	#[test]
+	fn update_is_not_required_if_head_is_already_updated_at_this_relay_block() {
+		assert!(!is_update_required(
+			10,
+			Some(ParaHash::repeat_byte(2)),
+			Some(best_head_at_target(10, ParaHash::repeat_byte(1)))
+		));
+	}
+}
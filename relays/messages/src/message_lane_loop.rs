@@ -18,7 +18,8 @@
 //!
 //! Single relay instance delivers messages of single lane in single direction.
 //! To serve two-way lane, you would need two instances of relay.
-//! To serve N two-way lanes, you would need N*2 instances of relay.
+//! To serve N two-way lanes, you would need N*2 instances of relay (or two instances
+//! of the multi-lane relay from the [`crate::message_lanes_loop`] module).
 //!
 //! Please keep in mind that the best header in this file is actually best
 //! finalized header. I.e. when talking about headers in lane context, we
@@ -40,7 +41,7 @@ use relay_utils::{
 	metrics::{GlobalMetrics, MetricsParams},
 	process_future_result,
 	relay_loop::Client as RelayClient,
-	retry_backoff, FailedClient,
+	retry_backoff, FailedClient, TransactionTracker,
 };
 use std::{collections::BTreeMap, fmt::Debug, future::Future, ops::RangeInclusive, time::Duration};
 
@@ -87,8 +88,16 @@ pub struct MessageDeliveryParams {
 	pub max_messages_in_single_batch: MessageNonce,
 	/// Maximal cumulative dispatch weight of relayed messages in single delivery transaction.
 	pub max_messages_weight_in_single_batch: Weight,
+	/// Maximal dispatch weight of the message that is dispatched during delivery. Heavier
+	/// messages are put into the overweight queue at the target chain, so their dispatch weight
+	/// is not declared in the delivery transaction.
+	pub max_inline_dispatch_weight: Weight,
 	/// Maximal cumulative size of relayed messages in single delivery transaction.
 	pub max_messages_size_in_single_batch: u32,
+	/// If the delivery slot at the target chain is owned by other relayer, we're waiting for
+	/// its delivery transaction for at most this time. If the slot owner hasn't delivered any
+	/// messages during this period, we're delivering messages ourselves.
+	pub delivery_slot_grace_period: Duration,
 	/// Relayer operating mode.
 	pub relayer_mode: RelayerMode,
 }
@@ -119,9 +128,20 @@ pub struct MessageProofParameters {
 	pub dispatch_weight: Weight,
 }
 
+/// Artifacts of submitting nonces proof.
+pub struct NoncesSubmitArtifacts<T> {
+	/// Submitted nonces range.
+	pub nonces: RangeInclusive<MessageNonce>,
+	/// Submitted transaction tracker.
+	pub tx_tracker: T,
+}
+
 /// Source client trait.
 #[async_trait]
 pub trait SourceClient<P: MessageLane>: RelayClient {
+	/// Transaction tracker to track submitted transactions.
+	type TransactionTracker: TransactionTracker;
+
 	/// Returns state of the client.
 	async fn state(&self) -> Result<SourceClientState<P>, Self::Error>;
 
@@ -160,18 +180,28 @@ pub trait SourceClient<P: MessageLane>: RelayClient {
 		&self,
 		generated_at_block: TargetHeaderIdOf<P>,
 		proof: P::MessagesReceivingProof,
-	) -> Result<(), Self::Error>;
+	) -> Result<Self::TransactionTracker, Self::Error>;
 
 	/// We need given finalized target header on source to continue synchronization.
 	async fn require_target_header_on_source(&self, id: TargetHeaderIdOf<P>);
 
 	/// Estimate cost of single message confirmation transaction in source chain tokens.
 	async fn estimate_confirmation_transaction(&self) -> P::SourceChainBalance;
+
+	/// Estimate cost of single target header submission transaction in source chain tokens.
+	///
+	/// This transaction is submitted by the on-demand headers relay when the source chain needs
+	/// to know the target header to accept messages receiving proof. Zero is returned if target
+	/// headers are not relayed on demand, or if the cost is not known yet.
+	async fn estimate_target_header_submission(&self) -> P::SourceChainBalance;
 }
 
 /// Target client trait.
 #[async_trait]
 pub trait TargetClient<P: MessageLane>: RelayClient {
+	/// Transaction tracker to track submitted transactions.
+	type TransactionTracker: TransactionTracker;
+
 	/// Returns state of the client.
 	async fn state(&self) -> Result<TargetClientState<P>, Self::Error>;
 
@@ -193,6 +223,16 @@ pub trait TargetClient<P: MessageLane>: RelayClient {
 		id: TargetHeaderIdOf<P>,
 	) -> Result<(TargetHeaderIdOf<P>, UnrewardedRelayersState), Self::Error>;
 
+	/// Returns true if our relayer may deliver messages at given block.
+	///
+	/// If there are relayers registered at the lane, the delivery slot of the block must be
+	/// owned by our relayer. Otherwise, delivery transactions of the slot owner will have priority
+	/// over our transactions and we'll be paying for failed transactions.
+	async fn is_delivery_slot_available(
+		&self,
+		id: TargetHeaderIdOf<P>,
+	) -> Result<(TargetHeaderIdOf<P>, bool), Self::Error>;
+
 	/// Prove messages receiving at given block.
 	async fn prove_messages_receiving(
 		&self,
@@ -205,7 +245,7 @@ pub trait TargetClient<P: MessageLane>: RelayClient {
 		generated_at_header: SourceHeaderIdOf<P>,
 		nonces: RangeInclusive<MessageNonce>,
 		proof: P::MessagesProof,
-	) -> Result<RangeInclusive<MessageNonce>, Self::Error>;
+	) -> Result<NoncesSubmitArtifacts<Self::TransactionTracker>, Self::Error>;
 
 	/// We need given finalized source header on target to continue synchronization.
 	async fn require_source_header_on_target(&self, id: SourceHeaderIdOf<P>);
@@ -221,6 +261,17 @@ pub trait TargetClient<P: MessageLane>: RelayClient {
 		total_dispatch_weight: Weight,
 		total_size: u32,
 	) -> Result<P::SourceChainBalance, Self::Error>;
+
+	/// Estimate cost of single source header submission transaction in source chain tokens.
+	///
+	/// This transaction is submitted by the on-demand headers relay when the target chain needs
+	/// to know the source header to accept messages proof. Please keep in mind that the returned
+	/// cost must be converted to the source chain tokens, even though the transaction fee will
+	/// be paid in the target chain tokens. Zero is returned if source headers are not relayed
+	/// on demand, or if the cost is not known yet.
+	async fn estimate_source_header_submission_in_source_tokens(
+		&self,
+	) -> Result<P::SourceChainBalance, Self::Error>;
 }
 
 /// State of the client.
@@ -452,7 +503,7 @@ pub(crate) mod tests {
 	use super::*;
 	use futures::stream::StreamExt;
 	use parking_lot::Mutex;
-	use relay_utils::{HeaderId, MaybeConnectionError};
+	use relay_utils::{HeaderId, MaybeConnectionError, TrackedTransactionStatus};
 	use std::sync::Arc;
 
 	pub fn header_id(number: TestSourceHeaderNumber) -> TestSourceHeaderId {
@@ -478,6 +529,22 @@ pub(crate) mod tests {
 	#[derive(Debug)]
 	pub struct TestError;
 
+	#[derive(Clone, Debug)]
+	pub struct TestTransactionTracker(TrackedTransactionStatus);
+
+	impl Default for TestTransactionTracker {
+		fn default() -> TestTransactionTracker {
+			TestTransactionTracker(TrackedTransactionStatus::Finalized)
+		}
+	}
+
+	#[async_trait]
+	impl TransactionTracker for TestTransactionTracker {
+		async fn wait(self) -> TrackedTransactionStatus {
+			self.0
+		}
+	}
+
 	impl MaybeConnectionError for TestError {
 		fn is_connection_error(&self) -> bool {
 			true
@@ -510,12 +577,14 @@ pub(crate) mod tests {
 		source_latest_generated_nonce: MessageNonce,
 		source_latest_confirmed_received_nonce: MessageNonce,
 		submitted_messages_receiving_proofs: Vec<TestMessagesReceivingProof>,
+		target_header_submission_cost: TestSourceChainBalance,
 		is_target_fails: bool,
 		is_target_reconnected: bool,
 		target_state: SourceClientState<TestMessageLane>,
 		target_latest_received_nonce: MessageNonce,
 		target_latest_confirmed_received_nonce: MessageNonce,
 		submitted_messages_proofs: Vec<TestMessagesProof>,
+		source_header_submission_cost: TestSourceChainBalance,
 		target_to_source_header_required: Option<TestTargetHeaderId>,
 		target_to_source_header_requirements: Vec<TestTargetHeaderId>,
 		source_to_target_header_required: Option<TestSourceHeaderId>,
@@ -553,6 +622,8 @@ pub(crate) mod tests {
 
 	#[async_trait]
 	impl SourceClient<TestMessageLane> for TestSourceClient {
+		type TransactionTracker = TestTransactionTracker;
+
 		async fn state(&self) -> Result<SourceClientState<TestMessageLane>, TestError> {
 			let mut data = self.data.lock();
 			(self.tick)(&mut *data);
@@ -632,7 +703,7 @@ pub(crate) mod tests {
 			&self,
 			_generated_at_block: TargetHeaderIdOf<TestMessageLane>,
 			proof: TestMessagesReceivingProof,
-		) -> Result<(), TestError> {
+		) -> Result<Self::TransactionTracker, TestError> {
 			let mut data = self.data.lock();
 			(self.tick)(&mut *data);
 			data.source_state.best_self =
@@ -640,7 +711,7 @@ pub(crate) mod tests {
 			data.source_state.best_finalized_self = data.source_state.best_self;
 			data.submitted_messages_receiving_proofs.push(proof);
 			data.source_latest_confirmed_received_nonce = proof;
-			Ok(())
+			Ok(TestTransactionTracker::default())
 		}
 
 		async fn require_target_header_on_source(&self, id: TargetHeaderIdOf<TestMessageLane>) {
@@ -653,6 +724,16 @@ pub(crate) mod tests {
 		async fn estimate_confirmation_transaction(&self) -> TestSourceChainBalance {
 			CONFIRMATION_TRANSACTION_COST
 		}
+
+		async fn estimate_target_header_submission(&self) -> TestSourceChainBalance {
+			self.data.lock().target_header_submission_cost
+		}
+	}
+
+	impl TestSourceClient {
+		pub fn set_target_header_submission_cost(&self, cost: TestSourceChainBalance) {
+			self.data.lock().target_header_submission_cost = cost;
+		}
 	}
 
 	#[derive(Clone)]
@@ -686,6 +767,8 @@ pub(crate) mod tests {
 
 	#[async_trait]
 	impl TargetClient<TestMessageLane> for TestTargetClient {
+		type TransactionTracker = TestTransactionTracker;
+
 		async fn state(&self) -> Result<TargetClientState<TestMessageLane>, TestError> {
 			let mut data = self.data.lock();
 			(self.tick)(&mut *data);
@@ -721,6 +804,13 @@ pub(crate) mod tests {
 			))
 		}
 
+		async fn is_delivery_slot_available(
+			&self,
+			id: TargetHeaderIdOf<TestMessageLane>,
+		) -> Result<(TargetHeaderIdOf<TestMessageLane>, bool), TestError> {
+			Ok((id, true))
+		}
+
 		async fn latest_confirmed_received_nonce(
 			&self,
 			id: TargetHeaderIdOf<TestMessageLane>,
@@ -745,7 +835,7 @@ pub(crate) mod tests {
 			_generated_at_header: SourceHeaderIdOf<TestMessageLane>,
 			nonces: RangeInclusive<MessageNonce>,
 			proof: TestMessagesProof,
-		) -> Result<RangeInclusive<MessageNonce>, TestError> {
+		) -> Result<NoncesSubmitArtifacts<TestTransactionTracker>, TestError> {
 			let mut data = self.data.lock();
 			(self.tick)(&mut *data);
 			if data.is_target_fails {
@@ -760,7 +850,7 @@ pub(crate) mod tests {
 					target_latest_confirmed_received_nonce;
 			}
 			data.submitted_messages_proofs.push(proof);
-			Ok(nonces)
+			Ok(NoncesSubmitArtifacts { nonces, tx_tracker: TestTransactionTracker::default() })
 		}
 
 		async fn require_source_header_on_target(&self, id: SourceHeaderIdOf<TestMessageLane>) {
@@ -781,6 +871,18 @@ pub(crate) mod tests {
 				total_dispatch_weight +
 				total_size as TestSourceChainBalance)
 		}
+
+		async fn estimate_source_header_submission_in_source_tokens(
+			&self,
+		) -> Result<TestSourceChainBalance, TestError> {
+			Ok(self.data.lock().source_header_submission_cost)
+		}
+	}
+
+	impl TestTargetClient {
+		pub fn set_source_header_submission_cost(&self, cost: TestSourceChainBalance) {
+			self.data.lock().source_header_submission_cost = cost;
+		}
 	}
 
 	fn run_loop_test(
@@ -806,7 +908,9 @@ pub(crate) mod tests {
 						max_unconfirmed_nonces_at_target: 4,
 						max_messages_in_single_batch: 4,
 						max_messages_weight_in_single_batch: 4,
+						max_inline_dispatch_weight: Weight::MAX,
 						max_messages_size_in_single_batch: 4,
+						delivery_slot_grace_period: Duration::from_secs(0),
 						relayer_mode: RelayerMode::Altruistic,
 					},
 				},
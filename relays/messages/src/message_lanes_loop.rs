@@ -0,0 +1,790 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Multi-lane message delivery loop. Designed to work with messages pallet.
+//!
+//! Single relay instance delivers messages of multiple lanes in single direction. All lanes
+//! are served using the same source and target clients (and connections). States of both
+//! clients are read once and then shared by all lanes. Message delivery transactions of
+//! different lanes are submitted one-by-one, so lanes aren't competing for the same
+//! relayer account nonce. The order in which lanes are served is selected by the
+//! [`LaneSchedulingStrategy`].
+
+use crate::{
+	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
+	message_lane_loop::{
+		metrics_prefix as lane_metrics_prefix, MessageDeliveryParams, MessageDetailsMap,
+		MessageProofParameters, NoncesSubmitArtifacts, SourceClient, SourceClientState,
+		TargetClient, TargetClientState,
+	},
+	message_race_delivery::run as run_message_delivery_race,
+	message_race_receiving::run as run_message_receiving_race,
+	metrics::MessageLaneLoopMetrics,
+};
+
+use async_trait::async_trait;
+use bp_messages::{LaneId, MessageNonce, UnrewardedRelayersState, Weight};
+use futures::{
+	channel::{mpsc::unbounded, oneshot},
+	future::FutureExt,
+	stream::{FuturesUnordered, StreamExt},
+};
+use num_traits::{SaturatingAdd, Zero};
+use parking_lot::Mutex;
+use relay_utils::{
+	interval,
+	metrics::{GlobalMetrics, MetricsParams},
+	process_future_result,
+	relay_loop::Client as RelayClient,
+	retry_backoff, FailedClient,
+};
+use std::{collections::BTreeMap, future::Future, ops::RangeInclusive, sync::Arc, time::Duration};
+
+/// Multi-lane message loop configuration params.
+#[derive(Debug, Clone)]
+pub struct Params {
+	/// Ids of lanes this loop is servicing.
+	pub lanes: Vec<LaneId>,
+	/// Interval at which we ask target node about its updates.
+	pub source_tick: Duration,
+	/// Interval at which we ask target node about its updates.
+	pub target_tick: Duration,
+	/// Delay between moments when connection error happens and our reconnect attempt.
+	pub reconnect_delay: Duration,
+	/// The loop will auto-restart if there has been no updates during this period.
+	pub stall_timeout: Duration,
+	/// Message delivery race parameters (shared by all lanes).
+	pub delivery_params: MessageDeliveryParams,
+	/// Strategy that is used to select lane which delivery transaction is submitted next.
+	pub scheduling: LaneSchedulingStrategy,
+}
+
+/// Strategy that is used to select lane, which delivery transaction is submitted next.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LaneSchedulingStrategy {
+	/// Lanes are served in round-robin order. The lane that has been waiting for its turn
+	/// for the longest time is served first.
+	RoundRobin,
+	/// The lane with maximal cumulative reward of queued (not yet delivered) messages is served
+	/// first. Lanes with the same reward are served in round-robin order.
+	QueuedReward,
+}
+
+/// Source client that is able to serve multiple lanes over the same connection.
+///
+/// The `SourceClient::state` of the client must be the same for all lanes.
+pub trait MultiLaneSourceClient<P: MessageLane>: SourceClient<P> {
+	/// Returns client of given lane, which is sharing connection with this client.
+	fn lane_client(&self, lane: LaneId) -> Self;
+}
+
+/// Target client that is able to serve multiple lanes over the same connection.
+///
+/// The `TargetClient::state` of the client must be the same for all lanes.
+pub trait MultiLaneTargetClient<P: MessageLane>: TargetClient<P> {
+	/// Returns client of given lane, which is sharing connection with this client.
+	fn lane_client(&self, lane: LaneId) -> Self;
+}
+
+/// Return prefix that will be used by default to expose Prometheus metrics of the multi-lane
+/// message loop. Per-lane metrics are exposed using the
+/// [`crate::message_lane_loop::metrics_prefix`] prefix.
+///
+/// If the loop is serving a single lane, the prefix is the same as the prefix of the
+/// single-lane loop, so existing dashboards keep working.
+pub fn metrics_prefix<P: MessageLane>(lanes: &[LaneId]) -> String {
+	match lanes {
+		[lane] => lane_metrics_prefix::<P>(lane),
+		_ => format!("{}_to_{}_MessageLanes", P::SOURCE_NAME, P::TARGET_NAME),
+	}
+}
+
+/// Run multi-lane message service loop.
+pub async fn run<P: MessageLane>(
+	params: Params,
+	source_client: impl MultiLaneSourceClient<P>,
+	target_client: impl MultiLaneTargetClient<P>,
+	metrics_params: MetricsParams,
+	exit_signal: impl Future<Output = ()> + Send + 'static,
+) -> anyhow::Result<()> {
+	let exit_signal = exit_signal.shared();
+	let lanes = params.lanes.clone();
+	relay_utils::relay_loop(source_client, target_client)
+		.reconnect_delay(params.reconnect_delay)
+		.with_metrics(Some(metrics_prefix::<P>(&lanes)), metrics_params)
+		.loop_metric(|registry, _| {
+			lanes
+				.iter()
+				.map(|lane| {
+					let lane_prefix = lane_metrics_prefix::<P>(lane);
+					Ok((*lane, MessageLaneLoopMetrics::new(registry, Some(&lane_prefix))?))
+				})
+				.collect::<Result<BTreeMap<_, _>, _>>()
+		})?
+		.standalone_metric(|registry, prefix| GlobalMetrics::new(registry, prefix))?
+		.expose()
+		.await?
+		.run(metrics_prefix::<P>(&params.lanes), move |source_client, target_client, metrics| {
+			run_until_connection_lost(
+				params.clone(),
+				source_client,
+				target_client,
+				metrics.unwrap_or_default(),
+				exit_signal.clone(),
+			)
+		})
+		.await
+}
+
+/// Senders of client state updates to races of the single lane.
+struct LaneStateSenders<P: MessageLane> {
+	delivery_source: futures::channel::mpsc::UnboundedSender<SourceClientState<P>>,
+	delivery_target: futures::channel::mpsc::UnboundedSender<TargetClientState<P>>,
+	receiving_source: futures::channel::mpsc::UnboundedSender<SourceClientState<P>>,
+	receiving_target: futures::channel::mpsc::UnboundedSender<TargetClientState<P>>,
+}
+
+/// Run multi-lane message delivery loop until connection with target or source node is lost, or
+/// exit signal is received.
+async fn run_until_connection_lost<
+	P: MessageLane,
+	SC: MultiLaneSourceClient<P>,
+	TC: MultiLaneTargetClient<P>,
+>(
+	params: Params,
+	source_client: SC,
+	target_client: TC,
+	metrics_msg: BTreeMap<LaneId, MessageLaneLoopMetrics>,
+	exit_signal: impl Future<Output = ()>,
+) -> Result<(), FailedClient> {
+	let mut source_retry_backoff = retry_backoff();
+	let mut source_client_is_online = false;
+	let mut source_state_required = true;
+	let source_state = source_client.state().fuse();
+	let source_go_offline_future = futures::future::Fuse::terminated();
+	let source_tick_stream = interval(params.source_tick).fuse();
+
+	let mut target_retry_backoff = retry_backoff();
+	let mut target_client_is_online = false;
+	let mut target_state_required = true;
+	let target_state = target_client.state().fuse();
+	let target_go_offline_future = futures::future::Fuse::terminated();
+	let target_tick_stream = interval(params.target_tick).fuse();
+
+	let scheduler = DeliveryScheduler::new(params.scheduling);
+	let mut lanes_state_senders = Vec::with_capacity(params.lanes.len());
+	let mut race_loops = FuturesUnordered::new();
+	for lane in &params.lanes {
+		let lane_source_client = ScheduledSourceClient {
+			client: source_client.lane_client(*lane),
+			lane: *lane,
+			scheduler: scheduler.clone(),
+		};
+		let lane_target_client = ScheduledTargetClient {
+			client: target_client.lane_client(*lane),
+			lane: *lane,
+			scheduler: scheduler.clone(),
+		};
+		let lane_metrics_msg = metrics_msg.get(lane).cloned();
+
+		let (
+			(delivery_source_state_sender, delivery_source_state_receiver),
+			(delivery_target_state_sender, delivery_target_state_receiver),
+		) = (unbounded(), unbounded());
+		race_loops.push(
+			run_message_delivery_race(
+				lane_source_client.clone(),
+				delivery_source_state_receiver,
+				lane_target_client.clone(),
+				delivery_target_state_receiver,
+				params.stall_timeout,
+				lane_metrics_msg.clone(),
+				params.delivery_params.clone(),
+			)
+			.boxed(),
+		);
+
+		let (
+			(receiving_source_state_sender, receiving_source_state_receiver),
+			(receiving_target_state_sender, receiving_target_state_receiver),
+		) = (unbounded(), unbounded());
+		race_loops.push(
+			run_message_receiving_race(
+				lane_source_client,
+				receiving_source_state_receiver,
+				lane_target_client,
+				receiving_target_state_receiver,
+				params.stall_timeout,
+				lane_metrics_msg,
+			)
+			.boxed(),
+		);
+
+		lanes_state_senders.push(LaneStateSenders::<P> {
+			delivery_source: delivery_source_state_sender,
+			delivery_target: delivery_target_state_sender,
+			receiving_source: receiving_source_state_sender,
+			receiving_target: receiving_target_state_sender,
+		});
+	}
+
+	let exit_signal = exit_signal.fuse();
+
+	futures::pin_mut!(
+		source_state,
+		source_go_offline_future,
+		source_tick_stream,
+		target_state,
+		target_go_offline_future,
+		target_tick_stream,
+		exit_signal
+	);
+
+	loop {
+		futures::select! {
+			new_source_state = source_state => {
+				source_state_required = false;
+
+				source_client_is_online = process_future_result(
+					new_source_state,
+					&mut source_retry_backoff,
+					|new_source_state| {
+						log::debug!(
+							target: "bridge",
+							"Received state from {} node: {:?}",
+							P::SOURCE_NAME,
+							new_source_state,
+						);
+						for lane_state_senders in &lanes_state_senders {
+							let _ = lane_state_senders.delivery_source.unbounded_send(new_source_state.clone());
+							let _ = lane_state_senders.receiving_source.unbounded_send(new_source_state.clone());
+						}
+
+						for lane_metrics_msg in metrics_msg.values() {
+							lane_metrics_msg.update_source_state::<P>(new_source_state.clone());
+						}
+					},
+					&mut source_go_offline_future,
+					async_std::task::sleep,
+					|| format!("Error retrieving state from {} node", P::SOURCE_NAME),
+				).fail_if_connection_error(FailedClient::Source)?;
+			},
+			_ = source_go_offline_future => {
+				source_client_is_online = true;
+			},
+			_ = source_tick_stream.next() => {
+				source_state_required = true;
+			},
+			new_target_state = target_state => {
+				target_state_required = false;
+
+				target_client_is_online = process_future_result(
+					new_target_state,
+					&mut target_retry_backoff,
+					|new_target_state| {
+						log::debug!(
+							target: "bridge",
+							"Received state from {} node: {:?}",
+							P::TARGET_NAME,
+							new_target_state,
+						);
+						for lane_state_senders in &lanes_state_senders {
+							let _ = lane_state_senders.delivery_target.unbounded_send(new_target_state.clone());
+							let _ = lane_state_senders.receiving_target.unbounded_send(new_target_state.clone());
+						}
+
+						for lane_metrics_msg in metrics_msg.values() {
+							lane_metrics_msg.update_target_state::<P>(new_target_state.clone());
+						}
+					},
+					&mut target_go_offline_future,
+					async_std::task::sleep,
+					|| format!("Error retrieving state from {} node", P::TARGET_NAME),
+				).fail_if_connection_error(FailedClient::Target)?;
+			},
+			_ = target_go_offline_future => {
+				target_client_is_online = true;
+			},
+			_ = target_tick_stream.next() => {
+				target_state_required = true;
+			},
+
+			race_error = race_loops.select_next_some() => {
+				match race_error {
+					Ok(_) => unreachable!("only ends with error; qed"),
+					Err(err) => return Err(err),
+				}
+			},
+
+			() = exit_signal => {
+				return Ok(());
+			}
+		}
+
+		if source_client_is_online && source_state_required {
+			log::debug!(target: "bridge", "Asking {} node about its state", P::SOURCE_NAME);
+			source_state.set(source_client.state().fuse());
+			source_client_is_online = false;
+		}
+
+		if target_client_is_online && target_state_required {
+			log::debug!(target: "bridge", "Asking {} node about its state", P::TARGET_NAME);
+			target_state.set(target_client.state().fuse());
+			target_client_is_online = false;
+		}
+	}
+}
+
+/// Scheduler of message delivery transactions of multiple lanes.
+///
+/// Only one lane may submit delivery transaction at a time. Other lanes are waiting for
+/// their turn, which is selected using the [`LaneSchedulingStrategy`].
+///
+/// Cloning only clones references.
+#[derive(Clone)]
+struct DeliveryScheduler<Balance> {
+	/// Scheduling strategy.
+	strategy: LaneSchedulingStrategy,
+	/// Shared scheduler state.
+	state: Arc<Mutex<DeliverySchedulerState<Balance>>>,
+}
+
+/// State of the delivery scheduler.
+struct DeliverySchedulerState<Balance> {
+	/// True if some lane is currently submitting delivery transaction.
+	is_busy: bool,
+	/// Lanes that are waiting for their turn.
+	pending: Vec<(LaneId, oneshot::Sender<()>)>,
+	/// Sequence number of the latest delivery transaction of every lane.
+	last_served: BTreeMap<LaneId, u64>,
+	/// Next sequence number.
+	next_sequence: u64,
+	/// Rewards of queued (not yet delivered) messages of every lane.
+	queued_rewards: BTreeMap<LaneId, BTreeMap<MessageNonce, Balance>>,
+}
+
+/// Exclusive right to submit delivery transaction. The right is passed to the next lane
+/// when the slot is dropped.
+struct DeliverySlot<Balance: Copy + PartialOrd + SaturatingAdd + Zero> {
+	lane: LaneId,
+	scheduler: DeliveryScheduler<Balance>,
+}
+
+impl<Balance: Copy + PartialOrd + SaturatingAdd + Zero> DeliveryScheduler<Balance> {
+	/// Create new delivery scheduler.
+	fn new(strategy: LaneSchedulingStrategy) -> Self {
+		DeliveryScheduler {
+			strategy,
+			state: Arc::new(Mutex::new(DeliverySchedulerState {
+				is_busy: false,
+				pending: Vec::new(),
+				last_served: BTreeMap::new(),
+				next_sequence: 0,
+				queued_rewards: BTreeMap::new(),
+			})),
+		}
+	}
+
+	/// Wait until given lane is allowed to submit its delivery transaction.
+	async fn acquire(&self, lane: LaneId) -> DeliverySlot<Balance> {
+		let turn = {
+			let mut state = self.state.lock();
+			if state.is_busy {
+				let (sender, receiver) = oneshot::channel();
+				state.pending.push((lane, sender));
+				Some(receiver)
+			} else {
+				state.is_busy = true;
+				None
+			}
+		};
+
+		if let Some(turn) = turn {
+			// sender is only dropped along with the scheduler => let's proceed in this case
+			let _ = turn.await;
+		}
+
+		DeliverySlot { lane, scheduler: self.clone() }
+	}
+
+	/// Pass the right to submit delivery transaction to the next lane.
+	fn release(&self, lane: LaneId) {
+		let mut state = self.state.lock();
+		let sequence = state.next_sequence;
+		state.next_sequence += 1;
+		state.last_served.insert(lane, sequence);
+
+		while let Some(next_index) = self.select_next(&state) {
+			let (_, sender) = state.pending.remove(next_index);
+			// if receiver has been dropped, let's select another lane
+			if sender.send(()).is_ok() {
+				return
+			}
+		}
+
+		state.is_busy = false;
+	}
+
+	/// Select index of the pending lane that will be served next.
+	fn select_next(&self, state: &DeliverySchedulerState<Balance>) -> Option<usize> {
+		let mut best: Option<(usize, Balance, Option<u64>)> = None;
+		for (index, (lane, _)) in state.pending.iter().enumerate() {
+			let queued_reward = match self.strategy {
+				LaneSchedulingStrategy::RoundRobin => Zero::zero(),
+				LaneSchedulingStrategy::QueuedReward => state.queued_reward(lane),
+			};
+			// lanes that have never been served go first
+			let last_served = state.last_served.get(lane).cloned();
+			let is_better = match best {
+				None => true,
+				Some((_, best_queued_reward, best_last_served)) =>
+					queued_reward > best_queued_reward ||
+						(queued_reward == best_queued_reward && last_served < best_last_served),
+			};
+			if is_better {
+				best = Some((index, queued_reward, last_served));
+			}
+		}
+
+		best.map(|(index, _, _)| index)
+	}
+
+	/// Remember rewards of messages that are queued at given lane.
+	fn note_queued_messages(&self, lane: LaneId, messages: &MessageDetailsMap<Balance>) {
+		let mut state = self.state.lock();
+		let queued_rewards = state.queued_rewards.entry(lane).or_default();
+		for (nonce, details) in messages {
+			queued_rewards.insert(*nonce, details.reward);
+		}
+	}
+
+	/// Forget rewards of messages that have been delivered over given lane.
+	fn note_delivered_messages(&self, lane: LaneId, nonces: &RangeInclusive<MessageNonce>) {
+		let mut state = self.state.lock();
+		if let Some(queued_rewards) = state.queued_rewards.get_mut(&lane) {
+			*queued_rewards = queued_rewards.split_off(&(*nonces.end() + 1));
+		}
+	}
+}
+
+impl<Balance: Copy + SaturatingAdd + Zero> DeliverySchedulerState<Balance> {
+	/// Returns cumulative reward of messages that are queued at given lane.
+	fn queued_reward(&self, lane: &LaneId) -> Balance {
+		self.queued_rewards
+			.get(lane)
+			.map(|queued_rewards| {
+				queued_rewards
+					.values()
+					.fold(Zero::zero(), |total: Balance, reward| total.saturating_add(reward))
+			})
+			.unwrap_or_else(Zero::zero)
+	}
+}
+
+impl<Balance: Copy + PartialOrd + SaturatingAdd + Zero> Drop for DeliverySlot<Balance> {
+	fn drop(&mut self) {
+		self.scheduler.release(self.lane);
+	}
+}
+
+/// Source client of the single lane that reports queued messages to the delivery scheduler.
+#[derive(Clone)]
+struct ScheduledSourceClient<P: MessageLane, SC> {
+	client: SC,
+	lane: LaneId,
+	scheduler: DeliveryScheduler<P::SourceChainBalance>,
+}
+
+#[async_trait]
+impl<P: MessageLane, SC: SourceClient<P>> RelayClient for ScheduledSourceClient<P, SC> {
+	type Error = SC::Error;
+
+	async fn reconnect(&mut self) -> Result<(), Self::Error> {
+		self.client.reconnect().await
+	}
+}
+
+#[async_trait]
+impl<P: MessageLane, SC: SourceClient<P>> SourceClient<P> for ScheduledSourceClient<P, SC> {
+	type TransactionTracker = SC::TransactionTracker;
+
+	async fn state(&self) -> Result<SourceClientState<P>, Self::Error> {
+		self.client.state().await
+	}
+
+	async fn latest_generated_nonce(
+		&self,
+		id: SourceHeaderIdOf<P>,
+	) -> Result<(SourceHeaderIdOf<P>, MessageNonce), Self::Error> {
+		self.client.latest_generated_nonce(id).await
+	}
+
+	async fn latest_confirmed_received_nonce(
+		&self,
+		id: SourceHeaderIdOf<P>,
+	) -> Result<(SourceHeaderIdOf<P>, MessageNonce), Self::Error> {
+		self.client.latest_confirmed_received_nonce(id).await
+	}
+
+	async fn generated_message_details(
+		&self,
+		id: SourceHeaderIdOf<P>,
+		nonces: RangeInclusive<MessageNonce>,
+	) -> Result<MessageDetailsMap<P::SourceChainBalance>, Self::Error> {
+		let messages = self.client.generated_message_details(id, nonces).await?;
+		self.scheduler.note_queued_messages(self.lane, &messages);
+		Ok(messages)
+	}
+
+	async fn prove_messages(
+		&self,
+		id: SourceHeaderIdOf<P>,
+		nonces: RangeInclusive<MessageNonce>,
+		proof_parameters: MessageProofParameters,
+	) -> Result<(SourceHeaderIdOf<P>, RangeInclusive<MessageNonce>, P::MessagesProof), Self::Error>
+	{
+		self.client.prove_messages(id, nonces, proof_parameters).await
+	}
+
+	async fn submit_messages_receiving_proof(
+		&self,
+		generated_at_block: TargetHeaderIdOf<P>,
+		proof: P::MessagesReceivingProof,
+	) -> Result<Self::TransactionTracker, Self::Error> {
+		self.client.submit_messages_receiving_proof(generated_at_block, proof).await
+	}
+
+	async fn require_target_header_on_source(&self, id: TargetHeaderIdOf<P>) {
+		self.client.require_target_header_on_source(id).await
+	}
+
+	async fn estimate_confirmation_transaction(&self) -> P::SourceChainBalance {
+		self.client.estimate_confirmation_transaction().await
+	}
+
+	async fn estimate_target_header_submission(&self) -> P::SourceChainBalance {
+		self.client.estimate_target_header_submission().await
+	}
+}
+
+/// Target client of the single lane that waits for its turn before submitting delivery
+/// transaction.
+#[derive(Clone)]
+struct ScheduledTargetClient<P: MessageLane, TC> {
+	client: TC,
+	lane: LaneId,
+	scheduler: DeliveryScheduler<P::SourceChainBalance>,
+}
+
+#[async_trait]
+impl<P: MessageLane, TC: TargetClient<P>> RelayClient for ScheduledTargetClient<P, TC> {
+	type Error = TC::Error;
+
+	async fn reconnect(&mut self) -> Result<(), Self::Error> {
+		self.client.reconnect().await
+	}
+}
+
+#[async_trait]
+impl<P: MessageLane, TC: TargetClient<P>> TargetClient<P> for ScheduledTargetClient<P, TC> {
+	type TransactionTracker = TC::TransactionTracker;
+
+	async fn state(&self) -> Result<TargetClientState<P>, Self::Error> {
+		self.client.state().await
+	}
+
+	async fn latest_received_nonce(
+		&self,
+		id: TargetHeaderIdOf<P>,
+	) -> Result<(TargetHeaderIdOf<P>, MessageNonce), Self::Error> {
+		self.client.latest_received_nonce(id).await
+	}
+
+	async fn latest_confirmed_received_nonce(
+		&self,
+		id: TargetHeaderIdOf<P>,
+	) -> Result<(TargetHeaderIdOf<P>, MessageNonce), Self::Error> {
+		self.client.latest_confirmed_received_nonce(id).await
+	}
+
+	async fn unrewarded_relayers_state(
+		&self,
+		id: TargetHeaderIdOf<P>,
+	) -> Result<(TargetHeaderIdOf<P>, UnrewardedRelayersState), Self::Error> {
+		self.client.unrewarded_relayers_state(id).await
+	}
+
+	async fn is_delivery_slot_available(
+		&self,
+		id: TargetHeaderIdOf<P>,
+	) -> Result<(TargetHeaderIdOf<P>, bool), Self::Error> {
+		self.client.is_delivery_slot_available(id).await
+	}
+
+	async fn prove_messages_receiving(
+		&self,
+		id: TargetHeaderIdOf<P>,
+	) -> Result<(TargetHeaderIdOf<P>, P::MessagesReceivingProof), Self::Error> {
+		self.client.prove_messages_receiving(id).await
+	}
+
+	async fn submit_messages_proof(
+		&self,
+		generated_at_header: SourceHeaderIdOf<P>,
+		nonces: RangeInclusive<MessageNonce>,
+		proof: P::MessagesProof,
+	) -> Result<NoncesSubmitArtifacts<Self::TransactionTracker>, Self::Error> {
+		let _slot = self.scheduler.acquire(self.lane).await;
+		let artifacts =
+			self.client.submit_messages_proof(generated_at_header, nonces, proof).await?;
+		self.scheduler.note_delivered_messages(self.lane, &artifacts.nonces);
+		Ok(artifacts)
+	}
+
+	async fn require_source_header_on_target(&self, id: SourceHeaderIdOf<P>) {
+		self.client.require_source_header_on_target(id).await
+	}
+
+	async fn estimate_delivery_transaction_in_source_tokens(
+		&self,
+		nonces: RangeInclusive<MessageNonce>,
+		total_prepaid_nonces: MessageNonce,
+		total_dispatch_weight: Weight,
+		total_size: u32,
+	) -> Result<P::SourceChainBalance, Self::Error> {
+		self.client
+			.estimate_delivery_transaction_in_source_tokens(
+				nonces,
+				total_prepaid_nonces,
+				total_dispatch_weight,
+				total_size,
+			)
+			.await
+	}
+
+	async fn estimate_source_header_submission_in_source_tokens(
+		&self,
+	) -> Result<P::SourceChainBalance, Self::Error> {
+		self.client.estimate_source_header_submission_in_source_tokens().await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::message_lane_loop::MessageDetails;
+	use bp_runtime::messages::DispatchFeePayment;
+
+	const LANE_A: LaneId = [0, 0, 0, 1];
+	const LANE_B: LaneId = [0, 0, 0, 2];
+	const LANE_C: LaneId = [0, 0, 0, 3];
+
+	fn message_details(reward: u64) -> MessageDetails<u64> {
+		MessageDetails {
+			dispatch_weight: 1,
+			size: 1,
+			reward,
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+		}
+	}
+
+	fn wait_for_turn(scheduler: &DeliveryScheduler<u64>, lane: LaneId) -> oneshot::Receiver<()> {
+		let (sender, receiver) = oneshot::channel();
+		scheduler.state.lock().pending.push((lane, sender));
+		receiver
+	}
+
+	#[async_std::test]
+	async fn first_lane_is_served_immediately() {
+		let scheduler = DeliveryScheduler::<u64>::new(LaneSchedulingStrategy::RoundRobin);
+		let slot = scheduler.acquire(LANE_A).await;
+		assert!(scheduler.state.lock().is_busy);
+		drop(slot);
+		assert!(!scheduler.state.lock().is_busy);
+	}
+
+	#[async_std::test]
+	async fn lanes_are_served_in_round_robin_order() {
+		let scheduler = DeliveryScheduler::<u64>::new(LaneSchedulingStrategy::RoundRobin);
+
+		// lane B has been served before lane C
+		drop(scheduler.acquire(LANE_B).await);
+		drop(scheduler.acquire(LANE_C).await);
+
+		// lane A is submitting transaction and lanes C and B are waiting
+		let slot = scheduler.acquire(LANE_A).await;
+		let mut lane_c_turn = wait_for_turn(&scheduler, LANE_C);
+		let mut lane_b_turn = wait_for_turn(&scheduler, LANE_B);
+
+		// => lane B is served first
+		drop(slot);
+		assert_eq!(lane_b_turn.try_recv(), Ok(Some(())));
+		assert_eq!(lane_c_turn.try_recv(), Ok(None));
+
+		// => lane C is served next
+		scheduler.release(LANE_B);
+		assert_eq!(lane_c_turn.try_recv(), Ok(Some(())));
+	}
+
+	#[async_std::test]
+	async fn lane_with_maximal_queued_reward_is_served_first() {
+		let scheduler = DeliveryScheduler::<u64>::new(LaneSchedulingStrategy::QueuedReward);
+		scheduler.note_queued_messages(
+			LANE_B,
+			&vec![(1, message_details(10)), (2, message_details(10))].into_iter().collect(),
+		);
+		scheduler
+			.note_queued_messages(LANE_C, &vec![(1, message_details(15))].into_iter().collect());
+
+		let slot = scheduler.acquire(LANE_A).await;
+		let mut lane_b_turn = wait_for_turn(&scheduler, LANE_B);
+		let mut lane_c_turn = wait_for_turn(&scheduler, LANE_C);
+
+		// lane B has queued reward of 20 and lane C has queued reward of 15 => lane B is served
+		drop(slot);
+		assert_eq!(lane_b_turn.try_recv(), Ok(Some(())));
+		assert_eq!(lane_c_turn.try_recv(), Ok(None));
+	}
+
+	#[test]
+	fn delivered_messages_are_removed_from_queue() {
+		let scheduler = DeliveryScheduler::<u64>::new(LaneSchedulingStrategy::QueuedReward);
+		scheduler.note_queued_messages(
+			LANE_A,
+			&vec![(1, message_details(10)), (2, message_details(20)), (3, message_details(30))]
+				.into_iter()
+				.collect(),
+		);
+		assert_eq!(scheduler.state.lock().queued_reward(&LANE_A), 60);
+
+		scheduler.note_delivered_messages(LANE_A, &(1..=2));
+		assert_eq!(scheduler.state.lock().queued_reward(&LANE_A), 30);
+	}
+
+	#[test]
+	fn single_lane_loop_uses_lane_metrics_prefix() {
+		use crate::message_lane_loop::tests::TestMessageLane;
+
+		assert_eq!(
+			metrics_prefix::<TestMessageLane>(&[LANE_A]),
+			lane_metrics_prefix::<TestMessageLane>(&LANE_A),
+		);
+		assert_eq!(
+			metrics_prefix::<TestMessageLane>(&[LANE_A, LANE_B]),
+			"TestSource_to_TestTarget_MessageLanes",
+		);
+	}
+}
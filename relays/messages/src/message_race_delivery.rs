@@ -16,8 +16,8 @@
 use crate::{
 	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
 	message_lane_loop::{
-		MessageDeliveryParams, MessageDetailsMap, MessageProofParameters, RelayerMode,
-		SourceClient as MessageLaneSourceClient, SourceClientState,
+		MessageDeliveryParams, MessageDetailsMap, MessageProofParameters, NoncesSubmitArtifacts,
+		RelayerMode, SourceClient as MessageLaneSourceClient, SourceClientState,
 		TargetClient as MessageLaneTargetClient, TargetClientState,
 	},
 	message_race_loop::{
@@ -38,7 +38,7 @@ use std::{
 	collections::VecDeque,
 	marker::PhantomData,
 	ops::{Range, RangeInclusive},
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 /// Run message delivery race.
@@ -73,8 +73,12 @@ pub async fn run<P: MessageLane>(
 			max_unconfirmed_nonces_at_target: params.max_unconfirmed_nonces_at_target,
 			max_messages_in_single_batch: params.max_messages_in_single_batch,
 			max_messages_weight_in_single_batch: params.max_messages_weight_in_single_batch,
+			max_inline_dispatch_weight: params.max_inline_dispatch_weight,
 			max_messages_size_in_single_batch: params.max_messages_size_in_single_batch,
+			delivery_slot_grace_period: params.delivery_slot_grace_period,
 			relayer_mode: params.relayer_mode,
+			headers_relay_cost: None,
+			delivery_slot_owner_stalled_since: None,
 			latest_confirmed_nonces_at_source: VecDeque::new(),
 			target_nonces: None,
 			strategy: BasicStrategy::new(),
@@ -177,6 +181,7 @@ where
 {
 	type Error = C::Error;
 	type TargetNoncesData = DeliveryRaceTargetNoncesData;
+	type TransactionTracker = C::TransactionTracker;
 
 	async fn require_source_header(&self, id: SourceHeaderIdOf<P>) {
 		self.client.require_source_header_on_target(id).await
@@ -193,6 +198,8 @@ where
 			self.client.latest_confirmed_received_nonce(at_block).await?;
 		let (at_block, unrewarded_relayers) =
 			self.client.unrewarded_relayers_state(at_block).await?;
+		let (at_block, is_delivery_slot_available) =
+			self.client.is_delivery_slot_available(at_block).await?;
 
 		if update_metrics {
 			if let Some(metrics_msg) = self.metrics_msg.as_ref() {
@@ -208,6 +215,7 @@ where
 				nonces_data: DeliveryRaceTargetNoncesData {
 					confirmed_nonce: latest_confirmed_nonce,
 					unrewarded_relayers,
+					is_delivery_slot_available,
 				},
 			},
 		))
@@ -218,7 +226,7 @@ where
 		generated_at_block: SourceHeaderIdOf<P>,
 		nonces: RangeInclusive<MessageNonce>,
 		proof: P::MessagesProof,
-	) -> Result<RangeInclusive<MessageNonce>, Self::Error> {
+	) -> Result<NoncesSubmitArtifacts<Self::TransactionTracker>, Self::Error> {
 		self.client.submit_messages_proof(generated_at_block, nonces, proof).await
 	}
 }
@@ -232,6 +240,9 @@ struct DeliveryRaceTargetNoncesData {
 	confirmed_nonce: MessageNonce,
 	/// State of the unrewarded relayers set at the target node.
 	unrewarded_relayers: UnrewardedRelayersState,
+	/// Whether our relayer may deliver messages at the target node, according to the delivery
+	/// slots schedule.
+	is_delivery_slot_available: bool,
 }
 
 /// Messages delivery strategy.
@@ -248,10 +259,23 @@ struct MessageDeliveryStrategy<P: MessageLane, SC, TC> {
 	max_messages_in_single_batch: MessageNonce,
 	/// Maximal cumulative messages weight in the single delivery transaction.
 	max_messages_weight_in_single_batch: Weight,
+	/// Maximal dispatch weight of the message that is dispatched during delivery.
+	max_inline_dispatch_weight: Weight,
 	/// Maximal messages size in the single delivery transaction.
 	max_messages_size_in_single_batch: u32,
+	/// Maximal time we're waiting for the delivery slot owner to deliver messages.
+	delivery_slot_grace_period: Duration,
 	/// Relayer operating mode.
 	relayer_mode: RelayerMode,
+	/// Estimated cost of header submissions (source header to the target chain and target header
+	/// to the source chain) that are required to deliver messages and confirmations.
+	///
+	/// Only estimated by the `Rational` relayer. `None` if the cost is not yet known.
+	headers_relay_cost: Option<P::SourceChainBalance>,
+	/// Time when we have first seen that the delivery slot is owned by other relayer and no
+	/// new messages have been delivered to the target since then. `None` if our relayer may
+	/// deliver messages.
+	delivery_slot_owner_stalled_since: Option<Instant>,
 	/// Latest confirmed nonces at the source client + the header id where we have first met this
 	/// nonce.
 	latest_confirmed_nonces_at_source: VecDeque<(SourceHeaderIdOf<P>, MessageNonce)>,
@@ -280,7 +304,11 @@ impl<P: MessageLane, SC, TC> std::fmt::Debug for MessageDeliveryStrategy<P, SC,
 			.field("max_unconfirmed_nonces_at_target", &self.max_unconfirmed_nonces_at_target)
 			.field("max_messages_in_single_batch", &self.max_messages_in_single_batch)
 			.field("max_messages_weight_in_single_batch", &self.max_messages_weight_in_single_batch)
+			.field("max_inline_dispatch_weight", &self.max_inline_dispatch_weight)
 			.field("max_messages_size_in_single_batch", &self.max_messages_size_in_single_batch)
+			.field("delivery_slot_grace_period", &self.delivery_slot_grace_period)
+			.field("headers_relay_cost", &self.headers_relay_cost)
+			.field("delivery_slot_owner_stalled_since", &self.delivery_slot_owner_stalled_since)
 			.field("latest_confirmed_nonces_at_source", &self.latest_confirmed_nonces_at_source)
 			.field("target_nonces", &self.target_nonces)
 			.field("strategy", &self.strategy)
@@ -289,14 +317,90 @@ impl<P: MessageLane, SC, TC> std::fmt::Debug for MessageDeliveryStrategy<P, SC,
 }
 
 impl<P: MessageLane, SC, TC> MessageDeliveryStrategy<P, SC, TC> {
-	/// Returns total weight of all undelivered messages.
+	/// Returns total dispatch weight of all undelivered messages that needs to be declared in
+	/// delivery transactions.
 	fn total_queued_dispatch_weight(&self) -> Weight {
+		let max_inline_dispatch_weight = self.max_inline_dispatch_weight;
 		self.strategy
 			.source_queue()
 			.iter()
-			.flat_map(|(_, range)| range.values().map(|details| details.dispatch_weight))
+			.flat_map(|(_, range)| {
+				range.values().map(move |details| {
+					declared_dispatch_weight(details, max_inline_dispatch_weight)
+				})
+			})
 			.fold(0, |total, weight| total.saturating_add(weight))
 	}
+
+	/// Returns total reward of all undelivered messages.
+	fn total_queued_reward(&self) -> P::SourceChainBalance {
+		self.strategy
+			.source_queue()
+			.iter()
+			.flat_map(|(_, range)| range.values().map(|details| details.reward))
+			.fold(P::SourceChainBalance::zero(), |total, reward| total.saturating_add(&reward))
+	}
+
+	/// Returns true if we want new source headers to be relayed to deliver queued messages.
+	///
+	/// Headers are relayed on demand and the relayer is paying for their submission. So when
+	/// headers relay is the dominant cost, the `Rational` relayer prefers to wait until rewards
+	/// of queued messages are able to cover it.
+	fn is_headers_relay_cost_covered(&self) -> bool {
+		let headers_relay_cost = match (self.relayer_mode, self.headers_relay_cost) {
+			(RelayerMode::Rational, Some(headers_relay_cost)) => headers_relay_cost,
+			_ => return true,
+		};
+
+		let total_queued_reward = self.total_queued_reward();
+		if total_queued_reward < headers_relay_cost {
+			log::debug!(
+				target: "bridge",
+				"Total reward of queued {} -> {} messages {:?} is less than the headers relay \
+				cost {:?}. Waiting for more messages",
+				MessageDeliveryRace::<P>::source_name(),
+				MessageDeliveryRace::<P>::target_name(),
+				total_queued_reward,
+				headers_relay_cost,
+			);
+
+			return false
+		}
+
+		true
+	}
+}
+
+impl<P, SC, TC> MessageDeliveryStrategy<P, SC, TC>
+where
+	P: MessageLane,
+	SC: MessageLaneSourceClient<P>,
+	TC: MessageLaneTargetClient<P>,
+{
+	/// Estimate cost of header submissions that are required to deliver messages and their
+	/// receiving confirmations.
+	///
+	/// We assume that every delivery transaction requires (at most) one source header to be
+	/// submitted to the target chain and that every confirmation transaction requires one target
+	/// header to be submitted to the source chain.
+	async fn estimate_headers_relay_cost(&self) -> Option<P::SourceChainBalance> {
+		let source_header_submission_cost = self
+			.lane_target_client
+			.estimate_source_header_submission_in_source_tokens()
+			.await
+			.map_err(|err| {
+				log::debug!(
+					target: "bridge",
+					"Failed to estimate {} header submission cost: {:?}",
+					MessageDeliveryRace::<P>::source_name(),
+					err,
+				);
+			})
+			.ok()?;
+		let target_header_submission_cost =
+			self.lane_source_client.estimate_target_header_submission().await;
+		Some(source_header_submission_cost.saturating_add(&target_header_submission_cost))
+	}
 }
 
 #[async_trait]
@@ -319,8 +423,10 @@ where
 		&self,
 		current_best: &SourceHeaderIdOf<P>,
 	) -> Option<SourceHeaderIdOf<P>> {
-		let header_required_for_messages_delivery =
-			self.strategy.required_source_header_at_target(current_best);
+		let header_required_for_messages_delivery = self
+			.strategy
+			.required_source_header_at_target(current_best)
+			.filter(|_| self.is_headers_relay_cost_covered());
 		let header_required_for_reward_confirmations_delivery =
 			self.latest_confirmed_nonces_at_source.back().map(|(id, _)| id.clone());
 		match (
@@ -364,6 +470,19 @@ where
 		nonces: TargetClientNonces<DeliveryRaceTargetNoncesData>,
 		race_state: &mut RaceState<SourceHeaderIdOf<P>, TargetHeaderIdOf<P>, P::MessagesProof>,
 	) {
+		// if the delivery slot is owned by other relayer, we're giving it some time to deliver
+		// messages. The countdown is restarted whenever new messages are delivered
+		let new_messages_delivered = self
+			.target_nonces
+			.as_ref()
+			.map(|target_nonces| nonces.latest_nonce > target_nonces.latest_nonce)
+			.unwrap_or(true);
+		self.delivery_slot_owner_stalled_since = match self.delivery_slot_owner_stalled_since {
+			_ if nonces.nonces_data.is_delivery_slot_available => None,
+			Some(stalled_since) if !new_messages_delivered => Some(stalled_since),
+			_ => Some(Instant::now()),
+		};
+
 		// best target nonces must always be ge than finalized target nonces
 		let mut target_nonces = self.target_nonces.take().unwrap_or_else(|| nonces.clone());
 		target_nonces.nonces_data = nonces.nonces_data.clone();
@@ -410,6 +529,12 @@ where
 		&mut self,
 		race_state: RaceState<SourceHeaderIdOf<P>, TargetHeaderIdOf<P>, P::MessagesProof>,
 	) -> Option<(RangeInclusive<MessageNonce>, Self::ProofParameters)> {
+		// headers relay cost may change over time, so let's refresh it before selecting nonces.
+		// The cost is also used to decide whether we need new headers for delivery
+		if self.relayer_mode == RelayerMode::Rational && !self.strategy.is_empty() {
+			self.headers_relay_cost = self.estimate_headers_relay_cost().await;
+		}
+
 		let best_finalized_source_header_id_at_best_target =
 			race_state.best_finalized_source_header_id_at_best_target.clone()?;
 		let latest_confirmed_nonce_at_source = self
@@ -420,6 +545,36 @@ where
 			.map(|(_, nonce)| *nonce)?;
 		let target_nonces = self.target_nonces.as_ref()?;
 
+		// If there are relayers registered at the lane and the current delivery slot is owned by
+		// some other relayer, our delivery transaction will have lower priority than the
+		// transaction of the slot owner. So let's give the slot owner some time to deliver
+		// messages. But if it isn't delivering anything (e.g. it is offline), we're delivering
+		// messages ourselves - otherwise the lane would stall until our slot comes.
+		if let Some(stalled_since) = self.delivery_slot_owner_stalled_since {
+			let stalled_for = stalled_since.elapsed();
+			if stalled_for < self.delivery_slot_grace_period {
+				log::debug!(
+					target: "bridge",
+					"Cannot deliver any more messages from {} to {}. Delivery slot is owned by \
+					other relayer, waiting for its delivery transaction for {:?}",
+					MessageDeliveryRace::<P>::source_name(),
+					MessageDeliveryRace::<P>::target_name(),
+					self.delivery_slot_grace_period - stalled_for,
+				);
+
+				return None
+			}
+
+			log::debug!(
+				target: "bridge",
+				"Delivery slot at {} is owned by other relayer, which hasn't delivered any \
+				messages for {:?}. Delivering messages from {}",
+				MessageDeliveryRace::<P>::target_name(),
+				stalled_for,
+				MessageDeliveryRace::<P>::source_name(),
+			);
+		}
+
 		// There's additional condition in the message delivery race: target would reject messages
 		// if there are too much unconfirmed messages at the inbound lane.
 
@@ -444,7 +599,7 @@ where
 				);
 
 				return None
-			}
+			},
 			_ => (),
 		}
 
@@ -503,8 +658,20 @@ where
 			.unwrap_or_default();
 		let max_nonces = std::cmp::min(max_nonces, self.max_messages_in_single_batch);
 		let max_messages_weight_in_single_batch = self.max_messages_weight_in_single_batch;
+		let max_inline_dispatch_weight = self.max_inline_dispatch_weight;
 		let max_messages_size_in_single_batch = self.max_messages_size_in_single_batch;
 		let relayer_mode = self.relayer_mode;
+		// if all queued nonces are already provable at the best finalized source header, known
+		// to the target chain, then we don't need to relay any headers to deliver them
+		let is_new_source_header_required = self
+			.strategy
+			.required_source_header_at_target(&best_finalized_source_header_id_at_best_target)
+			.is_some();
+		let headers_relay_cost = if is_new_source_header_required {
+			self.headers_relay_cost.unwrap_or_else(Zero::zero)
+		} else {
+			Zero::zero()
+		};
 		let lane_source_client = self.lane_source_client.clone();
 		let lane_target_client = self.lane_target_client.clone();
 
@@ -516,7 +683,9 @@ where
 			relayer_mode,
 			max_nonces,
 			max_messages_weight_in_single_batch,
+			max_inline_dispatch_weight,
 			max_messages_size_in_single_batch,
+			headers_relay_cost,
 			lane_source_client.clone(),
 			lane_target_client.clone(),
 			source_queue,
@@ -547,7 +716,9 @@ async fn select_nonces_for_delivery_transaction<P: MessageLane>(
 	relayer_mode: RelayerMode,
 	max_messages_in_this_batch: MessageNonce,
 	max_messages_weight_in_single_batch: Weight,
+	max_inline_dispatch_weight: Weight,
 	max_messages_size_in_single_batch: u32,
+	headers_relay_cost: P::SourceChainBalance,
 	lane_source_client: impl MessageLaneSourceClient<P>,
 	lane_target_client: impl MessageLaneTargetClient<P>,
 	nonces_queue: &SourceRangesQueue<
@@ -595,7 +766,8 @@ async fn select_nonces_for_delivery_transaction<P: MessageLane>(
 		// transaction will be rejected by the target runtime, but at least we have tried.
 
 		// limit messages in the batch by weight
-		let new_selected_weight = match selected_weight.checked_add(details.dispatch_weight) {
+		let dispatch_weight = declared_dispatch_weight(details, max_inline_dispatch_weight);
+		let new_selected_weight = match selected_weight.checked_add(dispatch_weight) {
 			Some(new_selected_weight)
 				if new_selected_weight <= max_messages_weight_in_single_batch =>
 				new_selected_weight,
@@ -647,7 +819,7 @@ async fn select_nonces_for_delivery_transaction<P: MessageLane>(
 		let new_selected_unpaid_weight = match details.dispatch_fee_payment {
 			DispatchFeePayment::AtSourceChain => {
 				new_selected_prepaid_nonces += 1;
-				selected_unpaid_weight.saturating_add(details.dispatch_weight)
+				selected_unpaid_weight.saturating_add(dispatch_weight)
 			},
 			DispatchFeePayment::AtTargetChain => selected_unpaid_weight,
 		};
@@ -685,7 +857,12 @@ async fn select_nonces_for_delivery_transaction<P: MessageLane>(
 				total_confirmations_cost =
 					total_confirmations_cost.saturating_add(&confirmation_transaction_cost);
 				total_reward = total_reward.saturating_add(&details.reward);
-				total_cost = total_confirmations_cost.saturating_add(&delivery_transaction_cost);
+				// headers, required to deliver messages and confirmations, are submitted once
+				// per batch, so their cost is amortized across all messages of the batch. If
+				// headers are the dominant cost, we'll end up waiting for a larger batch
+				total_cost = headers_relay_cost
+					.saturating_add(&total_confirmations_cost)
+					.saturating_add(&delivery_transaction_cost);
 				if !is_total_reward_less_than_cost && total_reward < total_cost {
 					log::debug!(
 						target: "bridge",
@@ -768,6 +945,22 @@ async fn select_nonces_for_delivery_transaction<P: MessageLane>(
 	}
 }
 
+/// Returns dispatch weight of the message that needs to be declared in the delivery transaction.
+///
+/// Messages that are heavier than `max_inline_dispatch_weight` are not dispatched during delivery.
+/// Instead, they're put into the overweight queue at the target chain, so we don't need to
+/// declare (and pay for) their dispatch weight.
+fn declared_dispatch_weight<SourceChainBalance>(
+	details: &MessageDetails<SourceChainBalance>,
+	max_inline_dispatch_weight: Weight,
+) -> Weight {
+	if details.dispatch_weight > max_inline_dispatch_weight {
+		0
+	} else {
+		details.dispatch_weight
+	}
+}
+
 impl<SourceChainBalance: std::fmt::Debug> NoncesRange for MessageDetailsMap<SourceChainBalance> {
 	fn begin(&self) -> MessageNonce {
 		self.keys().next().cloned().unwrap_or_default()
@@ -849,11 +1042,15 @@ mod tests {
 
 		let mut race_strategy = TestStrategy {
 			relayer_mode: RelayerMode::Altruistic,
+			headers_relay_cost: None,
 			max_unrewarded_relayer_entries_at_target: 4,
 			max_unconfirmed_nonces_at_target: 4,
 			max_messages_in_single_batch: 4,
 			max_messages_weight_in_single_batch: 4,
+			max_inline_dispatch_weight: Weight::MAX,
 			max_messages_size_in_single_batch: 4,
+			delivery_slot_grace_period: Duration::from_secs(60),
+			delivery_slot_owner_stalled_since: None,
 			latest_confirmed_nonces_at_source: vec![(header_id(1), 19)].into_iter().collect(),
 			lane_source_client: TestSourceClient::default(),
 			lane_target_client: TestTargetClient::default(),
@@ -866,6 +1063,7 @@ mod tests {
 						messages_in_oldest_entry: 0,
 						total_messages: 0,
 					},
+					is_delivery_slot_available: true,
 				},
 			}),
 			strategy: BasicStrategy::new(),
@@ -937,6 +1135,69 @@ mod tests {
 		);
 	}
 
+	fn target_nonces_with_foreign_slot(
+		strategy: &TestStrategy,
+		latest_nonce: MessageNonce,
+	) -> TargetClientNonces<DeliveryRaceTargetNoncesData> {
+		let mut nonces = strategy.target_nonces.clone().unwrap();
+		nonces.latest_nonce = latest_nonce;
+		nonces.nonces_data.is_delivery_slot_available = false;
+		nonces
+	}
+
+	#[async_std::test]
+	async fn message_delivery_strategy_selects_nothing_if_delivery_slot_is_not_available() {
+		let (mut state, mut strategy) = prepare_strategy();
+
+		// if the delivery slot is owned by other relayer, we shall not deliver anything during
+		// the grace period
+		let nonces = target_nonces_with_foreign_slot(&strategy, 19);
+		strategy.best_target_nonces_updated(nonces, &mut state);
+		assert!(strategy.delivery_slot_owner_stalled_since.is_some());
+		assert_eq!(strategy.select_nonces_to_deliver(state).await, None);
+	}
+
+	#[async_std::test]
+	async fn message_delivery_strategy_delivers_messages_if_slot_owner_is_stalled() {
+		let (mut state, mut strategy) = prepare_strategy();
+
+		// if the delivery slot owner hasn't delivered anything during the grace period, we
+		// are delivering messages ourselves
+		strategy.delivery_slot_grace_period = Duration::from_secs(0);
+		let nonces = target_nonces_with_foreign_slot(&strategy, 19);
+		strategy.best_target_nonces_updated(nonces, &mut state);
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=23), proof_parameters(false, 4)))
+		);
+	}
+
+	#[async_std::test]
+	async fn message_delivery_strategy_restarts_grace_period_when_slot_owner_delivers_messages() {
+		let (mut state, mut strategy) = prepare_strategy();
+
+		let nonces = target_nonces_with_foreign_slot(&strategy, 19);
+		strategy.best_target_nonces_updated(nonces, &mut state);
+		let stalled_since = strategy.delivery_slot_owner_stalled_since.unwrap();
+
+		// no new messages delivered => the grace period is not restarted
+		let nonces = target_nonces_with_foreign_slot(&strategy, 19);
+		strategy.best_target_nonces_updated(nonces, &mut state);
+		assert_eq!(strategy.delivery_slot_owner_stalled_since, Some(stalled_since));
+
+		// new messages delivered by the slot owner => the grace period is restarted
+		async_std::task::sleep(Duration::from_millis(1)).await;
+		let nonces = target_nonces_with_foreign_slot(&strategy, 20);
+		strategy.best_target_nonces_updated(nonces, &mut state);
+		assert!(strategy.delivery_slot_owner_stalled_since.unwrap() > stalled_since);
+
+		// our slot => we may deliver messages immediately
+		let mut nonces = target_nonces_with_foreign_slot(&strategy, 20);
+		nonces.nonces_data.is_delivery_slot_available = true;
+		strategy.best_target_nonces_updated(nonces, &mut state);
+		assert_eq!(strategy.delivery_slot_owner_stalled_since, None);
+	}
+
 	#[async_std::test]
 	async fn message_delivery_strategy_selects_nothing_if_too_many_confirmations_missing() {
 		let (state, mut strategy) = prepare_strategy();
@@ -1054,6 +1315,20 @@ mod tests {
 		);
 	}
 
+	#[async_std::test]
+	async fn message_delivery_strategy_does_not_declare_weight_of_overweight_messages() {
+		let (state, mut strategy) = prepare_strategy();
+
+		// first message is put into the overweight queue at the target chain, so its weight (10)
+		// is not declared and all messages fit in the batch
+		strategy.max_inline_dispatch_weight = 5;
+		strategy.strategy.source_queue_mut()[0].1.get_mut(&20).unwrap().dispatch_weight = 10;
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=23), proof_parameters(false, 3)))
+		);
+	}
+
 	#[async_std::test]
 	async fn message_delivery_strategy_limits_batch_by_messages_size() {
 		let (state, mut strategy) = prepare_strategy();
@@ -1180,6 +1455,7 @@ mod tests {
 						messages_in_oldest_entry: 2,
 						total_messages: 2,
 					},
+					is_delivery_slot_available: true,
 				},
 			},
 			&mut state,
@@ -1273,6 +1549,94 @@ mod tests {
 		);
 	}
 
+	#[async_std::test]
+	async fn rational_relayer_amortizes_headers_relay_cost_across_batch() {
+		async fn test_with_headers_relay_cost(
+			header_submission_cost: TestSourceChainBalance,
+		) -> Option<(RangeInclusive<MessageNonce>, MessageProofParameters)> {
+			let (mut state, mut strategy) = prepare_strategy();
+			let nonces = source_nonces(24..=25, 19, DEFAULT_REWARD + 3, AtSourceChain);
+			strategy.strategy.source_nonces_updated(header_id(2), nonces);
+			let nonces = source_nonces(26..=26, 19, DEFAULT_REWARD, AtSourceChain);
+			strategy.strategy.source_nonces_updated(header_id(3), nonces);
+			state.best_finalized_source_header_id_at_best_target = Some(header_id(2));
+			strategy.max_unrewarded_relayer_entries_at_target = 100;
+			strategy.max_unconfirmed_nonces_at_target = 100;
+			strategy.max_messages_in_single_batch = 100;
+			strategy.max_messages_weight_in_single_batch = 100;
+			strategy.max_messages_size_in_single_batch = 100;
+			strategy.relayer_mode = RelayerMode::Rational;
+			strategy
+				.lane_source_client
+				.set_target_header_submission_cost(header_submission_cost);
+			strategy
+				.lane_target_client
+				.set_source_header_submission_cost(header_submission_cost);
+
+			// so now we have:
+			// - 20..=23 with reward = cost
+			// - 24..=25 with reward = cost + 3
+			// - 26 with reward = cost, which requires new source header at the target chain
+			// => when headers relay cost is covered by the batch, strategy shall select 20..=25
+			// => when headers relay cost is larger than the profit, strategy shall wait for
+			//    larger batch
+			strategy.select_nonces_to_deliver(state).await
+		}
+
+		assert_eq!(
+			test_with_headers_relay_cost(3).await,
+			Some(((20..=25), proof_parameters(false, 6)))
+		);
+		assert_eq!(test_with_headers_relay_cost(4).await, None);
+	}
+
+	#[async_std::test]
+	async fn rational_relayer_ignores_headers_relay_cost_if_nonces_are_already_provable() {
+		let (mut state, mut strategy) = prepare_strategy();
+		let nonces = source_nonces(24..=25, 19, DEFAULT_REWARD + 3, AtSourceChain);
+		strategy.strategy.source_nonces_updated(header_id(2), nonces);
+		state.best_finalized_source_header_id_at_best_target = Some(header_id(2));
+		strategy.max_unrewarded_relayer_entries_at_target = 100;
+		strategy.max_unconfirmed_nonces_at_target = 100;
+		strategy.max_messages_in_single_batch = 100;
+		strategy.max_messages_weight_in_single_batch = 100;
+		strategy.max_messages_size_in_single_batch = 100;
+		strategy.relayer_mode = RelayerMode::Rational;
+		strategy.lane_source_client.set_target_header_submission_cost(100);
+		strategy.lane_target_client.set_source_header_submission_cost(100);
+
+		// all nonces are provable at the best finalized source header at the target chain, so
+		// the headers relay cost is not added to the cost of the batch
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state).await,
+			Some(((20..=25), proof_parameters(false, 6)))
+		);
+	}
+
+	#[async_std::test]
+	async fn rational_relayer_does_not_require_headers_if_rewards_do_not_cover_their_cost() {
+		let (_, mut strategy) = prepare_strategy();
+		strategy.strategy.source_nonces_updated(
+			header_id(2),
+			source_nonces(24..=25, 19, DEFAULT_REWARD, AtSourceChain),
+		);
+		strategy.relayer_mode = RelayerMode::Rational;
+
+		// total reward of queued messages is `6 * DEFAULT_REWARD`
+		// => header#2 is required if headers relay cost is covered by the reward
+		strategy.headers_relay_cost = Some(6 * DEFAULT_REWARD);
+		assert_eq!(strategy.required_source_header_at_target(&header_id(1)), Some(header_id(2)));
+
+		// => header#2 is not required if headers relay cost is larger than the reward (and we
+		//    still require header#1, because it is required to deliver confirmations)
+		strategy.headers_relay_cost = Some(6 * DEFAULT_REWARD + 1);
+		assert_eq!(strategy.required_source_header_at_target(&header_id(1)), Some(header_id(1)));
+
+		// => altruistic relayer always requires headers
+		strategy.relayer_mode = RelayerMode::Altruistic;
+		assert_eq!(strategy.required_source_header_at_target(&header_id(1)), Some(header_id(2)));
+	}
+
 	#[async_std::test]
 	async fn relayer_uses_flattened_view_of_the_source_queue_to_select_nonces() {
 		// Real scenario that has happened on test deployments:
@@ -20,7 +20,7 @@
 //! associated data - like messages, lane state, etc) to the target node by
 //! generating and submitting proof.
 
-use crate::message_lane_loop::ClientState;
+use crate::message_lane_loop::{ClientState, NoncesSubmitArtifacts};
 
 use async_trait::async_trait;
 use bp_messages::MessageNonce;
@@ -28,7 +28,10 @@ use futures::{
 	future::FutureExt,
 	stream::{FusedStream, StreamExt},
 };
-use relay_utils::{process_future_result, retry_backoff, FailedClient, MaybeConnectionError};
+use relay_utils::{
+	process_future_result, retry_backoff, FailedClient, MaybeConnectionError,
+	TrackedTransactionStatus, TransactionTracker,
+};
 use std::{
 	fmt::Debug,
 	ops::RangeInclusive,
@@ -124,6 +127,8 @@ pub trait TargetClient<P: MessageRace> {
 	type Error: std::fmt::Debug + MaybeConnectionError;
 	/// Type of the additional data from the target client, used by the race.
 	type TargetNoncesData: std::fmt::Debug;
+	/// Transaction tracker to track submitted transactions.
+	type TransactionTracker: TransactionTracker;
 
 	/// Ask headers relay to relay finalized headers up to (and including) given header
 	/// from race source to race target.
@@ -141,7 +146,7 @@ pub trait TargetClient<P: MessageRace> {
 		generated_at_block: P::SourceHeaderId,
 		nonces: RangeInclusive<MessageNonce>,
 		proof: P::Proof,
-	) -> Result<RangeInclusive<MessageNonce>, Self::Error>;
+	) -> Result<NoncesSubmitArtifacts<Self::TransactionTracker>, Self::Error>;
 }
 
 /// Race strategy.
@@ -250,6 +255,7 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 	let target_best_nonces = futures::future::Fuse::terminated();
 	let target_finalized_nonces = futures::future::Fuse::terminated();
 	let target_submit_proof = futures::future::Fuse::terminated();
+	let target_tx_tracker = futures::future::Fuse::terminated();
 	let target_go_offline_future = futures::future::Fuse::terminated();
 
 	futures::pin_mut!(
@@ -261,6 +267,7 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 		target_best_nonces,
 		target_finalized_nonces,
 		target_submit_proof,
+		target_tx_tracker,
 		target_go_offline_future,
 	);
 
@@ -400,16 +407,17 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 				target_client_is_online = process_future_result(
 					proof_submit_result,
 					&mut target_retry_backoff,
-					|nonces_range| {
+					|artifacts: NoncesSubmitArtifacts<TC::TransactionTracker>| {
 						log::debug!(
 							target: "bridge",
 							"Successfully submitted proof of nonces {:?} to {}",
-							nonces_range,
+							artifacts.nonces,
 							P::target_name(),
 						);
 
 						race_state.nonces_to_submit = None;
-						race_state.nonces_submitted = Some(nonces_range);
+						race_state.nonces_submitted = Some(artifacts.nonces);
+						target_tx_tracker.set(artifacts.tx_tracker.wait().fuse());
 						stall_countdown = Instant::now();
 					},
 					&mut target_go_offline_future,
@@ -417,6 +425,33 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 					|| format!("Error submitting proof {}", P::target_name()),
 				).fail_if_connection_error(FailedClient::Target)?;
 			},
+			target_transaction_status = target_tx_tracker => {
+				match target_transaction_status {
+					TrackedTransactionStatus::Finalized => {
+						log::debug!(
+							target: "bridge",
+							"{} -> {} race transaction with nonces {:?} has been finalized",
+							P::source_name(),
+							P::target_name(),
+							race_state.nonces_submitted,
+						);
+					},
+					TrackedTransactionStatus::Lost => {
+						// the transaction is not in the pool anymore, so nonces need to be
+						// resubmitted (if they are still not delivered)
+						log::warn!(
+							target: "bridge",
+							"{} -> {} race transaction with nonces {:?} has been lost. Going to resubmit",
+							P::source_name(),
+							P::target_name(),
+							race_state.nonces_submitted,
+						);
+
+						race_state.nonces_submitted = None;
+						stall_countdown = Instant::now();
+					},
+				}
+			},
 
 			// when we're ready to retry request
 			_ = source_go_offline_future => {
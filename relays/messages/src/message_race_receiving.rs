@@ -16,7 +16,7 @@
 use crate::{
 	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
 	message_lane_loop::{
-		SourceClient as MessageLaneSourceClient, SourceClientState,
+		NoncesSubmitArtifacts, SourceClient as MessageLaneSourceClient, SourceClientState,
 		TargetClient as MessageLaneTargetClient, TargetClientState,
 	},
 	message_race_loop::{
@@ -157,6 +157,7 @@ where
 {
 	type Error = C::Error;
 	type TargetNoncesData = ();
+	type TransactionTracker = C::TransactionTracker;
 
 	async fn require_source_header(&self, id: TargetHeaderIdOf<P>) {
 		self.client.require_target_header_on_source(id).await
@@ -182,9 +183,10 @@ where
 		generated_at_block: TargetHeaderIdOf<P>,
 		nonces: RangeInclusive<MessageNonce>,
 		proof: P::MessagesReceivingProof,
-	) -> Result<RangeInclusive<MessageNonce>, Self::Error> {
-		self.client.submit_messages_receiving_proof(generated_at_block, proof).await?;
-		Ok(nonces)
+	) -> Result<NoncesSubmitArtifacts<Self::TransactionTracker>, Self::Error> {
+		let tx_tracker =
+			self.client.submit_messages_receiving_proof(generated_at_block, proof).await?;
+		Ok(NoncesSubmitArtifacts { nonces, tx_tracker })
 	}
 }
 
@@ -18,6 +18,7 @@
 
 pub use relay_loop::{relay_loop, relay_metrics};
 
+use async_trait::async_trait;
 use backoff::{backoff::Backoff, ExponentialBackoff};
 use futures::future::FutureExt;
 use std::time::Duration;
@@ -104,6 +105,26 @@ macro_rules! bail_on_arg_error {
 #[derive(Debug, Default, Clone, Copy, Eq, Hash, PartialEq)]
 pub struct HeaderId<Hash, Number>(pub Number, pub Hash);
 
+/// Final status of the tracked transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackedTransactionStatus {
+	/// Transaction has been lost (dropped, invalidated, its mortality period has ended, or we
+	/// have been unable to track it for too long).
+	Lost,
+	/// Transaction has been included into finalized block.
+	Finalized,
+}
+
+/// Transaction tracker.
+///
+/// The tracker is returned by the client that has submitted transaction. It allows the submitter
+/// to react immediately when the transaction is finalized or lost.
+#[async_trait]
+pub trait TransactionTracker: Send + 'static {
+	/// Wait until transaction is either finalized or lost.
+	async fn wait(self) -> TrackedTransactionStatus;
+}
+
 /// Error type that can signal connection errors.
 pub trait MaybeConnectionError {
 	/// Returns true if error (maybe) represents connection error.